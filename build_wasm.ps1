@@ -20,8 +20,7 @@ try {
 
     Set-Location ../..
 
-    Write-Host "Removing pkg-web/.gitignore..." -ForegroundColor Green
-    Remove-Item "pkg-web/.gitignore" -Force -ErrorAction SilentlyContinue
+    # pkg-web 为构建产物，不提交，保留 wasm-pack 生成的 .gitignore
 
     Write-Host "WASM build completed successfully!" -ForegroundColor Green
 }
@@ -25,8 +25,6 @@ fi
 
 cd ../..
 
-# 删除 wasm-pack 生成的 .gitignore 文件，确保构建产物可以被提交
-echo "Removing pkg-web/.gitignore..."
-rm -rf pkg-web/.gitignore
+# pkg-web 为构建产物，不提交，保留 wasm-pack 生成的 .gitignore
 
 echo "WASM build completed successfully!"
\ No newline at end of file
@@ -0,0 +1,257 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::sync::{Arc, RwLock};
+
+use lazy_static::lazy_static;
+use serde_json::Value;
+use tracing::info;
+
+use crate::basefunc::csg_items::{CsgPwMode, CsgPwPolicy};
+use crate::basefunc::protocol::FrameAnalisyic;
+use crate::config::afntable::{AfnEntry, AfnTable};
+use crate::config::appconfig::{default_store, value_path, Config, ConfigStore, MemoryConfigStore};
+use crate::config::keystore::{KeyStore, MemoryKeyStore};
+use crate::config::oadmapconfig::{CompleteConfig, ListItem};
+use crate::config::xmlconfig::{BuiltinProtocol, QframeConfig, XmlElement};
+
+type ConfigSlot = Result<Arc<QframeConfig>, Arc<dyn Error + Send + Sync>>;
+
+struct AnalyzerInner {
+    // 按BuiltinProtocol的声明顺序存放
+    configs: Vec<ConfigSlot>,
+    store: Arc<dyn ConfigStore>,
+    oad: RwLock<CompleteConfig>,
+    csg_pw: RwLock<CsgPwPolicy>,
+    keys: RwLock<Arc<dyn KeyStore>>,
+}
+
+/// 报文解析器实例，持有一套协议配置、OAD映射、应用配置存储和终端密钥存储
+///
+/// 解析函数以参数接收实例并从中取得配置，ProtocolConfigManager等模块级函数使用默认实例
+/// `Analyzer::global()`。克隆只增加引用计数，多个克隆共享同一套配置。
+///
+/// ```ignore
+/// let old = Analyzer::new();
+/// old.load_protocol_config("CSG13", &old_xml)?;
+/// let (_, old_result) = old.process_frame(&frame, "南网");
+/// let (_, new_result) = Analyzer::global().process_frame(&frame, "南网");
+/// ```
+#[derive(Clone)]
+pub struct Analyzer {
+    inner: Arc<AnalyzerInner>,
+}
+
+lazy_static! {
+    static ref GLOBAL_ANALYZER: Analyzer = Analyzer::with_builtin();
+}
+
+impl Analyzer {
+    /// 协议配置和OAD映射都为空的实例，需要另行加载，应用配置只保存在内存中
+    pub fn new() -> Self {
+        Self::from_parts(
+            BuiltinProtocol::ALL
+                .iter()
+                .map(|_| Ok(Arc::new(QframeConfig::new())))
+                .collect(),
+            Arc::new(MemoryConfigStore::new()),
+            CompleteConfig::default(),
+            CsgPwPolicy::default(),
+        )
+    }
+
+    /// 使用默认配置存储并加载内置协议配置和OAD映射的实例
+    pub fn with_builtin() -> Self {
+        Self::with_store(default_store())
+    }
+
+    /// 按store中的应用配置加载内置协议配置和OAD映射，desktop下从配置文件指定的路径加载
+    pub fn with_store(store: Arc<dyn ConfigStore>) -> Self {
+        Self::from_parts(
+            BuiltinProtocol::ALL
+                .iter()
+                .map(|protocol| protocol.load(store.as_ref()).map(Arc::new))
+                .collect(),
+            store.clone(),
+            CompleteConfig::builtin(),
+            CsgPwPolicy::load(store.as_ref()),
+        )
+    }
+
+    fn from_parts(
+        configs: Vec<ConfigSlot>,
+        store: Arc<dyn ConfigStore>,
+        oad: CompleteConfig,
+        csg_pw: CsgPwPolicy,
+    ) -> Self {
+        Analyzer {
+            inner: Arc::new(AnalyzerInner {
+                configs,
+                store,
+                oad: RwLock::new(oad),
+                csg_pw: RwLock::new(csg_pw),
+                keys: RwLock::new(Arc::new(MemoryKeyStore::new())),
+            }),
+        }
+    }
+
+    /// 默认实例，ProtocolConfigManager等模块级函数使用
+    pub fn global() -> Analyzer {
+        GLOBAL_ANALYZER.clone()
+    }
+
+    /// 是否与另一个实例共享同一套配置
+    pub fn ptr_eq(&self, other: &Analyzer) -> bool {
+        Arc::ptr_eq(&self.inner, &other.inner)
+    }
+
+    /// 协议对应的配置
+    pub fn config(&self, protocol: &str) -> Result<Arc<QframeConfig>, Box<dyn Error>> {
+        let builtin = BuiltinProtocol::from_name(protocol)
+            .ok_or_else(|| format!("Unsupported protocol: {}", protocol))?;
+        self.inner.configs[builtin as usize]
+            .clone()
+            .map_err(|e| format!("{} config initialization failed: {}", builtin.label(), e).into())
+    }
+
+    /// 查找数据项配置，protocol为逗号分隔的多个协议时依次查找
+    pub fn get_config_xml(
+        &self,
+        data_item_id: &str,
+        protocol: &str,
+        region: &str,
+        dir: Option<u8>,
+    ) -> Option<XmlElement> {
+        let find_protocol = protocol.to_uppercase();
+        if find_protocol.contains(',') {
+            find_protocol
+                .split(',')
+                .map(|s| s.trim())
+                .filter_map(|single_protocol| self.config(single_protocol).ok())
+                .find_map(|config| config.get_item(data_item_id, protocol, region, dir))
+        } else {
+            self.config(&find_protocol)
+                .ok()?
+                .get_item(data_item_id, &find_protocol, region, dir)
+        }
+    }
+
+    pub fn get_template_element(
+        &self,
+        template: &str,
+        protocol: &str,
+        region: &str,
+        dir: Option<u8>,
+    ) -> Option<XmlElement> {
+        let find_protocol = protocol.to_uppercase();
+        self.config(&find_protocol)
+            .ok()?
+            .get_item(template, &find_protocol, region, dir)
+    }
+
+    /// 内置功能码表加上配置中声明的功能码
+    pub fn get_afn_table(&self, protocol: &str) -> Result<Vec<AfnEntry>, Box<dyn Error>> {
+        let mut entries = AfnTable::builtin(protocol);
+        entries.extend(self.config(protocol)?.afn_entries());
+        Ok(entries)
+    }
+
+    /// 查找功能码定义，协议配置不可用时只查内置功能码表
+    pub fn find_afn(
+        &self,
+        protocol: &str,
+        code: u8,
+        kind: Option<u8>,
+        item: Option<&str>,
+    ) -> Option<AfnEntry> {
+        let entries = self.get_afn_table(protocol).unwrap_or_else(|e| {
+            info!("读取{}功能码表失败: {}", protocol, e);
+            AfnTable::builtin(protocol)
+        });
+        AfnTable::find(&entries, code, kind, item).cloned()
+    }
+
+    /// 用XML内容替换协议配置
+    pub fn load_protocol_config(
+        &self,
+        protocol: &str,
+        content: &str,
+    ) -> Result<(), Box<dyn Error>> {
+        self.config(protocol)?
+            .load_from_str(content)
+            .map_err(|e| Box::new(e) as Box<dyn Error>)
+    }
+
+    /// 用YAML内容替换OAD映射，sub_yaml_map的键为主配置中列表的name
+    pub fn init_oad_map(
+        &self,
+        main_yaml: &str,
+        sub_yaml_map: &HashMap<String, String>,
+    ) -> Result<(), Box<dyn Error>> {
+        let new_config = CompleteConfig::new_from_strs(main_yaml, sub_yaml_map)?;
+        *self.inner.oad.write().unwrap() = new_config;
+        Ok(())
+    }
+
+    /// 应用配置存储
+    pub fn config_store(&self) -> Arc<dyn ConfigStore> {
+        self.inner.store.clone()
+    }
+
+    /// 读取应用配置值，key为空时返回整个section
+    pub fn get_config_value(&self, section: &str, key: &str) -> Result<Option<Value>, String> {
+        let config = Config::load(self.inner.store.as_ref())?;
+        Ok(config.get_value(&value_path(section, key)).cloned())
+    }
+
+    /// 写入应用配置值，value为JSON文本，key为空时替换整个section
+    pub fn set_config_value(&self, section: &str, key: &str, value: &str) -> Result<(), String> {
+        let value_json: Value =
+            serde_json::from_str(value).map_err(|e| format!("Failed to parse JSON: {}", e))?;
+
+        let store = self.inner.store.as_ref();
+        let mut config = Config::load(store)?;
+        config
+            .set_value(&value_path(section, key), value_json)
+            .map_err(|e| format!("{}", e))?;
+        config.save(store)?;
+        // csgpw只在创建实例时读取，修改后同步
+        if section == "protocolsetting" && (key.is_empty() || key == "csgpw") {
+            self.set_csg_pw_policy(CsgPwPolicy::load(store));
+        }
+        Ok(())
+    }
+
+    /// 设置南网终端报文是否带PW，新实例默认为auto，内置实例取应用配置中的设置
+    pub fn set_csg_pw_policy(&self, policy: CsgPwPolicy) {
+        *self.inner.csg_pw.write().unwrap() = policy;
+    }
+
+    pub fn csg_pw_mode(&self, terminal: &str) -> CsgPwMode {
+        self.inner.csg_pw.read().unwrap().mode(terminal)
+    }
+
+    /// 终端密钥存储，新实例的密钥只保存在内存中
+    pub fn key_store(&self) -> Arc<dyn KeyStore> {
+        self.inner.keys.read().unwrap().clone()
+    }
+
+    /// 替换终端密钥存储，之后的校验和加解密都使用新的存储
+    pub fn set_key_store(&self, store: Arc<dyn KeyStore>) {
+        *self.inner.keys.write().unwrap() = store;
+    }
+
+    pub fn get_voad(&self, master_oad: &str, v_oad: &str) -> Option<ListItem> {
+        self.inner.oad.read().unwrap().get_voad(master_oad, v_oad)
+    }
+
+    /// 使用该实例的配置解析报文，返回识别出的协议和解析结果
+    pub fn process_frame(&self, frame: &[u8], region: &str) -> (String, Vec<Value>) {
+        FrameAnalisyic::process_frame_with(self, frame, region)
+    }
+}
+
+impl Default for Analyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
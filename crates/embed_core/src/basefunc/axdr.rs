@@ -0,0 +1,1371 @@
+use crate::basefunc::frame_fun::FrameFun;
+use std::fmt;
+
+// Data类型标签
+pub const DT_NULL: u8 = 0x00;
+pub const DT_ARRAY: u8 = 0x01;
+pub const DT_STRUCTURE: u8 = 0x02;
+pub const DT_BOOL: u8 = 0x03;
+pub const DT_BIT_STRING: u8 = 0x04;
+pub const DT_DOUBLE_LONG: u8 = 0x05;
+pub const DT_DOUBLE_LONG_UNSIGNED: u8 = 0x06;
+pub const DT_OCTET_STRING: u8 = 0x09;
+pub const DT_VISIBLE_STRING: u8 = 0x0A;
+pub const DT_UTF8_STRING: u8 = 0x0C;
+pub const DT_INTEGER: u8 = 0x0F;
+pub const DT_LONG: u8 = 0x10;
+pub const DT_UNSIGNED: u8 = 0x11;
+pub const DT_LONG_UNSIGNED: u8 = 0x12;
+pub const DT_LONG64: u8 = 0x14;
+pub const DT_LONG64_UNSIGNED: u8 = 0x15;
+pub const DT_ENUM: u8 = 0x16;
+pub const DT_FLOAT32: u8 = 0x17;
+pub const DT_FLOAT64: u8 = 0x18;
+pub const DT_DATE_TIME: u8 = 0x19;
+pub const DT_DATE: u8 = 0x1A;
+pub const DT_TIME: u8 = 0x1B;
+pub const DT_DATE_TIME_S: u8 = 0x1C;
+pub const DT_OI: u8 = 0x50;
+pub const DT_OAD: u8 = 0x51;
+pub const DT_ROAD: u8 = 0x52;
+pub const DT_OMD: u8 = 0x53;
+pub const DT_TI: u8 = 0x54;
+pub const DT_TSA: u8 = 0x55;
+pub const DT_MAC: u8 = 0x56;
+pub const DT_RN: u8 = 0x57;
+pub const DT_REGION: u8 = 0x58;
+pub const DT_SCALER_UNIT: u8 = 0x59;
+pub const DT_RSD: u8 = 0x5A;
+pub const DT_CSD: u8 = 0x5B;
+pub const DT_MS: u8 = 0x5C;
+pub const DT_SID: u8 = 0x5D;
+pub const DT_SID_MAC: u8 = 0x5E;
+pub const DT_COMDCB: u8 = 0x5F;
+pub const DT_RCSD: u8 = 0x60;
+
+/// 698.45的Data，按A-XDR规则编解码
+#[derive(Clone, Debug, PartialEq)]
+pub enum AxdrData {
+    Null,
+    Array(Vec<AxdrData>),
+    Structure(Vec<AxdrData>),
+    Bool(bool),
+    BitString(BitString),
+    DoubleLong(i32),
+    DoubleLongUnsigned(u32),
+    OctetString(Vec<u8>),
+    VisibleString(Vec<u8>),
+    Utf8String(String),
+    Integer(i8),
+    Long(i16),
+    Unsigned(u8),
+    LongUnsigned(u16),
+    Long64(i64),
+    Long64Unsigned(u64),
+    Enum(u8),
+    Float32(f32),
+    Float64(f64),
+    DateTime(DateTime),
+    Date(Date),
+    Time(Time),
+    DateTimeS(DateTimeS),
+    Oi(u16),
+    Oad(u32),
+    Road(Road),
+    Omd(u32),
+    Ti(Ti),
+    Tsa(Tsa),
+    Mac(Vec<u8>),
+    Rn(Vec<u8>),
+    Region(Region),
+    ScalerUnit(ScalerUnit),
+    Rsd(Rsd),
+    Csd(Csd),
+    Ms(Ms),
+    Sid(Sid),
+    SidMac(SidMac),
+    Comdcb(Comdcb),
+    Rcsd(Vec<Csd>),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct BitString {
+    pub bits: usize,
+    pub data: Vec<u8>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct DateTime {
+    pub year: u16,
+    pub month: u8,
+    pub day: u8,
+    pub week: u8,
+    pub hour: u8,
+    pub minute: u8,
+    pub second: u8,
+    pub millisecond: u16,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Date {
+    pub year: u16,
+    pub month: u8,
+    pub day: u8,
+    pub week: u8,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Time {
+    pub hour: u8,
+    pub minute: u8,
+    pub second: u8,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct DateTimeS {
+    pub year: u16,
+    pub month: u8,
+    pub day: u8,
+    pub hour: u8,
+    pub minute: u8,
+    pub second: u8,
+}
+
+/// 时间间隔，单位0~5依次为秒、分、时、日、月、年
+#[derive(Clone, Debug, PartialEq)]
+pub struct Ti {
+    pub unit: u8,
+    pub interval: u16,
+}
+
+/// 目标服务器地址，内容首字节为地址特征，其后为地址
+#[derive(Clone, Debug, PartialEq)]
+pub struct Tsa(pub Vec<u8>);
+
+/// 区间，类型0~3依次为前闭后开、前开后闭、前闭后闭、前开后开
+#[derive(Clone, Debug, PartialEq)]
+pub struct Region {
+    pub kind: u8,
+    pub start: Box<AxdrData>,
+    pub end: Box<AxdrData>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ScalerUnit {
+    pub scaler: i8,
+    pub unit: u8,
+}
+
+/// 记录型对象属性描述符
+#[derive(Clone, Debug, PartialEq)]
+pub struct Road {
+    pub oad: u32,
+    pub oads: Vec<u32>,
+}
+
+/// 列选择描述符
+#[derive(Clone, Debug, PartialEq)]
+pub enum Csd {
+    Oad(u32),
+    Road(Road),
+}
+
+/// 行选择描述符
+#[derive(Clone, Debug, PartialEq)]
+pub enum Rsd {
+    None,
+    Selector1 { oad: u32, value: Box<AxdrData> },
+    Selector2(Selector2),
+    Selector3(Vec<Selector2>),
+    Selector4 { time: DateTimeS, ms: Ms },
+    Selector5 { time: DateTimeS, ms: Ms },
+    Selector6(TimeRange),
+    Selector7(TimeRange),
+    Selector8(TimeRange),
+    Selector9(u8),
+    Selector10 { count: u8, ms: Ms },
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Selector2 {
+    pub oad: u32,
+    pub start: Box<AxdrData>,
+    pub end: Box<AxdrData>,
+    pub interval: Box<AxdrData>,
+}
+
+/// 选择方法6~8的时间区间
+#[derive(Clone, Debug, PartialEq)]
+pub struct TimeRange {
+    pub start: DateTimeS,
+    pub end: DateTimeS,
+    pub interval: Ti,
+    pub ms: Ms,
+}
+
+/// 电能表集合
+#[derive(Clone, Debug, PartialEq)]
+pub enum Ms {
+    None,
+    All,
+    UserTypes(Vec<u8>),
+    Addresses(Vec<Tsa>),
+    Numbers(Vec<u16>),
+    UserTypeRegions(Vec<Region>),
+    AddressRegions(Vec<Region>),
+    NumberRegions(Vec<Region>),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Sid {
+    pub ident: u32,
+    pub data: Vec<u8>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SidMac {
+    pub sid: Sid,
+    pub mac: Vec<u8>,
+}
+
+/// 端口通信控制块
+#[derive(Clone, Debug, PartialEq)]
+pub struct Comdcb {
+    pub baud: u8,
+    pub parity: u8,
+    pub data_bits: u8,
+    pub stop_bits: u8,
+    pub flow: u8,
+}
+
+pub struct Axdr;
+
+impl Axdr {
+    pub fn type_name(tag: u8) -> &'static str {
+        match tag {
+            DT_NULL => "null",
+            DT_ARRAY => "array",
+            DT_STRUCTURE => "structure",
+            DT_BOOL => "bool",
+            DT_BIT_STRING => "bit-string",
+            DT_DOUBLE_LONG => "double-long",
+            DT_DOUBLE_LONG_UNSIGNED => "double-long-unsigned",
+            DT_OCTET_STRING => "octet-string",
+            DT_VISIBLE_STRING => "visible-string",
+            DT_UTF8_STRING => "UTF8-string",
+            DT_INTEGER => "integer",
+            DT_LONG => "long",
+            DT_UNSIGNED => "unsigned",
+            DT_LONG_UNSIGNED => "long-unsigned",
+            DT_LONG64 => "long64",
+            DT_LONG64_UNSIGNED => "long64-unsigned",
+            DT_ENUM => "enum",
+            DT_FLOAT32 => "float32",
+            DT_FLOAT64 => "float64",
+            DT_DATE_TIME => "date_time",
+            DT_DATE => "date",
+            DT_TIME => "time",
+            DT_DATE_TIME_S => "date_time_s",
+            DT_OI => "OI",
+            DT_OAD => "OAD",
+            DT_ROAD => "ROAD",
+            DT_OMD => "OMD",
+            DT_TI => "TI",
+            DT_TSA => "TSA",
+            DT_MAC => "MAC",
+            DT_RN => "RN",
+            DT_REGION => "Region",
+            DT_SCALER_UNIT => "Scaler_Unit",
+            DT_RSD => "RSD",
+            DT_CSD => "CSD",
+            DT_MS => "MS",
+            DT_SID => "SID",
+            DT_SID_MAC => "SID_MAC",
+            DT_COMDCB => "COMDCB",
+            DT_RCSD => "RCSD",
+            _ => "未知类型",
+        }
+    }
+
+    /// 长度小于128时用一个字节表示，否则首字节最高位置1，低7位为后续长度字节数
+    pub fn encode_length(length: usize, buf: &mut Vec<u8>) {
+        if length < 0x80 {
+            buf.push(length as u8);
+            return;
+        }
+        let bytes = length.to_be_bytes();
+        let skip = bytes.iter().take_while(|&&byte| byte == 0).count();
+        buf.push(0x80 | (bytes.len() - skip) as u8);
+        buf.extend_from_slice(&bytes[skip..]);
+    }
+
+    pub fn encode_octet_string(data: &[u8], buf: &mut Vec<u8>) {
+        Self::encode_length(data.len(), buf);
+        buf.extend_from_slice(data);
+    }
+
+    fn encode_sequence<T>(items: &[T], buf: &mut Vec<u8>, encode: fn(&T, &mut Vec<u8>)) {
+        Self::encode_length(items.len(), buf);
+        for item in items {
+            encode(item, buf);
+        }
+    }
+
+    // 日期时间中全为FF的部分表示无效
+    fn time_part(value: u16, width: usize) -> String {
+        if (width == 4 && value == 0xFFFF) || (width != 4 && value == 0xFF) {
+            "F".repeat(width.max(2))
+        } else {
+            format!("{:0width$}", value, width = width)
+        }
+    }
+}
+
+/// A-XDR顺序读取器，数值均为大端
+pub struct AxdrReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> AxdrReader<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        AxdrReader { data, pos: 0 }
+    }
+
+    pub fn position(&self) -> usize {
+        self.pos
+    }
+
+    pub fn set_position(&mut self, pos: usize) {
+        self.pos = pos.min(self.data.len());
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pos >= self.data.len()
+    }
+
+    pub fn skip_to_end(&mut self) {
+        self.pos = self.data.len();
+    }
+
+    /// 从start到当前位置已读取的字节
+    pub fn bytes(&self, start: usize) -> &'a [u8] {
+        &self.data[start.min(self.pos)..self.pos]
+    }
+
+    pub fn remaining(&self) -> &'a [u8] {
+        &self.data[self.pos..]
+    }
+
+    pub fn peek(&self) -> Result<u8, String> {
+        self.data
+            .get(self.pos)
+            .copied()
+            .ok_or_else(|| "数据长度不足，需要1字节，剩余0字节".to_string())
+    }
+
+    pub fn take(&mut self, length: usize) -> Result<&'a [u8], String> {
+        if self.pos + length > self.data.len() {
+            return Err(format!(
+                "数据长度不足，需要{}字节，剩余{}字节",
+                length,
+                self.data.len() - self.pos
+            ));
+        }
+        let data = &self.data[self.pos..self.pos + length];
+        self.pos += length;
+        Ok(data)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], String> {
+        let mut data = [0u8; N];
+        data.copy_from_slice(self.take(N)?);
+        Ok(data)
+    }
+
+    pub fn read_u8(&mut self) -> Result<u8, String> {
+        Ok(self.take(1)?[0])
+    }
+
+    pub fn read_u16(&mut self) -> Result<u16, String> {
+        Ok(u16::from_be_bytes(self.array()?))
+    }
+
+    pub fn read_u32(&mut self) -> Result<u32, String> {
+        Ok(u32::from_be_bytes(self.array()?))
+    }
+
+    pub fn read_length(&mut self) -> Result<usize, String> {
+        let first = self.read_u8()?;
+        if first & 0x80 == 0 {
+            return Ok(first as usize);
+        }
+        let count = (first & 0x7F) as usize;
+        if count == 0 || count > 4 {
+            return Err(format!("长度域[{:02X}]无效", first));
+        }
+        Ok(self
+            .take(count)?
+            .iter()
+            .fold(0, |length, &byte| (length << 8) | byte as usize))
+    }
+
+    pub fn read_octet_string(&mut self) -> Result<&'a [u8], String> {
+        let length = self.read_length()?;
+        self.take(length)
+    }
+
+    /// 读取指定类型标签的数据
+    pub fn expect_tag(&mut self, tag: u8) -> Result<(), String> {
+        let actual = self.read_u8()?;
+        if actual != tag {
+            return Err(format!(
+                "数据类型应为{}({:02X})，实际为{:02X}",
+                Axdr::type_name(tag),
+                tag,
+                actual
+            ));
+        }
+        Ok(())
+    }
+
+    /// 读取带类型标签的Data
+    pub fn read_data(&mut self) -> Result<AxdrData, String> {
+        let tag = self.read_u8()?;
+        self.read_value(tag)
+    }
+
+    /// 按类型标签读取数据内容，标签已被读取
+    pub fn read_value(&mut self, tag: u8) -> Result<AxdrData, String> {
+        Ok(match tag {
+            DT_NULL => AxdrData::Null,
+            DT_ARRAY | DT_STRUCTURE => {
+                let count = self.read_length()?;
+                let mut items = Vec::new();
+                for _ in 0..count {
+                    items.push(self.read_data()?);
+                }
+                if tag == DT_ARRAY {
+                    AxdrData::Array(items)
+                } else {
+                    AxdrData::Structure(items)
+                }
+            }
+            DT_BOOL => AxdrData::Bool(self.read_u8()? != 0),
+            DT_BIT_STRING => AxdrData::BitString(self.read_bit_string()?),
+            DT_DOUBLE_LONG => AxdrData::DoubleLong(i32::from_be_bytes(self.array()?)),
+            DT_DOUBLE_LONG_UNSIGNED => AxdrData::DoubleLongUnsigned(self.read_u32()?),
+            DT_OCTET_STRING => AxdrData::OctetString(self.read_octet_string()?.to_vec()),
+            DT_VISIBLE_STRING => AxdrData::VisibleString(self.read_octet_string()?.to_vec()),
+            DT_UTF8_STRING => {
+                AxdrData::Utf8String(String::from_utf8_lossy(self.read_octet_string()?).to_string())
+            }
+            DT_INTEGER => AxdrData::Integer(self.read_u8()? as i8),
+            DT_LONG => AxdrData::Long(i16::from_be_bytes(self.array()?)),
+            DT_UNSIGNED => AxdrData::Unsigned(self.read_u8()?),
+            DT_LONG_UNSIGNED => AxdrData::LongUnsigned(self.read_u16()?),
+            DT_LONG64 => AxdrData::Long64(i64::from_be_bytes(self.array()?)),
+            DT_LONG64_UNSIGNED => AxdrData::Long64Unsigned(u64::from_be_bytes(self.array()?)),
+            DT_ENUM => AxdrData::Enum(self.read_u8()?),
+            DT_FLOAT32 => AxdrData::Float32(f32::from_be_bytes(self.array()?)),
+            DT_FLOAT64 => AxdrData::Float64(f64::from_be_bytes(self.array()?)),
+            DT_DATE_TIME => AxdrData::DateTime(self.read_date_time()?),
+            DT_DATE => AxdrData::Date(Date {
+                year: self.read_u16()?,
+                month: self.read_u8()?,
+                day: self.read_u8()?,
+                week: self.read_u8()?,
+            }),
+            DT_TIME => AxdrData::Time(Time {
+                hour: self.read_u8()?,
+                minute: self.read_u8()?,
+                second: self.read_u8()?,
+            }),
+            DT_DATE_TIME_S => AxdrData::DateTimeS(self.read_date_time_s()?),
+            DT_OI => AxdrData::Oi(self.read_u16()?),
+            DT_OAD => AxdrData::Oad(self.read_u32()?),
+            DT_ROAD => AxdrData::Road(self.read_road()?),
+            DT_OMD => AxdrData::Omd(self.read_u32()?),
+            DT_TI => AxdrData::Ti(self.read_ti()?),
+            DT_TSA => AxdrData::Tsa(self.read_tsa()?),
+            DT_MAC => AxdrData::Mac(self.read_octet_string()?.to_vec()),
+            DT_RN => AxdrData::Rn(self.read_octet_string()?.to_vec()),
+            DT_REGION => AxdrData::Region(self.read_region()?),
+            DT_SCALER_UNIT => AxdrData::ScalerUnit(ScalerUnit {
+                scaler: self.read_u8()? as i8,
+                unit: self.read_u8()?,
+            }),
+            DT_RSD => AxdrData::Rsd(self.read_rsd()?),
+            DT_CSD => AxdrData::Csd(self.read_csd()?),
+            DT_MS => AxdrData::Ms(self.read_ms()?),
+            DT_SID => AxdrData::Sid(self.read_sid()?),
+            DT_SID_MAC => AxdrData::SidMac(self.read_sid_mac()?),
+            DT_COMDCB => AxdrData::Comdcb(self.read_comdcb()?),
+            DT_RCSD => AxdrData::Rcsd(self.read_rcsd()?),
+            _ => return Err(format!("未知的数据类型{:02X}", tag)),
+        })
+    }
+
+    pub fn read_bit_string(&mut self) -> Result<BitString, String> {
+        let bits = self.read_length()?;
+        let data = self.take(bits.div_ceil(8))?.to_vec();
+        Ok(BitString { bits, data })
+    }
+
+    pub fn read_date_time(&mut self) -> Result<DateTime, String> {
+        Ok(DateTime {
+            year: self.read_u16()?,
+            month: self.read_u8()?,
+            day: self.read_u8()?,
+            week: self.read_u8()?,
+            hour: self.read_u8()?,
+            minute: self.read_u8()?,
+            second: self.read_u8()?,
+            millisecond: self.read_u16()?,
+        })
+    }
+
+    pub fn read_date_time_s(&mut self) -> Result<DateTimeS, String> {
+        Ok(DateTimeS {
+            year: self.read_u16()?,
+            month: self.read_u8()?,
+            day: self.read_u8()?,
+            hour: self.read_u8()?,
+            minute: self.read_u8()?,
+            second: self.read_u8()?,
+        })
+    }
+
+    pub fn read_ti(&mut self) -> Result<Ti, String> {
+        Ok(Ti {
+            unit: self.read_u8()?,
+            interval: self.read_u16()?,
+        })
+    }
+
+    pub fn read_tsa(&mut self) -> Result<Tsa, String> {
+        Ok(Tsa(self.read_octet_string()?.to_vec()))
+    }
+
+    pub fn read_region(&mut self) -> Result<Region, String> {
+        Ok(Region {
+            kind: self.read_u8()?,
+            start: Box::new(self.read_data()?),
+            end: Box::new(self.read_data()?),
+        })
+    }
+
+    pub fn read_road(&mut self) -> Result<Road, String> {
+        let oad = self.read_u32()?;
+        let count = self.read_length()?;
+        let mut oads = Vec::new();
+        for _ in 0..count {
+            oads.push(self.read_u32()?);
+        }
+        Ok(Road { oad, oads })
+    }
+
+    pub fn read_csd(&mut self) -> Result<Csd, String> {
+        match self.read_u8()? {
+            0 => Ok(Csd::Oad(self.read_u32()?)),
+            1 => Ok(Csd::Road(self.read_road()?)),
+            kind => Err(format!("CSD类型{}无效", kind)),
+        }
+    }
+
+    pub fn read_rcsd(&mut self) -> Result<Vec<Csd>, String> {
+        let count = self.read_length()?;
+        let mut columns = Vec::new();
+        for _ in 0..count {
+            columns.push(self.read_csd()?);
+        }
+        Ok(columns)
+    }
+
+    fn read_selector2(&mut self) -> Result<Selector2, String> {
+        Ok(Selector2 {
+            oad: self.read_u32()?,
+            start: Box::new(self.read_data()?),
+            end: Box::new(self.read_data()?),
+            interval: Box::new(self.read_data()?),
+        })
+    }
+
+    fn read_time_range(&mut self) -> Result<TimeRange, String> {
+        Ok(TimeRange {
+            start: self.read_date_time_s()?,
+            end: self.read_date_time_s()?,
+            interval: self.read_ti()?,
+            ms: self.read_ms()?,
+        })
+    }
+
+    pub fn read_rsd(&mut self) -> Result<Rsd, String> {
+        Ok(match self.read_u8()? {
+            0 => Rsd::None,
+            1 => Rsd::Selector1 {
+                oad: self.read_u32()?,
+                value: Box::new(self.read_data()?),
+            },
+            2 => Rsd::Selector2(self.read_selector2()?),
+            3 => {
+                let count = self.read_length()?;
+                let mut selectors = Vec::new();
+                for _ in 0..count {
+                    selectors.push(self.read_selector2()?);
+                }
+                Rsd::Selector3(selectors)
+            }
+            4 => Rsd::Selector4 {
+                time: self.read_date_time_s()?,
+                ms: self.read_ms()?,
+            },
+            5 => Rsd::Selector5 {
+                time: self.read_date_time_s()?,
+                ms: self.read_ms()?,
+            },
+            6 => Rsd::Selector6(self.read_time_range()?),
+            7 => Rsd::Selector7(self.read_time_range()?),
+            8 => Rsd::Selector8(self.read_time_range()?),
+            9 => Rsd::Selector9(self.read_u8()?),
+            10 => Rsd::Selector10 {
+                count: self.read_u8()?,
+                ms: self.read_ms()?,
+            },
+            selector => return Err(format!("行选择方法{}无效", selector)),
+        })
+    }
+
+    pub fn read_ms(&mut self) -> Result<Ms, String> {
+        let kind = self.read_u8()?;
+        if kind > 7 {
+            return Err(format!("电能表集合类型{}无效", kind));
+        }
+        if kind < 2 {
+            return Ok(if kind == 0 { Ms::None } else { Ms::All });
+        }
+        let count = self.read_length()?;
+        Ok(match kind {
+            2 => Ms::UserTypes(self.take(count)?.to_vec()),
+            3 => Ms::Addresses(
+                (0..count)
+                    .map(|_| self.read_tsa())
+                    .collect::<Result<_, _>>()?,
+            ),
+            4 => Ms::Numbers(
+                (0..count)
+                    .map(|_| self.read_u16())
+                    .collect::<Result<_, _>>()?,
+            ),
+            _ => {
+                let regions = (0..count)
+                    .map(|_| self.read_region())
+                    .collect::<Result<_, _>>()?;
+                match kind {
+                    5 => Ms::UserTypeRegions(regions),
+                    6 => Ms::AddressRegions(regions),
+                    _ => Ms::NumberRegions(regions),
+                }
+            }
+        })
+    }
+
+    pub fn read_sid(&mut self) -> Result<Sid, String> {
+        Ok(Sid {
+            ident: self.read_u32()?,
+            data: self.read_octet_string()?.to_vec(),
+        })
+    }
+
+    pub fn read_sid_mac(&mut self) -> Result<SidMac, String> {
+        Ok(SidMac {
+            sid: self.read_sid()?,
+            mac: self.read_octet_string()?.to_vec(),
+        })
+    }
+
+    pub fn read_comdcb(&mut self) -> Result<Comdcb, String> {
+        let data = self.take(5)?;
+        Ok(Comdcb {
+            baud: data[0],
+            parity: data[1],
+            data_bits: data[2],
+            stop_bits: data[3],
+            flow: data[4],
+        })
+    }
+}
+
+impl AxdrData {
+    /// 解析一个带类型标签的Data，返回数据及占用的字节数
+    pub fn decode(data: &[u8]) -> Result<(Self, usize), String> {
+        let mut reader = AxdrReader::new(data);
+        let value = reader.read_data()?;
+        Ok((value, reader.position()))
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut buf = Vec::new();
+        self.encode(&mut buf);
+        buf
+    }
+
+    pub fn tag(&self) -> u8 {
+        match self {
+            AxdrData::Null => DT_NULL,
+            AxdrData::Array(_) => DT_ARRAY,
+            AxdrData::Structure(_) => DT_STRUCTURE,
+            AxdrData::Bool(_) => DT_BOOL,
+            AxdrData::BitString(_) => DT_BIT_STRING,
+            AxdrData::DoubleLong(_) => DT_DOUBLE_LONG,
+            AxdrData::DoubleLongUnsigned(_) => DT_DOUBLE_LONG_UNSIGNED,
+            AxdrData::OctetString(_) => DT_OCTET_STRING,
+            AxdrData::VisibleString(_) => DT_VISIBLE_STRING,
+            AxdrData::Utf8String(_) => DT_UTF8_STRING,
+            AxdrData::Integer(_) => DT_INTEGER,
+            AxdrData::Long(_) => DT_LONG,
+            AxdrData::Unsigned(_) => DT_UNSIGNED,
+            AxdrData::LongUnsigned(_) => DT_LONG_UNSIGNED,
+            AxdrData::Long64(_) => DT_LONG64,
+            AxdrData::Long64Unsigned(_) => DT_LONG64_UNSIGNED,
+            AxdrData::Enum(_) => DT_ENUM,
+            AxdrData::Float32(_) => DT_FLOAT32,
+            AxdrData::Float64(_) => DT_FLOAT64,
+            AxdrData::DateTime(_) => DT_DATE_TIME,
+            AxdrData::Date(_) => DT_DATE,
+            AxdrData::Time(_) => DT_TIME,
+            AxdrData::DateTimeS(_) => DT_DATE_TIME_S,
+            AxdrData::Oi(_) => DT_OI,
+            AxdrData::Oad(_) => DT_OAD,
+            AxdrData::Road(_) => DT_ROAD,
+            AxdrData::Omd(_) => DT_OMD,
+            AxdrData::Ti(_) => DT_TI,
+            AxdrData::Tsa(_) => DT_TSA,
+            AxdrData::Mac(_) => DT_MAC,
+            AxdrData::Rn(_) => DT_RN,
+            AxdrData::Region(_) => DT_REGION,
+            AxdrData::ScalerUnit(_) => DT_SCALER_UNIT,
+            AxdrData::Rsd(_) => DT_RSD,
+            AxdrData::Csd(_) => DT_CSD,
+            AxdrData::Ms(_) => DT_MS,
+            AxdrData::Sid(_) => DT_SID,
+            AxdrData::SidMac(_) => DT_SID_MAC,
+            AxdrData::Comdcb(_) => DT_COMDCB,
+            AxdrData::Rcsd(_) => DT_RCSD,
+        }
+    }
+
+    /// 整数类型的值，用于换算
+    pub fn integer(&self) -> Option<i128> {
+        match self {
+            AxdrData::DoubleLong(value) => Some(*value as i128),
+            AxdrData::DoubleLongUnsigned(value) => Some(*value as i128),
+            AxdrData::Integer(value) => Some(*value as i128),
+            AxdrData::Long(value) => Some(*value as i128),
+            AxdrData::Unsigned(value) => Some(*value as i128),
+            AxdrData::LongUnsigned(value) => Some(*value as i128),
+            AxdrData::Long64(value) => Some(*value as i128),
+            AxdrData::Long64Unsigned(value) => Some(*value as i128),
+            _ => None,
+        }
+    }
+
+    /// 编码类型标签和数据内容
+    pub fn encode(&self, buf: &mut Vec<u8>) {
+        buf.push(self.tag());
+        self.encode_value(buf);
+    }
+
+    /// 只编码数据内容
+    pub fn encode_value(&self, buf: &mut Vec<u8>) {
+        match self {
+            AxdrData::Null => {}
+            AxdrData::Array(items) | AxdrData::Structure(items) => {
+                Axdr::encode_sequence(items, buf, AxdrData::encode)
+            }
+            AxdrData::Bool(value) => buf.push(*value as u8),
+            AxdrData::BitString(value) => {
+                Axdr::encode_length(value.bits, buf);
+                buf.extend_from_slice(&value.data);
+            }
+            AxdrData::DoubleLong(value) => buf.extend_from_slice(&value.to_be_bytes()),
+            AxdrData::DoubleLongUnsigned(value) => buf.extend_from_slice(&value.to_be_bytes()),
+            AxdrData::OctetString(data)
+            | AxdrData::VisibleString(data)
+            | AxdrData::Mac(data)
+            | AxdrData::Rn(data) => Axdr::encode_octet_string(data, buf),
+            AxdrData::Utf8String(value) => Axdr::encode_octet_string(value.as_bytes(), buf),
+            AxdrData::Integer(value) => buf.push(*value as u8),
+            AxdrData::Long(value) => buf.extend_from_slice(&value.to_be_bytes()),
+            AxdrData::Unsigned(value) | AxdrData::Enum(value) => buf.push(*value),
+            AxdrData::LongUnsigned(value) | AxdrData::Oi(value) => {
+                buf.extend_from_slice(&value.to_be_bytes())
+            }
+            AxdrData::Long64(value) => buf.extend_from_slice(&value.to_be_bytes()),
+            AxdrData::Long64Unsigned(value) => buf.extend_from_slice(&value.to_be_bytes()),
+            AxdrData::Float32(value) => buf.extend_from_slice(&value.to_be_bytes()),
+            AxdrData::Float64(value) => buf.extend_from_slice(&value.to_be_bytes()),
+            AxdrData::DateTime(value) => value.encode(buf),
+            AxdrData::Date(value) => {
+                buf.extend_from_slice(&value.year.to_be_bytes());
+                buf.extend_from_slice(&[value.month, value.day, value.week]);
+            }
+            AxdrData::Time(value) => {
+                buf.extend_from_slice(&[value.hour, value.minute, value.second])
+            }
+            AxdrData::DateTimeS(value) => value.encode(buf),
+            AxdrData::Oad(value) | AxdrData::Omd(value) => {
+                buf.extend_from_slice(&value.to_be_bytes())
+            }
+            AxdrData::Road(value) => value.encode(buf),
+            AxdrData::Ti(value) => value.encode(buf),
+            AxdrData::Tsa(value) => value.encode(buf),
+            AxdrData::Region(value) => value.encode(buf),
+            AxdrData::ScalerUnit(value) => buf.extend_from_slice(&[value.scaler as u8, value.unit]),
+            AxdrData::Rsd(value) => value.encode(buf),
+            AxdrData::Csd(value) => value.encode(buf),
+            AxdrData::Ms(value) => value.encode(buf),
+            AxdrData::Sid(value) => value.encode(buf),
+            AxdrData::SidMac(value) => {
+                value.sid.encode(buf);
+                Axdr::encode_octet_string(&value.mac, buf);
+            }
+            AxdrData::Comdcb(value) => value.encode(buf),
+            AxdrData::Rcsd(columns) => Axdr::encode_sequence(columns, buf, Csd::encode),
+        }
+    }
+}
+
+impl DateTime {
+    pub fn encode(&self, buf: &mut Vec<u8>) {
+        buf.extend_from_slice(&self.year.to_be_bytes());
+        buf.extend_from_slice(&[
+            self.month,
+            self.day,
+            self.week,
+            self.hour,
+            self.minute,
+            self.second,
+        ]);
+        buf.extend_from_slice(&self.millisecond.to_be_bytes());
+    }
+}
+
+impl DateTimeS {
+    pub fn encode(&self, buf: &mut Vec<u8>) {
+        buf.extend_from_slice(&self.year.to_be_bytes());
+        buf.extend_from_slice(&[self.month, self.day, self.hour, self.minute, self.second]);
+    }
+}
+
+impl Ti {
+    pub fn encode(&self, buf: &mut Vec<u8>) {
+        buf.push(self.unit);
+        buf.extend_from_slice(&self.interval.to_be_bytes());
+    }
+
+    pub fn unit_name(&self) -> &'static str {
+        match self.unit {
+            0 => "秒",
+            1 => "分",
+            2 => "时",
+            3 => "日",
+            4 => "月",
+            5 => "年",
+            _ => "未知单位",
+        }
+    }
+}
+
+impl Tsa {
+    /// 按地址字符串生成单地址的TSA，地址按传输顺序
+    pub fn from_address(address: &[u8]) -> Self {
+        let mut data = vec![(address.len().max(1) - 1) as u8 & 0x0F];
+        data.extend_from_slice(address);
+        Tsa(data)
+    }
+
+    pub fn address(&self) -> &[u8] {
+        match self.0.first() {
+            Some(flag) => {
+                let length = ((flag & 0x0F) as usize + 1).min(self.0.len() - 1);
+                &self.0[1..1 + length]
+            }
+            None => &[],
+        }
+    }
+
+    pub fn encode(&self, buf: &mut Vec<u8>) {
+        Axdr::encode_octet_string(&self.0, buf);
+    }
+}
+
+impl Region {
+    pub fn kind_name(kind: u8) -> &'static str {
+        match kind {
+            0 => "前闭后开",
+            1 => "前开后闭",
+            2 => "前闭后闭",
+            3 => "前开后开",
+            _ => "未知",
+        }
+    }
+
+    pub fn encode(&self, buf: &mut Vec<u8>) {
+        buf.push(self.kind);
+        self.start.encode(buf);
+        self.end.encode(buf);
+    }
+}
+
+impl Road {
+    pub fn encode(&self, buf: &mut Vec<u8>) {
+        buf.extend_from_slice(&self.oad.to_be_bytes());
+        Axdr::encode_sequence(&self.oads, buf, |oad, buf| {
+            buf.extend_from_slice(&oad.to_be_bytes())
+        });
+    }
+}
+
+impl Csd {
+    pub fn oad(&self) -> u32 {
+        match self {
+            Csd::Oad(oad) => *oad,
+            Csd::Road(road) => road.oad,
+        }
+    }
+
+    pub fn encode(&self, buf: &mut Vec<u8>) {
+        match self {
+            Csd::Oad(oad) => {
+                buf.push(0);
+                buf.extend_from_slice(&oad.to_be_bytes());
+            }
+            Csd::Road(road) => {
+                buf.push(1);
+                road.encode(buf);
+            }
+        }
+    }
+}
+
+impl Selector2 {
+    fn encode(&self, buf: &mut Vec<u8>) {
+        buf.extend_from_slice(&self.oad.to_be_bytes());
+        self.start.encode(buf);
+        self.end.encode(buf);
+        self.interval.encode(buf);
+    }
+}
+
+impl TimeRange {
+    fn encode(&self, buf: &mut Vec<u8>) {
+        self.start.encode(buf);
+        self.end.encode(buf);
+        self.interval.encode(buf);
+        self.ms.encode(buf);
+    }
+}
+
+impl Rsd {
+    pub fn selector(&self) -> u8 {
+        match self {
+            Rsd::None => 0,
+            Rsd::Selector1 { .. } => 1,
+            Rsd::Selector2(_) => 2,
+            Rsd::Selector3(_) => 3,
+            Rsd::Selector4 { .. } => 4,
+            Rsd::Selector5 { .. } => 5,
+            Rsd::Selector6(_) => 6,
+            Rsd::Selector7(_) => 7,
+            Rsd::Selector8(_) => 8,
+            Rsd::Selector9(_) => 9,
+            Rsd::Selector10 { .. } => 10,
+        }
+    }
+
+    pub fn selector_name(selector: u8) -> &'static str {
+        match selector {
+            0 => "不选择",
+            1 => "选择方法1：指定对象指定值",
+            2 => "选择方法2：指定对象区间内连续间隔值",
+            3 => "选择方法3：多个指定对象连续间隔值",
+            4 => "选择方法4：指定电能表集合、指定采集启动时间",
+            5 => "选择方法5：指定电能表集合、指定采集存储时间",
+            6 => "选择方法6：指定电能表集合、指定采集启动时间区间内连续间隔值",
+            7 => "选择方法7：指定电能表集合、指定采集存储时间区间内连续间隔值",
+            8 => "选择方法8：指定电能表集合、指定采集到时间区间内连续间隔值",
+            9 => "选择方法9：指定选取上第n次记录",
+            10 => "选择方法10：指定选取最新的n条记录",
+            _ => "未知选择方法",
+        }
+    }
+
+    pub fn encode(&self, buf: &mut Vec<u8>) {
+        buf.push(self.selector());
+        match self {
+            Rsd::None => {}
+            Rsd::Selector1 { oad, value } => {
+                buf.extend_from_slice(&oad.to_be_bytes());
+                value.encode(buf);
+            }
+            Rsd::Selector2(selector) => selector.encode(buf),
+            Rsd::Selector3(selectors) => Axdr::encode_sequence(selectors, buf, Selector2::encode),
+            Rsd::Selector4 { time, ms } | Rsd::Selector5 { time, ms } => {
+                time.encode(buf);
+                ms.encode(buf);
+            }
+            Rsd::Selector6(range) | Rsd::Selector7(range) | Rsd::Selector8(range) => {
+                range.encode(buf)
+            }
+            Rsd::Selector9(last) => buf.push(*last),
+            Rsd::Selector10 { count, ms } => {
+                buf.push(*count);
+                ms.encode(buf);
+            }
+        }
+    }
+}
+
+impl Ms {
+    pub fn kind(&self) -> u8 {
+        match self {
+            Ms::None => 0,
+            Ms::All => 1,
+            Ms::UserTypes(_) => 2,
+            Ms::Addresses(_) => 3,
+            Ms::Numbers(_) => 4,
+            Ms::UserTypeRegions(_) => 5,
+            Ms::AddressRegions(_) => 6,
+            Ms::NumberRegions(_) => 7,
+        }
+    }
+
+    pub fn kind_name(kind: u8) -> &'static str {
+        match kind {
+            0 => "无电能表",
+            1 => "全部用户地址",
+            2 => "一组用户类型",
+            3 => "一组用户地址",
+            4 => "一组配置序号",
+            5 => "一组用户类型区间",
+            6 => "一组用户地址区间",
+            7 => "一组配置序号区间",
+            _ => "未知的电能表集合",
+        }
+    }
+
+    pub fn encode(&self, buf: &mut Vec<u8>) {
+        buf.push(self.kind());
+        match self {
+            Ms::None | Ms::All => {}
+            Ms::UserTypes(types) => Axdr::encode_octet_string(types, buf),
+            Ms::Addresses(addresses) => Axdr::encode_sequence(addresses, buf, Tsa::encode),
+            Ms::Numbers(numbers) => Axdr::encode_sequence(numbers, buf, |number, buf| {
+                buf.extend_from_slice(&number.to_be_bytes())
+            }),
+            Ms::UserTypeRegions(regions)
+            | Ms::AddressRegions(regions)
+            | Ms::NumberRegions(regions) => Axdr::encode_sequence(regions, buf, Region::encode),
+        }
+    }
+}
+
+impl Sid {
+    pub fn encode(&self, buf: &mut Vec<u8>) {
+        buf.extend_from_slice(&self.ident.to_be_bytes());
+        Axdr::encode_octet_string(&self.data, buf);
+    }
+}
+
+impl Comdcb {
+    pub fn encode(&self, buf: &mut Vec<u8>) {
+        buf.extend_from_slice(&[
+            self.baud,
+            self.parity,
+            self.data_bits,
+            self.stop_bits,
+            self.flow,
+        ]);
+    }
+}
+
+impl fmt::Display for AxdrData {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AxdrData::Null => write!(f, "空"),
+            AxdrData::Array(items) => write!(f, "array[{}]", items.len()),
+            AxdrData::Structure(items) => write!(f, "structure[{}]", items.len()),
+            AxdrData::Bool(value) => write!(f, "{}", value),
+            AxdrData::BitString(value) => write!(f, "{}", value),
+            AxdrData::OctetString(data) | AxdrData::Mac(data) | AxdrData::Rn(data) => {
+                write!(f, "{}", FrameFun::get_data_str_order(data))
+            }
+            AxdrData::VisibleString(data) => write!(f, "{}", FrameFun::ascii_to_str(data)),
+            AxdrData::Utf8String(value) => write!(f, "{}", value),
+            AxdrData::Float32(value) => write!(f, "{}", value),
+            AxdrData::Float64(value) => write!(f, "{}", value),
+            AxdrData::Enum(value) => write!(f, "{}", value),
+            AxdrData::DateTime(value) => write!(f, "{}", value),
+            AxdrData::Date(value) => write!(
+                f,
+                "{}-{}-{} 星期{}",
+                Axdr::time_part(value.year, 4),
+                Axdr::time_part(value.month as u16, 2),
+                Axdr::time_part(value.day as u16, 2),
+                Axdr::time_part(value.week as u16, 1)
+            ),
+            AxdrData::Time(value) => write!(
+                f,
+                "{}:{}:{}",
+                Axdr::time_part(value.hour as u16, 2),
+                Axdr::time_part(value.minute as u16, 2),
+                Axdr::time_part(value.second as u16, 2)
+            ),
+            AxdrData::DateTimeS(value) => write!(f, "{}", value),
+            AxdrData::Oi(value) => write!(f, "{:04X}", value),
+            AxdrData::Oad(value) | AxdrData::Omd(value) => write!(f, "{:08X}", value),
+            AxdrData::Road(value) => write!(f, "{:08X}", value.oad),
+            AxdrData::Ti(value) => write!(f, "{}", value),
+            AxdrData::Tsa(value) => write!(f, "{}", value),
+            AxdrData::Region(value) => write!(f, "{}", value),
+            AxdrData::ScalerUnit(value) => write!(f, "换算{}，单位{}", value.scaler, value.unit),
+            AxdrData::Rsd(value) => write!(f, "{}", Rsd::selector_name(value.selector())),
+            AxdrData::Csd(value) => write!(f, "{:08X}", value.oad()),
+            AxdrData::Ms(value) => write!(f, "{}", value),
+            AxdrData::Sid(value) => write!(f, "{:08X}", value.ident),
+            AxdrData::SidMac(value) => write!(
+                f,
+                "{:08X}，MAC：{}",
+                value.sid.ident,
+                FrameFun::get_data_str_order(&value.mac)
+            ),
+            AxdrData::Comdcb(value) => write!(f, "{}", value),
+            AxdrData::Rcsd(columns) => write!(f, "共{}列", columns.len()),
+            _ => match self.integer() {
+                Some(value) => write!(f, "{}", value),
+                None => Ok(()),
+            },
+        }
+    }
+}
+
+impl fmt::Display for BitString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let bits: String = self
+            .data
+            .iter()
+            .flat_map(|byte| (0..8).rev().map(move |bit| (byte >> bit) & 0x01))
+            .take(self.bits)
+            .map(|bit| char::from(b'0' + bit))
+            .collect();
+        write!(f, "{}", bits)
+    }
+}
+
+impl fmt::Display for DateTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}-{}-{} {}:{}:{}.{} 星期{}",
+            Axdr::time_part(self.year, 4),
+            Axdr::time_part(self.month as u16, 2),
+            Axdr::time_part(self.day as u16, 2),
+            Axdr::time_part(self.hour as u16, 2),
+            Axdr::time_part(self.minute as u16, 2),
+            Axdr::time_part(self.second as u16, 2),
+            if self.millisecond == 0xFFFF {
+                "FFFF".to_string()
+            } else {
+                format!("{:03}", self.millisecond)
+            },
+            Axdr::time_part(self.week as u16, 1)
+        )
+    }
+}
+
+impl fmt::Display for DateTimeS {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}-{}-{} {}:{}:{}",
+            Axdr::time_part(self.year, 4),
+            Axdr::time_part(self.month as u16, 2),
+            Axdr::time_part(self.day as u16, 2),
+            Axdr::time_part(self.hour as u16, 2),
+            Axdr::time_part(self.minute as u16, 2),
+            Axdr::time_part(self.second as u16, 2)
+        )
+    }
+}
+
+impl fmt::Display for Ti {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.interval, self.unit_name())
+    }
+}
+
+impl fmt::Display for Tsa {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.0.is_empty() {
+            return write!(f, "空");
+        }
+        write!(f, "{}", FrameFun::get_data_str_order(self.address()))
+    }
+}
+
+impl fmt::Display for Region {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}：{}~{}",
+            Region::kind_name(self.kind),
+            self.start,
+            self.end
+        )
+    }
+}
+
+impl fmt::Display for Ms {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let count = match self {
+            Ms::None | Ms::All => return write!(f, "{}", Ms::kind_name(self.kind())),
+            Ms::UserTypes(types) => types.len(),
+            Ms::Addresses(addresses) => addresses.len(),
+            Ms::Numbers(numbers) => numbers.len(),
+            Ms::UserTypeRegions(regions)
+            | Ms::AddressRegions(regions)
+            | Ms::NumberRegions(regions) => regions.len(),
+        };
+        write!(f, "{}，共{}项", Ms::kind_name(self.kind()), count)
+    }
+}
+
+impl fmt::Display for Comdcb {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let baud = match self.baud {
+            0 => "300bps",
+            1 => "600bps",
+            2 => "1200bps",
+            3 => "2400bps",
+            4 => "4800bps",
+            5 => "7200bps",
+            6 => "9600bps",
+            7 => "19200bps",
+            8 => "38400bps",
+            9 => "57600bps",
+            10 => "115200bps",
+            255 => "自适应",
+            _ => "未知波特率",
+        };
+        let parity = match self.parity {
+            0 => "无校验",
+            1 => "奇校验",
+            2 => "偶校验",
+            _ => "未知校验",
+        };
+        let flow = match self.flow {
+            0 => "无流控",
+            1 => "硬件流控",
+            2 => "软件流控",
+            _ => "未知流控",
+        };
+        write!(
+            f,
+            "{}，{}，{}位数据位，{}位停止位，{}",
+            baud, parity, self.data_bits, self.stop_bits, flow
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn structure_roundtrip() {
+        let value = AxdrData::Structure(vec![
+            AxdrData::Oad(0x40010200),
+            AxdrData::Array(vec![AxdrData::DoubleLong(-5), AxdrData::LongUnsigned(2201)]),
+            AxdrData::DateTimeS(DateTimeS {
+                year: 2024,
+                month: 1,
+                day: 2,
+                hour: 3,
+                minute: 4,
+                second: 5,
+            }),
+            AxdrData::Tsa(Tsa::from_address(&[0, 0, 0, 0, 0, 1])),
+            AxdrData::Rsd(Rsd::Selector10 {
+                count: 1,
+                ms: Ms::Addresses(vec![Tsa::from_address(&[1, 2, 3, 4, 5, 6])]),
+            }),
+            AxdrData::Rcsd(vec![
+                Csd::Oad(0x20210200),
+                Csd::Road(Road {
+                    oad: 0x50040200,
+                    oads: vec![0x00100200],
+                }),
+            ]),
+            AxdrData::Region(Region {
+                kind: 2,
+                start: Box::new(AxdrData::Unsigned(1)),
+                end: Box::new(AxdrData::Unsigned(5)),
+            }),
+            AxdrData::BitString(BitString {
+                bits: 10,
+                data: vec![0xA5, 0xC0],
+            }),
+            AxdrData::Ti(Ti {
+                unit: 1,
+                interval: 15,
+            }),
+        ]);
+        let bytes = value.to_bytes();
+        let (decoded, used) = AxdrData::decode(&bytes).unwrap();
+        assert_eq!(decoded, value);
+        assert_eq!(used, bytes.len());
+    }
+
+    #[test]
+    fn long_form_length() {
+        for (length, expected) in [
+            (0x7F, vec![0x7F]),
+            (0x80, vec![0x81, 0x80]),
+            (300, vec![0x82, 0x01, 0x2C]),
+            (70000, vec![0x83, 0x01, 0x11, 0x70]),
+        ] {
+            let mut buf = vec![];
+            Axdr::encode_length(length, &mut buf);
+            assert_eq!(buf, expected);
+            let mut reader = AxdrReader::new(&buf);
+            assert_eq!(reader.read_length().unwrap(), length);
+            assert!(reader.remaining().is_empty());
+        }
+
+        let value = AxdrData::OctetString(vec![0xAA; 200]);
+        let bytes = value.to_bytes();
+        assert_eq!(&bytes[..3], &[DT_OCTET_STRING, 0x81, 0xC8]);
+        assert_eq!(AxdrData::decode(&bytes).unwrap(), (value, bytes.len()));
+    }
+
+    #[test]
+    fn invalid_length() {
+        // 长度域字节数为0或超过4字节均无效
+        assert!(AxdrReader::new(&[0x80]).read_length().is_err());
+        assert!(AxdrReader::new(&[0x85, 1, 2, 3, 4, 5])
+            .read_length()
+            .is_err());
+        // 声明的长度超过剩余数据
+        assert!(AxdrData::decode(&[DT_OCTET_STRING, 0x82, 0x01, 0x00, 0xAA]).is_err());
+    }
+
+    #[test]
+    fn oversized_array_count() {
+        // 数组声明0xFFFFFFFF个元素，实际只有一个
+        let data = [DT_ARRAY, 0x84, 0xFF, 0xFF, 0xFF, 0xFF, DT_UNSIGNED, 0x01];
+        assert!(AxdrData::decode(&data).is_err());
+        let data = [DT_STRUCTURE, 0x03, DT_UNSIGNED, 0x01];
+        assert!(AxdrData::decode(&data).is_err());
+    }
+}
@@ -0,0 +1,1288 @@
+use crate::analyzer::Analyzer;
+use crate::basefunc::csg_security::CsgSecurity;
+use crate::basefunc::frame_csg::FrameCsg;
+use crate::basefunc::frame_fun::FrameFun;
+use crate::basefunc::protocol::FrameAnalisyic;
+use crate::config::appconfig::{Config, ConfigStore};
+use crate::config::xmlconfig::XmlElement;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::error::Error;
+
+// 信息体之前：68 L L L L 68 C A(7) AFN SEQ
+const BODY_START: usize = 16;
+const DA_LENGTH: usize = 2;
+const DI_LENGTH: usize = 4;
+const PW_LENGTH: usize = 16;
+const TP_LENGTH: usize = 5;
+const TIME_RANGE_LENGTH: usize = 12;
+// 读历史数据的数据时间CCYYMMDDhhmm
+const DATA_TIME_LENGTH: usize = 6;
+// 读任务数据的数据时间YYMMDDhhmm
+const TASK_TIME_LENGTH: usize = 5;
+// 任务DA+DI+数据结构方式+1字节数据组数
+const TASK_HEAD_LENGTH: usize = DA_LENGTH + DI_LENGTH + 2;
+// 读历史数据应答每组数据标识后的数据时间个数，只在带该字节的地区配置
+const TIME_COUNT_ITEM: &str = "HISTORY_TIME_COUNT";
+
+const PW_DESCRIPTION: &str = "PW由16个字节组成，是由主站按系统约定的认证算法产生，并在主站发送的报文中下发给终端，由终端进行校验认证。";
+
+/// 信息体中每组DA+DI之后跟随的内容
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CsgItemBody {
+    /// 查询，只有配置中以dir属性单独定义了本方向的数据标识才带数据
+    Query,
+    /// 按配置长度解析的数据
+    Data,
+    /// 1字节的错误码
+    ErrCode,
+    /// 数据起始时间+数据结束时间
+    TimeRange,
+    /// 数据起始时间+数据结束时间+数据密度
+    TimeRangeDensity,
+    /// 按配置长度解析的数据+数据时间，地区配置了数据时间个数时为个数+多组数据和数据时间
+    DataTime,
+    /// 只有数据时间
+    Time,
+    /// 任务头之后每组为按配置长度解析的数据+5字节数据时间，末尾可带任务数据时间
+    TaskData,
+}
+
+/// AFN在各传输方向上的信息体结构
+#[derive(Clone, Copy, Debug)]
+pub struct CsgAfnLayout {
+    /// 下行(DIR=0)
+    pub down: CsgItemBody,
+    /// 上行应答(DIR=1,PRM=0)
+    pub response: CsgItemBody,
+    /// 上行主动上报(DIR=1,PRM=1)
+    pub report: CsgItemBody,
+    /// 数据标识先按"AFN+DI"查找，供厂家为自定义AFN单独配置数据标识
+    pub afn_items: bool,
+    /// 错误码的名称，如"写参数返回结果"
+    pub err_name: &'static str,
+    /// 安全认证数据内容按终端密钥校验MAC、解密密文
+    pub security: bool,
+}
+
+impl CsgAfnLayout {
+    const fn new(down: CsgItemBody, response: CsgItemBody, report: CsgItemBody) -> Self {
+        CsgAfnLayout {
+            down,
+            response,
+            report,
+            afn_items: false,
+            err_name: "返回结果",
+            security: false,
+        }
+    }
+
+    pub fn body(&self, dir: u8, prm: u8) -> CsgItemBody {
+        match (dir, prm) {
+            (0, _) => self.down,
+            (_, 0) => self.response,
+            _ => self.report,
+        }
+    }
+}
+
+/// 终端报文是否带消息验证码PW，上下行使用同一设置
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CsgPwMode {
+    /// 按报文结构判断，无法确定时两种方式同时给出
+    #[default]
+    Auto,
+    Present,
+    Absent,
+}
+
+impl CsgPwMode {
+    pub fn parse(mode: &str) -> Option<Self> {
+        match mode.trim().to_lowercase().as_str() {
+            "auto" => Some(CsgPwMode::Auto),
+            "present" => Some(CsgPwMode::Present),
+            "absent" => Some(CsgPwMode::Absent),
+            _ => None,
+        }
+    }
+
+    /// 配置确定时直接采用，auto时使用推测结果
+    pub fn resolve(self, guess: impl FnOnce() -> bool) -> bool {
+        match self {
+            CsgPwMode::Auto => guess(),
+            CsgPwMode::Present => true,
+            CsgPwMode::Absent => false,
+        }
+    }
+}
+
+/// 各终端报文是否带PW的设置，终端按逻辑地址匹配，未单独设置的终端取default
+#[derive(Clone, Debug, Default)]
+pub struct CsgPwPolicy {
+    pub default: CsgPwMode,
+    pub terminals: HashMap<String, CsgPwMode>,
+}
+
+impl CsgPwPolicy {
+    /// 按store中的应用配置protocolsetting.csgpw生成，未配置时为auto
+    ///
+    /// 配置形如`{"default": "auto", "terminals": {"124400005634": "present"}}`，
+    /// 取值为auto/present/absent，无法识别的取值视为未设置。
+    pub fn load(store: &dyn ConfigStore) -> Self {
+        Config::load(store)
+            .ok()
+            .and_then(|config| config.get_value(&["protocolsetting", "csgpw"]).cloned())
+            .map(|setting| Self::from_value(&setting))
+            .unwrap_or_default()
+    }
+
+    pub fn from_value(setting: &Value) -> Self {
+        let mode = |value: &Value| value.as_str().and_then(CsgPwMode::parse);
+        CsgPwPolicy {
+            default: setting.get("default").and_then(mode).unwrap_or_default(),
+            terminals: setting
+                .get("terminals")
+                .and_then(Value::as_object)
+                .map(|terminals| {
+                    terminals
+                        .iter()
+                        .filter_map(|(terminal, value)| Some((terminal.clone(), mode(value)?)))
+                        .collect()
+                })
+                .unwrap_or_default(),
+        }
+    }
+
+    pub fn mode(&self, terminal: &str) -> CsgPwMode {
+        self.terminals
+            .get(terminal)
+            .copied()
+            .unwrap_or(self.default)
+    }
+}
+
+enum PwLayout {
+    Without,
+    With,
+    // 无法确定是否带PW，两种方式都给出，附带原因
+    Both(&'static str),
+}
+
+use CsgItemBody::{Data, DataTime, ErrCode, Query, TaskData, Time, TimeRange, TimeRangeDensity};
+
+// 按DA+DI逐组解析的功能码，键为功能码表中的解析方式
+const CSG13_LAYOUTS: &[(&str, CsgAfnLayout)] = &[
+    ("ack", CsgAfnLayout::new(Data, Data, Data)),
+    (
+        "link",
+        CsgAfnLayout {
+            err_name: "链路接口检测返回结果",
+            ..CsgAfnLayout::new(Data, ErrCode, Data)
+        },
+    ),
+    (
+        "write",
+        CsgAfnLayout {
+            err_name: "写参数返回结果",
+            ..CsgAfnLayout::new(Data, ErrCode, Data)
+        },
+    ),
+    (
+        "security",
+        CsgAfnLayout {
+            security: true,
+            ..CsgAfnLayout::new(Query, Data, Data)
+        },
+    ),
+    ("read_param", CsgAfnLayout::new(Query, Data, Data)),
+    ("read_cur", CsgAfnLayout::new(Query, Data, Data)),
+    ("read_event", CsgAfnLayout::new(TimeRange, Data, Data)),
+    (
+        "filetrans",
+        CsgAfnLayout {
+            err_name: "文件传输返回结果",
+            ..CsgAfnLayout::new(Data, ErrCode, Data)
+        },
+    ),
+    ("relay", CsgAfnLayout::new(Data, Data, Data)),
+    ("read_alarm", CsgAfnLayout::new(TimeRange, Data, Data)),
+    ("topo", CsgAfnLayout::new(Data, Data, Data)),
+    // 级联命令和用户自定义数据的下行也带数据，未按方向单独定义的数据标识取通用定义
+    ("cascade", CsgAfnLayout::new(Data, Data, Data)),
+    // 主动上报只带数据时间
+    (
+        "read_history",
+        CsgAfnLayout::new(TimeRangeDensity, DataTime, Time),
+    ),
+    (
+        "read_task",
+        CsgAfnLayout::new(TimeRangeDensity, TaskData, TaskData),
+    ),
+    (
+        "user_defined",
+        CsgAfnLayout {
+            afn_items: true,
+            ..CsgAfnLayout::new(Data, Data, Data)
+        },
+    ),
+];
+
+/// 南网13信息体的通用解析：DA+DI(+内容)逐组解析，末尾依次为消息验证码PW和时间标签Tp
+pub struct CsgItemEngine;
+
+impl CsgItemEngine {
+    /// 解析方式对应的信息体结构
+    pub fn layout(handler: &str) -> Option<CsgAfnLayout> {
+        CSG13_LAYOUTS
+            .iter()
+            .find(|(name, _)| *name == handler)
+            .map(|(_, layout)| *layout)
+    }
+
+    /// 当前解析器实例中该终端报文是否带PW的设置
+    pub fn pw_mode(analyzer: &Analyzer, frame: &[u8]) -> CsgPwMode {
+        analyzer.csg_pw_mode(&FrameCsg::get_csg_adress(frame))
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn analysic(
+        analyzer: &Analyzer,
+        frame: &[u8],
+        dir: u8,
+        prm: u8,
+        layout: &CsgAfnLayout,
+        result_list: &mut Vec<Value>,
+        start_pos: usize,
+        protocol: &str,
+        region: &str,
+    ) -> Result<(), Box<dyn Error>> {
+        let total_length = frame.len();
+        let valid_data_segment = &frame[BODY_START..total_length - 2];
+        let tpv =
+            FrameCsg::get_afn_and_seq_result(analyzer, &frame[14..16], start_pos + 14, result_list);
+        let index = BODY_START + start_pos;
+        let tpv = tpv && valid_data_segment.len() >= TP_LENGTH;
+        let length = if tpv {
+            valid_data_segment.len() - TP_LENGTH
+        } else {
+            valid_data_segment.len()
+        };
+        let body = layout.body(dir, prm);
+        let walker = ItemWalker {
+            analyzer,
+            data: &valid_data_segment[..length],
+            index,
+            body,
+            task_head: body == TaskData,
+            time_count: (body == DataTime)
+                .then(|| {
+                    analyzer.get_template_element(TIME_COUNT_ITEM, protocol, region, Some(dir))
+                })
+                .flatten(),
+            err_name: layout.err_name,
+            item_prefix: layout.afn_items.then(|| format!("{:02X}", frame[14])),
+            terminal: layout.security.then(|| FrameCsg::get_csg_adress(frame)),
+            protocol,
+            region,
+            dir,
+        };
+        let pw_data = &valid_data_segment[length.saturating_sub(PW_LENGTH)..length];
+        let pw_node = || {
+            let mut node = Vec::new();
+            let pw_pos = vec![index + length - PW_LENGTH, index + length];
+            FrameFun::add_data(
+                &mut node,
+                "消息验证码Pw".to_string(),
+                FrameFun::get_data_str_with_space(pw_data),
+                PW_DESCRIPTION.to_string(),
+                pw_pos.clone(),
+                CsgSecurity::pw_check_result(
+                    analyzer,
+                    frame,
+                    BODY_START + length - PW_LENGTH,
+                    pw_pos,
+                ),
+                None,
+            );
+            node
+        };
+
+        let mut sub_result = match Self::pw_layout(analyzer, frame, &walker) {
+            PwLayout::Without => walker.parse(length),
+            PwLayout::With => {
+                let mut items = walker.parse(length - PW_LENGTH);
+                items.extend(pw_node());
+                items
+            }
+            PwLayout::Both(reason) => {
+                let mut with_pw = walker.parse(length - PW_LENGTH);
+                with_pw.extend(pw_node());
+                let without_pw = walker.parse(length);
+                let mut sub_result = Vec::new();
+                FrameFun::add_data(
+                    &mut sub_result,
+                    "解析方式1：含消息验证码PW".to_string(),
+                    FrameFun::get_data_str_with_space(walker.data),
+                    format!("{}，无法确定是否带PW，请核对终端配置", reason),
+                    vec![index, index + length],
+                    Some(with_pw),
+                    None,
+                );
+                FrameFun::add_data(
+                    &mut sub_result,
+                    "解析方式2：不含消息验证码PW".to_string(),
+                    FrameFun::get_data_str_with_space(walker.data),
+                    format!("{}，无法确定是否带PW，请核对终端配置", reason),
+                    vec![index, index + length],
+                    Some(without_pw),
+                    None,
+                );
+                sub_result
+            }
+        };
+        if tpv {
+            let tpv_data = &frame[total_length - 7..total_length - 2];
+            FrameFun::add_data(
+                &mut sub_result,
+                "时间标签Tp".to_string(),
+                FrameFun::get_data_str_with_space(tpv_data),
+                FrameCsg::prase_tpv_data(tpv_data),
+                vec![start_pos + total_length - 7, start_pos + total_length - 2],
+                None,
+                None,
+            );
+        }
+        FrameFun::add_data(
+            result_list,
+            "信息体".to_string(),
+            FrameFun::get_data_str_with_space(valid_data_segment),
+            "".to_string(),
+            vec![index, start_pos + total_length - 2],
+            Some(sub_result),
+            None,
+        );
+
+        Ok(())
+    }
+
+    /// 不带帧头的任务数据，逐组为DA+DI+数据+5字节数据时间
+    pub fn analysic_task_data(
+        analyzer: &Analyzer,
+        data: &[u8],
+        index: usize,
+        protocol: &str,
+        region: &str,
+        dir: u8,
+    ) -> Vec<Value> {
+        ItemWalker {
+            analyzer,
+            data,
+            index,
+            body: TaskData,
+            task_head: false,
+            time_count: None,
+            err_name: "返回结果",
+            item_prefix: None,
+            terminal: None,
+            protocol,
+            region,
+            dir,
+        }
+        .parse(data.len())
+    }
+
+    // 上下行都按终端设置判断；auto时以DI能否恰好解析完剩余数据判断
+    fn pw_layout(analyzer: &Analyzer, frame: &[u8], walker: &ItemWalker) -> PwLayout {
+        let length = walker.data.len();
+        if length <= PW_LENGTH {
+            return PwLayout::Without;
+        }
+        match Self::pw_mode(analyzer, frame) {
+            CsgPwMode::Present => PwLayout::With,
+            CsgPwMode::Absent => PwLayout::Without,
+            CsgPwMode::Auto => match (walker.fits(length), walker.fits(length - PW_LENGTH)) {
+                // 配置了密钥且PW校验通过时按带PW解析；校验失败可能是密钥错误或PW被篡改，
+                // 两种方式都给出，带PW的方式中显示校验失败
+                (true, true) => {
+                    match CsgSecurity::verify_pw(analyzer, frame, BODY_START + length - PW_LENGTH) {
+                        Some((true, _)) => PwLayout::With,
+                        Some((false, _)) | None => PwLayout::Both("报文结构两种方式均可完整解析"),
+                    }
+                }
+                (false, true) => PwLayout::With,
+                (true, false) => PwLayout::Without,
+                // 去掉PW后放不下一组DA+DI时不可能带PW
+                (false, false) if length < PW_LENGTH + DA_LENGTH + DI_LENGTH => PwLayout::Without,
+                // 数据标识未配置或数据有误时结构无法判断，不能因此丢掉PW
+                (false, false) => PwLayout::Both("报文结构两种方式均无法完整解析"),
+            },
+        }
+    }
+
+    fn find_item(
+        analyzer: &Analyzer,
+        data_item: &str,
+        item_prefix: Option<&str>,
+        protocol: &str,
+        region: &str,
+        dir: u8,
+    ) -> Option<XmlElement> {
+        item_prefix
+            .and_then(|prefix| {
+                analyzer.get_config_xml(
+                    &format!("{}{}", prefix, data_item),
+                    protocol,
+                    region,
+                    Some(dir),
+                )
+            })
+            .or_else(|| analyzer.get_config_xml(data_item, protocol, region, Some(dir)))
+    }
+
+    fn has_dir(elem: &XmlElement, dir: u8) -> bool {
+        elem.get_attribute("dir")
+            .is_some_and(|value| value.trim() == dir.to_string())
+    }
+
+    // 数据长度取自配置，unknown按长度规则计算，未配置长度时取剩余数据中数据时间之前的部分
+    fn item_data_length<'a>(
+        analyzer: &Analyzer,
+        elem: &mut XmlElement,
+        content: &'a [u8],
+        time_length: usize,
+        protocol: &str,
+        region: &str,
+        dir: u8,
+    ) -> (usize, &'a [u8]) {
+        if elem.get_child_text("length").is_none() {
+            let length = content.len().saturating_sub(time_length);
+            return (length, &content[..length]);
+        }
+        FrameCsg::recalculate_sub_length(analyzer, elem, content, protocol, region, Some(dir))
+    }
+}
+
+// 信息体中DA+DI逐组部分，data不含Tp
+struct ItemWalker<'a> {
+    analyzer: &'a Analyzer,
+    data: &'a [u8],
+    index: usize,
+    body: CsgItemBody,
+    // 读任务数据上行的信息体以任务头开始
+    task_head: bool,
+    // 本地区读历史数据应答带数据时间个数时的配置项
+    time_count: Option<XmlElement>,
+    err_name: &'static str,
+    item_prefix: Option<String>,
+    // 安全认证报文的终端地址
+    terminal: Option<String>,
+    protocol: &'a str,
+    region: &'a str,
+    dir: u8,
+}
+
+impl ItemWalker<'_> {
+    fn find_item(&self, item: &[u8]) -> Option<XmlElement> {
+        CsgItemEngine::find_item(
+            self.analyzer,
+            &FrameFun::get_data_str_reverser(item),
+            self.item_prefix.as_deref(),
+            self.protocol,
+            self.region,
+            self.dir,
+        )
+    }
+
+    // 查询类只有配置中单独定义了本方向的数据标识才带数据
+    fn item_body(&self, elem: Option<&XmlElement>) -> CsgItemBody {
+        match self.body {
+            Query if elem.is_some_and(|elem| CsgItemEngine::has_dir(elem, self.dir)) => Data,
+            body => body,
+        }
+    }
+
+    // 前limit字节能否恰好按DA+DI逐组解析完，查询和数据的数据标识须已配置
+    fn fits(&self, limit: usize) -> bool {
+        let mut pos = 0;
+        let mut groups = None;
+        if self.task_head {
+            let Some((head_length, count)) = self.task_head(limit) else {
+                return false;
+            };
+            pos = head_length;
+            groups = Some(count);
+        }
+        let mut num = 0;
+        while pos < limit {
+            if self.at_task_time(pos, limit) {
+                return true;
+            }
+            if pos + DA_LENGTH + DI_LENGTH > limit || groups.is_some_and(|count| num >= count) {
+                return false;
+            }
+            num += 1;
+            let elem = self.find_item(&self.data[pos + DA_LENGTH..pos + DA_LENGTH + DI_LENGTH]);
+            pos += DA_LENGTH + DI_LENGTH;
+            pos += match (self.item_body(elem.as_ref()), elem) {
+                (ErrCode, _) => 1,
+                (TimeRange, _) => TIME_RANGE_LENGTH,
+                (TimeRangeDensity, _) => TIME_RANGE_LENGTH + 1,
+                (Time, _) => DATA_TIME_LENGTH,
+                (_, None) => return false,
+                (Query, _) => 0,
+                (Data, _) if pos == limit => return false,
+                (Data, Some(elem)) if elem.get_child_text("length").is_none() => limit - pos,
+                // 按全部数据计算长度，超出limit即说明结构不符
+                (Data, Some(mut elem)) => self.data_length(&mut elem, &self.data[pos..], 0).0,
+                (DataTime, Some(elem)) => match self.timed_length(elem, pos, DATA_TIME_LENGTH) {
+                    Some(length) => length,
+                    None => return false,
+                },
+                (TaskData, Some(elem)) => match self.timed_length(elem, pos, TASK_TIME_LENGTH) {
+                    Some(length) => length,
+                    None => return false,
+                },
+            };
+        }
+        pos == limit
+    }
+
+    fn data_length<'b>(
+        &self,
+        elem: &mut XmlElement,
+        content: &'b [u8],
+        time_length: usize,
+    ) -> (usize, &'b [u8]) {
+        CsgItemEngine::item_data_length(
+            self.analyzer,
+            elem,
+            content,
+            time_length,
+            self.protocol,
+            self.region,
+            self.dir,
+        )
+    }
+
+    // 数据+数据时间部分的长度，带数据时间个数时包括个数字节和全部数据时间
+    fn timed_length(&self, elem: XmlElement, pos: usize, time_length: usize) -> Option<usize> {
+        let (count, mut length) = match self.time_count {
+            Some(_) => (usize::from(*self.data.get(pos)?), 1),
+            None => (1, 0),
+        };
+        for _ in 0..count {
+            let content = self.data.get(pos + length..)?;
+            length += self.data_length(&mut elem.clone(), content, time_length).0 + time_length;
+        }
+        Some(length)
+    }
+
+    // 任务数据的各组之后恰好剩余任务数据时间
+    fn at_task_time(&self, pos: usize, limit: usize) -> bool {
+        self.task_head && limit - pos == DATA_TIME_LENGTH
+    }
+
+    // 任务头的长度和数据组数，数据组数为信息点标识数+数据标识编码数两个字节时，
+    // 其后第一组的数据标识是已配置的数据标识
+    fn task_head(&self, limit: usize) -> Option<(usize, usize)> {
+        let data = self
+            .data
+            .get(..limit)
+            .filter(|data| data.len() >= TASK_HEAD_LENGTH)?;
+        let item_pos = TASK_HEAD_LENGTH + 1 + DA_LENGTH;
+        let two_counts = data
+            .get(item_pos..item_pos + DI_LENGTH)
+            .is_some_and(|item| self.find_item(item).is_some());
+        if two_counts {
+            let count =
+                usize::from(data[TASK_HEAD_LENGTH - 1]) * usize::from(data[TASK_HEAD_LENGTH]);
+            Some((TASK_HEAD_LENGTH + 1, count))
+        } else {
+            Some((TASK_HEAD_LENGTH, usize::from(data[TASK_HEAD_LENGTH - 1])))
+        }
+    }
+
+    fn add_task_head(&self, sub_result: &mut Vec<Value>, length: usize) -> Option<(usize, usize)> {
+        let (head_length, count) = self.task_head(length)?;
+        let index = self.index;
+        let da = &self.data[..DA_LENGTH];
+        let item = &self.data[DA_LENGTH..DA_LENGTH + DI_LENGTH];
+        let task = FrameFun::get_data_str_reverser(item);
+        let task_name = match self.find_item(item) {
+            Some(elem) => format!(
+                "{}号： {}",
+                elem.get_child_text("name").unwrap_or_default(),
+                task
+            ),
+            None => format!("任务号：{}", task),
+        };
+        FrameFun::add_data(
+            sub_result,
+            "信息点标识DA".to_string(),
+            FrameFun::get_data_str_with_space(da),
+            FrameCsg::prase_da_data([da[0], da[1]]),
+            vec![index, index + DA_LENGTH],
+            None,
+            None,
+        );
+        FrameFun::add_data(
+            sub_result,
+            "数据标识编码DI".to_string(),
+            FrameFun::get_data_str_with_space(item),
+            task_name,
+            vec![index + DA_LENGTH, index + DA_LENGTH + DI_LENGTH],
+            None,
+            None,
+        );
+        let kind_pos = DA_LENGTH + DI_LENGTH;
+        let kind = match self.data[kind_pos] {
+            0 => "自描述方式",
+            1 => "任务模板",
+            2 => "补上报数据",
+            _ => "未知",
+        };
+        FrameFun::add_data(
+            sub_result,
+            "数据结构方式".to_string(),
+            format!("{:02X}", self.data[kind_pos]),
+            kind.to_string(),
+            vec![index + kind_pos, index + kind_pos + 1],
+            None,
+            None,
+        );
+        let counts = &self.data[kind_pos + 1..head_length];
+        let description = match counts {
+            [pn_count, item_count] => format!(
+                "信息点标识数{},数据标识编码数{},共有{}个数据组数",
+                pn_count, item_count, count
+            ),
+            _ => format!("共有{}个数据组数", count),
+        };
+        FrameFun::add_data(
+            sub_result,
+            "数据组数".to_string(),
+            FrameFun::get_data_str_with_space(counts),
+            description,
+            vec![index + kind_pos + 1, index + head_length],
+            None,
+            None,
+        );
+        Some((head_length, count))
+    }
+
+    // 按配置解析一组的数据内容，返回数据长度；数据标识未配置时剩余部分整体作为数据内容，返回None
+    fn add_data_content(
+        &self,
+        sub_result: &mut Vec<Value>,
+        group: &ItemGroup,
+        content: &[u8],
+        pos: usize,
+        time_length: usize,
+    ) -> Option<usize> {
+        let index = self.index;
+        let Some(mut elem) = group.elem.clone() else {
+            // 未配置的数据标识无法确定长度，剩余部分整体作为数据内容
+            FrameFun::add_data(
+                sub_result,
+                format!("<第{}组>数据内容", group.num),
+                FrameFun::get_data_str_with_space(content),
+                format!("未查找到数据标识：{},请检查配置文件！", group.data_item),
+                vec![index + pos, index + pos + content.len()],
+                None,
+                None,
+            );
+            return None;
+        };
+        let (sub_length, sub_datament) = self.data_length(&mut elem, content, time_length);
+        elem.update_value("length", sub_length.to_string());
+        let mut item_data = FrameAnalisyic::prase_data(
+            self.analyzer,
+            &mut elem,
+            self.protocol,
+            self.region,
+            sub_datament,
+            index + pos,
+            Some(self.dir),
+        );
+        if let Some(terminal) = &self.terminal {
+            item_data.extend(CsgSecurity::security_item_result(
+                self.analyzer,
+                terminal,
+                &group.data_item,
+                sub_datament,
+                index + pos,
+            ));
+        }
+        FrameFun::add_data(
+            sub_result,
+            format!("<第{}组>数据内容", group.num),
+            FrameFun::get_data_str_with_space(sub_datament),
+            format!("{}-{}", group.point.replace("Pn=", ""), group.identifier),
+            vec![index + pos, index + pos + sub_length],
+            Some(item_data),
+            None,
+        );
+        Some(sub_length)
+    }
+
+    // 数据时间不足时返回None
+    fn add_data_time(
+        &self,
+        sub_result: &mut Vec<Value>,
+        num: usize,
+        content: &[u8],
+        pos: usize,
+        time_length: usize,
+    ) -> Option<usize> {
+        let time = content.get(..time_length)?;
+        let format = if time_length == DATA_TIME_LENGTH {
+            "CCYYMMDDhhmm"
+        } else {
+            "YYMMDDhhmm"
+        };
+        FrameFun::add_data(
+            sub_result,
+            format!("<第{}组>数据时间", num),
+            FrameFun::get_data_str_with_space(time),
+            format!(
+                "数据时间：{}",
+                FrameFun::parse_time_data(time, format, false)
+            ),
+            vec![self.index + pos, self.index + pos + time_length],
+            None,
+            None,
+        );
+        Some(time_length)
+    }
+
+    fn add_timed_data(
+        &self,
+        sub_result: &mut Vec<Value>,
+        group: &ItemGroup,
+        content: &[u8],
+        pos: usize,
+        time_length: usize,
+    ) -> Timed {
+        let Some(&first) = content.first() else {
+            return Timed::Short(0);
+        };
+        let mut consumed = 0;
+        let count = match &self.time_count {
+            Some(elem) => {
+                let name = elem.get_child_text("name").unwrap_or_default();
+                FrameFun::add_data(
+                    sub_result,
+                    format!("<第{}组>{}", group.num, name),
+                    format!("{:02X}", first),
+                    format!("{}：{}", name, first),
+                    vec![self.index + pos, self.index + pos + 1],
+                    None,
+                    None,
+                );
+                consumed = 1;
+                usize::from(first)
+            }
+            None => 1,
+        };
+        for _ in 0..count {
+            let rest = &content[consumed..];
+            match self.add_data_content(sub_result, group, rest, pos + consumed, time_length) {
+                Some(length) => consumed += length,
+                None => return Timed::Unknown,
+            }
+            let rest = &content[consumed..];
+            match self.add_data_time(sub_result, group.num, rest, pos + consumed, time_length) {
+                Some(length) => consumed += length,
+                None => return Timed::Short(consumed),
+            }
+        }
+        Timed::Done(consumed)
+    }
+
+    fn parse(&self, length: usize) -> Vec<Value> {
+        let data_segment = &self.data[..length];
+        let index = self.index;
+        let mut sub_result = Vec::new();
+        let mut pos = 0;
+        let mut num = 0;
+        let mut groups = None;
+        // 数据不足时即使已无剩余数据也标出截断位置
+        let mut truncated = false;
+        if self.task_head {
+            match self.add_task_head(&mut sub_result, length) {
+                Some((head_length, count)) => {
+                    pos = head_length;
+                    groups = Some(count);
+                }
+                None => truncated = true,
+            }
+        }
+        while !truncated
+            && pos + DA_LENGTH + DI_LENGTH <= length
+            && groups.is_none_or(|count| num < count)
+            && !self.at_task_time(pos, length)
+        {
+            num += 1;
+            let da = &data_segment[pos..pos + DA_LENGTH];
+            let item = &data_segment[pos + DA_LENGTH..pos + DA_LENGTH + DI_LENGTH];
+            let data_item = FrameFun::get_data_str_reverser(item);
+            let elem = self.find_item(item);
+            let identifier = match &elem {
+                Some(elem) => format!(
+                    "[{}]-{}",
+                    data_item,
+                    elem.get_child_text("name").unwrap_or_default()
+                ),
+                None => format!("[{}]", data_item),
+            };
+            let group = ItemGroup {
+                num,
+                point: FrameCsg::prase_da_data([da[0], da[1]]),
+                data_item,
+                identifier,
+                elem,
+            };
+            FrameFun::add_data(
+                &mut sub_result,
+                format!("<第{}组>信息点标识DA", num),
+                FrameFun::get_data_str_with_space(da),
+                group.point.clone(),
+                vec![index + pos, index + pos + DA_LENGTH],
+                None,
+                None,
+            );
+            pos += DA_LENGTH;
+            FrameFun::add_data(
+                &mut sub_result,
+                format!("<第{}组>数据标识编码DI", num),
+                FrameFun::get_data_str_with_space(item),
+                format!("数据标识编码：{}", group.identifier),
+                vec![index + pos, index + pos + DI_LENGTH],
+                None,
+                None,
+            );
+            pos += DI_LENGTH;
+
+            let content = &data_segment[pos..];
+            let consumed = match self.item_body(group.elem.as_ref()) {
+                Query => 0,
+                Data if content.is_empty() => 0,
+                Data => match self.add_data_content(&mut sub_result, &group, content, pos, 0) {
+                    Some(length) => length,
+                    None => {
+                        pos = length;
+                        break;
+                    }
+                },
+                ErrCode => {
+                    let Some(&errcode) = content.first() else {
+                        break;
+                    };
+                    FrameFun::add_data(
+                        &mut sub_result,
+                        format!("<第{}组>ERR", num),
+                        format!("{:02X}", errcode),
+                        format!(
+                            "{}：{:02X}-{}",
+                            self.err_name,
+                            errcode,
+                            FrameCsg::prase_err_code_result(errcode)
+                        ),
+                        vec![index + pos, index + pos + 1],
+                        None,
+                        None,
+                    );
+                    1
+                }
+                body @ (TimeRange | TimeRangeDensity) => {
+                    let with_density = body == TimeRangeDensity;
+                    if content.len() < TIME_RANGE_LENGTH + usize::from(with_density) {
+                        break;
+                    }
+                    let start_time = &content[..6];
+                    let end_time = &content[6..TIME_RANGE_LENGTH];
+                    FrameFun::add_data(
+                        &mut sub_result,
+                        format!("<第{}组>数据起始时间", num),
+                        FrameFun::get_data_str_with_space(start_time),
+                        FrameFun::parse_time_data(start_time, "CCYYMMDDhhmm", false),
+                        vec![index + pos, index + pos + 6],
+                        None,
+                        None,
+                    );
+                    FrameFun::add_data(
+                        &mut sub_result,
+                        format!("<第{}组>数据结束时间", num),
+                        FrameFun::get_data_str_with_space(end_time),
+                        FrameFun::parse_time_data(end_time, "CCYYMMDDhhmm", false),
+                        vec![index + pos + 6, index + pos + TIME_RANGE_LENGTH],
+                        None,
+                        None,
+                    );
+                    if !with_density {
+                        TIME_RANGE_LENGTH
+                    } else {
+                        let density = content[TIME_RANGE_LENGTH];
+                        FrameFun::add_data(
+                            &mut sub_result,
+                            format!("<第{}组>数据密度", num),
+                            format!("{:02X}", density),
+                            format!("数据间隔时间：{}", FrameCsg::get_data_dinsty(density)),
+                            vec![
+                                index + pos + TIME_RANGE_LENGTH,
+                                index + pos + TIME_RANGE_LENGTH + 1,
+                            ],
+                            None,
+                            None,
+                        );
+                        TIME_RANGE_LENGTH + 1
+                    }
+                }
+                Time => {
+                    match self.add_data_time(&mut sub_result, num, content, pos, DATA_TIME_LENGTH) {
+                        Some(length) => length,
+                        None => {
+                            truncated = true;
+                            break;
+                        }
+                    }
+                }
+                body @ (DataTime | TaskData) => {
+                    let time_length = if body == DataTime {
+                        DATA_TIME_LENGTH
+                    } else {
+                        TASK_TIME_LENGTH
+                    };
+                    match self.add_timed_data(&mut sub_result, &group, content, pos, time_length) {
+                        Timed::Done(length) => length,
+                        Timed::Short(length) => {
+                            pos += length;
+                            truncated = true;
+                            break;
+                        }
+                        Timed::Unknown => {
+                            pos = length;
+                            break;
+                        }
+                    }
+                }
+            };
+            pos += consumed;
+        }
+
+        if !truncated && self.at_task_time(pos, length) {
+            let time = &data_segment[pos..length];
+            FrameFun::add_data(
+                &mut sub_result,
+                "任务数据时间".to_string(),
+                FrameFun::get_data_str_with_space(time),
+                FrameFun::parse_time_data(time, "CCYYMMDDhhmm", false),
+                vec![index + pos, index + length],
+                None,
+                None,
+            );
+            pos = length;
+        }
+        if pos < length || truncated {
+            FrameFun::add_data(
+                &mut sub_result,
+                "未解析数据".to_string(),
+                FrameFun::get_data_str_with_space(&data_segment[pos..length]),
+                "剩余数据不足以按报文结构解析".to_string(),
+                vec![index + pos, index + length],
+                None,
+                None,
+            );
+        }
+        sub_result
+    }
+}
+
+// 一组DA+DI，供其后的数据内容引用
+struct ItemGroup {
+    num: usize,
+    point: String,
+    data_item: String,
+    identifier: String,
+    elem: Option<XmlElement>,
+}
+
+// 数据+数据时间部分的解析结果
+enum Timed {
+    // 完整解析的长度
+    Done(usize),
+    // 数据不足，已解析的长度
+    Short(usize),
+    // 数据标识未配置，剩余部分已整体作为数据内容
+    Unknown,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::basefunc::test_util::find;
+
+    const PW: [u8; PW_LENGTH] = [0x5A; PW_LENGTH];
+
+    // 主站下行报文，终端地址取自地址域
+    fn frame(afn: u8, payload: &[u8]) -> Vec<u8> {
+        frame_with_control(0x4A, afn, payload)
+    }
+
+    fn frame_with_control(control: u8, afn: u8, payload: &[u8]) -> Vec<u8> {
+        let mut body = vec![control, 0x00, 0x44, 0x12, 0x34, 0x56, 0x00, 0x00, afn, 0x60];
+        body.extend_from_slice(payload);
+        let [low, high] = (body.len() as u16).to_le_bytes();
+        let mut frame = vec![0x68, low, high, low, high, 0x68];
+        frame.extend_from_slice(&body);
+        frame.push(body.iter().fold(0u8, |sum, b| sum.wrapping_add(*b)));
+        frame.push(0x16);
+        frame
+    }
+
+    // 读当前数据，数据标识E0FFFFFF未配置，带PW与否都无法按结构解析完
+    fn unknown_item_frame() -> Vec<u8> {
+        let mut payload = vec![0x00, 0x00, 0xFF, 0xFF, 0xFF, 0xE0, 0x11, 0x22, 0x33, 0x44];
+        payload.extend_from_slice(&PW);
+        frame(0x0C, &payload)
+    }
+
+    #[test]
+    fn unknown_item_keeps_both_readings() {
+        let analyzer = Analyzer::with_builtin();
+        let (_, result) = analyzer.process_frame(&unknown_item_frame(), "南网");
+        let with_pw = find(&result, "解析方式1：含消息验证码PW").unwrap();
+        assert!(with_pw["description"]
+            .as_str()
+            .unwrap()
+            .starts_with("报文结构两种方式均无法完整解析"));
+        let pw = find(with_pw["children"].as_array().unwrap(), "消息验证码Pw").unwrap();
+        assert_eq!(pw["position"], serde_json::json!([26, 42]));
+        let without_pw = find(&result, "解析方式2：不含消息验证码PW").unwrap();
+        assert!(find(without_pw["children"].as_array().unwrap(), "消息验证码Pw").is_none());
+    }
+
+    #[test]
+    fn read_history_request_with_pw() {
+        let analyzer = Analyzer::with_builtin();
+        let mut payload = vec![0x00, 0x00, 0x00, 0x00, 0x01, 0x00];
+        payload.extend_from_slice(&[0x00, 0x00, 0x01, 0x10, 0x26, 0x20]);
+        payload.extend_from_slice(&[0x00, 0x00, 0x02, 0x10, 0x26, 0x20, 0x05]);
+        payload.extend_from_slice(&PW);
+        for afn in [0x0D, 0x12] {
+            let (_, result) = analyzer.process_frame(&frame(afn, &payload), "南网");
+            assert!(find(&result, "解析方式1：含消息验证码PW").is_none());
+            let density = find(&result, "<第1组>数据密度").unwrap();
+            assert_eq!(density["description"], "数据间隔时间：60分钟");
+            assert_eq!(density["position"], serde_json::json!([34, 35]));
+            let pw = find(&result, "消息验证码Pw").unwrap();
+            assert_eq!(pw["position"], serde_json::json!([35, 51]));
+        }
+    }
+
+    #[test]
+    fn pw_policy_per_analyzer() {
+        let frame = unknown_item_frame();
+        let terminal = FrameCsg::get_csg_adress(&frame);
+        let policy = CsgPwPolicy::from_value(&serde_json::json!({
+            "default": "absent",
+            "terminals": { terminal.clone(): "present", "000000000000": "bogus" },
+        }));
+        assert_eq!(policy.mode(&terminal), CsgPwMode::Present);
+        assert_eq!(policy.mode("000000000000"), CsgPwMode::Absent);
+        assert_eq!(
+            CsgPwPolicy::from_value(&Value::Null).mode(&terminal),
+            CsgPwMode::Auto
+        );
+
+        let present = Analyzer::with_builtin();
+        present.set_csg_pw_policy(policy);
+        let absent = Analyzer::with_builtin();
+        absent.set_csg_pw_policy(CsgPwPolicy {
+            default: CsgPwMode::Absent,
+            ..Default::default()
+        });
+
+        let (_, result) = present.process_frame(&frame, "南网");
+        assert!(find(&result, "解析方式1：含消息验证码PW").is_none());
+        assert!(find(&result, "消息验证码Pw").is_some());
+        let (_, result) = absent.process_frame(&frame, "南网");
+        assert!(find(&result, "解析方式1：含消息验证码PW").is_none());
+        assert!(find(&result, "消息验证码Pw").is_none());
+    }
+
+    // 终端时间E0000130未按方向单独定义，上下行均按通用定义解析6字节数据
+    const TIME_ITEM: [u8; 12] = [
+        0x00, 0x00, 0x30, 0x01, 0x00, 0xE0, 0x00, 0x00, 0x12, 0x19, 0x10, 0x26,
+    ];
+
+    fn assert_time_item(result: &[Value]) {
+        let content = find(result, "<第1组>数据内容").unwrap();
+        assert_eq!(content["position"], serde_json::json!([22, 28]));
+        assert!(content["description"]
+            .as_str()
+            .unwrap()
+            .ends_with("[E0000130]-终端时间"));
+        assert!(find(result, "未解析数据").is_none());
+        assert!(find(result, "解析方式1：含消息验证码PW").is_none());
+    }
+
+    #[test]
+    fn cascade_and_user_defined_use_generic_items() {
+        let analyzer = Analyzer::with_builtin();
+        let mut with_pw = TIME_ITEM.to_vec();
+        with_pw.extend_from_slice(&PW);
+        for afn in [0x14, 0x15] {
+            let (_, result) = analyzer.process_frame(&frame(afn, &TIME_ITEM), "南网");
+            assert_time_item(&result);
+            assert!(find(&result, "消息验证码Pw").is_none());
+
+            let (_, result) = analyzer.process_frame(&frame(afn, &with_pw), "南网");
+            assert_time_item(&result);
+            let pw = find(&result, "消息验证码Pw").unwrap();
+            assert_eq!(pw["position"], serde_json::json!([28, 44]));
+
+            // 上行应答与下行按同样的结构判断PW
+            let uplink = frame_with_control(0x88, afn, &TIME_ITEM);
+            let (_, result) = analyzer.process_frame(&uplink, "南网");
+            assert_time_item(&result);
+            assert!(find(&result, "消息验证码Pw").is_none());
+            let uplink = frame_with_control(0x88, afn, &with_pw);
+            let (_, result) = analyzer.process_frame(&uplink, "南网");
+            assert_time_item(&result);
+            let pw = find(&result, "消息验证码Pw").unwrap();
+            assert_eq!(pw["position"], serde_json::json!([28, 44]));
+        }
+    }
+
+    // 上行报文同样按终端设置决定是否带PW
+    #[test]
+    fn uplink_pw_follows_policy() {
+        let mut with_pw = TIME_ITEM.to_vec();
+        with_pw.extend_from_slice(&PW);
+        let uplink = frame_with_control(0x88, 0x15, &with_pw);
+        let analyzer = Analyzer::with_builtin();
+        for (mode, has_pw) in [(CsgPwMode::Present, true), (CsgPwMode::Absent, false)] {
+            analyzer.set_csg_pw_policy(CsgPwPolicy {
+                default: mode,
+                ..Default::default()
+            });
+            let (_, result) = analyzer.process_frame(&uplink, "南网");
+            assert_eq!(find(&result, "消息验证码Pw").is_some(), has_pw);
+            assert!(find(&result, "解析方式1：含消息验证码PW").is_none());
+        }
+    }
+
+    #[test]
+    fn err_code_named_per_handler() {
+        let analyzer = Analyzer::with_builtin();
+        let payload = [0x00, 0x00, 0x30, 0x01, 0x00, 0xE0, 0x00];
+        for (afn, name) in [
+            (0x02, "链路接口检测返回结果"),
+            (0x04, "写参数返回结果"),
+            (0x0F, "文件传输返回结果"),
+        ] {
+            let response = frame_with_control(0x88, afn, &payload);
+            let (_, result) = analyzer.process_frame(&response, "南网");
+            let err = find(&result, "<第1组>ERR").unwrap();
+            assert_eq!(
+                err["description"],
+                format!("{}：00-{}", name, FrameCsg::prase_err_code_result(0))
+            );
+        }
+    }
+
+    // 上行应答截断在数据时间或任务头之前，标出截断位置
+    #[test]
+    fn truncated_read_history_and_task_reply() {
+        let analyzer = Analyzer::with_builtin();
+        let value = [0x01, 0x01, 0x00, 0x00, 0x01, 0x00, 0x11, 0x22, 0x33, 0x44];
+        let mut task = vec![0x00, 0x00, 0x01, 0x03, 0x00, 0xE0, 0x00, 0x01, 0x01];
+        task.extend_from_slice(&value);
+        for (afn, payload, position) in [
+            (0x0D, &[0x00, 0x00, 0x00, 0x00, 0x29, 0x01][..], [22, 22]),
+            (0x0D, &value[..], [26, 26]),
+            (0x12, &task[..], [35, 35]),
+            (0x12, &task[..7], [16, 23]),
+        ] {
+            let response = frame_with_control(0x88, afn, payload);
+            let (_, result) = analyzer.process_frame(&response, "南网");
+            let short = find(&result, "未解析数据").unwrap();
+            assert_eq!(short["position"], serde_json::json!(position));
+        }
+    }
+
+    // 主动上报每组为DA+DI+数据时间，末尾剩余16字节为PW
+    #[test]
+    fn read_history_report_with_pw() {
+        let analyzer = Analyzer::with_builtin();
+        let mut report = vec![0x01, 0x01, 0x00, 0x00, 0x01, 0x00];
+        report.extend_from_slice(&[0x00, 0x00, 0x01, 0x10, 0x26, 0x20]);
+        let mut response = report.clone();
+        response.splice(6..6, [0x11, 0x22, 0x33, 0x44]);
+        for (control, mut payload, position) in
+            [(0xC8, report, [28, 44]), (0x88, response, [32, 48])]
+        {
+            payload.extend_from_slice(&PW);
+            let uplink = frame_with_control(control, 0x0D, &payload);
+            let (_, result) = analyzer.process_frame(&uplink, "南网");
+            assert!(find(&result, "<第1组>数据时间").is_some());
+            assert!(find(&result, "<第2组>信息点标识DA").is_none());
+            assert!(find(&result, "未解析数据").is_none());
+            let pw = find(&result, "消息验证码Pw").unwrap();
+            assert_eq!(pw["position"], serde_json::json!(position));
+        }
+    }
+
+    // 数据时间个数只在配置了该项的地区出现，其后为对应个数的数据和数据时间
+    #[test]
+    fn read_history_time_count_by_region() {
+        let analyzer = Analyzer::with_builtin();
+        let value = [0x11, 0x22, 0x33, 0x44];
+        let time = [0x00, 0x00, 0x01, 0x10, 0x26, 0x20];
+        let mut payload = vec![0x01, 0x01, 0x00, 0x00, 0x01, 0x00, 0x02];
+        for _ in 0..2 {
+            payload.extend_from_slice(&value);
+            payload.extend_from_slice(&time);
+        }
+        let response = frame_with_control(0x88, 0x0D, &payload);
+        let (_, result) = analyzer.process_frame(&response, "海南");
+        let count = find(&result, "<第1组>数据时间个数").unwrap();
+        assert_eq!(count["description"], "数据时间个数：2");
+        assert_eq!(count["position"], serde_json::json!([22, 23]));
+        assert!(find(&result, "未解析数据").is_none());
+        assert!(find(&result, "<第2组>信息点标识DA").is_none());
+
+        let mut payload = vec![0x01, 0x01, 0x00, 0x00, 0x01, 0x00];
+        payload.extend_from_slice(&value);
+        payload.extend_from_slice(&time);
+        let response = frame_with_control(0x88, 0x0D, &payload);
+        let (_, result) = analyzer.process_frame(&response, "南网");
+        assert!(find(&result, "<第1组>数据时间个数").is_none());
+        let time = find(&result, "<第1组>数据时间").unwrap();
+        assert_eq!(time["position"], serde_json::json!([26, 32]));
+        assert!(find(&result, "未解析数据").is_none());
+    }
+
+    // 任务数据应答：任务头+按数据组数逐组的数据和数据时间+任务数据时间
+    #[test]
+    fn read_task_reply_groups() {
+        let analyzer = Analyzer::with_builtin();
+        let mut payload = vec![0x00, 0x00, 0x01, 0x03, 0x00, 0xE0, 0x00, 0x01, 0x02];
+        for _ in 0..2 {
+            payload.extend_from_slice(&[0x01, 0x01, 0x00, 0x00, 0x01, 0x00]);
+            payload.extend_from_slice(&[0x11, 0x22, 0x33, 0x44]);
+            payload.extend_from_slice(&[0x00, 0x01, 0x19, 0x10, 0x26]);
+        }
+        payload.extend_from_slice(&[0x00, 0x00, 0x01, 0x19, 0x10, 0x26]);
+        let response = frame_with_control(0x88, 0x12, &payload);
+        let (_, result) = analyzer.process_frame(&response, "南网");
+        let groups = find(&result, "数据组数").unwrap();
+        assert_eq!(groups["position"], serde_json::json!([23, 25]));
+        let time = find(&result, "<第2组>数据时间").unwrap();
+        assert_eq!(time["position"], serde_json::json!([50, 55]));
+        let task_time = find(&result, "任务数据时间").unwrap();
+        assert_eq!(task_time["position"], serde_json::json!([55, 61]));
+        assert!(find(&result, "未解析数据").is_none());
+    }
+}
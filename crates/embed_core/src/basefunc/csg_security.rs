@@ -0,0 +1,414 @@
+use crate::analyzer::Analyzer;
+use crate::basefunc::frame_csg::FrameCsg;
+use crate::basefunc::frame_fun::FrameFun;
+use crate::basefunc::sm_crypto::{Sm4, SM4_BLOCK_LENGTH};
+use crate::config::keystore::{KeyUsage, KEY_LENGTH};
+use serde_json::Value;
+
+// 68 L L L L 68之后为控制域C
+const USER_DATA_START: usize = 6;
+const BODY_START: usize = 16;
+const PW_LENGTH: usize = 16;
+const TP_LENGTH: usize = 5;
+const MAC_LENGTH: usize = 4;
+const ZERO_IV: [u8; SM4_BLOCK_LENGTH] = [0; SM4_BLOCK_LENGTH];
+
+// 安全认证数据标识中需要校验或解密的部分
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum SecurityCheck {
+    /// 数据末尾4字节为MAC
+    Mac,
+    /// 数据为密文
+    Cipher,
+    /// 密文后跟4字节MAC
+    CipherMac,
+}
+
+// (数据标识, 数据前长度域的字节数, 校验方式)
+const SECURITY_ITEMS: &[(&str, usize, SecurityCheck)] = &[
+    ("E0010186", 0, SecurityCheck::CipherMac),
+    ("E0010188", 0, SecurityCheck::Mac),
+    ("E0010189", 0, SecurityCheck::Mac),
+    ("E001018A", 0, SecurityCheck::Mac),
+    ("E001018B", 2, SecurityCheck::Mac),
+    ("E001018C", 0, SecurityCheck::Mac),
+    ("E001018D", 0, SecurityCheck::Cipher),
+];
+
+/// 南网13安全认证：消息验证码PW/MAC的计算校验，数据单元的加解密
+///
+/// 上行通信规约只规定PW为16字节、MAC为4字节，由安全模块计算，没有给出算法，本仓库中也
+/// 没有安全模块的技术规范。这里采用的方案是：分组密码为SM4(GB/T 32907-2016)；PW为
+/// GB/T 15852.1 MAC算法1(CBC-MAC，初始向量全0)，按填充方法2补0x80后补0x00，覆盖控制域C
+/// 至PW之前的数据单元；数据单元中的MAC取同一算法结果的前4字节；数据单元按SM4 CBC(初始
+/// 向量全0，同样填充)加密。
+///
+/// SM4本身按GB/T 32907附录A的示例测试，上述方案还没有用终端实测报文或规范示例验证，
+/// 校验不一致时可能是密钥错误，也可能是方案与终端不符。
+/// 密钥按终端逻辑地址从解析器实例的密钥存储中获取。
+pub struct CsgSecurity;
+
+impl CsgSecurity {
+    /// 终端的密钥，未配置时返回错误
+    pub fn terminal_key(
+        analyzer: &Analyzer,
+        terminal: &str,
+        usage: KeyUsage,
+    ) -> Result<[u8; KEY_LENGTH], String> {
+        analyzer
+            .key_store()
+            .get_key(terminal, usage)
+            .ok_or_else(|| format!("未配置终端{}的{}密钥", terminal, usage.name()))
+    }
+
+    pub fn calc_pw(key: &[u8; KEY_LENGTH], data: &[u8]) -> [u8; PW_LENGTH] {
+        Sm4::new(key).cbc_mac(&ZERO_IV, data)
+    }
+
+    pub fn calc_mac(key: &[u8; KEY_LENGTH], data: &[u8]) -> [u8; MAC_LENGTH] {
+        let pw = Self::calc_pw(key, data);
+        [pw[0], pw[1], pw[2], pw[3]]
+    }
+
+    /// 校验数据末尾4字节MAC，返回是否一致和按密钥计算的MAC
+    pub fn verify_mac(
+        key: &[u8; KEY_LENGTH],
+        data_with_mac: &[u8],
+    ) -> Result<(bool, [u8; MAC_LENGTH]), String> {
+        if data_with_mac.len() < MAC_LENGTH {
+            return Err("数据长度不足4字节MAC".to_string());
+        }
+        let (data, mac) = data_with_mac.split_at(data_with_mac.len() - MAC_LENGTH);
+        let expected = Self::calc_mac(key, data);
+        Ok((mac == expected, expected))
+    }
+
+    /// 按终端PW密钥校验报文中pw_start处的PW，未配置密钥时返回None
+    pub fn verify_pw(
+        analyzer: &Analyzer,
+        frame: &[u8],
+        pw_start: usize,
+    ) -> Option<(bool, [u8; PW_LENGTH])> {
+        if pw_start < BODY_START || frame.len() < pw_start + PW_LENGTH {
+            return None;
+        }
+        let key = analyzer
+            .key_store()
+            .get_key(&FrameCsg::get_csg_adress(frame), KeyUsage::Mac)?;
+        let expected = Self::calc_pw(&key, &frame[USER_DATA_START..pw_start]);
+        Some((frame[pw_start..pw_start + PW_LENGTH] == expected, expected))
+    }
+
+    /// PW校验结果节点，作为消息验证码Pw的子项；未配置密钥时返回None
+    pub fn pw_check_result(
+        analyzer: &Analyzer,
+        frame: &[u8],
+        pw_start: usize,
+        position: Vec<usize>,
+    ) -> Option<Vec<Value>> {
+        let (matched, expected) = Self::verify_pw(analyzer, frame, pw_start)?;
+        let description = if matched {
+            "PW校验通过".to_string()
+        } else {
+            format!(
+                "PW校验失败(密钥或算法与终端不一致)，按密钥计算应为：{}",
+                FrameFun::get_data_str_with_space(&expected)
+            )
+        };
+        let mut result = Vec::new();
+        FrameFun::add_data(
+            &mut result,
+            "PW校验".to_string(),
+            FrameFun::get_data_str_with_space(&frame[pw_start..pw_start + PW_LENGTH]),
+            description,
+            position,
+            None,
+            None,
+        );
+        Some(result)
+    }
+
+    /// 在不带PW的完整报文中插入终端PW，重新计算长度和校验和
+    ///
+    /// PW插在数据单元之后、时间标签Tp之前。
+    pub fn append_pw(analyzer: &Analyzer, frame: &[u8]) -> Result<Vec<u8>, String> {
+        if frame.len() < BODY_START + 2 || frame[0] != 0x68 || frame[5] != 0x68 {
+            return Err("不是有效的南网报文".to_string());
+        }
+        let key = Self::terminal_key(analyzer, &FrameCsg::get_csg_adress(frame), KeyUsage::Mac)?;
+        let tp_length = if frame[15] & 0x80 != 0 { TP_LENGTH } else { 0 };
+        let pw_start = frame.len() - 2 - tp_length;
+        if pw_start < BODY_START {
+            return Err("报文长度不足以包含时间标签".to_string());
+        }
+
+        let mut result = frame[..pw_start].to_vec();
+        result.extend_from_slice(&Self::calc_pw(&key, &frame[USER_DATA_START..pw_start]));
+        result.extend_from_slice(&frame[pw_start..frame.len() - 2]);
+        let length = (result.len() - USER_DATA_START) as u16;
+        let [low, high] = length.to_le_bytes();
+        result[1..5].copy_from_slice(&[low, high, low, high]);
+        let cs = result[USER_DATA_START..]
+            .iter()
+            .fold(0u8, |sum, b| sum.wrapping_add(*b));
+        result.push(cs);
+        result.push(0x16);
+        Ok(result)
+    }
+
+    /// 用终端数据密钥解密数据单元，去掉补位
+    pub fn decrypt_data(
+        analyzer: &Analyzer,
+        terminal: &str,
+        cipher: &[u8],
+    ) -> Result<Vec<u8>, String> {
+        let key = Self::terminal_key(analyzer, terminal, KeyUsage::Data)?;
+        let plain = Sm4::new(&key).decrypt_cbc(&ZERO_IV, cipher)?;
+        Sm4::unpad_80(&plain).map(<[u8]>::to_vec)
+    }
+
+    /// 安全认证(AFN 06)数据内容的MAC校验和解密结果，作为数据内容的子项
+    ///
+    /// 只处理带MAC或密文的数据标识，未配置对应密钥时不给出结果。
+    pub fn security_item_result(
+        analyzer: &Analyzer,
+        terminal: &str,
+        data_item: &str,
+        content: &[u8],
+        start_pos: usize,
+    ) -> Vec<Value> {
+        let mut result = Vec::new();
+        let keys = analyzer.key_store();
+        let Some(&(_, head_length, check)) = SECURITY_ITEMS
+            .iter()
+            .find(|(item, _, _)| *item == data_item)
+        else {
+            return result;
+        };
+        let data = &content[head_length.min(content.len())..];
+        let start_pos = start_pos + content.len() - data.len();
+        let cipher_length = match check {
+            SecurityCheck::Mac => 0,
+            SecurityCheck::Cipher => data.len(),
+            SecurityCheck::CipherMac => data.len().saturating_sub(MAC_LENGTH),
+        };
+
+        if check != SecurityCheck::Cipher {
+            if let Some(key) = keys.get_key(terminal, KeyUsage::Mac) {
+                let description = match Self::verify_mac(&key, data) {
+                    Ok((true, _)) => "MAC校验通过".to_string(),
+                    Ok((false, expected)) => format!(
+                        "MAC校验失败(密钥或算法与终端不一致)，按密钥计算应为：{}",
+                        FrameFun::get_data_str_with_space(&expected)
+                    ),
+                    Err(err) => err,
+                };
+                let mac_start = data.len().saturating_sub(MAC_LENGTH);
+                FrameFun::add_data(
+                    &mut result,
+                    "MAC校验".to_string(),
+                    FrameFun::get_data_str_with_space(&data[mac_start..]),
+                    description,
+                    vec![start_pos + mac_start, start_pos + data.len()],
+                    None,
+                    None,
+                );
+            }
+        }
+        if check != SecurityCheck::Mac && keys.get_key(terminal, KeyUsage::Data).is_some() {
+            let cipher = &data[..cipher_length];
+            let (plain, description) = match Self::decrypt_data(analyzer, terminal, cipher) {
+                Ok(plain) => (plain, "按终端数据密钥解密".to_string()),
+                Err(err) => (Vec::new(), format!("解密失败：{}", err)),
+            };
+            FrameFun::add_data(
+                &mut result,
+                "解密数据".to_string(),
+                FrameFun::get_data_str_with_space(&plain),
+                description,
+                vec![start_pos, start_pos + cipher_length],
+                None,
+                None,
+            );
+        }
+        result
+    }
+
+    /// 用终端数据密钥加密数据单元，补0x80后补0x00
+    pub fn encrypt_data(
+        analyzer: &Analyzer,
+        terminal: &str,
+        plain: &[u8],
+    ) -> Result<Vec<u8>, String> {
+        let key = Self::terminal_key(analyzer, terminal, KeyUsage::Data)?;
+        Sm4::new(&key).encrypt_cbc(&ZERO_IV, &Sm4::pad_80(plain))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyzer::Analyzer;
+    use crate::basefunc::test_util::find;
+
+    const KEY: [u8; KEY_LENGTH] = [
+        0x01, 0x23, 0x45, 0x67, 0x89, 0xAB, 0xCD, 0xEF, 0xFE, 0xDC, 0xBA, 0x98, 0x76, 0x54, 0x32,
+        0x10,
+    ];
+
+    // 下行读当前数据，终端地址取自地址域，不带PW
+    fn frame(seq: u8, tp: &[u8]) -> Vec<u8> {
+        let mut body = vec![0x4A, 0x00, 0x44, 0x12, 0x34, 0x56, 0x00, 0x00, 0x0C, seq];
+        body.extend_from_slice(&[0x00, 0x00, 0x30, 0x01, 0x00, 0xE0]);
+        body.extend_from_slice(tp);
+        let [low, high] = (body.len() as u16).to_le_bytes();
+        let mut frame = vec![0x68, low, high, low, high, 0x68];
+        frame.extend_from_slice(&body);
+        frame.push(body.iter().fold(0u8, |sum, b| sum.wrapping_add(*b)));
+        frame.push(0x16);
+        frame
+    }
+
+    // 每个测试使用自己的解析器实例，密钥互不影响
+    fn set_key(analyzer: &Analyzer, frame: &[u8]) {
+        analyzer
+            .key_store()
+            .set_key(&FrameCsg::get_csg_adress(frame), KeyUsage::Mac, Some(KEY))
+            .unwrap();
+    }
+
+    #[test]
+    fn pw_roundtrip() {
+        let analyzer = Analyzer::new();
+        let plain = frame(0x60, &[]);
+        set_key(&analyzer, &plain);
+        let frame = CsgSecurity::append_pw(&analyzer, &plain).unwrap();
+        assert_eq!(frame.len(), plain.len() + PW_LENGTH);
+        assert_eq!(frame[1], plain[1] + PW_LENGTH as u8);
+        assert!(FrameCsg::is_csg_frame(&frame));
+        let pw_start = frame.len() - 2 - PW_LENGTH;
+        let expected = CsgSecurity::calc_pw(&KEY, &frame[USER_DATA_START..pw_start]);
+        assert_eq!(
+            CsgSecurity::verify_pw(&analyzer, &frame, pw_start),
+            Some((true, expected))
+        );
+        // 密钥只属于设置它的实例
+        assert_eq!(
+            CsgSecurity::verify_pw(&Analyzer::new(), &frame, pw_start),
+            None
+        );
+
+        let mut tampered = frame.clone();
+        tampered[pw_start] ^= 0xFF;
+        assert_eq!(
+            CsgSecurity::verify_pw(&analyzer, &tampered, pw_start),
+            Some((false, expected))
+        );
+        let check = CsgSecurity::pw_check_result(&analyzer, &tampered, pw_start, vec![]).unwrap();
+        assert!(check[0]["description"]
+            .as_str()
+            .unwrap()
+            .starts_with("PW校验失败"));
+    }
+
+    #[test]
+    fn pw_before_tp() {
+        let tp = [0x01, 0x10, 0x10, 0x19, 0x05];
+        let analyzer = Analyzer::new();
+        let plain = frame(0xE0, &tp);
+        set_key(&analyzer, &plain);
+        let frame = CsgSecurity::append_pw(&analyzer, &plain).unwrap();
+        assert_eq!(&frame[frame.len() - 2 - TP_LENGTH..frame.len() - 2], &tp);
+        let pw_start = frame.len() - 2 - TP_LENGTH - PW_LENGTH;
+        assert!(matches!(
+            CsgSecurity::verify_pw(&analyzer, &frame, pw_start),
+            Some((true, _))
+        ));
+    }
+
+    #[test]
+    fn mac_roundtrip() {
+        let mut data = vec![0x11, 0x22, 0x33, 0x44, 0x55];
+        let mac = CsgSecurity::calc_mac(&KEY, &data);
+        assert_eq!(mac, CsgSecurity::calc_pw(&KEY, &data)[..MAC_LENGTH]);
+        data.extend_from_slice(&mac);
+        assert_eq!(CsgSecurity::verify_mac(&KEY, &data), Ok((true, mac)));
+        data[0] ^= 0x01;
+        assert!(!CsgSecurity::verify_mac(&KEY, &data).unwrap().0);
+        assert!(CsgSecurity::verify_mac(&KEY, &data[..3]).is_err());
+    }
+
+    // 期望值由OpenSSL的SM4-CBC按同一方案独立计算，只说明实现与上述方案一致，
+    // 不能说明方案与终端一致；取得终端实测的带PW报文后应补充到这里
+    #[test]
+    fn pw_known_answer() {
+        let plain = frame(0x60, &[]);
+        assert_eq!(
+            CsgSecurity::calc_pw(&KEY, &plain[USER_DATA_START..plain.len() - 2]),
+            [
+                0xAE, 0x1F, 0xC0, 0x20, 0xAA, 0x5C, 0x80, 0x04, 0xF0, 0x5E, 0x32, 0x9F, 0x4F, 0x40,
+                0x21, 0x59
+            ]
+        );
+        assert_eq!(
+            CsgSecurity::calc_mac(&KEY, &[0x11, 0x22, 0x33, 0x44, 0x55]),
+            [0x78, 0x20, 0x90, 0x5E]
+        );
+    }
+
+    // 上行安全认证：模式切换带MAC，会话协商确认为密文
+    #[test]
+    fn security_items_checked_and_decrypted() {
+        const DATA_KEY: [u8; KEY_LENGTH] = [
+            0x00, 0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77, 0x88, 0x99, 0xAA, 0xBB, 0xCC, 0xDD,
+            0xEE, 0xFF,
+        ];
+        let mut body = vec![0x88, 0x00, 0x44, 0x12, 0x34, 0x56, 0x00, 0x00, 0x06, 0x60];
+        body.extend_from_slice(&[0x00, 0x00, 0x8C, 0x01, 0x01, 0xE0, 0x01, 0x02, 0x03]);
+        body.extend_from_slice(&[0xBE, 0xF7, 0xC1, 0x67]);
+        body.extend_from_slice(&[0x00, 0x00, 0x8D, 0x01, 0x01, 0xE0]);
+        body.extend_from_slice(&[
+            0x86, 0x6A, 0x69, 0xDE, 0xA0, 0xC1, 0xD3, 0x43, 0x59, 0x5B, 0x57, 0x15, 0x18, 0x63,
+            0x5B, 0x7D, 0x61, 0x1A, 0xF1, 0xFC, 0xB3, 0xA6, 0x95, 0xB7, 0x91, 0x6F, 0x66, 0xF1,
+            0xAD, 0x46, 0x3A, 0x46,
+        ]);
+        let build = |body: &[u8]| {
+            let [low, high] = (body.len() as u16).to_le_bytes();
+            let mut frame = vec![0x68, low, high, low, high, 0x68];
+            frame.extend_from_slice(body);
+            frame.push(body.iter().fold(0u8, |sum, b| sum.wrapping_add(*b)));
+            frame.push(0x16);
+            frame
+        };
+        let frame = build(&body);
+        let analyzer = Analyzer::with_builtin();
+        set_key(&analyzer, &frame);
+        analyzer
+            .key_store()
+            .set_key(
+                &FrameCsg::get_csg_adress(&frame),
+                KeyUsage::Data,
+                Some(DATA_KEY),
+            )
+            .unwrap();
+
+        let (_, result) = analyzer.process_frame(&frame, "南网");
+        let mac = find(&result, "MAC校验").unwrap();
+        assert_eq!(mac["description"], "MAC校验通过");
+        assert_eq!(mac["position"], serde_json::json!([25, 29]));
+        let plain = find(&result, "解密数据").unwrap();
+        assert_eq!(
+            plain["data"],
+            FrameFun::get_data_str_with_space(&(1..=20).collect::<Vec<u8>>())
+        );
+        assert_eq!(plain["position"], serde_json::json!([35, 67]));
+
+        body[19] ^= 0xFF;
+        let (_, result) = analyzer.process_frame(&build(&body), "南网");
+        let mac = find(&result, "MAC校验").unwrap();
+        assert!(mac["description"]
+            .as_str()
+            .unwrap()
+            .starts_with("MAC校验失败"));
+    }
+}
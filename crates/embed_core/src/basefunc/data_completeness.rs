@@ -0,0 +1,473 @@
+use crate::basefunc::frame_csg::FrameCsg;
+use crate::basefunc::frame_err::CustomError;
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime, NaiveTime, Timelike};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// 已采集到的一条时序数据(测量点 + 数据项 + 数据时标)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DataSample {
+    pub point: u16,
+    pub item: String,
+    pub time: NaiveDateTime,
+}
+
+/// 应采的一组测量点/数据项
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExpectedSeries {
+    pub point: u16,
+    pub item: String,
+}
+
+/// 单个测量点/数据项的完整率统计
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompletenessEntry {
+    pub point: u16,
+    pub item: String,
+    pub expected: usize,
+    pub actual: usize,
+    pub missing: Vec<NaiveDateTime>,
+    /// 时标不在数据密度时间点上的数据，不计入完整率
+    pub misaligned: Vec<NaiveDateTime>,
+    pub rate: f64,
+}
+
+/// 完整率报表
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompletenessReport {
+    pub density: u8,
+    pub density_name: String,
+    pub start: NaiveDateTime,
+    pub end: NaiveDateTime,
+    pub expected_total: usize,
+    pub actual_total: usize,
+    pub misaligned_total: usize,
+    pub rate: f64,
+    pub entries: Vec<CompletenessEntry>,
+}
+
+/// 单个测量点/数据项已采集到的时标
+#[derive(Default)]
+struct SeriesTimes {
+    aligned: BTreeSet<NaiveDateTime>,
+    misaligned: BTreeSet<NaiveDateTime>,
+}
+
+pub struct DataCompleteness;
+
+impl DataCompleteness {
+    /// 数据密度对应的分钟间隔，日/月冻结及终端实际间隔返回None
+    pub fn density_minutes(density: u8) -> Option<i64> {
+        match density {
+            1 => Some(1),
+            2 => Some(5),
+            3 => Some(15),
+            4 => Some(30),
+            5 => Some(60),
+            _ => None,
+        }
+    }
+
+    /// 将时标对齐到数据密度的时间点(向下取整)
+    pub fn align_time(density: u8, time: NaiveDateTime) -> Option<NaiveDateTime> {
+        match density {
+            1..=5 => {
+                let interval = Self::density_minutes(density)?;
+                let minutes = time.hour() as i64 * 60 + time.minute() as i64;
+                let aligned = minutes - minutes % interval;
+                let day_start = time.date().and_time(NaiveTime::MIN);
+                Some(day_start + Duration::minutes(aligned))
+            }
+            6 => Some(time.date().and_time(NaiveTime::MIN)),
+            7 => NaiveDate::from_ymd_opt(time.year(), time.month(), 1)
+                .map(|date| date.and_time(NaiveTime::MIN)),
+            _ => None,
+        }
+    }
+
+    fn next_time(density: u8, time: NaiveDateTime) -> Option<NaiveDateTime> {
+        match density {
+            1..=5 => Some(time + Duration::minutes(Self::density_minutes(density)?)),
+            6 => Some(time + Duration::days(1)),
+            7 => {
+                let (year, month) = if time.month() == 12 {
+                    (time.year() + 1, 1)
+                } else {
+                    (time.year(), time.month() + 1)
+                };
+                NaiveDate::from_ymd_opt(year, month, 1).map(|date| date.and_time(NaiveTime::MIN))
+            }
+            _ => None,
+        }
+    }
+
+    /// 计算时间范围[start, end]内按数据密度应有的全部时标
+    pub fn expected_times(
+        density: u8,
+        start: NaiveDateTime,
+        end: NaiveDateTime,
+    ) -> Result<Vec<NaiveDateTime>, CustomError> {
+        if !(1..=7).contains(&density) {
+            return Err(CustomError::new(
+                1,
+                format!(
+                    "数据密度[{}]-{}无法计算应采时标",
+                    density,
+                    FrameCsg::get_data_dinsty(density)
+                ),
+            ));
+        }
+        if start > end {
+            return Err(CustomError::new(
+                2,
+                format!("起始时间{}晚于结束时间{}", start, end),
+            ));
+        }
+
+        let mut times = Vec::new();
+        let mut cur = Self::align_time(density, start).unwrap();
+        if cur < start {
+            cur = Self::next_time(density, cur).unwrap();
+        }
+        while cur <= end {
+            times.push(cur);
+            cur = match Self::next_time(density, cur) {
+                Some(next) => next,
+                None => break,
+            };
+        }
+        Ok(times)
+    }
+
+    /// 统计各测量点/数据项的缺失时标和完整率
+    ///
+    /// # Arguments
+    ///
+    /// * `density` - 数据密度，取值同`FrameCsg::get_data_dinsty`
+    /// * `start` - 起始时间(含)
+    /// * `end` - 结束时间(含)
+    /// * `samples` - 已采集到的数据
+    /// * `series` - 应采的测量点/数据项，为空时取`samples`中出现的组合
+    ///
+    /// # Returns
+    ///
+    /// 完整率报表，完整率为百分比；重复的时标只计一次，
+    /// 不在数据密度时间点上的时标记为misaligned，不计入完整率
+    pub fn analyze(
+        density: u8,
+        start: NaiveDateTime,
+        end: NaiveDateTime,
+        samples: &[DataSample],
+        series: &[ExpectedSeries],
+    ) -> Result<CompletenessReport, CustomError> {
+        let expected_times = Self::expected_times(density, start, end)?;
+
+        let mut collected: BTreeMap<(u16, String), SeriesTimes> = BTreeMap::new();
+        for expected in series {
+            collected
+                .entry((expected.point, expected.item.to_uppercase()))
+                .or_default();
+        }
+        for sample in samples {
+            let key = (sample.point, sample.item.to_uppercase());
+            if !series.is_empty() && !collected.contains_key(&key) {
+                continue;
+            }
+            if sample.time < start || sample.time > end {
+                continue;
+            }
+            let times = collected.entry(key).or_default();
+            if Self::align_time(density, sample.time) == Some(sample.time) {
+                times.aligned.insert(sample.time);
+            } else {
+                times.misaligned.insert(sample.time);
+            }
+        }
+
+        let mut entries = Vec::new();
+        let mut actual_total = 0;
+        let mut misaligned_total = 0;
+        for (
+            (point, item),
+            SeriesTimes {
+                aligned,
+                misaligned,
+            },
+        ) in collected
+        {
+            let missing: Vec<NaiveDateTime> = expected_times
+                .iter()
+                .filter(|time| !aligned.contains(time))
+                .cloned()
+                .collect();
+            let actual = expected_times.len() - missing.len();
+            actual_total += actual;
+            misaligned_total += misaligned.len();
+            entries.push(CompletenessEntry {
+                point,
+                item,
+                expected: expected_times.len(),
+                actual,
+                missing,
+                misaligned: misaligned.into_iter().collect(),
+                rate: Self::percent(actual, expected_times.len()),
+            });
+        }
+
+        let expected_total = expected_times.len() * entries.len();
+        Ok(CompletenessReport {
+            density,
+            density_name: FrameCsg::get_data_dinsty(density).to_string(),
+            start,
+            end,
+            expected_total,
+            actual_total,
+            misaligned_total,
+            rate: Self::percent(actual_total, expected_total),
+            entries,
+        })
+    }
+
+    fn percent(actual: usize, expected: usize) -> f64 {
+        if expected == 0 {
+            return 100.0;
+        }
+        (actual as f64 * 10000.0 / expected as f64).round() / 100.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn time(s: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S").unwrap()
+    }
+
+    fn sample(point: u16, item: &str, at: &str) -> DataSample {
+        DataSample {
+            point,
+            item: item.to_string(),
+            time: time(at),
+        }
+    }
+
+    fn series(point: u16, item: &str) -> ExpectedSeries {
+        ExpectedSeries {
+            point,
+            item: item.to_string(),
+        }
+    }
+
+    #[test]
+    fn report_gaps() {
+        let samples = vec![
+            sample(1, "05060101", "2024-01-01 00:00:00"),
+            sample(1, "05060101", "2024-01-01 00:30:00"),
+            sample(1, "05060101", "2024-01-01 01:00:00"),
+        ];
+        let report = DataCompleteness::analyze(
+            3,
+            time("2024-01-01 00:00:00"),
+            time("2024-01-01 01:00:00"),
+            &samples,
+            &[],
+        )
+        .unwrap();
+
+        assert_eq!(report.expected_total, 5);
+        assert_eq!(report.actual_total, 3);
+        assert_eq!(report.rate, 60.0);
+        let entry = &report.entries[0];
+        assert_eq!(
+            entry.missing,
+            vec![time("2024-01-01 00:15:00"), time("2024-01-01 00:45:00")]
+        );
+    }
+
+    #[test]
+    fn expected_series_without_samples() {
+        let samples = vec![sample(1, "05060101", "2024-01-01 00:00:00")];
+        let report = DataCompleteness::analyze(
+            6,
+            time("2024-01-01 00:00:00"),
+            time("2024-01-02 00:00:00"),
+            &samples,
+            &[series(1, "05060101"), series(2, "05060101")],
+        )
+        .unwrap();
+
+        assert_eq!(report.entries.len(), 2);
+        assert_eq!(report.entries[0].rate, 50.0);
+        assert_eq!(report.entries[1].actual, 0);
+        assert_eq!(report.entries[1].missing.len(), 2);
+        assert_eq!(report.rate, 25.0);
+    }
+
+    #[test]
+    fn ignore_unexpected_series() {
+        let samples = vec![
+            sample(1, "05060101", "2024-01-01 00:00:00"),
+            sample(9, "05060101", "2024-01-01 00:00:00"),
+        ];
+        let report = DataCompleteness::analyze(
+            6,
+            time("2024-01-01 00:00:00"),
+            time("2024-01-01 00:00:00"),
+            &samples,
+            &[series(1, "05060101")],
+        )
+        .unwrap();
+
+        assert_eq!(report.entries.len(), 1);
+        assert_eq!(report.entries[0].point, 1);
+        assert_eq!(report.rate, 100.0);
+    }
+
+    #[test]
+    fn count_duplicates_once() {
+        let samples = vec![
+            sample(1, "05060101", "2024-01-01 00:00:00"),
+            sample(1, "05060101", "2024-01-01 00:00:00"),
+            sample(1, "05060101", "2024-01-01 00:00:00"),
+        ];
+        let report = DataCompleteness::analyze(
+            5,
+            time("2024-01-01 00:00:00"),
+            time("2024-01-01 01:00:00"),
+            &samples,
+            &[],
+        )
+        .unwrap();
+
+        assert_eq!(report.actual_total, 1);
+        assert_eq!(report.rate, 50.0);
+    }
+
+    #[test]
+    fn report_off_grid_samples_as_misaligned() {
+        // 00:07和00:16不在15分钟时间点上，不能补足00:00和00:15
+        let samples = vec![
+            sample(1, "05060101", "2024-01-01 00:07:00"),
+            sample(1, "05060101", "2024-01-01 00:16:00"),
+            sample(1, "05060101", "2024-01-01 00:30:00"),
+            sample(1, "05060101", "2024-01-01 00:30:30"),
+        ];
+        let report = DataCompleteness::analyze(
+            3,
+            time("2024-01-01 00:00:00"),
+            time("2024-01-01 00:30:00"),
+            &samples,
+            &[],
+        )
+        .unwrap();
+
+        let entry = &report.entries[0];
+        assert_eq!(entry.actual, 1);
+        assert_eq!(
+            entry.missing,
+            vec![time("2024-01-01 00:00:00"), time("2024-01-01 00:15:00")]
+        );
+        assert_eq!(
+            entry.misaligned,
+            vec![time("2024-01-01 00:07:00"), time("2024-01-01 00:16:00")]
+        );
+        assert_eq!(report.misaligned_total, 2);
+        assert_eq!(report.rate, 33.33);
+    }
+
+    #[test]
+    fn skip_samples_outside_range() {
+        let samples = vec![
+            sample(1, "05060101", "2023-12-31 23:45:00"),
+            sample(1, "05060101", "2024-01-01 00:15:00"),
+        ];
+        let report = DataCompleteness::analyze(
+            3,
+            time("2024-01-01 00:00:00"),
+            time("2024-01-01 00:14:59"),
+            &samples,
+            &[series(1, "05060101")],
+        )
+        .unwrap();
+
+        assert_eq!(report.entries.len(), 1);
+        assert_eq!(report.expected_total, 1);
+        assert_eq!(report.actual_total, 0);
+        assert_eq!(report.misaligned_total, 0);
+    }
+
+    #[test]
+    fn expected_times_per_density() {
+        let start = time("2024-01-01 00:00:00");
+        let end = time("2024-01-01 01:00:00");
+        let counts: Vec<usize> = (1..=5)
+            .map(|density| {
+                DataCompleteness::expected_times(density, start, end)
+                    .unwrap()
+                    .len()
+            })
+            .collect();
+        assert_eq!(counts, vec![61, 13, 5, 3, 2]);
+    }
+
+    #[test]
+    fn expected_times_range_boundaries() {
+        // 起始时间不在时间点上时从下一个时间点开始，结束时间包含在内
+        let times = DataCompleteness::expected_times(
+            4,
+            time("2024-01-01 00:10:00"),
+            time("2024-01-01 01:00:00"),
+        )
+        .unwrap();
+        assert_eq!(
+            times,
+            vec![time("2024-01-01 00:30:00"), time("2024-01-01 01:00:00")]
+        );
+
+        let times = DataCompleteness::expected_times(
+            5,
+            time("2024-01-01 01:00:00"),
+            time("2024-01-01 01:00:00"),
+        )
+        .unwrap();
+        assert_eq!(times, vec![time("2024-01-01 01:00:00")]);
+    }
+
+    #[test]
+    fn expected_times_day_and_month() {
+        let days = DataCompleteness::expected_times(
+            6,
+            time("2024-02-28 00:00:00"),
+            time("2024-03-01 00:00:00"),
+        )
+        .unwrap();
+        assert_eq!(days.len(), 3);
+        assert_eq!(days[1], time("2024-02-29 00:00:00"));
+
+        let months = DataCompleteness::expected_times(
+            7,
+            time("2023-11-15 00:00:00"),
+            time("2024-02-01 00:00:00"),
+        )
+        .unwrap();
+        assert_eq!(
+            months,
+            vec![
+                time("2023-12-01 00:00:00"),
+                time("2024-01-01 00:00:00"),
+                time("2024-02-01 00:00:00"),
+            ]
+        );
+    }
+
+    #[test]
+    fn reject_invalid_density_and_range() {
+        let start = time("2024-01-01 00:00:00");
+        let end = time("2024-01-02 00:00:00");
+        assert!(DataCompleteness::expected_times(0, start, end).is_err());
+        assert!(DataCompleteness::expected_times(8, start, end).is_err());
+        assert!(DataCompleteness::expected_times(3, end, start).is_err());
+        assert!(DataCompleteness::analyze(0, start, end, &[], &[]).is_err());
+    }
+}
@@ -0,0 +1,787 @@
+use crate::basefunc::frame_csg::FrameCsg;
+use crate::basefunc::frame_fun::FrameFun;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap, VecDeque};
+
+const AFN_FILE_TRANSFER: u8 = 0x0F;
+const BODY_START: usize = 16;
+const DA_LENGTH: usize = 2;
+const DI_LENGTH: usize = 4;
+// 文件性质(2)+文件总段数(2)+文件大小(4)+文件名称(32)+总校验(2)
+const FILE_INFO_LENGTH: usize = 42;
+const FILE_NAME_LENGTH: usize = 32;
+// 查询起始段号(2)+查询结束段号(2)
+const QUERY_RANGE_LENGTH: usize = 4;
+const SEGMENT_HEAD_LENGTH: usize = 4;
+const CHECK_LENGTH: usize = 2;
+const CUSTOM_HEAD_LENGTH: usize = 84;
+
+const DI_START: &str = "E3010001";
+const DI_SEGMENT: &str = "E3010002";
+const DI_QUERY: &str = "E3010003";
+const FILE_TYPE_CLEAR: u16 = 0x000F;
+
+// 一帧下行报文中等待应答的数据标识和段号
+type PendingRequests = VecDeque<(String, Option<u16>)>;
+
+/// 传输过程中的一个事件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransferEvent {
+    /// 报文在输入中的序号，从0开始
+    pub frame_index: usize,
+    pub timestamp: Option<i64>,
+    pub event: String,
+    pub segment: Option<u16>,
+    pub description: String,
+}
+
+/// 一次文件传输的重组结果
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FileTransferSummary {
+    pub terminal: String,
+    pub file_type: Option<String>,
+    pub file_name: Option<String>,
+    pub total_segments: Option<u16>,
+    pub file_size: Option<u32>,
+    pub received_size: usize,
+    pub declared_checksum: Option<String>,
+    pub calculated_checksum: Option<String>,
+    /// 文件未收全时为None
+    pub checksum_ok: Option<bool>,
+    pub complete: bool,
+    pub cancelled: bool,
+    pub received_segments: usize,
+    pub missing_segments: Vec<u16>,
+    pub retransmitted_segments: Vec<u16>,
+    /// 重传内容与之前不一致的段
+    pub conflicting_segments: Vec<u16>,
+    pub out_of_order_segments: Vec<u16>,
+    /// 段校验错误或段号超出总段数的段
+    pub bad_segments: Vec<u16>,
+    /// 文件收全时按段号拼接的文件内容(十六进制)，未收全或已清除时为空
+    pub data: String,
+    pub timeline: Vec<TransferEvent>,
+}
+
+/// 文件传输重组结果
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FileTransferReport {
+    pub total_frames: usize,
+    pub transfer_frames: usize,
+    /// 没有对应文件传输启动的文件传输报文数，这些报文不参与重组
+    pub untracked_frames: usize,
+    pub transfers: Vec<FileTransferSummary>,
+}
+
+#[derive(Debug, Default)]
+struct TransferSession {
+    terminal: String,
+    file_type: Option<u16>,
+    file_name: Option<String>,
+    total_segments: Option<u16>,
+    file_size: Option<u32>,
+    declared_checksum: Option<u16>,
+    cancelled: bool,
+    segments: BTreeMap<u16, Vec<u8>>,
+    max_segment: Option<u16>,
+    retransmitted: BTreeSet<u16>,
+    conflicting: BTreeSet<u16>,
+    out_of_order: BTreeSet<u16>,
+    bad: BTreeSet<u16>,
+    timeline: Vec<TransferEvent>,
+}
+
+impl TransferSession {
+    fn missing_segments(&self) -> Vec<u16> {
+        let end = match (self.total_segments, self.max_segment) {
+            (Some(total), _) => total,
+            (None, Some(max)) => max.saturating_add(1),
+            (None, None) => 0,
+        };
+        (0..end)
+            .filter(|index| !self.segments.contains_key(index))
+            .collect()
+    }
+
+    fn data(&self) -> Vec<u8> {
+        self.segments.values().flatten().copied().collect()
+    }
+
+    fn is_complete(&self) -> bool {
+        !self.cancelled && self.total_segments.is_some() && self.missing_segments().is_empty()
+    }
+}
+
+/// 南网13文件传输(AFN=0FH)重组器，按终端跟踪文件传输启动、文件段和终端应答
+///
+/// 文件段号从0开始；段校验和总校验均为字节累加和的低16位。
+/// 每个终端同一时间只有一个进行中的传输，新的文件传输启动会结束之前的传输。
+/// 只有文件传输启动(E3010001)会建立传输，之前的文件段、应答和错误报文只计数。
+#[derive(Debug, Default)]
+pub struct FileTransferReassembler {
+    total_frames: usize,
+    transfer_frames: usize,
+    untracked_frames: usize,
+    sessions: Vec<TransferSession>,
+    // 终端 -> 进行中的传输
+    active: HashMap<String, usize>,
+    // (终端, SEQ) -> 等待应答的请求
+    pending: HashMap<(String, u8), PendingRequests>,
+}
+
+impl FileTransferReassembler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    /// 加入一帧报文，非文件传输报文只计数
+    ///
+    /// # Arguments
+    ///
+    /// * `frame` - 报文
+    /// * `timestamp` - 报文收发时间(毫秒)，用于传输时间线
+    pub fn add_frame(&mut self, frame: &[u8], timestamp: Option<i64>) {
+        let frame_index = self.total_frames;
+        self.total_frames += 1;
+
+        let mut frame = frame;
+        if frame.len() > CUSTOM_HEAD_LENGTH
+            && FrameCsg::is_contoine_custom_head(&frame[..CUSTOM_HEAD_LENGTH])
+        {
+            frame = &frame[CUSTOM_HEAD_LENGTH..];
+        }
+        if !FrameCsg::is_csg_frame(frame) || frame[14] != AFN_FILE_TRANSFER {
+            return;
+        }
+        self.transfer_frames += 1;
+
+        let length = frame.len();
+        let terminal = FrameCsg::get_csg_adress(frame);
+        let dir = frame[6] >> 7;
+        let seq = frame[15] & 0x0F;
+        let event = |event: &str, segment: Option<u16>, description: String| TransferEvent {
+            frame_index,
+            timestamp,
+            event: event.to_string(),
+            segment,
+            description,
+        };
+        let was_active = self.active.contains_key(&terminal);
+        if FrameFun::calculate_cs(&frame[6..length - 2]) != frame[length - 2] {
+            match self.active_session(&terminal) {
+                Some(session) => session.timeline.push(event(
+                    "报文错误",
+                    None,
+                    "校验和错误，报文未参与重组".to_string(),
+                )),
+                None => self.untracked_frames += 1,
+            }
+            return;
+        }
+
+        // PW和Tp在各组之后，按数据标识的结构逐组解析，遇到无法解析的组即停止
+        let data = &frame[BODY_START..length - 2];
+        let mut pos = 0;
+        let mut requests = VecDeque::new();
+        while pos + DA_LENGTH + DI_LENGTH <= data.len() {
+            let di = FrameFun::get_data_str_reverser(
+                &data[pos + DA_LENGTH..pos + DA_LENGTH + DI_LENGTH],
+            );
+            pos += DA_LENGTH + DI_LENGTH;
+            let content = &data[pos..];
+            let consumed = match (dir, di.as_str()) {
+                (0, DI_START) if content.len() >= FILE_INFO_LENGTH => {
+                    self.start(&terminal, &content[..FILE_INFO_LENGTH], event);
+                    requests.push_back((di, None));
+                    FILE_INFO_LENGTH
+                }
+                (0, DI_SEGMENT) if content.len() >= SEGMENT_HEAD_LENGTH => {
+                    let index = u16::from_le_bytes([content[0], content[1]]);
+                    let segment_length = u16::from_le_bytes([content[2], content[3]]) as usize;
+                    let end = SEGMENT_HEAD_LENGTH + segment_length;
+                    if content.len() < end + CHECK_LENGTH {
+                        if let Some(session) = self.active_session(&terminal) {
+                            session.timeline.push(event(
+                                "报文错误",
+                                Some(index),
+                                format!("文件段长度{}超出报文剩余数据", segment_length),
+                            ));
+                        }
+                        break;
+                    }
+                    let check = u16::from_le_bytes([content[end], content[end + 1]]);
+                    self.segment(
+                        &terminal,
+                        index,
+                        &content[SEGMENT_HEAD_LENGTH..end],
+                        check,
+                        event,
+                    );
+                    requests.push_back((di, Some(index)));
+                    end + CHECK_LENGTH
+                }
+                (0, DI_QUERY) if content.len() >= FILE_INFO_LENGTH + QUERY_RANGE_LENGTH => {
+                    let range = &content[FILE_INFO_LENGTH..];
+                    if let Some(session) = self.active_session(&terminal) {
+                        session.timeline.push(event(
+                            "查询文件信息",
+                            None,
+                            format!(
+                                "查询段号{}~{}的接收情况",
+                                u16::from_le_bytes([range[0], range[1]]),
+                                u16::from_le_bytes([range[2], range[3]])
+                            ),
+                        ));
+                    }
+                    requests.push_back((di, None));
+                    FILE_INFO_LENGTH + QUERY_RANGE_LENGTH
+                }
+                (1, DI_START) | (1, DI_SEGMENT) if !content.is_empty() => {
+                    self.response(&terminal, seq, &di, content[0], event);
+                    1
+                }
+                (1, DI_QUERY) => match self.query_response(&terminal, content, event) {
+                    Some(consumed) => consumed,
+                    None => break,
+                },
+                _ => break,
+            };
+            pos += consumed;
+        }
+        if !was_active && !self.active.contains_key(&terminal) {
+            self.untracked_frames += 1;
+        }
+        if !requests.is_empty() {
+            self.pending.insert((terminal, seq), requests);
+        }
+    }
+
+    /// 输出各次传输的重组结果
+    pub fn report(&self) -> FileTransferReport {
+        FileTransferReport {
+            total_frames: self.total_frames,
+            transfer_frames: self.transfer_frames,
+            untracked_frames: self.untracked_frames,
+            transfers: self.sessions.iter().map(Self::summary).collect(),
+        }
+    }
+
+    /// 第index次传输重组后的文件内容，传输已清除、有缺失的段或总校验不符时返回错误
+    pub fn image(&self, index: usize) -> Result<Vec<u8>, String> {
+        let session = self
+            .sessions
+            .get(index)
+            .ok_or_else(|| format!("没有第{}次文件传输", index))?;
+        if session.cancelled {
+            return Err("文件传输已清除".to_string());
+        }
+        let missing = session.missing_segments();
+        if !missing.is_empty() {
+            return Err(format!("文件未收全，缺少{}", Self::segments_text(&missing)));
+        }
+        let data = session.data();
+        let calculated = Self::checksum(&data);
+        if let Some(declared) = session.declared_checksum.filter(|&c| c != calculated) {
+            return Err(format!(
+                "文件总校验{:04X}与重组后的计算值{:04X}不符",
+                declared, calculated
+            ));
+        }
+        Ok(data)
+    }
+
+    fn active_session(&mut self, terminal: &str) -> Option<&mut TransferSession> {
+        let index = *self.active.get(terminal)?;
+        self.sessions.get_mut(index)
+    }
+
+    fn start(
+        &mut self,
+        terminal: &str,
+        info: &[u8],
+        event: impl Fn(&str, Option<u16>, String) -> TransferEvent,
+    ) {
+        let file_type = u16::from_le_bytes([info[0], info[1]]);
+        if let Some(index) = self.active.remove(terminal) {
+            let session = &mut self.sessions[index];
+            if file_type == FILE_TYPE_CLEAR {
+                session.cancelled = true;
+                session
+                    .timeline
+                    .push(event("清除文件", None, "清除正在传输的文件".to_string()));
+                return;
+            }
+            if !session.is_complete() {
+                session.timeline.push(event(
+                    "传输中断",
+                    None,
+                    "传输未完成即重新启动文件传输".to_string(),
+                ));
+            }
+        } else if file_type == FILE_TYPE_CLEAR {
+            return;
+        }
+
+        let total_segments = u16::from_le_bytes([info[2], info[3]]);
+        let file_size = u32::from_le_bytes([info[4], info[5], info[6], info[7]]);
+        let name = &info[8..8 + FILE_NAME_LENGTH];
+        let file_name = String::from_utf8_lossy(name)
+            .trim_end_matches(['\0', ' '])
+            .to_string();
+        let checksum = u16::from_le_bytes([info[40], info[41]]);
+        self.sessions.push(TransferSession {
+            terminal: terminal.to_string(),
+            ..Default::default()
+        });
+        self.active
+            .insert(terminal.to_string(), self.sessions.len() - 1);
+        let session = self.sessions.last_mut().unwrap();
+        session.file_type = Some(file_type);
+        session.file_name = Some(file_name.clone());
+        session.total_segments = Some(total_segments);
+        session.file_size = Some(file_size);
+        session.declared_checksum = Some(checksum);
+        session.timeline.push(event(
+            "文件传输启动",
+            None,
+            format!(
+                "文件名称：{}，文件性质：{:04X}，文件大小：{}字节，总段数：{}，总校验：{:04X}",
+                file_name, file_type, file_size, total_segments, checksum
+            ),
+        ));
+    }
+
+    fn segment(
+        &mut self,
+        terminal: &str,
+        index: u16,
+        content: &[u8],
+        check: u16,
+        event: impl Fn(&str, Option<u16>, String) -> TransferEvent,
+    ) {
+        let Some(session) = self.active_session(terminal) else {
+            return;
+        };
+        if session.total_segments.is_some_and(|total| index >= total) {
+            session.bad.insert(index);
+            session.timeline.push(event(
+                "段号错误",
+                Some(index),
+                format!(
+                    "段号超出总段数{}",
+                    session.total_segments.unwrap_or_default()
+                ),
+            ));
+            return;
+        }
+        let calculated = Self::checksum(content);
+        if calculated != check {
+            if !session.segments.contains_key(&index) {
+                session.bad.insert(index);
+            }
+            session.timeline.push(event(
+                "段校验错误",
+                Some(index),
+                format!("段校验{:04X}，计算值{:04X}", check, calculated),
+            ));
+            return;
+        }
+        session.bad.remove(&index);
+
+        let (name, description) = if let Some(previous) = session.segments.get(&index) {
+            session.retransmitted.insert(index);
+            if previous.as_slice() == content {
+                ("重传", format!("段长度{}，内容与之前一致", content.len()))
+            } else {
+                session.conflicting.insert(index);
+                (
+                    "重传",
+                    format!("段长度{}，内容与之前不一致，以本次为准", content.len()),
+                )
+            }
+        } else {
+            match session.max_segment {
+                Some(max) if index < max => {
+                    session.out_of_order.insert(index);
+                    (
+                        "乱序",
+                        format!("段长度{}，晚于第{}段到达", content.len(), max),
+                    )
+                }
+                Some(max) if index > max.saturating_add(1) => (
+                    "文件段",
+                    format!(
+                        "段长度{}，跳过{}",
+                        content.len(),
+                        Self::range_text(max + 1, index - 1)
+                    ),
+                ),
+                None if index > 0 => (
+                    "文件段",
+                    format!(
+                        "段长度{}，跳过{}",
+                        content.len(),
+                        Self::range_text(0, index - 1)
+                    ),
+                ),
+                _ => ("文件段", format!("段长度{}", content.len())),
+            }
+        };
+        session.segments.insert(index, content.to_vec());
+        session.max_segment = Some(session.max_segment.map_or(index, |max| max.max(index)));
+        session.timeline.push(event(name, Some(index), description));
+    }
+
+    fn response(
+        &mut self,
+        terminal: &str,
+        seq: u8,
+        di: &str,
+        errcode: u8,
+        event: impl Fn(&str, Option<u16>, String) -> TransferEvent,
+    ) {
+        // 按SEQ找到对应的下行请求，取出同一数据标识的第一项
+        let segment = self
+            .pending
+            .get_mut(&(terminal.to_string(), seq))
+            .and_then(|requests| {
+                let position = requests.iter().position(|(item, _)| item == di)?;
+                requests.remove(position)
+            })
+            .and_then(|(_, segment)| segment);
+        let name = if errcode == 0 {
+            "终端确认"
+        } else {
+            "终端否认"
+        };
+        let Some(session) = self.active_session(terminal) else {
+            return;
+        };
+        session.timeline.push(event(
+            name,
+            segment,
+            format!(
+                "[{}]{:02X}-{}",
+                di,
+                errcode,
+                FrameCsg::prase_err_code_result(errcode)
+            ),
+        ));
+    }
+
+    fn query_response(
+        &mut self,
+        terminal: &str,
+        content: &[u8],
+        event: impl Fn(&str, Option<u16>, String) -> TransferEvent,
+    ) -> Option<usize> {
+        let count_pos = FILE_INFO_LENGTH + QUERY_RANGE_LENGTH;
+        if content.len() < count_pos + 2 {
+            return None;
+        }
+        let count = u16::from_le_bytes([content[count_pos], content[count_pos + 1]]) as usize;
+        let list_end = count_pos + 2 + 2 * count;
+        if content.len() < list_end {
+            return None;
+        }
+        let missing: Vec<String> = content[count_pos + 2..list_end]
+            .chunks(2)
+            .map(|index| u16::from_le_bytes([index[0], index[1]]).to_string())
+            .collect();
+        let description = if missing.is_empty() {
+            "查询范围内各段均已接收".to_string()
+        } else {
+            format!("终端未成功接收{}段：{}", count, missing.join(","))
+        };
+        if let Some(session) = self.active_session(terminal) {
+            session
+                .timeline
+                .push(event("终端文件信息", None, description));
+        }
+        Some(list_end)
+    }
+
+    fn summary(session: &TransferSession) -> FileTransferSummary {
+        let data = session.data();
+        let complete = session.is_complete();
+        let calculated = Self::checksum(&data);
+        FileTransferSummary {
+            terminal: session.terminal.clone(),
+            file_type: session
+                .file_type
+                .map(|file_type| format!("{:04X}", file_type)),
+            file_name: session.file_name.clone(),
+            total_segments: session.total_segments,
+            file_size: session.file_size,
+            received_size: data.len(),
+            declared_checksum: session
+                .declared_checksum
+                .map(|checksum| format!("{:04X}", checksum)),
+            calculated_checksum: complete.then(|| format!("{:04X}", calculated)),
+            checksum_ok: session
+                .declared_checksum
+                .filter(|_| complete)
+                .map(|checksum| checksum == calculated),
+            complete,
+            cancelled: session.cancelled,
+            received_segments: session.segments.len(),
+            missing_segments: session.missing_segments(),
+            retransmitted_segments: session.retransmitted.iter().copied().collect(),
+            conflicting_segments: session.conflicting.iter().copied().collect(),
+            out_of_order_segments: session.out_of_order.iter().copied().collect(),
+            bad_segments: session.bad.iter().copied().collect(),
+            data: if complete {
+                hex::encode_upper(&data)
+            } else {
+                String::new()
+            },
+            timeline: session.timeline.clone(),
+        }
+    }
+
+    // 连续的段号合并为范围
+    fn segments_text(segments: &[u16]) -> String {
+        let mut ranges: Vec<(u16, u16)> = Vec::new();
+        for &index in segments {
+            match ranges.last_mut() {
+                Some((_, last)) if last.checked_add(1) == Some(index) => *last = index,
+                _ => ranges.push((index, index)),
+            }
+        }
+        ranges
+            .into_iter()
+            .map(|(first, last)| Self::range_text(first, last))
+            .collect::<Vec<_>>()
+            .join("、")
+    }
+
+    fn range_text(first: u16, last: u16) -> String {
+        if first == last {
+            format!("第{}段", first)
+        } else {
+            format!("第{}~{}段", first, last)
+        }
+    }
+
+    fn checksum(data: &[u8]) -> u16 {
+        data.iter()
+            .fold(0u16, |sum, &byte| sum.wrapping_add(byte as u16))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FILE_DATA: [u8; 3] = [0x11, 0x22, 0x33];
+
+    // 南网文件传输(AFN=0FH)报文
+    fn csg_frame(control: u8, seq: u8, data: &[u8]) -> Vec<u8> {
+        let mut body = vec![control, 0x00, 0x44, 0x12, 0x34, 0x56, 0x00, 0x00, 0x0F, seq];
+        body.extend_from_slice(data);
+        let [low, high] = (body.len() as u16).to_le_bytes();
+        let mut frame = vec![0x68, low, high, low, high, 0x68];
+        frame.extend_from_slice(&body);
+        frame.push(FrameFun::calculate_cs(&body));
+        frame.push(0x16);
+        frame
+    }
+
+    // DA=P0，di为数据标识的低字节在前编码
+    fn item(di: u8, content: &[u8]) -> Vec<u8> {
+        let mut data = vec![0x00, 0x00, di, 0x00, 0x01, 0xE3];
+        data.extend_from_slice(content);
+        data
+    }
+
+    fn file_info(file_type: u16, total: u16, size: u32, checksum: u16) -> Vec<u8> {
+        let mut info = file_type.to_le_bytes().to_vec();
+        info.extend_from_slice(&total.to_le_bytes());
+        info.extend_from_slice(&size.to_le_bytes());
+        let mut name = b"update.bin".to_vec();
+        name.resize(FILE_NAME_LENGTH, 0);
+        info.extend(name);
+        info.extend_from_slice(&checksum.to_le_bytes());
+        info
+    }
+
+    fn start(seq: u8, total: u16, data: &[u8]) -> Vec<u8> {
+        let checksum = FileTransferReassembler::checksum(data);
+        start_with_checksum(seq, total, data.len() as u32, checksum)
+    }
+
+    fn start_with_checksum(seq: u8, total: u16, size: u32, checksum: u16) -> Vec<u8> {
+        csg_frame(
+            0x4A,
+            seq,
+            &item(0x01, &file_info(0x0001, total, size, checksum)),
+        )
+    }
+
+    fn segment(seq: u8, index: u16, content: &[u8]) -> Vec<u8> {
+        let check = FileTransferReassembler::checksum(content);
+        segment_with_check(seq, index, content, check)
+    }
+
+    fn segment_with_check(seq: u8, index: u16, content: &[u8], check: u16) -> Vec<u8> {
+        let mut data = index.to_le_bytes().to_vec();
+        data.extend_from_slice(&(content.len() as u16).to_le_bytes());
+        data.extend_from_slice(content);
+        data.extend_from_slice(&check.to_le_bytes());
+        csg_frame(0x4A, seq, &item(0x02, &data))
+    }
+
+    fn reassemble(frames: &[Vec<u8>]) -> FileTransferReassembler {
+        let mut reassembler = FileTransferReassembler::new();
+        for (index, frame) in frames.iter().enumerate() {
+            reassembler.add_frame(frame, Some(index as i64 * 100));
+        }
+        reassembler
+    }
+
+    fn events(summary: &FileTransferSummary) -> Vec<&str> {
+        summary
+            .timeline
+            .iter()
+            .map(|event| event.event.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn complete_transfer() {
+        let reassembler = reassemble(&[
+            start(1, 3, &FILE_DATA),
+            segment(2, 0, &FILE_DATA[..1]),
+            csg_frame(0x88, 2, &item(0x02, &[0x00])),
+            segment(3, 1, &FILE_DATA[1..2]),
+            segment(4, 2, &FILE_DATA[2..]),
+        ]);
+        let report = reassembler.report();
+        assert_eq!(report.transfer_frames, 5);
+        assert_eq!(report.untracked_frames, 0);
+        let summary = &report.transfers[0];
+        assert_eq!(summary.terminal, "124400005634");
+        assert_eq!(summary.file_name.as_deref(), Some("update.bin"));
+        assert!(summary.complete);
+        assert_eq!(summary.checksum_ok, Some(true));
+        assert_eq!(summary.data, "112233");
+        // 终端确认按SEQ对应到第0段
+        assert_eq!(summary.timeline[2].event, "终端确认");
+        assert_eq!(summary.timeline[2].segment, Some(0));
+        assert_eq!(reassembler.image(0).unwrap(), FILE_DATA);
+        assert!(reassembler.image(1).is_err());
+    }
+
+    #[test]
+    fn checksum_mismatch() {
+        let reassembler = reassemble(&[
+            start_with_checksum(1, 1, 3, 0x1234),
+            segment(2, 0, &FILE_DATA),
+        ]);
+        let summary = &reassembler.report().transfers[0];
+        assert!(summary.complete);
+        assert_eq!(summary.checksum_ok, Some(false));
+        assert_eq!(summary.calculated_checksum.as_deref(), Some("0066"));
+        assert_eq!(
+            reassembler.image(0).unwrap_err(),
+            "文件总校验1234与重组后的计算值0066不符"
+        );
+    }
+
+    #[test]
+    fn missing_segments() {
+        let reassembler = reassemble(&[
+            start(1, 5, &[0; 5]),
+            segment(2, 0, &[0]),
+            segment(3, 3, &[0]),
+        ]);
+        let summary = &reassembler.report().transfers[0];
+        assert!(!summary.complete);
+        assert_eq!(summary.checksum_ok, None);
+        assert_eq!(summary.missing_segments, vec![1, 2, 4]);
+        assert!(summary.data.is_empty());
+        assert_eq!(summary.timeline[2].description, "段长度1，跳过第1~2段");
+        assert_eq!(
+            reassembler.image(0).unwrap_err(),
+            "文件未收全，缺少第1~2段、第4段"
+        );
+    }
+
+    #[test]
+    fn retransmission_and_out_of_order() {
+        let reassembler = reassemble(&[
+            start(1, 3, &FILE_DATA),
+            segment(2, 0, &[0xFF]),
+            segment(3, 2, &FILE_DATA[2..]),
+            segment(4, 1, &FILE_DATA[1..2]),
+            segment(5, 2, &FILE_DATA[2..]),
+            // 第0段重传内容不同，以最后一次为准
+            segment(6, 0, &FILE_DATA[..1]),
+        ]);
+        let summary = &reassembler.report().transfers[0];
+        assert_eq!(
+            events(summary),
+            ["文件传输启动", "文件段", "文件段", "乱序", "重传", "重传"]
+        );
+        assert_eq!(summary.out_of_order_segments, vec![1]);
+        assert_eq!(summary.retransmitted_segments, vec![0, 2]);
+        assert_eq!(summary.conflicting_segments, vec![0]);
+        assert_eq!(summary.checksum_ok, Some(true));
+        assert_eq!(reassembler.image(0).unwrap(), FILE_DATA);
+    }
+
+    #[test]
+    fn bad_and_untracked_frames() {
+        let mut corrupted = segment(3, 1, &[0x22]);
+        let length = corrupted.len();
+        corrupted[length - 2] ^= 0xFF;
+        let reassembler = reassemble(&[
+            // 启动之前的文件段不参与重组
+            segment(1, 0, &[0x11]),
+            start(2, 2, &[0x11, 0x22]),
+            corrupted,
+            segment_with_check(4, 0, &[0x11], 0x0000),
+            segment(5, 2, &[0x33]),
+            segment(6, 0, &[0x11]),
+        ]);
+        let report = reassembler.report();
+        assert_eq!(report.untracked_frames, 1);
+        let summary = &report.transfers[0];
+        assert_eq!(
+            events(summary),
+            [
+                "文件传输启动",
+                "报文错误",
+                "段校验错误",
+                "段号错误",
+                "文件段"
+            ]
+        );
+        assert_eq!(summary.bad_segments, vec![2]);
+        assert_eq!(summary.missing_segments, vec![1]);
+    }
+
+    #[test]
+    fn query_and_clear() {
+        let mut query = file_info(0x0001, 2, 2, 0);
+        query.extend_from_slice(&[0x00, 0x00, 0x01, 0x00]);
+        let mut query_response = query.clone();
+        query_response.extend_from_slice(&[0x01, 0x00, 0x01, 0x00]);
+        let reassembler = reassemble(&[
+            start(1, 2, &[0x11, 0x22]),
+            segment(2, 0, &[0x11]),
+            csg_frame(0x4A, 3, &item(0x03, &query)),
+            csg_frame(0x88, 3, &item(0x03, &query_response)),
+            csg_frame(0x4A, 4, &item(0x01, &file_info(FILE_TYPE_CLEAR, 0, 0, 0))),
+        ]);
+        let summary = &reassembler.report().transfers[0];
+        assert_eq!(summary.timeline[2].description, "查询段号0~1的接收情况");
+        assert_eq!(summary.timeline[3].description, "终端未成功接收1段：1");
+        assert!(summary.cancelled);
+        assert_eq!(reassembler.image(0).unwrap_err(), "文件传输已清除");
+    }
+}
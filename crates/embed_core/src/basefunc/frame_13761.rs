@@ -0,0 +1,853 @@
+use crate::analyzer::Analyzer;
+use crate::basefunc::frame_csg::FrameCsg;
+use crate::basefunc::frame_fun::FrameFun;
+use crate::basefunc::protocol::{FrameAnalisyic, ProtocolInfo};
+use crate::config::xmlconfig::XmlElement;
+use serde_json::Value;
+use tracing::{info, warn};
+
+/// Q/GDW 1376.1 主站与采集终端通信协议
+///
+/// 帧格式与南网13相近：68 L L 68 C A AFN SEQ 数据单元 附加信息AUX CS 16，
+/// 长度域D0~D1为规约标识(10)、D2~D15为用户数据长度，地址域5字节，
+/// 数据单元标识由信息点DA(Pn)和信息类DT(Fn)组成
+pub struct Frame13761;
+
+// 消息认证码PW、事件计数器EC、时间标签Tp的长度
+const PW_LENGTH: usize = 16;
+const EC_LENGTH: usize = 2;
+const TP_LENGTH: usize = 6;
+
+impl Frame13761 {
+    pub fn is_13761_frame(frame: &[u8]) -> bool {
+        if frame.len() < 16 {
+            return false;
+        }
+        if frame[0] != 0x68 || frame[5] != 0x68 {
+            return false;
+        }
+        if frame[1] != frame[3] || frame[2] != frame[4] {
+            return false;
+        }
+        let length = u16::from_le_bytes([frame[1], frame[2]]);
+        // 规约标识D1D0=10为本规约
+        if length & 0x03 != 0x02 {
+            return false;
+        }
+        (length >> 2) as usize + 8 == frame.len()
+            && frame[frame.len() - 1] == 0x16
+            && FrameFun::calculate_cs(&frame[6..frame.len() - 2]) == frame[frame.len() - 2]
+    }
+
+    pub fn analysic_13761_frame(
+        analyzer: &Analyzer,
+        frame: &[u8],
+        result_list: &mut Vec<Value>,
+        index: usize,
+        region: &str,
+    ) {
+        let protocol = ProtocolInfo::ProtocolGDW13761.name().to_string();
+        let (dir, prm, acd) = Self::analysic_head_frame(frame, result_list, index);
+        let afn = frame[12];
+        let tpv = FrameCsg::get_afn_and_seq_result_with(
+            analyzer,
+            &protocol,
+            &frame[12..14],
+            index + 12,
+            result_list,
+        );
+
+        let handler = analyzer
+            .find_afn(&protocol, afn, None, None)
+            .and_then(|entry| entry.handler);
+        let with_pw = match handler.as_deref() {
+            Some("ack") | Some("request") => false,
+            Some("command") => dir == 0,
+            Some(handler) => {
+                warn!("AFN:{:02X}的解析方式[{}]不支持", afn, handler);
+                false
+            }
+            None => false,
+        };
+        let request = handler.as_deref() == Some("request") && dir == 0 && prm == 1;
+
+        // 附加信息域：下行重要报文的PW、上行ACD=1时的EC、TpV=1时的Tp
+        let body_end = frame.len() - 2;
+        let pw_length = if with_pw { PW_LENGTH } else { 0 };
+        let ec_length = if dir == 1 && acd == 1 { EC_LENGTH } else { 0 };
+        let tp_length = if tpv { TP_LENGTH } else { 0 };
+        let data_end = body_end
+            .saturating_sub(pw_length + ec_length + tp_length)
+            .max(14);
+
+        let mut sub_result = Vec::new();
+        Self::analysic_data_units(
+            analyzer,
+            afn,
+            &frame[14..data_end],
+            index + 14,
+            dir,
+            request,
+            &protocol,
+            region,
+            &mut sub_result,
+        );
+
+        let mut pos = data_end;
+        if pw_length > 0 && pos + pw_length <= body_end {
+            FrameFun::add_data(
+                &mut sub_result,
+                "消息认证码PW".to_string(),
+                FrameFun::get_data_str_with_space(&frame[pos..pos + pw_length]),
+                "PW由主站按系统约定的认证算法产生，在主站发送的重要报文中下发给终端校验"
+                    .to_string(),
+                vec![index + pos, index + pos + pw_length],
+                None,
+                None,
+            );
+            pos += pw_length;
+        }
+        if ec_length > 0 && pos + ec_length <= body_end {
+            Self::analysic_ec(&frame[pos..pos + ec_length], index + pos, &mut sub_result);
+            pos += ec_length;
+        }
+        if tp_length > 0 && pos + tp_length <= body_end {
+            Self::analysic_tp(&frame[pos..pos + tp_length], index + pos, &mut sub_result);
+        }
+
+        FrameFun::add_data(
+            result_list,
+            "信息体".to_string(),
+            FrameFun::get_data_str_with_space(&frame[14..body_end]),
+            "".to_string(),
+            vec![index + 14, index + body_end],
+            Some(sub_result),
+            None,
+        );
+
+        FrameCsg::analyze_csg_end_frame(frame, result_list, index);
+    }
+
+    /// 解析帧头，返回传输方向、启动标志和要求访问位ACD(仅上行有效)
+    pub fn analysic_head_frame(
+        frame: &[u8],
+        result_list: &mut Vec<Value>,
+        start_pos: usize,
+    ) -> (u8, u8, u8) {
+        let length_data = &frame[1..5];
+        let length_field = u16::from_le_bytes([frame[1], frame[2]]);
+        let length = (length_field >> 2) as usize;
+        FrameFun::add_data(
+            result_list,
+            "起始符".to_string(),
+            format!("{:02X}", frame[0]),
+            "起始符".to_string(),
+            vec![start_pos, start_pos + 1],
+            None,
+            None,
+        );
+        FrameFun::add_data(
+            result_list,
+            "长度".to_string(),
+            FrameFun::get_data_str_with_space(length_data),
+            format!(
+                "规约标识={:02b}(Q/GDW 1376.1),长度={},总长度={}(总长度=长度+8)",
+                length_field & 0x03,
+                length,
+                length + 8
+            ),
+            vec![start_pos + 1, start_pos + 5],
+            None,
+            None,
+        );
+        FrameFun::add_data(
+            result_list,
+            "起始符".to_string(),
+            format!("{:02X}", frame[5]),
+            "起始符".to_string(),
+            vec![start_pos + 5, start_pos + 6],
+            None,
+            None,
+        );
+        // 控制域定义与南网13相同
+        let control = frame[6];
+        let (control_result, control_str, dir, prm) =
+            FrameCsg::get_control_code_str(control, start_pos);
+        FrameFun::add_data(
+            result_list,
+            "控制域".to_string(),
+            format!("{:02X}", control),
+            control_str,
+            vec![start_pos + 6, start_pos + 7],
+            Some(control_result),
+            None,
+        );
+        let (adress_result, ertu_adress) = Self::get_adress_result(&frame[7..12], start_pos + 7);
+        FrameFun::add_data(
+            result_list,
+            "地址域".to_string(),
+            FrameFun::get_data_str_with_space(&frame[7..12]),
+            format!("终端地址{}", ertu_adress),
+            vec![start_pos + 7, start_pos + 12],
+            Some(adress_result),
+            None,
+        );
+        (dir, prm, (control >> 5) & 0x01)
+    }
+
+    /// 地址域：行政区划码A1(BCD)、终端地址A2(BIN)、主站地址和组地址标志A3
+    pub fn get_adress_result(adress: &[u8], index: usize) -> (Vec<Value>, String) {
+        let mut adress_result: Vec<Value> = Vec::new();
+        let a1 = &adress[..2];
+        let a2 = &adress[2..4];
+        let a3 = adress[4];
+        let terminal = u16::from_le_bytes([a2[0], a2[1]]);
+        let group = a3 & 0x01;
+        let msa = a3 >> 1;
+
+        FrameFun::add_data(
+            &mut adress_result,
+            "行政区划码 A1".to_string(),
+            FrameFun::get_data_str_with_space(a1),
+            format!("行政区划码={}", FrameFun::get_data_str_reverser(a1)),
+            vec![index, index + 2],
+            None,
+            None,
+        );
+        let terminal_str = match (terminal, group) {
+            (0x0000, _) => "无效地址".to_string(),
+            (0xFFFF, 1) => "广播地址".to_string(),
+            (_, 1) => format!("组地址={}", terminal),
+            _ => format!("终端地址={}", terminal),
+        };
+        FrameFun::add_data(
+            &mut adress_result,
+            "终端地址 A2".to_string(),
+            FrameFun::get_data_str_with_space(a2),
+            terminal_str,
+            vec![index + 2, index + 4],
+            None,
+            None,
+        );
+        let mut a3_result: Vec<Value> = Vec::new();
+        FrameFun::add_data(
+            &mut a3_result,
+            "D7~D1主站地址MSA".to_string(),
+            format!("{}", msa),
+            format!("主站地址={}", msa),
+            vec![index + 4, index + 5],
+            None,
+            None,
+        );
+        FrameFun::add_data(
+            &mut a3_result,
+            "D0终端组地址标志".to_string(),
+            format!("{}", group),
+            if group == 1 {
+                "A2为终端组地址".to_string()
+            } else {
+                "A2为单地址".to_string()
+            },
+            vec![index + 4, index + 5],
+            None,
+            None,
+        );
+        FrameFun::add_data(
+            &mut adress_result,
+            "主站地址和组地址标志 A3".to_string(),
+            format!("{:02X}", a3),
+            "".to_string(),
+            vec![index + 4, index + 5],
+            Some(a3_result),
+            None,
+        );
+        (
+            adress_result,
+            format!(
+                "{}{}",
+                FrameFun::get_data_str_reverser(a1),
+                FrameFun::get_data_str_reverser(a2)
+            ),
+        )
+    }
+
+    /// 信息点DA对应的Pn，DA2=0时DA1=0为P0、DA1=FF为除P0外的所有有效信息点
+    pub fn calculate_pn(da: &[u8]) -> Vec<u16> {
+        match (da[0], da[1]) {
+            (0x00, 0x00) => vec![0],
+            (0xFF, 0x00) => vec![0xFFFF],
+            (_, 0x00) => Vec::new(),
+            (da1, da2) => (0..8)
+                .filter(|&i| (da1 >> i) & 1 == 1)
+                .map(|i| (da2 as u16 - 1) * 8 + i + 1)
+                .collect(),
+        }
+    }
+
+    pub fn parse_pn_data(da: &[u8]) -> String {
+        let points = Self::calculate_pn(da);
+        match points.as_slice() {
+            [] => "Pn解析失败".to_string(),
+            [0] => "Pn=P0(终端)".to_string(),
+            [0xFFFF] => "Pn=所有有效信息点(不含P0)".to_string(),
+            _ => format!(
+                "Pn={}",
+                points
+                    .iter()
+                    .map(|p| format!("P{}", p))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        }
+    }
+
+    /// 数据项标识为AFN+Fn，查询参数的上行数据与设置参数格式相同
+    fn item_id(afn: u8, fn_value: u16, dir: u8) -> String {
+        let afn = if afn == 0x0A && dir == 1 { 0x04 } else { afn };
+        format!("{:02X}F{}", afn, fn_value)
+    }
+
+    fn pn_name(pn: u16) -> String {
+        if pn == 0xFFFF {
+            "Pall".to_string()
+        } else {
+            format!("P{}", pn)
+        }
+    }
+
+    /// 按DA、DT展开数据单元，同一组内先按Pn、再按Fn依次排列
+    ///
+    /// Fn在当前方向上没有配置时视为只有数据单元标识；配置中完全没有的Fn
+    /// 只在下行请求报文中视为没有数据内容，其他情况无法确定长度，剩余数据不再解析
+    #[allow(clippy::too_many_arguments)]
+    pub fn analysic_data_units(
+        analyzer: &Analyzer,
+        afn: u8,
+        data: &[u8],
+        index: usize,
+        dir: u8,
+        request: bool,
+        protocol: &str,
+        region: &str,
+        result_list: &mut Vec<Value>,
+    ) {
+        let mut pos = 0;
+        let mut num = 0;
+        'units: while pos + 4 <= data.len() {
+            num += 1;
+            let da = &data[pos..pos + 2];
+            let dt = &data[pos + 2..pos + 4];
+            FrameFun::add_data(
+                result_list,
+                format!("<第{}组>信息点DA", num),
+                FrameFun::get_data_str_with_space(da),
+                Self::parse_pn_data(da),
+                vec![index + pos, index + pos + 2],
+                None,
+                None,
+            );
+            FrameFun::add_data(
+                result_list,
+                format!("<第{}组>信息类DT", num),
+                FrameFun::get_data_str_with_space(dt),
+                FrameFun::parse_dt_data(dt),
+                vec![index + pos + 2, index + pos + 4],
+                None,
+                None,
+            );
+            pos += 4;
+
+            let points = Self::calculate_pn(da);
+            let (_, fns) = FrameFun::calculate_fn(dt);
+            let units: Vec<(u16, u16)> = points
+                .iter()
+                .flat_map(|&pn| fns.iter().map(move |&fn_value| (pn, fn_value)))
+                .collect();
+            for (unit_index, &(pn, fn_value)) in units.iter().enumerate() {
+                let item_id = Self::item_id(afn, fn_value, dir);
+                let unit_name = format!("<第{}组>{} F{}", num, Self::pn_name(pn), fn_value);
+                let item_elem = analyzer.get_config_xml(&item_id, protocol, region, Some(dir));
+                let name = item_elem
+                    .as_ref()
+                    .and_then(|elem| elem.get_child_text("name"))
+                    .or_else(|| {
+                        [item_id.clone(), Self::item_id(afn, fn_value, 1)]
+                            .iter()
+                            .find_map(|id| analyzer.get_config_xml(id, protocol, region, None))
+                            .and_then(|elem| elem.get_child_text("name"))
+                    });
+                let Some(name) = name else {
+                    let desc = format!("数据标识编码：[{}]", item_id);
+                    if request || pos >= data.len() {
+                        FrameFun::add_data(
+                            result_list,
+                            unit_name,
+                            "".to_string(),
+                            desc,
+                            vec![index + pos, index + pos],
+                            None,
+                            None,
+                        );
+                        continue;
+                    }
+                    // 配置中有长度但无名称时按长度跳过该数据单元
+                    if let Some(mut item_elem) = item_elem {
+                        let (length, item_data) = Self::parse_item(
+                            analyzer,
+                            &mut item_elem,
+                            &data[pos..],
+                            index + pos,
+                            dir,
+                            protocol,
+                            region,
+                        );
+                        if length > 0 {
+                            FrameFun::add_data(
+                                result_list,
+                                unit_name,
+                                FrameFun::get_data_str_with_space(&data[pos..pos + length]),
+                                desc,
+                                vec![index + pos, index + pos + length],
+                                Some(item_data).filter(|data| !data.is_empty()),
+                                None,
+                            );
+                            pos += length;
+                            continue;
+                        }
+                    }
+                    FrameFun::add_data(
+                        result_list,
+                        unit_name,
+                        FrameFun::get_data_str_with_space(&data[pos..]),
+                        format!("{}，配置中无此数据单元，无法确定数据长度", desc),
+                        vec![index + pos, index + data.len()],
+                        None,
+                        None,
+                    );
+                    // 长度未知时无法定位后续数据单元，明确列出未解析的Fn
+                    let rest: Vec<String> = units[unit_index + 1..]
+                        .iter()
+                        .map(|&(pn, fn_value)| format!("{} F{}", Self::pn_name(pn), fn_value))
+                        .collect();
+                    if !rest.is_empty() {
+                        FrameFun::add_data(
+                            result_list,
+                            format!("<第{}组>后续Fn未解析", num),
+                            "".to_string(),
+                            format!("{}未解析", rest.join("、")),
+                            vec![index + data.len(), index + data.len()],
+                            None,
+                            None,
+                        );
+                    }
+                    pos = data.len();
+                    break 'units;
+                };
+                let desc = format!("数据标识编码：[{}]-{}", item_id, name);
+
+                // 按数据单元标识确认和否认
+                if afn == 0x00 && fn_value == 3 {
+                    let (sub_result, length) =
+                        Self::analysic_confirm_units(analyzer, &data[pos..], index + pos, protocol);
+                    FrameFun::add_data(
+                        result_list,
+                        unit_name,
+                        FrameFun::get_data_str_with_space(&data[pos..pos + length]),
+                        desc,
+                        vec![index + pos, index + pos + length],
+                        Some(sub_result),
+                        None,
+                    );
+                    pos += length;
+                    continue;
+                }
+
+                let (length, item_data) = match item_elem {
+                    Some(mut item_elem) => Self::parse_item(
+                        analyzer,
+                        &mut item_elem,
+                        &data[pos..],
+                        index + pos,
+                        dir,
+                        protocol,
+                        region,
+                    ),
+                    None => (0, Vec::new()),
+                };
+                let mut item_data = item_data;
+                let mut total = length;
+                // 上行的事件数据后跟事件记录
+                if afn == 0x0E && dir == 1 && length > 0 {
+                    total += Self::analysic_event_records(
+                        analyzer,
+                        &data[pos + length..],
+                        index + pos + length,
+                        protocol,
+                        region,
+                        &mut item_data,
+                    );
+                }
+                FrameFun::add_data(
+                    result_list,
+                    unit_name,
+                    FrameFun::get_data_str_with_space(&data[pos..pos + total]),
+                    desc,
+                    vec![index + pos, index + pos + total],
+                    if item_data.is_empty() {
+                        None
+                    } else {
+                        Some(item_data)
+                    },
+                    None,
+                );
+                pos += total;
+            }
+        }
+
+        if pos < data.len() {
+            FrameFun::add_data(
+                result_list,
+                "未解析数据".to_string(),
+                FrameFun::get_data_str_with_space(&data[pos..]),
+                "数据单元标识不完整".to_string(),
+                vec![index + pos, index + data.len()],
+                None,
+                None,
+            );
+        }
+    }
+
+    /// 按配置计算数据单元长度并解析，长度超出剩余数据时截断
+    pub fn parse_item(
+        analyzer: &Analyzer,
+        item_elem: &mut XmlElement,
+        data: &[u8],
+        index: usize,
+        dir: u8,
+        protocol: &str,
+        region: &str,
+    ) -> (usize, Vec<Value>) {
+        let length_text = item_elem.get_child_text("length").unwrap_or_default();
+        let (length, segment) = if length_text.eq_ignore_ascii_case("UNKNOWN") {
+            let length = FrameCsg::calculate_item_length(
+                analyzer,
+                item_elem,
+                data,
+                protocol,
+                region,
+                Some(dir),
+                None,
+            )
+            .min(data.len());
+            (length, &data[..length])
+        } else {
+            let length = length_text.parse::<usize>().unwrap_or(0).min(data.len());
+            FrameCsg::recalculate_sub_length(
+                analyzer,
+                item_elem,
+                &data[..length],
+                protocol,
+                region,
+                Some(dir),
+            )
+        };
+        info!("1376.1 data unit length: {}", length);
+        if length == 0 {
+            return (0, Vec::new());
+        }
+        let item_data = FrameAnalisyic::prase_data(
+            analyzer,
+            item_elem,
+            protocol,
+            region,
+            segment,
+            index,
+            Some(dir),
+        );
+        (length, item_data)
+    }
+
+    // 被确认的AFN后跟若干组数据单元标识及其确认结果
+    fn analysic_confirm_units(
+        analyzer: &Analyzer,
+        data: &[u8],
+        index: usize,
+        protocol: &str,
+    ) -> (Vec<Value>, usize) {
+        let mut result = Vec::new();
+        if data.is_empty() {
+            return (result, 0);
+        }
+        let afn = data[0];
+        let afn_name = analyzer
+            .find_afn(protocol, afn, None, None)
+            .map(|entry| entry.name)
+            .unwrap_or_else(|| "备用".to_string());
+        FrameFun::add_data(
+            &mut result,
+            "要被确认的AFN".to_string(),
+            format!("{:02X}", afn),
+            afn_name,
+            vec![index, index + 1],
+            None,
+            None,
+        );
+        let mut pos = 1;
+        let mut num = 0;
+        while pos + 5 <= data.len() {
+            num += 1;
+            let da = &data[pos..pos + 2];
+            let dt = &data[pos + 2..pos + 4];
+            let err = data[pos + 4];
+            let mut unit_result = Vec::new();
+            FrameFun::add_data(
+                &mut unit_result,
+                "信息点DA".to_string(),
+                FrameFun::get_data_str_with_space(da),
+                Self::parse_pn_data(da),
+                vec![index + pos, index + pos + 2],
+                None,
+                None,
+            );
+            FrameFun::add_data(
+                &mut unit_result,
+                "信息类DT".to_string(),
+                FrameFun::get_data_str_with_space(dt),
+                FrameFun::parse_dt_data(dt),
+                vec![index + pos + 2, index + pos + 4],
+                None,
+                None,
+            );
+            FrameFun::add_data(
+                &mut unit_result,
+                "错误标志ERR".to_string(),
+                format!("{:02X}", err),
+                match err {
+                    0x00 => "正确".to_string(),
+                    0x01 => "错误".to_string(),
+                    _ => "备用".to_string(),
+                },
+                vec![index + pos + 4, index + pos + 5],
+                None,
+                None,
+            );
+            FrameFun::add_data(
+                &mut result,
+                format!("<第{}组>数据单元标识确认", num),
+                FrameFun::get_data_str_with_space(&data[pos..pos + 5]),
+                format!(
+                    "{} {}：{}",
+                    Self::parse_pn_data(da),
+                    FrameFun::parse_dt_data(dt),
+                    if err == 0 { "确认" } else { "否认" }
+                ),
+                vec![index + pos, index + pos + 5],
+                Some(unit_result),
+                None,
+            );
+            pos += 5;
+        }
+        (result, pos)
+    }
+
+    // 事件记录依次为事件代码ERC、长度Le和事件记录内容，返回解析的长度
+    fn analysic_event_records(
+        analyzer: &Analyzer,
+        data: &[u8],
+        index: usize,
+        protocol: &str,
+        region: &str,
+        result_list: &mut Vec<Value>,
+    ) -> usize {
+        let mut pos = 0;
+        while pos + 2 <= data.len() {
+            let erc = data[pos];
+            let length = data[pos + 1] as usize;
+            if pos + 2 + length > data.len() {
+                break;
+            }
+            let content = &data[pos + 2..pos + 2 + length];
+            let item_id = format!("ERC{}", erc);
+            let item_elem = analyzer.get_config_xml(&item_id, protocol, region, None);
+            let name = item_elem
+                .as_ref()
+                .and_then(|elem| elem.get_child_text("name"))
+                .unwrap_or_else(|| "事件记录".to_string());
+            let item_data = match item_elem {
+                Some(mut item_elem) if length > 0 => FrameAnalisyic::prase_data(
+                    analyzer,
+                    &mut item_elem,
+                    protocol,
+                    region,
+                    content,
+                    index + pos + 2,
+                    Some(1),
+                ),
+                _ => Vec::new(),
+            };
+            FrameFun::add_data(
+                result_list,
+                format!("{}:{}", item_id, name),
+                FrameFun::get_data_str_with_space(&data[pos..pos + 2 + length]),
+                format!("事件代码ERC={},事件记录长度Le={}", erc, length),
+                vec![index + pos, index + pos + 2 + length],
+                if item_data.is_empty() {
+                    None
+                } else {
+                    Some(item_data)
+                },
+                None,
+            );
+            pos += 2 + length;
+        }
+        pos
+    }
+
+    fn analysic_ec(ec: &[u8], index: usize, result_list: &mut Vec<Value>) {
+        FrameFun::add_data(
+            result_list,
+            "事件计数器EC".to_string(),
+            FrameFun::get_data_str_with_space(ec),
+            format!("重要事件计数器EC1={},一般事件计数器EC2={}", ec[0], ec[1]),
+            vec![index, index + EC_LENGTH],
+            None,
+            None,
+        );
+    }
+
+    // 时间标签：启动帧帧序号计数器PFC、启动帧发送时标(秒分时日)、允许发送传输延时时间
+    fn analysic_tp(tp: &[u8], index: usize, result_list: &mut Vec<Value>) {
+        FrameFun::add_data(
+            result_list,
+            "时间标签Tp".to_string(),
+            FrameFun::get_data_str_with_space(tp),
+            format!(
+                "启动帧帧序号计数器PFC={}。启动帧发送时标：{}。允许发送传输延时时间：{}分",
+                tp[0],
+                FrameFun::parse_time_data(&tp[1..5], "ssmmhhDD", false),
+                tp[5]
+            ),
+            vec![index, index + TP_LENGTH],
+            None,
+            None,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::basefunc::test_util::find;
+
+    // 主站请求1类数据：P1 F25及P0 F2
+    const GOLDEN: &str = "68 42 00 42 00 68 4A 01 33 01 00 02 0C 60 01 01 01 03 00 00 02 00 F5 16";
+
+    #[test]
+    fn parse_golden_frame() {
+        let analyzer = Analyzer::with_builtin();
+        let frame = FrameFun::get_frame_list_from_str(GOLDEN);
+        assert!(Frame13761::is_13761_frame(&frame));
+        let (protocol, result) = analyzer.process_frame(&frame, "南网");
+        assert_eq!(protocol, "Q/GDW1376.1");
+        assert_eq!(
+            find(&result, "<第1组>信息类DT").unwrap()["description"],
+            "Fn=F25"
+        );
+        assert!(find(&result, "<第1组>P1 F25").unwrap()["description"]
+            .as_str()
+            .unwrap()
+            .starts_with("数据标识编码：[0CF25]"));
+        assert_eq!(
+            find(&result, "<第2组>P0 F2").unwrap()["description"],
+            "数据标识编码：[0CF2]-终端日历时钟"
+        );
+        assert_eq!(
+            find(&result, "校验码CS").unwrap()["description"],
+            "校验正确"
+        );
+    }
+
+    #[test]
+    fn unknown_fn_reports_rest() {
+        let analyzer = Analyzer::with_builtin();
+        // 上行P0 F1+F2，F1无配置，长度未知
+        let frame = FrameFun::get_frame_list_from_str(
+            "68 4A 00 4A 00 68 88 33 01 00 02 0C 0C 60 00 00 03 00 11 22 33 44 55 66 9E 16",
+        );
+        let (_, result) = analyzer.process_frame(&frame, "南网");
+        assert!(find(&result, "<第1组>P0 F1").unwrap()["description"]
+            .as_str()
+            .unwrap()
+            .contains("配置中无此数据单元"));
+        let rest = find(&result, "<第1组>后续Fn未解析").unwrap();
+        assert_eq!(rest["description"], "P0 F2未解析");
+    }
+
+    // 地址域同GOLDEN，按用户数据计算长度和校验和
+    fn frame(control: u8, seq: u8, data: &[u8]) -> Vec<u8> {
+        let mut body = vec![control, 0x01, 0x33, 0x01, 0x00, 0x02, 0x0C, seq];
+        body.extend_from_slice(data);
+        let [low, high] = ((body.len() as u16) << 2 | 0x02).to_le_bytes();
+        let mut frame = vec![0x68, low, high, low, high, 0x68];
+        frame.extend_from_slice(&body);
+        frame.push(FrameFun::calculate_cs(&body));
+        frame.push(0x16);
+        frame
+    }
+
+    #[test]
+    fn checksum_mismatch_rejected() {
+        let mut frame = FrameFun::get_frame_list_from_str(GOLDEN);
+        let cs = frame.len() - 2;
+        frame[cs] ^= 0xFF;
+        assert!(!Frame13761::is_13761_frame(&frame));
+    }
+
+    #[test]
+    fn uplink_with_ec_and_tp() {
+        // ACD=1带EC，TpV=1带Tp：P0 F2终端日历时钟
+        let mut data = vec![0x00, 0x00, 0x02, 0x00, 0x30, 0x15, 0x10, 0x19, 0x10, 0x26];
+        data.extend_from_slice(&[0x01, 0x02]);
+        data.extend_from_slice(&[0x05, 0x30, 0x15, 0x10, 0x19, 0x0A]);
+        let frame = frame(0xA8, 0xE0, &data);
+        assert!(Frame13761::is_13761_frame(&frame));
+        let (_, result) = Analyzer::with_builtin().process_frame(&frame, "南网");
+        let clock = find(&result, "<第1组>P0 F2").unwrap();
+        assert_eq!(clock["position"], serde_json::json!([18, 24]));
+        let ec = find(&result, "事件计数器EC").unwrap();
+        assert_eq!(ec["description"], "重要事件计数器EC1=1,一般事件计数器EC2=2");
+        assert_eq!(ec["position"], serde_json::json!([24, 26]));
+        let tp = find(&result, "时间标签Tp").unwrap();
+        assert_eq!(tp["position"], serde_json::json!([26, 32]));
+    }
+
+    #[test]
+    fn uplink_with_multiple_pn_and_fn() {
+        // P1、P2的F129、F130，费率数均为0：正向有功示值5字节，正向无功示值4字节
+        let active = [
+            0x00, 0x15, 0x10, 0x19, 0x10, 0x00, 0x00, 0x00, 0x34, 0x12, 0x00,
+        ];
+        let reactive = [0x00, 0x15, 0x10, 0x19, 0x10, 0x00, 0x00, 0x34, 0x12, 0x00];
+        let mut data = vec![0x03, 0x01, 0x03, 0x10];
+        for _ in 0..2 {
+            data.extend_from_slice(&active);
+            data.extend_from_slice(&reactive);
+        }
+        let frame = frame(0x88, 0x60, &data);
+        let (_, result) = Analyzer::with_builtin().process_frame(&frame, "南网");
+        assert_eq!(
+            find(&result, "<第1组>信息点DA").unwrap()["description"],
+            "Pn=P1, P2"
+        );
+        for (name, position) in [
+            ("<第1组>P1 F129", [18, 29]),
+            ("<第1组>P1 F130", [29, 39]),
+            ("<第1组>P2 F129", [39, 50]),
+            ("<第1组>P2 F130", [50, 60]),
+        ] {
+            let node = find(&result, name).unwrap();
+            assert_eq!(node["position"], serde_json::json!(position));
+        }
+        assert!(find(&result, "未解析数据").is_none());
+    }
+}
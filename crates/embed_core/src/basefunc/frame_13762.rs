@@ -0,0 +1,731 @@
+use crate::analyzer::Analyzer;
+use crate::basefunc::frame_13761::Frame13761;
+use crate::basefunc::frame_cco::FrameCCO;
+use crate::basefunc::frame_fun::FrameFun;
+use crate::basefunc::protocol::ProtocolInfo;
+use crate::config::afntable::AfnTable;
+use serde_json::Value;
+use std::ops::Range;
+
+/// Q/GDW 1376.2 集中器本地通信模块接口协议
+///
+/// 帧格式：68 L L C 用户数据 CS 16，长度L为整帧字节数，用户数据依次为信息域R、
+/// 地址域A(通信模块标识为1时才有)、AFN、数据单元标识DT和数据单元，
+/// 校验和与南网16的本地模块报文相同，为控制域和用户数据的算术和
+pub struct Frame13762;
+
+// 信息域R、单个地址的长度
+const INFO_LENGTH: usize = 6;
+const ADDRESS_LENGTH: usize = 6;
+// 68 L L C R AFN DT CS 16
+const MIN_FRAME_LENGTH: usize = 15;
+// 中继级别占信息域第1字节的D4~D7
+const MAX_RELAY_LEVEL: usize = 15;
+
+impl Frame13762 {
+    pub fn is_13762_frame(frame: &[u8]) -> bool {
+        let len = frame.len();
+        if len < MIN_FRAME_LENGTH {
+            return false;
+        }
+        if frame[0] != 0x68 || frame[len - 1] != 0x16 {
+            return false;
+        }
+        if u16::from_le_bytes([frame[1], frame[2]]) as usize != len {
+            return false;
+        }
+        // 控制域D5~D0为通信方式，南网16的本地模块报文这几位为地址标志和版本号
+        if Self::comm_mode_name(frame[3] & 0x3F).is_none() {
+            return false;
+        }
+        if FrameFun::calculate_cs(&frame[3..len - 2]) != frame[len - 2] {
+            return false;
+        }
+        let pos = 4 + INFO_LENGTH + Self::address_length(frame[4]);
+        if pos + 3 > len - 2 {
+            return false;
+        }
+        // 每帧只有一个数据单元标识，DT1中只能有一位为1
+        let protocol = ProtocolInfo::ProtocolGDW13762.name();
+        frame[pos + 1].count_ones() == 1
+            && AfnTable::builtin(protocol)
+                .iter()
+                .any(|entry| entry.code == frame[pos])
+    }
+
+    /// 控制域D5~D0通信方式的名称，备用值返回None
+    pub fn comm_mode_name(mode: u8) -> Option<&'static str> {
+        match mode {
+            1 => Some("集中式路由载波通信"),
+            2 => Some("分布式路由载波通信"),
+            3 => Some("宽带载波通信"),
+            10 => Some("微功率无线通信"),
+            20 => Some("以太网通信"),
+            _ => None,
+        }
+    }
+
+    /// 地址域长度，通信模块标识为1时有源地址、中继级别个中继地址和目的地址
+    pub fn address_length(info: u8) -> usize {
+        if info & 0x04 == 0 {
+            return 0;
+        }
+        ADDRESS_LENGTH * (2 + (info >> 4) as usize)
+    }
+
+    pub fn analysic_13762_frame(
+        analyzer: &Analyzer,
+        frame: &[u8],
+        result_list: &mut Vec<Value>,
+        index: usize,
+        region: &str,
+    ) {
+        let protocol = ProtocolInfo::ProtocolGDW13762.name().to_string();
+        let len = frame.len();
+        let dir = Self::analysic_head_frame(frame, result_list, index);
+
+        let mut user_result = Vec::new();
+        let info = &frame[4..4 + INFO_LENGTH];
+        Self::analysic_info_field(info, dir, index + 4, &mut user_result);
+
+        let mut pos = 4 + INFO_LENGTH;
+        let address_length = Self::address_length(info[0]);
+        if address_length > 0 {
+            Self::analysic_address_field(
+                &frame[pos..pos + address_length],
+                index + pos,
+                &mut user_result,
+            );
+            pos += address_length;
+        }
+
+        let afn = frame[pos];
+        let afn_name = analyzer
+            .find_afn(&protocol, afn, None, None)
+            .map(|entry| entry.name)
+            .unwrap_or_else(|| "备用".to_string());
+        FrameFun::add_data(
+            &mut user_result,
+            "应用功能码AFN".to_string(),
+            format!("{:02X}", afn),
+            afn_name,
+            vec![index + pos, index + pos + 1],
+            None,
+            None,
+        );
+        let dt = &frame[pos + 1..pos + 3];
+        FrameFun::add_data(
+            &mut user_result,
+            "数据单元标识DT".to_string(),
+            FrameFun::get_data_str_with_space(dt),
+            FrameFun::parse_dt_data(dt),
+            vec![index + pos + 1, index + pos + 3],
+            None,
+            None,
+        );
+        pos += 3;
+
+        Self::analysic_data_unit(
+            analyzer,
+            afn,
+            dt,
+            &frame[pos..len - 2],
+            index + pos,
+            dir,
+            &protocol,
+            region,
+            &mut user_result,
+        );
+
+        FrameFun::add_data(
+            result_list,
+            "用户数据".to_string(),
+            FrameFun::get_data_str_with_space(&frame[4..len - 2]),
+            "".to_string(),
+            vec![index + 4, index + len - 2],
+            Some(user_result),
+            None,
+        );
+
+        FrameCCO::analysic_cco_end_frame(frame, result_list, dir, index);
+    }
+
+    // 解析起始符、长度和控制域，返回传输方向
+    fn analysic_head_frame(frame: &[u8], result_list: &mut Vec<Value>, index: usize) -> u8 {
+        FrameFun::add_data(
+            result_list,
+            "起始符".to_string(),
+            format!("{:02X}", frame[0]),
+            "起始符".to_string(),
+            vec![index, index + 1],
+            None,
+            None,
+        );
+        FrameFun::add_data(
+            result_list,
+            "长度".to_string(),
+            FrameFun::get_data_str_with_space(&frame[1..3]),
+            format!("总长度={}", u16::from_le_bytes([frame[1], frame[2]])),
+            vec![index + 1, index + 3],
+            None,
+            None,
+        );
+
+        let control = frame[3];
+        let dir = control >> 7;
+        let prm = (control >> 6) & 0x01;
+        let mode = control & 0x3F;
+        let mode_str = Self::comm_mode_name(mode).unwrap_or("备用");
+        let mut control_result = Vec::new();
+        FrameFun::add_data(
+            &mut control_result,
+            "D7传输方向位DIR".to_string(),
+            dir.to_string(),
+            if dir == 0 {
+                "集中器发出的下行报文".to_string()
+            } else {
+                "通信模块发出的上行报文".to_string()
+            },
+            vec![index + 3, index + 4],
+            None,
+            None,
+        );
+        FrameFun::add_data(
+            &mut control_result,
+            "D6启动标志位PRM".to_string(),
+            prm.to_string(),
+            if prm == 1 {
+                "来自启动站".to_string()
+            } else {
+                "来自从动站".to_string()
+            },
+            vec![index + 3, index + 4],
+            None,
+            None,
+        );
+        FrameFun::add_data(
+            &mut control_result,
+            "D5~D0通信方式".to_string(),
+            mode.to_string(),
+            mode_str.to_string(),
+            vec![index + 3, index + 4],
+            None,
+            None,
+        );
+        FrameFun::add_data(
+            result_list,
+            "控制域C".to_string(),
+            format!("{:02X}", control),
+            format!("{}，{}", if dir == 0 { "下行" } else { "上行" }, mode_str),
+            vec![index + 3, index + 4],
+            Some(control_result),
+            None,
+        );
+        dir
+    }
+
+    /// 信息域R，上下行格式不同：下行带冲突检测、纠错编码、预计应答字节数和通信速率，
+    /// 上行带实测相线、信号品质和事件标志
+    pub fn analysic_info_field(info: &[u8], dir: u8, index: usize, result_list: &mut Vec<Value>) {
+        let relay_level = info[0] >> 4;
+        let channel = info[1] & 0x0F;
+        let seq = info[5];
+        let channel_str = if channel == 0 {
+            "不分信道".to_string()
+        } else {
+            format!("第{}信道", channel)
+        };
+
+        // (名称, 值, 描述, 所在字节)
+        let mut fields: Vec<(&str, String, String, Range<usize>)> = vec![(
+            "D0路由标识",
+            (info[0] & 0x01).to_string(),
+            if info[0] & 0x01 == 0 {
+                "通信模块带路由或工作在路由模式".to_string()
+            } else {
+                "通信模块不带路由或工作在旁路模式".to_string()
+            },
+            0..1,
+        )];
+        if dir == 0 {
+            fields.push((
+                "D1附属节点标识",
+                ((info[0] >> 1) & 0x01).to_string(),
+                if info[0] & 0x02 == 0 {
+                    "无附属节点".to_string()
+                } else {
+                    "有附属节点".to_string()
+                },
+                0..1,
+            ));
+        }
+        fields.push((
+            "D2通信模块标识",
+            ((info[0] >> 2) & 0x01).to_string(),
+            if info[0] & 0x04 == 0 {
+                "对集中器的通信模块操作，无地址域".to_string()
+            } else {
+                "对载波表的通信模块操作，带地址域".to_string()
+            },
+            0..1,
+        ));
+        if dir == 0 {
+            fields.push((
+                "D3冲突检测",
+                ((info[0] >> 3) & 0x01).to_string(),
+                if info[0] & 0x08 == 0 {
+                    "不进行冲突检测".to_string()
+                } else {
+                    "要进行冲突检测".to_string()
+                },
+                0..1,
+            ));
+        }
+        fields.push((
+            "D7~D4中继级别",
+            relay_level.to_string(),
+            if relay_level == 0 {
+                "无中继".to_string()
+            } else {
+                format!("{}级中继", relay_level)
+            },
+            0..1,
+        ));
+        fields.push((
+            "D3~D0信道标识",
+            channel.to_string(),
+            channel_str.clone(),
+            1..2,
+        ));
+
+        let summary = if dir == 0 {
+            let code = info[1] >> 4;
+            let rate = u16::from_le_bytes([info[3], info[4]]);
+            let rate_value = rate & 0x7FFF;
+            let rate_str = match (rate_value, rate >> 15) {
+                (0, _) => "默认通信速率".to_string(),
+                (value, 0) => format!("{} bps", value),
+                (value, _) => format!("{} kbps", value),
+            };
+            fields.push((
+                "D7~D4纠错编码标识",
+                code.to_string(),
+                match code {
+                    0 => "信道未编码".to_string(),
+                    1 => "RS编码".to_string(),
+                    _ => "备用".to_string(),
+                },
+                1..2,
+            ));
+            fields.push((
+                "预计应答字节数",
+                info[2].to_string(),
+                format!("预计应答字节数={}", info[2]),
+                2..3,
+            ));
+            fields.push((
+                "通信速率",
+                FrameFun::get_data_str_with_space(&info[3..5]),
+                rate_str.clone(),
+                3..5,
+            ));
+            format!(
+                "中继级别={}，{}，{}，报文序列号={}",
+                relay_level, channel_str, rate_str, seq
+            )
+        } else {
+            let phase = info[2] & 0x0F;
+            let feature = info[2] >> 4;
+            let command_quality = info[3] & 0x0F;
+            let answer_quality = info[3] >> 4;
+            let phase_str = match phase {
+                0 => "不确定".to_string(),
+                1..=3 => format!("第{}相", phase),
+                _ => "备用".to_string(),
+            };
+            fields.push((
+                "D3~D0实测相线标识",
+                phase.to_string(),
+                phase_str.clone(),
+                2..3,
+            ));
+            fields.push((
+                "D7~D4电表通道特征",
+                feature.to_string(),
+                match feature {
+                    1 => "单相供电，逻辑单信道".to_string(),
+                    2 => "单相供电，逻辑两信道".to_string(),
+                    3 => "单相供电，逻辑三信道".to_string(),
+                    4 => "三相供电，逻辑三信道".to_string(),
+                    _ => "保留".to_string(),
+                },
+                2..3,
+            ));
+            fields.push((
+                "D3~D0末级命令信号品质",
+                command_quality.to_string(),
+                Self::quality_str(command_quality),
+                3..4,
+            ));
+            fields.push((
+                "D7~D4末级应答信号品质",
+                answer_quality.to_string(),
+                Self::quality_str(answer_quality),
+                3..4,
+            ));
+            fields.push((
+                "D0事件标志",
+                (info[4] & 0x01).to_string(),
+                if info[4] & 0x01 == 0 {
+                    "无上报事件".to_string()
+                } else {
+                    "有上报事件".to_string()
+                },
+                4..5,
+            ));
+            format!(
+                "中继级别={}，{}，相线={}，信号品质(命令/应答)={}/{}，报文序列号={}",
+                relay_level, channel_str, phase_str, command_quality, answer_quality, seq
+            )
+        };
+        fields.push((
+            "报文序列号",
+            seq.to_string(),
+            format!("报文序列号={}", seq),
+            5..6,
+        ));
+
+        let mut info_result = Vec::new();
+        for (name, value, desc, range) in fields {
+            FrameFun::add_data(
+                &mut info_result,
+                name.to_string(),
+                value,
+                desc,
+                vec![index + range.start, index + range.end],
+                None,
+                None,
+            );
+        }
+        FrameFun::add_data(
+            result_list,
+            "信息域R".to_string(),
+            FrameFun::get_data_str_with_space(info),
+            summary,
+            vec![index, index + INFO_LENGTH],
+            Some(info_result),
+            None,
+        );
+    }
+
+    fn quality_str(quality: u8) -> String {
+        if quality == 0 {
+            "无信号品质".to_string()
+        } else {
+            format!("信号品质={}(1最低，15最高)", quality)
+        }
+    }
+
+    /// 地址域：源地址A1、各级中继地址A2和目的地址A3，均为6字节低字节在前
+    pub fn analysic_address_field(address: &[u8], index: usize, result_list: &mut Vec<Value>) {
+        let count = address.len() / ADDRESS_LENGTH;
+        let mut address_result = Vec::new();
+        for i in 0..count {
+            let name = match i {
+                0 => "源地址A1".to_string(),
+                i if i == count - 1 => "目的地址A3".to_string(),
+                i => format!("第{}级中继地址A2", i),
+            };
+            let start = i * ADDRESS_LENGTH;
+            let value = &address[start..start + ADDRESS_LENGTH];
+            FrameFun::add_data(
+                &mut address_result,
+                name,
+                FrameFun::get_data_str_with_space(value),
+                format!("地址:{}", FrameFun::get_data_str_reverser(value)),
+                vec![index + start, index + start + ADDRESS_LENGTH],
+                None,
+                None,
+            );
+        }
+        let target = &address[address.len() - ADDRESS_LENGTH..];
+        FrameFun::add_data(
+            result_list,
+            "地址域A".to_string(),
+            FrameFun::get_data_str_with_space(address),
+            format!("目的地址:{}", FrameFun::get_data_str_reverser(target)),
+            vec![index, index + address.len()],
+            Some(address_result),
+            None,
+        );
+    }
+
+    // 数据单元按AFN+Fn查找配置，当前方向没有配置时视为只有数据单元标识
+    #[allow(clippy::too_many_arguments)]
+    fn analysic_data_unit(
+        analyzer: &Analyzer,
+        afn: u8,
+        dt: &[u8],
+        data: &[u8],
+        index: usize,
+        dir: u8,
+        protocol: &str,
+        region: &str,
+        result_list: &mut Vec<Value>,
+    ) {
+        let (_, fns) = FrameFun::calculate_fn(dt);
+        let Some(&fn_value) = fns.first() else {
+            if !data.is_empty() {
+                FrameFun::add_data(
+                    result_list,
+                    "数据单元".to_string(),
+                    FrameFun::get_data_str_with_space(data),
+                    "Fn解析失败".to_string(),
+                    vec![index, index + data.len()],
+                    None,
+                    None,
+                );
+            }
+            return;
+        };
+        let item_id = format!("{:02X}F{}", afn, fn_value);
+        let item_elem = analyzer.get_config_xml(&item_id, protocol, region, Some(dir));
+        let name = item_elem
+            .as_ref()
+            .and_then(|elem| elem.get_child_text("name"))
+            .or_else(|| {
+                analyzer
+                    .get_config_xml(&item_id, protocol, region, None)
+                    .and_then(|elem| elem.get_child_text("name"))
+            });
+        let Some(name) = name else {
+            FrameFun::add_data(
+                result_list,
+                "数据单元".to_string(),
+                FrameFun::get_data_str_with_space(data),
+                format!("数据标识编码：[{}]，配置中无此数据单元", item_id),
+                vec![index, index + data.len()],
+                None,
+                None,
+            );
+            return;
+        };
+
+        let (length, item_data) = match item_elem {
+            Some(mut item_elem) => {
+                Frame13761::parse_item(analyzer, &mut item_elem, data, index, dir, protocol, region)
+            }
+            None => (0, Vec::new()),
+        };
+        FrameFun::add_data(
+            result_list,
+            "数据单元".to_string(),
+            FrameFun::get_data_str_with_space(&data[..length]),
+            format!("数据标识编码：[{}]-{}", item_id, name),
+            vec![index, index + length],
+            if item_data.is_empty() {
+                None
+            } else {
+                Some(item_data)
+            },
+            None,
+        );
+        if length < data.len() {
+            FrameFun::add_data(
+                result_list,
+                "未解析数据".to_string(),
+                FrameFun::get_data_str_with_space(&data[length..]),
+                "数据单元长度与配置不符".to_string(),
+                vec![index + length, index + data.len()],
+                None,
+                None,
+            );
+        }
+    }
+
+    /// 组一帧1376.2报文
+    ///
+    /// addresses依次为源地址、各级中继地址和目的地址(高字节在前)，为空时不带地址域，
+    /// 信息域R中的通信模块标识和中继级别按地址个数设置
+    pub fn build_frame(
+        control: u8,
+        info: [u8; 6],
+        addresses: &[&str],
+        afn: u8,
+        fn_value: u16,
+        data: &[u8],
+    ) -> Result<Vec<u8>, String> {
+        if addresses.len() == 1 || addresses.len() > MAX_RELAY_LEVEL + 2 {
+            return Err(format!(
+                "地址域应包含源地址和目的地址，中继地址不超过{}级",
+                MAX_RELAY_LEVEL
+            ));
+        }
+        if !(1..=248).contains(&fn_value) {
+            return Err(format!("无效的Fn: {}", fn_value));
+        }
+        let mut address_bytes: Vec<u8> = Vec::with_capacity(addresses.len() * ADDRESS_LENGTH);
+        for address in addresses {
+            let address = address.replace(' ', "");
+            let value = FrameFun::get_hex_frame(&address)
+                .filter(|value| address.len() == 12 && value.len() == ADDRESS_LENGTH)
+                .ok_or_else(|| format!("地址[{}]应为12位十六进制数", address))?;
+            address_bytes.extend(value.iter().rev());
+        }
+        let length = 4 + INFO_LENGTH + address_bytes.len() + 3 + data.len() + 2;
+        if length > u16::MAX as usize {
+            return Err(format!("帧长度{}超过65535字节", length));
+        }
+
+        let mut info = info;
+        info[0] &= 0x0B;
+        if !addresses.is_empty() {
+            info[0] |= 0x04 | (((addresses.len() - 2) as u8) << 4);
+        }
+        let (dt1, dt2) = FrameFun::to_dt(&[fn_value])[0];
+
+        let mut frame = Vec::with_capacity(length);
+        frame.push(0x68);
+        frame.extend_from_slice(&(length as u16).to_le_bytes());
+        frame.push(control);
+        frame.extend_from_slice(&info);
+        frame.extend(address_bytes);
+        frame.push(afn);
+        frame.push(dt1);
+        frame.push(dt2);
+        frame.extend_from_slice(data);
+        frame.push(FrameFun::calculate_cs(&frame[3..]));
+        frame.push(0x16);
+        Ok(frame)
+    }
+
+    /// 组集中器发出的下行命令，信息域除报文序列号外取默认值
+    pub fn build_command(
+        comm_mode: u8,
+        seq: u8,
+        afn: u8,
+        fn_value: u16,
+        addresses: &[&str],
+        data: &[u8],
+    ) -> Result<Vec<u8>, String> {
+        if Self::comm_mode_name(comm_mode).is_none() {
+            return Err(format!("通信方式{}无效", comm_mode));
+        }
+        Self::build_frame(
+            0x40 | comm_mode,
+            [0, 0, 0, 0, 0, seq],
+            addresses,
+            afn,
+            fn_value,
+            data,
+        )
+    }
+
+    /// 组监控从节点(13H-F1)报文，content为转发给从节点的电表报文
+    pub fn build_monitor_frame(
+        comm_mode: u8,
+        seq: u8,
+        source: &str,
+        target: &str,
+        protocol_type: u8,
+        content: &[u8],
+    ) -> Result<Vec<u8>, String> {
+        let length = u8::try_from(content.len())
+            .map_err(|_| format!("报文长度{}超过255字节", content.len()))?;
+        // 通信延时无关、无附属节点
+        let mut data = vec![protocol_type, 0x00, 0x00, length];
+        data.extend_from_slice(content);
+        Self::build_command(comm_mode, seq, 0x13, 1, &[source, target], &data)
+    }
+
+    /// 组转发通信协议数据帧(02H-F1)报文
+    pub fn build_forward_frame(
+        comm_mode: u8,
+        seq: u8,
+        protocol_type: u8,
+        content: &[u8],
+    ) -> Result<Vec<u8>, String> {
+        let length = u8::try_from(content.len())
+            .map_err(|_| format!("报文长度{}超过255字节", content.len()))?;
+        let mut data = vec![protocol_type, length];
+        data.extend_from_slice(content);
+        Self::build_command(comm_mode, seq, 0x02, 1, &[], &data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::basefunc::test_util::find;
+
+    // 经1级中继监控从节点(13H-F1)，转发645-2007读正向有功总电能
+    const GOLDEN: &str = "68 35 00 41 1C 10 20 60 09 07 01 00 00 00 00 00 FF EE DD CC BB AA \
+                          66 55 44 33 22 11 13 01 00 02 00 00 10 68 01 00 00 00 00 00 68 11 \
+                          04 33 33 34 33 B3 16 00 16";
+    const METER_FRAME: &str = "6801000000000068110433333433B316";
+
+    #[test]
+    fn parse_golden_relay_frame() {
+        let analyzer = Analyzer::with_builtin();
+        let frame = FrameFun::get_frame_list_from_str(GOLDEN);
+        assert!(Frame13762::is_13762_frame(&frame));
+        let (protocol, result) = analyzer.process_frame(&frame, "南网");
+        assert_eq!(protocol, "Q/GDW1376.2");
+
+        let info = find(&result, "信息域R").unwrap();
+        assert_eq!(
+            info["description"],
+            "中继级别=1，不分信道，2400 bps，报文序列号=7"
+        );
+        assert_eq!(find(&result, "D3冲突检测").unwrap()["data"], "1");
+        assert_eq!(
+            find(&result, "D7~D4纠错编码标识").unwrap()["description"],
+            "RS编码"
+        );
+        assert_eq!(find(&result, "预计应答字节数").unwrap()["data"], "32");
+
+        assert_eq!(
+            find(&result, "第1级中继地址A2").unwrap()["description"],
+            "地址:AABBCCDDEEFF"
+        );
+        assert_eq!(
+            find(&result, "目的地址A3").unwrap()["description"],
+            "地址:112233445566"
+        );
+
+        // 报文内容按645报文展开，位置为在整帧中的位置
+        let content = find(&result, "报文内容").unwrap();
+        assert_eq!(content["position"], serde_json::json!([35, 51]));
+        let di = find(content["children"].as_array().unwrap(), "数据标识编码").unwrap();
+        assert!(di["description"]
+            .as_str()
+            .unwrap()
+            .starts_with("数据标识编码：[00010000]"));
+        assert_eq!(
+            find(&result, "校验和CS").unwrap()["description"],
+            "校验和:正确"
+        );
+    }
+
+    #[test]
+    fn build_frame_round_trip() {
+        let analyzer = Analyzer::with_builtin();
+        let addresses = ["000000000001", "AABBCCDDEEFF", "112233445566"];
+        let data = format!("02000010{}", METER_FRAME);
+        // 信息域中的通信模块标识和中继级别由地址个数决定
+        let frame =
+            crate::api::build_gdw13762_frame(0x41, "081020600907", &addresses, 0x13, 1, &data)
+                .unwrap();
+        assert_eq!(frame, FrameFun::get_frame_list_from_str(GOLDEN));
+
+        let (_, result) = analyzer.process_frame(&frame, "南网");
+        assert_eq!(find(&result, "D7~D4中继级别").unwrap()["data"], "1");
+        assert_eq!(
+            find(&result, "数据单元标识DT").unwrap()["description"],
+            "Fn=F1"
+        );
+
+        assert!(crate::api::build_gdw13762_frame(0x41, "0810206009", &[], 0x13, 1, "").is_err());
+        assert!(Frame13762::build_frame(0x41, [0; 6], &addresses[..1], 0x13, 1, &[]).is_err());
+    }
+}
@@ -1,7 +1,8 @@
+use crate::analyzer::Analyzer;
+use crate::basefunc::frame_645_97::Frame64597;
 use crate::basefunc::frame_csg::FrameCsg;
 use crate::basefunc::frame_fun::FrameFun;
 use crate::basefunc::protocol::{FrameAnalisyic, ProtocolInfo};
-use crate::config::xmlconfig::ProtocolConfigManager;
 use serde_json::Value;
 use tracing::info;
 pub struct Frame645;
@@ -29,7 +30,33 @@ impl Frame645 {
         return true;
     }
 
+    /// 组一帧645报文：68 地址 68 控制码 长度 数据域 校验码 16，数据域按字节加33H
+    ///
+    /// address为通信地址的显示形式(高字节在前)，如"000000000001"；data为加33H前的数据域
+    pub fn build_frame(address: &str, control: u8, data: &[u8]) -> Result<Vec<u8>, String> {
+        let address = address.replace(' ', "");
+        if address.len() != 12 || !address.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(format!("通信地址[{}]应为12位十六进制数", address));
+        }
+        let data_length = u8::try_from(data.len())
+            .map_err(|_| format!("数据域长度{}超过255字节", data.len()))?;
+        let address = FrameFun::get_hex_frame(&address)
+            .ok_or_else(|| format!("通信地址[{}]应为12位十六进制数", address))?;
+
+        let mut frame = Vec::with_capacity(data.len() + 12);
+        frame.push(0x68);
+        frame.extend(address.iter().rev());
+        frame.push(0x68);
+        frame.push(control);
+        frame.push(data_length);
+        frame.extend(FrameFun::frame_add_33h(data));
+        frame.push(FrameFun::calculate_cs(&frame));
+        frame.push(0x16);
+        Ok(frame)
+    }
+
     pub fn analysic_645_frame_by_afn(
+        analyzer: &Analyzer,
         frame: &[u8],
         result_list: &mut Vec<Value>,
         index: usize,
@@ -38,169 +65,257 @@ impl Frame645 {
         if frame.len() < 12 {
             return 0;
         }
-        
+        // 中继、转发报文中嵌套的也可能是1997规约
+        if Frame64597::is_dlt645_97_frame(frame) {
+            return Frame64597::analysic_645_97_frame_by_afn(
+                analyzer,
+                frame,
+                result_list,
+                index,
+                region,
+            );
+        }
+
         let (mut updated_index, dir) = Self::analysic_head_frame(frame, result_list, index);
         let data_content = &frame[updated_index..];
         updated_index += index;
         let afn = frame[8];
         let protocol = ProtocolInfo::ProtocolDLT64507.name().to_string();
 
-        if afn == 0x11 {
-            // 下行读取报文
-            Self::analysic_read_frame(data_content, result_list, updated_index, &protocol, region, dir);
-        } else if afn == 0x91 || afn == 0xB1 {
-            // 读取回复正常
-            Self::analysic_read_response_frame(
-                data_content,
-                result_list,
-                updated_index,
-                &protocol,
-                region,
-                dir,
-            );
-        } else if matches!(afn, 0xD1 | 0xD2 | 0xD4 | 0xD6 | 0xD7 | 0xD9 | 0xDA | 0xDB) {
-            // 异常应答
-            Self::analysic_read_err_frame(
-                data_content,
-                result_list,
-                updated_index,
-                &protocol,
-                region,
-                dir,
-            );
-        } else if afn == 0x12 {
-            // 读取后续帧下行报文
-            Self::analysic_read_subsequent_frame(
-                data_content,
-                result_list,
-                updated_index,
-                &protocol,
-                region,
-                dir,
-            );
-        } else if afn == 0x92 || afn == 0xB2 {
-            // 读取后续帧回复报文
-            Self::analysic_read_subsequent_response_frame(
-                data_content,
-                result_list,
-                updated_index,
-                &protocol,
-                region,
-                dir,
-            );
-        } else if afn == 0x14 {
-            // 写数据
-            Self::analysic_write_frame(data_content, result_list, updated_index, &protocol, region, dir);
-        } else if afn == 0x93 {
-            // 读通信地址正常应答
-            Self::analysic_read_address_frame(
-                data_content,
-                result_list,
-                updated_index,
-                &protocol,
-                region,
-                dir,
-            );
-        } else if afn == 0x15 {
-            // 写数据
-            Self::analysic_write_address_frame(
-                data_content,
-                result_list,
-                updated_index,
-                &protocol,
-                region,
-                dir,
-            );
-        } else if afn == 0x08 {
-            Self::analysic_broadcast_time_frame(
-                data_content,
-                result_list,
-                updated_index,
-                &protocol,
-                region,
-                dir,
-            );
-        } else if afn == 0x16 {
-            // 冻结命令
-            Self::analysic_write_frozen_time_frame(
-                data_content,
-                result_list,
-                updated_index,
-                &protocol,
-                region,
-                dir,
-            );
-        } else if afn == 0x17 || afn == 0x97 {
-            // 更改通信速率
-            Self::analysic_write_baud_rate_frame(
-                data_content,
-                result_list,
-                updated_index,
-                &protocol,
-                region,
-                dir,
-            );
-        } else if afn == 0x18 {
-            // 更改密码
-            Self::analysic_write_password_frame(
-                data_content,
-                result_list,
-                updated_index,
-                &protocol,
-                region,
-                dir,
-            );
-        } else if afn == 0x98 {
-            // 修改密码应答
-            Self::analysic_write_password_response_frame(
-                data_content,
-                result_list,
-                updated_index,
-                &protocol,
-                region,
-                dir,
-            );
-        } else if afn == 0x19 {
-            // 最大需量清零
-            Self::analysic_maximum_demand_reset_frame(
-                data_content,
-                result_list,
-                updated_index,
-                &protocol,
-                region,
-                dir,
-            );
-        } else if afn == 0x1A {
-            // 电表清零
-            Self::analysic_meter_reset_frame(
-                data_content,
-                result_list,
-                updated_index,
-                &protocol,
-                region,
-                dir,
-            );
-        } else if afn == 0x1B {
-            // 事件清零
-            Self::analysic_event_reset_frame(
-                data_content,
-                result_list,
-                updated_index,
-                &protocol,
-                region,
-                dir,
-            );
-        } else {
-            Self::analysic_invalid_frame(data_content, result_list, updated_index, &protocol, region, dir);
+        // 控制码的解析方式来自功能码表，厂家自定义控制码可在配置中指定
+        let handler = analyzer
+            .find_afn(&protocol, afn, None, None)
+            .and_then(|entry| entry.handler);
+        match handler.as_deref() {
+            Some("read") => {
+                // 下行读取报文
+                Self::analysic_read_frame(
+                    analyzer,
+                    data_content,
+                    result_list,
+                    updated_index,
+                    &protocol,
+                    region,
+                    dir,
+                );
+            }
+            Some("read_response") => {
+                // 读取回复正常
+                Self::analysic_read_response_frame(
+                    analyzer,
+                    data_content,
+                    result_list,
+                    updated_index,
+                    &protocol,
+                    region,
+                    dir,
+                );
+            }
+            Some("read_err") => {
+                // 异常应答
+                Self::analysic_read_err_frame(
+                    data_content,
+                    result_list,
+                    updated_index,
+                    &protocol,
+                    region,
+                    dir,
+                );
+            }
+            Some("read_subsequent") => {
+                // 读取后续帧下行报文
+                Self::analysic_read_subsequent_frame(
+                    analyzer,
+                    data_content,
+                    result_list,
+                    updated_index,
+                    &protocol,
+                    region,
+                    dir,
+                );
+            }
+            Some("read_subsequent_response") => {
+                // 读取后续帧回复报文
+                Self::analysic_read_subsequent_response_frame(
+                    analyzer,
+                    data_content,
+                    result_list,
+                    updated_index,
+                    &protocol,
+                    region,
+                    dir,
+                );
+            }
+            Some("write") => {
+                // 写数据
+                Self::analysic_write_frame(
+                    analyzer,
+                    data_content,
+                    result_list,
+                    updated_index,
+                    &protocol,
+                    region,
+                    dir,
+                );
+            }
+            Some("read_address") => {
+                // 读通信地址正常应答
+                Self::analysic_read_address_frame(
+                    data_content,
+                    result_list,
+                    updated_index,
+                    &protocol,
+                    region,
+                    dir,
+                );
+            }
+            Some("write_address") => {
+                // 写通信地址
+                Self::analysic_write_address_frame(
+                    data_content,
+                    result_list,
+                    updated_index,
+                    &protocol,
+                    region,
+                    dir,
+                );
+            }
+            Some("broadcast_time") => {
+                Self::analysic_broadcast_time_frame(
+                    data_content,
+                    result_list,
+                    updated_index,
+                    &protocol,
+                    region,
+                    dir,
+                );
+            }
+            Some("frozen_time") => {
+                // 冻结命令
+                Self::analysic_write_frozen_time_frame(
+                    data_content,
+                    result_list,
+                    updated_index,
+                    &protocol,
+                    region,
+                    dir,
+                );
+            }
+            Some("baud_rate") => {
+                // 更改通信速率
+                Self::analysic_write_baud_rate_frame(
+                    data_content,
+                    result_list,
+                    updated_index,
+                    &protocol,
+                    region,
+                    dir,
+                );
+            }
+            Some("password") => {
+                // 更改密码
+                Self::analysic_write_password_frame(
+                    data_content,
+                    result_list,
+                    updated_index,
+                    &protocol,
+                    region,
+                    dir,
+                );
+            }
+            Some("password_response") => {
+                // 修改密码应答
+                Self::analysic_write_password_response_frame(
+                    data_content,
+                    result_list,
+                    updated_index,
+                    &protocol,
+                    region,
+                    dir,
+                );
+            }
+            Some("demand_reset") => {
+                // 最大需量清零
+                Self::analysic_maximum_demand_reset_frame(
+                    data_content,
+                    result_list,
+                    updated_index,
+                    &protocol,
+                    region,
+                    dir,
+                );
+            }
+            Some("meter_reset") => {
+                // 电表清零
+                Self::analysic_meter_reset_frame(
+                    data_content,
+                    result_list,
+                    updated_index,
+                    &protocol,
+                    region,
+                    dir,
+                );
+            }
+            Some("event_reset") => {
+                // 事件清零
+                Self::analysic_event_reset_frame(
+                    data_content,
+                    result_list,
+                    updated_index,
+                    &protocol,
+                    region,
+                    dir,
+                );
+            }
+            _ => {
+                Self::analysic_invalid_frame(
+                    data_content,
+                    result_list,
+                    updated_index,
+                    &protocol,
+                    region,
+                    dir,
+                );
+            }
         }
         Self::analysic_end_frame(data_content, result_list, updated_index);
         updated_index
     }
 
     pub fn analysic_head_frame(
+        frame: &[u8],
+        result_list: &mut Vec<Value>,
+        indx: usize,
+    ) -> (usize, u8) {
+        Self::analysic_head_frame_with(frame, result_list, indx, Self::func_code_name)
+    }
+
+    /// 控制码D0~D4功能码的名称
+    pub fn func_code_name(func_code: u8) -> &'static str {
+        match func_code {
+            0x00 => "保留",
+            0x08 => "广播校时",
+            0x11 => "读数据",
+            0x12 => "读后续数据",
+            0x13 => "读通信地址",
+            0x14 => "写数据",
+            0x15 => "写通信地址",
+            0x16 => "冻结命令",
+            0x17 => "更改通信速率",
+            0x18 => "修改密码",
+            0x19 => "最大需量清零",
+            0x1A => "电表清零",
+            0x1B => "事件清零",
+            _ => "未知",
+        }
+    }
+
+    /// 解析帧头，2007和1997规约的帧头结构相同，只有功能码定义不同
+    pub fn analysic_head_frame_with(
         frame: &[u8],
         result_list: &mut Vec<Value>,
         mut indx: usize,
+        func_code_name: fn(u8) -> &'static str,
     ) -> (usize, u8) {
         // 拷贝原始数据到一个新的Vec
         let origin_array = frame.to_vec();
@@ -259,32 +374,9 @@ impl Frame645 {
 
         let mut afn_data = Vec::new();
         let binary_array = FrameFun::get_bit_array(control_code);
-        let func_code: String = binary_array
-            .iter()
-            .rev()
-            .take(5)
-            .map(|&bit| bit.to_string())
-            .collect();
-
-        let func_code_str = match func_code.as_str() {
-            "00000" => "保留",
-            "01000" => "广播校时",
-            "10001" => "读数据",
-            "10010" => "读后续数据",
-            "10011" => "读通信地址",
-            "10100" => "写数据",
-            "10101" => "写通信地址",
-            "10110" => "冻结命令",
-            "10111" => "更改通信速率",
-            "11000" => "修改密码",
-            "11001" => "最大需量清零",
-            "11010" => "电表清零",
-            "11011" => "事件清零",
-            _ => "未知",
-        };
-
-        let binary_decimal = usize::from_str_radix(&func_code, 2).unwrap_or(0);
-        let hexadecimal = format!("{:X}", binary_decimal);
+        let func_code = control_code & 0x1F;
+        let func_code_str = func_code_name(func_code);
+        let hexadecimal = format!("{:X}", func_code);
 
         let d7_str = if binary_array[0] == 1 {
             "主站发出的命令帧"
@@ -398,6 +490,7 @@ impl Frame645 {
     }
 
     pub fn analysic_read_frame(
+        analyzer: &Analyzer,
         frame: &[u8],
         result_list: &mut Vec<Value>,
         indx: usize,
@@ -414,7 +507,7 @@ impl Frame645 {
 
         let mut data_list: Vec<Value> = Vec::new();
         if let Some(data_item) =
-            ProtocolConfigManager::get_config_xml(&data_item_str, protocol, region, Some(dir))
+            analyzer.get_config_xml(&data_item_str, protocol, region, Some(dir))
         {
             let name_text = data_item.get_child_text("name");
             let data_identifier_str = if let Some(name_text) = name_text {
@@ -504,21 +597,47 @@ impl Frame645 {
     }
 
     pub fn analysic_read_response_frame(
+        analyzer: &Analyzer,
         frame: &[u8],
         result_list: &mut Vec<Value>,
         indx: usize,
         protocol: &str,
         region: &str,
         dir: u8,
+    ) {
+        Self::analysic_data_identifier_frame(
+            analyzer,
+            frame,
+            result_list,
+            indx,
+            protocol,
+            region,
+            dir,
+            4,
+        );
+    }
+
+    /// 解析数据标识及其后的数据内容，di_len为数据标识的字节数(2007为4，1997为2)
+    #[allow(clippy::too_many_arguments)]
+    pub fn analysic_data_identifier_frame(
+        analyzer: &Analyzer,
+        frame: &[u8],
+        result_list: &mut Vec<Value>,
+        indx: usize,
+        protocol: &str,
+        region: &str,
+        dir: u8,
+        di_len: usize,
     ) {
         let mut data_list = Vec::new();
-        let data_identifier = &frame[10..14];
-        let data_content = &frame[14..frame.len() - 2];
+        let data_start = 10 + di_len;
+        let data_identifier = &frame[10..data_start];
+        let data_content = &frame[data_start..frame.len() - 2];
         let length = frame.len();
         let mut pos = 0;
         let data_item_str = FrameFun::get_data_str_delete_33h_reverse(data_identifier);
         if let Some(mut data_item_elem) =
-            ProtocolConfigManager::get_config_xml(&data_item_str, protocol, region, Some(dir))
+            analyzer.get_config_xml(&data_item_str, protocol, region, Some(dir))
         {
             let mut sub_result: Vec<Value> = Vec::new();
             let sublength_ele = data_item_elem.get_child_text("length");
@@ -526,6 +645,7 @@ impl Frame645 {
             let sublength = if let Some(sublength_ele) = sublength_ele {
                 if sublength_ele.to_uppercase() == "UNKNOWN" {
                     FrameCsg::calculate_item_length(
+                        analyzer,
                         &mut data_item_elem,
                         data_content,
                         protocol,
@@ -535,6 +655,7 @@ impl Frame645 {
                     )
                 } else {
                     let (sub_length, _new_datament) = FrameCsg::recalculate_sub_length(
+                        analyzer,
                         &mut data_item_elem,
                         data_content,
                         protocol,
@@ -548,7 +669,19 @@ impl Frame645 {
             };
 
             let all_length = data_content.len();
-            if all_length % sublength != 0 && all_length > sublength {
+            if all_length == 0 || sublength == 0 {
+                // 只有数据标识没有数据内容，或数据项长度无法确定
+                FrameFun::add_data(
+                    &mut sub_result,
+                    "数据长度不足".to_string(),
+                    FrameFun::get_data_str_with_space(data_content),
+                    format!("数据标识[{}]数据长度不足，无法解析数据内容", data_item_str),
+                    vec![indx + data_start, indx + length - 2],
+                    None,
+                    None,
+                );
+                pos = all_length;
+            } else if all_length % sublength != 0 && all_length > sublength && all_length >= 5 {
                 let time = &data_content[..5];
                 let time_str = FrameFun::parse_time_data(time, "mmhhDDMMYY", true);
                 FrameFun::add_data(
@@ -556,7 +689,7 @@ impl Frame645 {
                     "数据起始时间".to_string(),
                     FrameFun::get_data_str_with_space(time),
                     time_str,
-                    vec![indx + 14, indx + 19],
+                    vec![indx + data_start, indx + data_start + 5],
                     None,
                     None,
                 );
@@ -568,13 +701,14 @@ impl Frame645 {
                 pos, sublength, data_item_str, all_length
             );
             data_item_elem.update_value("length", sublength.to_string());
-            while pos + sublength <= all_length {
+            while sublength > 0 && pos + sublength <= all_length {
                 let alalysic_result = FrameAnalisyic::prase_data(
+                    analyzer,
                     &mut data_item_elem,
                     protocol,
                     region,
                     &data_content[pos..pos + sublength],
-                    14 + pos,
+                    indx + data_start + pos,
                     Some(dir),
                 );
                 pos += sublength;
@@ -594,7 +728,7 @@ impl Frame645 {
                 "数据标识编码".to_string(),
                 FrameFun::get_data_str_with_space(data_identifier),
                 data_identifier_str.clone(),
-                vec![indx + 10, indx + 14],
+                vec![indx + 10, indx + data_start],
                 None,
                 None,
             );
@@ -607,7 +741,7 @@ impl Frame645 {
                     data_item_str.clone(),
                     FrameFun::get_data_str_delete_33h_reverse(data_content)
                 ),
-                vec![indx + 14, indx + length - 2],
+                vec![indx + data_start, indx + length - 2],
                 Some(sub_result),
                 None,
             );
@@ -618,7 +752,7 @@ impl Frame645 {
                 "数据标识编码".to_string(),
                 FrameFun::get_data_str_with_space(data_identifier),
                 dis_data_identifier.clone(),
-                vec![indx + 10, indx + 14],
+                vec![indx + 10, indx + data_start],
                 None,
                 None,
             );
@@ -631,7 +765,7 @@ impl Frame645 {
                     data_item_str.clone(),
                     FrameFun::get_data_str_delete_33h_reverse(data_content)
                 ),
-                vec![indx + 14, indx + length - 2],
+                vec![indx + data_start, indx + length - 2],
                 None,
                 None,
             );
@@ -706,6 +840,7 @@ impl Frame645 {
     }
 
     pub fn analysic_read_subsequent_frame(
+        analyzer: &Analyzer,
         frame: &[u8],
         result_list: &mut Vec<Value>,
         indx: usize,
@@ -721,7 +856,7 @@ impl Frame645 {
 
         let mut data_list = Vec::new();
         if let Some(data_item) =
-            ProtocolConfigManager::get_config_xml(&data_identifier_str, protocol, region, Some(dir))
+            analyzer.get_config_xml(&data_identifier_str, protocol, region, Some(dir))
         {
             let name_ele = data_item.get_child_text("name");
             let data_identifier_str = if let Some(name) = name_ele {
@@ -773,6 +908,7 @@ impl Frame645 {
     }
 
     pub fn analysic_read_subsequent_response_frame(
+        analyzer: &Analyzer,
         frame: &[u8],
         result_list: &mut Vec<Value>,
         indx: usize,
@@ -788,7 +924,7 @@ impl Frame645 {
         let data_identifier_str = FrameFun::get_data_str_delete_33h_reverse(data_identifier);
 
         if let Some(mut data_item_elem) =
-            ProtocolConfigManager::get_config_xml(&data_identifier_str, protocol, region, Some(dir))
+            analyzer.get_config_xml(&data_identifier_str, protocol, region, Some(dir))
         {
             let name_ele = data_item_elem.get_child_text("name");
             let data_item_str = if let Some(name) = name_ele {
@@ -798,6 +934,7 @@ impl Frame645 {
             };
 
             let sub_result = FrameAnalisyic::prase_data(
+                analyzer,
                 &mut data_item_elem,
                 protocol,
                 region,
@@ -874,6 +1011,7 @@ impl Frame645 {
     }
 
     pub fn analysic_write_frame(
+        analyzer: &Analyzer,
         frame: &[u8],
         result_list: &mut Vec<Value>,
         indx: usize,
@@ -891,8 +1029,7 @@ impl Frame645 {
         let item_str = FrameFun::get_data_str_delete_33h_reverse(data_identifier);
         let mut data_list = Vec::new();
 
-        if let Some(mut data_item) =
-            ProtocolConfigManager::get_config_xml(&item_str, protocol, region, Some(dir))
+        if let Some(mut data_item) = analyzer.get_config_xml(&item_str, protocol, region, Some(dir))
         {
             let name_ele = data_item.get_child_text("name");
             let data_identifier_str = if let Some(name) = name_ele {
@@ -945,6 +1082,7 @@ impl Frame645 {
                 None,
             );
             let write_result = FrameAnalisyic::prase_data(
+                analyzer,
                 &mut data_item,
                 protocol,
                 region,
@@ -0,0 +1,620 @@
+use crate::analyzer::Analyzer;
+use crate::basefunc::frame_645::Frame645;
+use crate::basefunc::frame_fun::FrameFun;
+use crate::basefunc::protocol::{FrameAnalisyic, ProtocolInfo};
+use serde_json::Value;
+
+/// DL/T645-1997规约
+///
+/// 帧格式与2007相同，区别在于控制码的功能码定义和2字节的数据标识(DI1 DI0)
+pub struct Frame64597;
+
+impl Frame64597 {
+    /// 是否为645-1997报文，按控制码和数据域长度与2007区分
+    ///
+    /// 广播校时(08H)两个版本格式相同，按2007解析；重读数据(03H)与2007的安全认证共用功能码，
+    /// 只识别不带数据的下行命令
+    pub fn is_dlt645_97_frame(data: &[u8]) -> bool {
+        if !Frame645::is_dlt645_frame(data) {
+            return false;
+        }
+        let pos = FrameFun::get_frame_fe_count(data);
+        let control = data[pos + 8];
+        let data_length = data[pos + 9];
+        let is_response = control & 0x80 != 0;
+
+        // 异常应答只带1字节错误信息字
+        if is_response && control & 0x40 != 0 {
+            return matches!(control & 0x1F, 0x01 | 0x02 | 0x04 | 0x0C | 0x0F | 0x10)
+                && data_length == 1;
+        }
+        match (control & 0x1F, is_response) {
+            (0x01 | 0x02, false) => data_length == 2,
+            (0x01 | 0x02, true) => data_length >= 2,
+            (0x03, false) => data_length == 0,
+            (0x04, false) => data_length > 2,
+            (0x04 | 0x0A | 0x10, true) => data_length == 0,
+            (0x0A, false) => data_length == 6,
+            (0x0C, _) => data_length == 1,
+            (0x0F, false) => data_length == 10,
+            (0x0F, true) => data_length == 4,
+            (0x10, false) => data_length == 0,
+            _ => false,
+        }
+    }
+
+    /// 控制码D0~D4功能码的名称
+    pub fn func_code_name(func_code: u8) -> &'static str {
+        match func_code {
+            0x00 => "保留",
+            0x01 => "读数据",
+            0x02 => "读后续数据",
+            0x03 => "重读数据",
+            0x04 => "写数据",
+            0x08 => "广播校时",
+            0x0A => "写设备地址",
+            0x0C => "更改通信速率",
+            0x0F => "修改密码",
+            0x10 => "最大需量清零",
+            _ => "未知",
+        }
+    }
+
+    pub fn analysic_645_97_frame_by_afn(
+        analyzer: &Analyzer,
+        frame: &[u8],
+        result_list: &mut Vec<Value>,
+        index: usize,
+        region: &str,
+    ) -> usize {
+        if frame.len() < 12 {
+            return 0;
+        }
+
+        let (mut updated_index, dir) =
+            Frame645::analysic_head_frame_with(frame, result_list, index, Self::func_code_name);
+        let data_content = &frame[updated_index..];
+        updated_index += index;
+        let control = data_content[8];
+        let protocol = ProtocolInfo::ProtocolDLT64597.name().to_string();
+
+        let handler = analyzer
+            .find_afn(&protocol, control, None, None)
+            .and_then(|entry| entry.handler);
+        match handler.as_deref() {
+            Some("read") => {
+                Self::analysic_read_frame(
+                    analyzer,
+                    data_content,
+                    result_list,
+                    updated_index,
+                    &protocol,
+                    region,
+                    dir,
+                );
+            }
+            Some("read_response") => {
+                // 数据标识后跟数据内容
+                Frame645::analysic_data_identifier_frame(
+                    analyzer,
+                    data_content,
+                    result_list,
+                    updated_index,
+                    &protocol,
+                    region,
+                    dir,
+                    2,
+                );
+            }
+            Some("write") => {
+                // 数据标识后跟密码，再跟写入的数据
+                Self::analysic_write_frame(
+                    analyzer,
+                    data_content,
+                    result_list,
+                    updated_index,
+                    &protocol,
+                    region,
+                    dir,
+                );
+            }
+            Some("read_err") => {
+                Self::analysic_read_err_frame(data_content, result_list, updated_index);
+            }
+            Some("reread") | Some("write_response") | Some("demand_reset") => {
+                // 标准中不带数据域，带了则按原始数据显示
+                if data_content.len() > 12 {
+                    Frame645::analysic_invalid_frame(
+                        data_content,
+                        result_list,
+                        updated_index,
+                        &protocol,
+                        region,
+                        dir,
+                    );
+                }
+            }
+            Some("broadcast_time") => {
+                Frame645::analysic_broadcast_time_frame(
+                    data_content,
+                    result_list,
+                    updated_index,
+                    &protocol,
+                    region,
+                    dir,
+                );
+            }
+            Some("write_address") => {
+                Frame645::analysic_write_address_frame(
+                    data_content,
+                    result_list,
+                    updated_index,
+                    &protocol,
+                    region,
+                    dir,
+                );
+            }
+            Some("baud_rate") => {
+                Frame645::analysic_write_baud_rate_frame(
+                    data_content,
+                    result_list,
+                    updated_index,
+                    &protocol,
+                    region,
+                    dir,
+                );
+            }
+            Some("password") => {
+                Self::analysic_write_password_frame(
+                    analyzer,
+                    data_content,
+                    result_list,
+                    updated_index,
+                    &protocol,
+                    region,
+                    dir,
+                );
+            }
+            Some("password_response") => {
+                Self::analysic_password_response_frame(data_content, result_list, updated_index);
+            }
+            _ => {
+                Frame645::analysic_invalid_frame(
+                    data_content,
+                    result_list,
+                    updated_index,
+                    &protocol,
+                    region,
+                    dir,
+                );
+            }
+        }
+        Frame645::analysic_end_frame(data_content, result_list, updated_index);
+        updated_index
+    }
+
+    fn data_identifier_desc(
+        analyzer: &Analyzer,
+        data_identifier: &[u8],
+        protocol: &str,
+        region: &str,
+        dir: u8,
+    ) -> String {
+        let data_item_str = FrameFun::get_data_str_delete_33h_reverse(data_identifier);
+        let name = analyzer
+            .get_config_xml(&data_item_str, protocol, region, Some(dir))
+            .and_then(|data_item| data_item.get_child_text("name"));
+        match name {
+            Some(name) => format!("数据标识编码：[{}] - {}", data_item_str, name),
+            None => format!("数据标识编码：[{}]", data_item_str),
+        }
+    }
+
+    pub fn analysic_read_frame(
+        analyzer: &Analyzer,
+        frame: &[u8],
+        result_list: &mut Vec<Value>,
+        indx: usize,
+        protocol: &str,
+        region: &str,
+        dir: u8,
+    ) {
+        let length = frame.len();
+        let data_identifier = &frame[10..12];
+        let mut data_list = Vec::new();
+        FrameFun::add_data(
+            &mut data_list,
+            "数据标识编码".to_string(),
+            FrameFun::get_data_str_with_space(data_identifier),
+            Self::data_identifier_desc(analyzer, data_identifier, protocol, region, dir),
+            vec![indx + 10, indx + 12],
+            None,
+            None,
+        );
+        FrameFun::add_data(
+            result_list,
+            "数据域".to_string(),
+            "".to_string(),
+            "数据域传输时按字节进行加33H处理，接收后应按字节减33H处理".to_string(),
+            vec![indx + 10, indx + length - 2],
+            Some(data_list),
+            None,
+        );
+    }
+
+    /// 写数据：DI1 DI0 + 密码PA P0 P1 P2 + 数据
+    pub fn analysic_write_frame(
+        analyzer: &Analyzer,
+        frame: &[u8],
+        result_list: &mut Vec<Value>,
+        indx: usize,
+        protocol: &str,
+        region: &str,
+        dir: u8,
+    ) {
+        let length = frame.len();
+        let data_end = length - 2;
+        let data_identifier = &frame[10..12];
+        let mut data_list = Vec::new();
+        FrameFun::add_data(
+            &mut data_list,
+            "数据标识编码".to_string(),
+            FrameFun::get_data_str_with_space(data_identifier),
+            Self::data_identifier_desc(analyzer, data_identifier, protocol, region, dir),
+            vec![indx + 10, indx + 12],
+            None,
+            None,
+        );
+
+        if data_end < 16 {
+            FrameFun::add_data(
+                &mut data_list,
+                "数据长度不足".to_string(),
+                FrameFun::get_data_str_with_space(&frame[12..data_end]),
+                "写数据应包含4字节密码，数据长度不足".to_string(),
+                vec![indx + 12, indx + data_end],
+                None,
+                None,
+            );
+        } else {
+            let password = &frame[12..16];
+            let write_data = &frame[16..data_end];
+            FrameFun::add_data(
+                &mut data_list,
+                "密码权限".to_string(),
+                format!("{:02X}", password[0]),
+                format!("权限：{:02X}", password[0].wrapping_sub(0x33)),
+                vec![indx + 12, indx + 13],
+                None,
+                None,
+            );
+            FrameFun::add_data(
+                &mut data_list,
+                "密码".to_string(),
+                FrameFun::get_data_str_with_space(&password[1..]),
+                format!(
+                    "密码：{}",
+                    FrameFun::get_data_str_delete_33h_reverse(&password[1..])
+                ),
+                vec![indx + 13, indx + 16],
+                None,
+                None,
+            );
+
+            let data_item_str = FrameFun::get_data_str_delete_33h_reverse(data_identifier);
+            let write_result = analyzer
+                .get_config_xml(&data_item_str, protocol, region, Some(dir))
+                .filter(|_| !write_data.is_empty())
+                .map(|mut data_item| {
+                    FrameAnalisyic::prase_data(
+                        analyzer,
+                        &mut data_item,
+                        protocol,
+                        region,
+                        write_data,
+                        indx + 16,
+                        Some(dir),
+                    )
+                });
+            FrameFun::add_data(
+                &mut data_list,
+                "数据内容".to_string(),
+                FrameFun::get_data_str_with_space(write_data),
+                format!(
+                    "写数据内容：{}",
+                    FrameFun::get_data_str_delete_33h_reverse(write_data)
+                ),
+                vec![indx + 16, indx + data_end],
+                write_result,
+                None,
+            );
+        }
+
+        FrameFun::add_data(
+            result_list,
+            "数据域".to_string(),
+            "".to_string(),
+            "数据域传输时按字节进行加33H处理，接收后应按字节减33H处理".to_string(),
+            vec![indx + 10, indx + data_end],
+            Some(data_list),
+            None,
+        );
+    }
+
+    pub fn analysic_read_err_frame(frame: &[u8], result_list: &mut Vec<Value>, indx: usize) {
+        let err_code = frame[10].wrapping_sub(0x33);
+        let err_list: Vec<&str> = [
+            (0, "非法数据"),
+            (1, "数据标识错"),
+            (2, "密码错"),
+            (4, "年时区数超"),
+            (5, "日时段数超"),
+            (6, "费率数超"),
+        ]
+        .iter()
+        .filter(|(bit, _)| err_code & (1 << bit) != 0)
+        .map(|(_, name)| *name)
+        .collect();
+        let err_str = if !err_list.is_empty() {
+            err_list.join("、")
+        } else if err_code != 0 {
+            "其他错误".to_string()
+        } else {
+            "".to_string()
+        };
+
+        FrameFun::add_data(
+            result_list,
+            "错误信息字".to_string(),
+            format!("{:02X}", frame[10]),
+            format!("错误类型: {}", err_str),
+            vec![indx + 10, indx + 11],
+            None,
+            None,
+        );
+    }
+
+    pub fn analysic_write_password_frame(
+        analyzer: &Analyzer,
+        frame: &[u8],
+        result_list: &mut Vec<Value>,
+        indx: usize,
+        protocol: &str,
+        region: &str,
+        dir: u8,
+    ) {
+        let data_identifier = &frame[10..12];
+        let original_password = &frame[12..16];
+        let new_password = &frame[16..20];
+        let mut data_list = Vec::new();
+        FrameFun::add_data(
+            &mut data_list,
+            "数据标识编码".to_string(),
+            FrameFun::get_data_str_with_space(data_identifier),
+            Self::data_identifier_desc(analyzer, data_identifier, protocol, region, dir),
+            vec![indx + 10, indx + 12],
+            None,
+            None,
+        );
+        FrameFun::add_data(
+            &mut data_list,
+            "原密码".to_string(),
+            FrameFun::get_data_str_with_space(original_password),
+            format!(
+                "原密码权限及密码：{}",
+                FrameFun::get_data_str_delete_33h_reverse(original_password)
+            ),
+            vec![indx + 12, indx + 16],
+            None,
+            None,
+        );
+        FrameFun::add_data(
+            &mut data_list,
+            "新密码".to_string(),
+            FrameFun::get_data_str_with_space(new_password),
+            format!(
+                "新密码权限及密码：{}",
+                FrameFun::get_data_str_delete_33h_reverse(new_password)
+            ),
+            vec![indx + 16, indx + 20],
+            None,
+            None,
+        );
+        FrameFun::add_data(
+            result_list,
+            "数据域".to_string(),
+            "".to_string(),
+            "数据域传输时按字节进行加33H处理，接收后应按字节减33H处理".to_string(),
+            vec![indx + 10, indx + 20],
+            Some(data_list),
+            None,
+        );
+    }
+
+    pub fn analysic_password_response_frame(
+        frame: &[u8],
+        result_list: &mut Vec<Value>,
+        indx: usize,
+    ) {
+        let new_password = &frame[10..14];
+        FrameFun::add_data(
+            result_list,
+            "新密码".to_string(),
+            FrameFun::get_data_str_with_space(new_password),
+            format!(
+                "新密码权限及密码：{}",
+                FrameFun::get_data_str_delete_33h_reverse(new_password)
+            ),
+            vec![indx + 10, indx + 14],
+            None,
+            None,
+        );
+    }
+
+    /// 组读数据帧，di为数据标识，如0x9010
+    pub fn build_read_frame(address: &str, di: u16) -> Result<Vec<u8>, String> {
+        Frame645::build_frame(address, 0x01, &di.to_le_bytes())
+    }
+
+    /// 组写数据帧，password为权限加3字节密码(低字节在前)，data为加33H前的数据内容(低字节在前)
+    pub fn build_write_frame(
+        address: &str,
+        di: u16,
+        password: &[u8],
+        data: &[u8],
+    ) -> Result<Vec<u8>, String> {
+        if password.len() != 4 {
+            return Err(format!(
+                "密码应为4字节(权限+密码)，实际{}字节",
+                password.len()
+            ));
+        }
+        let mut content = di.to_le_bytes().to_vec();
+        content.extend_from_slice(password);
+        content.extend_from_slice(data);
+        Frame645::build_frame(address, 0x04, &content)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::basefunc::test_util::find;
+
+    // 9010 (当前)正向有功总电能 = 123456.78 kWh 的读数据应答
+    fn read_response() -> Vec<u8> {
+        Frame645::build_frame("000000000001", 0x81, &[0x10, 0x90, 0x78, 0x56, 0x34, 0x12]).unwrap()
+    }
+
+    // 在报文(或内嵌报文)的解析结果中检查数据标识及其内容的位置
+    fn assert_energy(result: &[Value], start: usize) {
+        let di = find(result, "数据标识编码").unwrap();
+        assert_eq!(di["data"], "43 C3");
+        assert_eq!(
+            di["description"],
+            "数据标识编码：[9010] - (当前)正向有功总电能"
+        );
+        assert_eq!(di["position"], serde_json::json!([start, start + 2]));
+        let value = find(result, "9010_(当前)正向有功总电能").unwrap();
+        assert_eq!(
+            value["description"],
+            "[9010_(当前)正向有功总电能]: 123456.78 kWh"
+        );
+        assert_eq!(value["position"], serde_json::json!([start + 2, start + 6]));
+    }
+
+    #[test]
+    fn read_request_and_response() {
+        let analyzer = Analyzer::with_builtin();
+        let request = Frame64597::build_read_frame("000000000001", 0x9010).unwrap();
+        assert_eq!(
+            FrameFun::get_data_str_with_space(&request),
+            "68 01 00 00 00 00 00 68 01 02 43 C3 DA 16"
+        );
+        let (protocol, result) = analyzer.process_frame(&request, "南网");
+        assert_eq!(protocol, ProtocolInfo::ProtocolDLT64597.name());
+        assert_eq!(
+            find(&result, "D0~D4功能码").unwrap()["description"],
+            "读数据"
+        );
+        assert_eq!(
+            find(&result, "数据标识编码").unwrap()["description"],
+            "数据标识编码：[9010] - (当前)正向有功总电能"
+        );
+
+        let response = read_response();
+        assert_eq!(
+            FrameFun::get_data_str_with_space(&response),
+            "68 01 00 00 00 00 00 68 81 06 43 C3 AB 89 67 45 3E 16"
+        );
+        let (protocol, result) = analyzer.process_frame(&response, "南网");
+        assert_eq!(protocol, ProtocolInfo::ProtocolDLT64597.name());
+        assert_energy(&result, 10);
+    }
+
+    #[test]
+    fn told_apart_from_2007() {
+        let read_97 = Frame64597::build_read_frame("000000000001", 0x9010).unwrap();
+        assert!(Frame64597::is_dlt645_97_frame(&read_97));
+        assert!(Frame64597::is_dlt645_97_frame(&read_response()));
+
+        // 2007读数据 11H/91H，4字节数据标识
+        let read_07 =
+            Frame645::build_frame("000000000001", 0x11, &[0x00, 0x00, 0x01, 0x00]).unwrap();
+        let response_07 = Frame645::build_frame(
+            "000000000001",
+            0x91,
+            &[0x00, 0x00, 0x01, 0x00, 0x78, 0x56, 0x34, 0x12],
+        )
+        .unwrap();
+        assert!(!Frame64597::is_dlt645_97_frame(&read_07));
+        assert!(!Frame64597::is_dlt645_97_frame(&response_07));
+        let (protocol, _) = Analyzer::with_builtin().process_frame(&read_07, "南网");
+        assert_eq!(protocol, ProtocolInfo::ProtocolDLT64507.name());
+
+        // 97读数据的控制码带4字节数据标识不是97报文
+        let four_byte_di =
+            Frame645::build_frame("000000000001", 0x01, &[0x00, 0x00, 0x01, 0x00]).unwrap();
+        assert!(!Frame64597::is_dlt645_97_frame(&four_byte_di));
+    }
+
+    #[test]
+    fn nested_in_csg_relay() {
+        let inner = read_response();
+        // AFN 10 上行，DA 0000，DI E3020001，中继类型 00，中继报文长度，中继报文
+        let mut body = vec![0x88, 0x00, 0x44, 0x12, 0x34, 0x56, 0x00, 0x00, 0x10, 0x60];
+        body.extend_from_slice(&[0x00, 0x00, 0x01, 0x00, 0x02, 0xE3, 0x00, inner.len() as u8]);
+        body.extend_from_slice(&inner);
+        let length = (body.len() as u16).to_le_bytes();
+        let mut frame = vec![0x68, length[0], length[1], length[0], length[1], 0x68];
+        frame.extend_from_slice(&body);
+        frame.push(FrameFun::calculate_cs(&body));
+        frame.push(0x16);
+
+        let (protocol, result) = Analyzer::with_builtin().process_frame(&frame, "南网");
+        assert_eq!(protocol, ProtocolInfo::ProtocolCSG13.name());
+        let relay = find(&result, "中继报文").unwrap();
+        assert_eq!(relay["position"], serde_json::json!([24, 42]));
+        assert_energy(relay["children"].as_array().unwrap(), 34);
+    }
+
+    #[test]
+    fn nested_in_cco_report() {
+        let inner = read_response();
+        // 上行启动站不带地址域，AFN 05，DI E8050501 上报任务数据：任务ID、报文长度、报文内容
+        let mut frame = vec![0x68, 0x00, 0x00, 0xC0, 0x05, 0x01, 0x01, 0x05, 0x05, 0xE8];
+        frame.extend_from_slice(&[0x01, 0x00, inner.len() as u8]);
+        frame.extend_from_slice(&inner);
+        let length = (frame.len() as u16 + 2).to_le_bytes();
+        frame[1] = length[0];
+        frame[2] = length[1];
+        frame.push(FrameFun::calculate_cs(&frame[3..]));
+        frame.push(0x16);
+
+        let (protocol, result) = Analyzer::with_builtin().process_frame(&frame, "南网");
+        assert_eq!(protocol, ProtocolInfo::ProtocolCSG16.name());
+        let content = find(&result, "报文内容").unwrap();
+        assert_eq!(content["position"], serde_json::json!([13, 31]));
+        assert_energy(content["children"].as_array().unwrap(), 23);
+    }
+
+    #[test]
+    fn write_frame_with_password() {
+        let frame = Frame64597::build_write_frame(
+            "000000000001",
+            0xC011,
+            &[0x02, 0x11, 0x11, 0x11],
+            &[0x56, 0x34, 0x12],
+        )
+        .unwrap();
+        // 数据域依次为数据标识、权限、密码、数据，均加33H
+        assert_eq!(frame[9], 9);
+        assert_eq!(
+            &frame[10..19],
+            &[0x44, 0xF3, 0x35, 0x44, 0x44, 0x44, 0x89, 0x67, 0x45]
+        );
+        assert!(Frame64597::is_dlt645_97_frame(&frame));
+
+        assert!(Frame64597::build_write_frame("000000000001", 0xC011, &[0x02], &[]).is_err());
+    }
+}
@@ -0,0 +1,2053 @@
+use crate::analyzer::Analyzer;
+use crate::basefunc::axdr::*;
+use crate::basefunc::frame_645::Frame645;
+use crate::basefunc::frame_fun::FrameFun;
+use crate::basefunc::protocol::ProtocolInfo;
+use serde_json::Value;
+
+// 698.45 Data类型标签
+
+/// 对象的换算及单位，实际值 = 编码值 × 10^scaler
+#[derive(Clone, Debug)]
+pub struct OadScale {
+    pub scaler: i32,
+    pub unit: Option<String>,
+}
+
+impl OadScale {
+    pub fn format(&self, value: i128) -> String {
+        let number = if self.scaler < 0 {
+            let decimal = self.scaler.unsigned_abs();
+            let divisor = 10u128.pow(decimal);
+            let sign = if value < 0 { "-" } else { "" };
+            let abs = value.unsigned_abs();
+            format!(
+                "{}{}.{:0width$}",
+                sign,
+                abs / divisor,
+                abs % divisor,
+                width = decimal as usize
+            )
+        } else {
+            (value * 10i128.pow(self.scaler as u32)).to_string()
+        };
+        match &self.unit {
+            Some(unit) => format!("{}{}", number, unit),
+            None => number,
+        }
+    }
+}
+
+/// DL/T698.45面向对象的用电信息数据交换协议
+///
+/// 帧格式：68 L(2) C SA CA HCS(2) APDU FCS(2) 16，HCS和FCS为PPP FCS16校验，低字节在前
+pub struct Frame698;
+
+impl Frame698 {
+    pub fn is_698_frame(data: &[u8]) -> bool {
+        let frame = &data[FrameFun::get_frame_fe_count(data)..];
+        let head_length = match Self::get_head_length(frame) {
+            Some(length) => length,
+            None => return false,
+        };
+        let length = (u16::from_le_bytes([frame[1], frame[2]]) & 0x3FFF) as usize;
+        if frame.len() != length + 2 || frame[length + 1] != 0x16 || head_length + 3 > frame.len() {
+            return false;
+        }
+        if !matches!(frame[3] & 0x07, 0x01 | 0x03) {
+            return false;
+        }
+        // 帧头校验或帧校验有一个正确即按698解析，便于显示校验错误
+        FrameFun::check_fcs16(
+            &frame[1..head_length - 2],
+            &frame[head_length - 2..head_length],
+        ) || FrameFun::check_fcs16(&frame[1..length - 1], &frame[length - 1..length + 1])
+    }
+
+    /// 帧头校验和帧校验均正确，frame不含唤醒符
+    pub fn is_check_ok(frame: &[u8]) -> bool {
+        let head_length = match Self::get_head_length(frame) {
+            Some(length) if frame.len() >= length + 3 => length,
+            _ => return false,
+        };
+        let length = frame.len();
+        FrameFun::check_fcs16(
+            &frame[1..head_length - 2],
+            &frame[head_length - 2..head_length],
+        ) && FrameFun::check_fcs16(&frame[1..length - 3], &frame[length - 3..length - 1])
+    }
+
+    /// 帧头长度，从起始字符到HCS
+    fn get_head_length(frame: &[u8]) -> Option<usize> {
+        if frame.len() < 5 || frame[0] != 0x68 {
+            return None;
+        }
+        let head_length = 8 + (frame[4] & 0x0F) as usize + 1;
+        if frame.len() < head_length {
+            return None;
+        }
+        Some(head_length)
+    }
+
+    /// 组一帧698.45报文：68 L C SA CA HCS APDU FCS 16
+    ///
+    /// address_type为地址类型(0单地址、1通配地址、2组地址、3广播地址)，address为服务器地址的
+    /// 显示形式(高字节在前)；分帧时apdu中应包含分帧格式域
+    pub fn build_frame(
+        control: u8,
+        address_type: u8,
+        address: &str,
+        client_address: u8,
+        apdu: &[u8],
+    ) -> Result<Vec<u8>, String> {
+        let address_str = address.replace(' ', "");
+        if !(2..=32).contains(&address_str.len())
+            || !address_str.len().is_multiple_of(2)
+            || !address_str.chars().all(|c| c.is_ascii_hexdigit())
+        {
+            return Err(format!("服务器地址[{}]应为2~32位十六进制数", address_str));
+        }
+        let address = FrameFun::get_hex_frame(&address_str)
+            .ok_or_else(|| format!("服务器地址[{}]应为2~32位十六进制数", address_str))?;
+        if address_type > 3 {
+            return Err(format!("地址类型{}无效", address_type));
+        }
+        let length = 2 + 1 + 1 + address.len() + 1 + 2 + apdu.len() + 2;
+        if length > 0x3FFF {
+            return Err(format!("帧长度{}超过16383字节", length));
+        }
+
+        let mut frame = Vec::with_capacity(length + 2);
+        frame.push(0x68);
+        frame.extend_from_slice(&(length as u16).to_le_bytes());
+        frame.push(control);
+        frame.push((address_type << 6) | (address.len() - 1) as u8);
+        frame.extend(address.iter().rev());
+        frame.push(client_address);
+        FrameFun::append_fcs16(&mut frame, 1);
+        frame.extend_from_slice(apdu);
+        FrameFun::append_fcs16(&mut frame, 1);
+        frame.push(0x16);
+        Ok(frame)
+    }
+
+    pub fn analysic_698_frame(
+        analyzer: &Analyzer,
+        frame: &[u8],
+        result_list: &mut Vec<Value>,
+        index: usize,
+        region: &str,
+    ) -> usize {
+        let fe_count = FrameFun::get_frame_fe_count(frame);
+        if fe_count > 0 {
+            FrameFun::add_data(
+                result_list,
+                "唤醒符".to_string(),
+                FrameFun::get_data_str_with_space(&frame[..fe_count]),
+                "698.45规约：唤醒符".to_string(),
+                vec![index, index + fe_count],
+                None,
+                None,
+            );
+        }
+        let data = &frame[fe_count..];
+        let indx = index + fe_count;
+        let head_length = match Self::get_head_length(data) {
+            Some(length) if data.len() >= length + 3 => length,
+            _ => return 0,
+        };
+        let control = data[3];
+        Self::analysic_head_frame(data, result_list, indx, head_length);
+
+        let mut pos = head_length;
+        if control & 0x20 != 0 && data.len() >= pos + 5 {
+            Self::analysic_split_frame(&data[pos..pos + 2], result_list, indx + pos);
+            pos += 2;
+        }
+        let length = data.len();
+        Self::analysic_link_data_frame(
+            analyzer,
+            &data[pos..length - 3],
+            control,
+            result_list,
+            indx + pos,
+            region,
+        );
+        Self::analysic_end_frame(data, result_list, indx);
+        index + frame.len()
+    }
+
+    pub fn analysic_head_frame(
+        frame: &[u8],
+        result_list: &mut Vec<Value>,
+        indx: usize,
+        head_length: usize,
+    ) {
+        FrameFun::add_data(
+            result_list,
+            "起始字符".to_string(),
+            format!("{:02X}", frame[0]),
+            "698.45规约：标识一帧信息的开始".to_string(),
+            vec![indx, indx + 1],
+            None,
+            None,
+        );
+
+        let length_value = u16::from_le_bytes([frame[1], frame[2]]);
+        let length = length_value & 0x3FFF;
+        let unit = if length_value & 0x4000 != 0 {
+            "千字节"
+        } else {
+            "字节"
+        };
+        FrameFun::add_data(
+            result_list,
+            "长度域".to_string(),
+            FrameFun::get_data_str_with_space(&frame[1..3]),
+            format!("长度={}{}(起始字符和结束字符之外的帧长度)", length, unit),
+            vec![indx + 1, indx + 3],
+            None,
+            None,
+        );
+
+        Self::analysic_control_frame(frame[3], result_list, indx + 3);
+
+        let sa_flag = frame[4];
+        let sa_length = (sa_flag & 0x0F) as usize + 1;
+        let address = &frame[5..5 + sa_length];
+        let address_type = match sa_flag >> 6 {
+            0 => "单地址",
+            1 => "通配地址",
+            2 => "组地址",
+            _ => "广播地址",
+        };
+        let mut flag_list = Vec::new();
+        FrameFun::add_data(
+            &mut flag_list,
+            "D6~D7地址类型".to_string(),
+            format!("{}", sa_flag >> 6),
+            address_type.to_string(),
+            vec![indx + 4, indx + 5],
+            None,
+            None,
+        );
+        FrameFun::add_data(
+            &mut flag_list,
+            "D4~D5逻辑地址".to_string(),
+            format!("{}", (sa_flag >> 4) & 0x03),
+            format!("逻辑地址：{}", (sa_flag >> 4) & 0x03),
+            vec![indx + 4, indx + 5],
+            None,
+            None,
+        );
+        FrameFun::add_data(
+            &mut flag_list,
+            "D0~D3地址长度".to_string(),
+            format!("{}", sa_flag & 0x0F),
+            format!("服务器地址长度：{}字节", sa_length),
+            vec![indx + 4, indx + 5],
+            None,
+            None,
+        );
+        let mut sa_list = Vec::new();
+        FrameFun::add_data(
+            &mut sa_list,
+            "地址特征".to_string(),
+            format!("{:02X}", sa_flag),
+            format!("{}，逻辑地址{}", address_type, (sa_flag >> 4) & 0x03),
+            vec![indx + 4, indx + 5],
+            Some(flag_list),
+            None,
+        );
+        FrameFun::add_data(
+            &mut sa_list,
+            "服务器地址".to_string(),
+            FrameFun::get_data_str_with_space(address),
+            format!("服务器地址：{}", FrameFun::get_data_str_reverser(address)),
+            vec![indx + 5, indx + 5 + sa_length],
+            None,
+            None,
+        );
+        FrameFun::add_data(
+            result_list,
+            "服务器地址SA".to_string(),
+            FrameFun::get_data_str_with_space(&frame[4..5 + sa_length]),
+            format!(
+                "服务器地址：{}({})",
+                FrameFun::get_data_str_reverser(address),
+                address_type
+            ),
+            vec![indx + 4, indx + 5 + sa_length],
+            Some(sa_list),
+            None,
+        );
+
+        let ca = frame[5 + sa_length];
+        FrameFun::add_data(
+            result_list,
+            "客户机地址CA".to_string(),
+            format!("{:02X}", ca),
+            if ca == 0 {
+                "客户机地址：00(不关注客户机地址)".to_string()
+            } else {
+                format!("客户机地址：{:02X}", ca)
+            },
+            vec![indx + 5 + sa_length, indx + 6 + sa_length],
+            None,
+            None,
+        );
+
+        let hcs_str = FrameFun::fcs16_check_desc(
+            "帧头校验",
+            &frame[1..head_length - 2],
+            &frame[head_length - 2..head_length],
+        );
+        FrameFun::add_data(
+            result_list,
+            "帧头校验HCS".to_string(),
+            FrameFun::get_data_str_with_space(&frame[head_length - 2..head_length]),
+            hcs_str,
+            vec![indx + head_length - 2, indx + head_length],
+            None,
+            None,
+        );
+    }
+
+    fn analysic_control_frame(control: u8, result_list: &mut Vec<Value>, indx: usize) {
+        let dir = control >> 7;
+        let prm = (control >> 6) & 0x01;
+        let service = match (dir, prm) {
+            (0, 1) => "客户机对服务器的请求",
+            (1, 0) => "服务器对客户机请求的响应",
+            (1, _) => "服务器发起的上报",
+            _ => "客户机对服务器上报的响应",
+        };
+        let func_code = control & 0x07;
+        let func_str = match func_code {
+            0x01 => "链路管理",
+            0x03 => "用户数据",
+            _ => "保留",
+        };
+        let bits = [
+            (
+                "D7传输方向位",
+                dir,
+                if dir == 0 {
+                    "客户机发出"
+                } else {
+                    "服务器发出"
+                },
+            ),
+            (
+                "D6启动标志位",
+                prm,
+                if prm == 1 {
+                    "启动站发出(请求或上报)"
+                } else {
+                    "从动站发出(响应)"
+                },
+            ),
+            (
+                "D5分帧标志位",
+                (control >> 5) & 0x01,
+                if control & 0x20 != 0 {
+                    "链路用户数据为APDU片段"
+                } else {
+                    "链路用户数据为完整APDU"
+                },
+            ),
+            (
+                "D3扰码标志位",
+                (control >> 3) & 0x01,
+                if control & 0x08 != 0 {
+                    "链路用户数据按字节加33H扰码"
+                } else {
+                    "链路用户数据不扰码"
+                },
+            ),
+            ("D0~D2功能码", func_code, func_str),
+        ];
+        let mut control_list = Vec::new();
+        for (name, value, desc) in bits {
+            FrameFun::add_data(
+                &mut control_list,
+                name.to_string(),
+                format!("{}", value),
+                desc.to_string(),
+                vec![indx, indx + 1],
+                None,
+                None,
+            );
+        }
+        FrameFun::add_data(
+            result_list,
+            "控制域C".to_string(),
+            format!("{:02X}", control),
+            format!("{}：{}", service, func_str),
+            vec![indx, indx + 1],
+            Some(control_list),
+            None,
+        );
+    }
+
+    fn analysic_split_frame(frame: &[u8], result_list: &mut Vec<Value>, indx: usize) {
+        let split = u16::from_le_bytes([frame[0], frame[1]]);
+        let split_type = match split >> 14 {
+            0 => "起始帧",
+            1 => "最后帧",
+            2 => "确认帧",
+            _ => "中间帧",
+        };
+        FrameFun::add_data(
+            result_list,
+            "分帧格式域".to_string(),
+            FrameFun::get_data_str_with_space(frame),
+            format!("分帧序号：{}，{}", split & 0x0FFF, split_type),
+            vec![indx, indx + 2],
+            None,
+            None,
+        );
+    }
+
+    fn analysic_link_data_frame(
+        analyzer: &Analyzer,
+        frame: &[u8],
+        control: u8,
+        result_list: &mut Vec<Value>,
+        indx: usize,
+        region: &str,
+    ) {
+        if frame.is_empty() {
+            return;
+        }
+        let mut data_list = Vec::new();
+        let scrambled = control & 0x08 != 0;
+        let apdu = if scrambled {
+            FrameFun::frame_delete_33h(frame)
+        } else {
+            frame.to_vec()
+        };
+        if control & 0x20 != 0 {
+            FrameFun::add_data(
+                &mut data_list,
+                "APDU片段".to_string(),
+                FrameFun::get_data_str_with_space(&apdu),
+                "分帧传输的APDU片段，全部接收后拼接解析".to_string(),
+                vec![indx, indx + frame.len()],
+                None,
+                None,
+            );
+        } else {
+            Self::analysic_apdu(analyzer, &apdu, &mut data_list, indx, region);
+        }
+        FrameFun::add_data(
+            result_list,
+            "链路用户数据".to_string(),
+            "".to_string(),
+            if scrambled {
+                "应用层数据单元(APDU)，已按字节减33H".to_string()
+            } else {
+                "应用层数据单元(APDU)".to_string()
+            },
+            vec![indx, indx + frame.len()],
+            Some(data_list),
+            None,
+        );
+    }
+
+    /// 解析应用层数据单元，indx为APDU在整帧中的位置
+    pub fn analysic_apdu(
+        analyzer: &Analyzer,
+        apdu: &[u8],
+        result_list: &mut Vec<Value>,
+        indx: usize,
+        region: &str,
+    ) {
+        let mut reader = ApduReader::new(analyzer, apdu, indx, region);
+        reader.apdu_with_error(result_list);
+    }
+
+    pub fn analysic_end_frame(frame: &[u8], result_list: &mut Vec<Value>, indx: usize) {
+        let length = frame.len();
+        let fcs_str = FrameFun::fcs16_check_desc(
+            "帧校验",
+            &frame[1..length - 3],
+            &frame[length - 3..length - 1],
+        );
+        FrameFun::add_data(
+            result_list,
+            "帧校验FCS".to_string(),
+            FrameFun::get_data_str_with_space(&frame[length - 3..length - 1]),
+            fcs_str,
+            vec![indx + length - 3, indx + length - 1],
+            None,
+            None,
+        );
+        FrameFun::add_data(
+            result_list,
+            "结束字符".to_string(),
+            format!("{:02X}", frame[length - 1]),
+            "698.45规约：标识一帧信息的结束".to_string(),
+            vec![indx + length - 1, indx + length],
+            None,
+            None,
+        );
+    }
+
+    /// 按对象字典查找OAD的名称和换算
+    ///
+    /// 先按OAD查找，再按OI查找；OI上配置的换算只对属性2生效
+    pub fn get_oad_info(analyzer: &Analyzer, oad: u32, region: &str) -> (String, Option<OadScale>) {
+        let protocol = ProtocolInfo::ProtocolDLT69845.name();
+        let oi = (oad >> 16) as u16;
+        let attribute = (oad >> 8) as u8;
+        let (element, by_oad) = match analyzer.get_config_xml(
+            &format!("{:04X}{:02X}00", oi, attribute),
+            protocol,
+            region,
+            None,
+        ) {
+            Some(element) => (Some(element), true),
+            None => (
+                analyzer.get_config_xml(&format!("{:04X}", oi), protocol, region, None),
+                false,
+            ),
+        };
+        let name = element
+            .as_ref()
+            .and_then(|element| element.get_child_text("name"))
+            .unwrap_or_else(|| format!("未知{}", Self::oi_class_name(oi)));
+        let scale = element
+            .filter(|_| by_oad || attribute & 0x1F == 2)
+            .and_then(|element| {
+                let scaler = element
+                    .get_child_text("scaler")?
+                    .trim()
+                    .parse::<i32>()
+                    .ok()
+                    .filter(|scaler| (-10..=10).contains(scaler))?;
+                Some(OadScale {
+                    scaler,
+                    unit: element.get_child_text("unit"),
+                })
+            });
+        (name, scale)
+    }
+
+    pub fn oad_desc(analyzer: &Analyzer, oad: u32, region: &str) -> String {
+        let (name, _) = Self::get_oad_info(analyzer, oad, region);
+        let attribute = (oad >> 8) as u8;
+        let mut desc = format!("{:08X} - {} 属性{}", oad, name, attribute & 0x1F);
+        if attribute >> 5 != 0 {
+            desc.push_str(&format!("(特征{})", attribute >> 5));
+        }
+        if oad & 0xFF != 0 {
+            desc.push_str(&format!(" 第{}个元素", oad & 0xFF));
+        }
+        desc
+    }
+
+    pub fn omd_desc(analyzer: &Analyzer, omd: u32, region: &str) -> String {
+        let (name, _) = Self::get_oad_info(analyzer, omd & 0xFFFF_0000, region);
+        let mut desc = format!("{:08X} - {} 方法{}", omd, name, (omd >> 8) & 0xFF);
+        if omd & 0xFF != 0 {
+            desc.push_str(&format!(" 操作模式{}", omd & 0xFF));
+        }
+        desc
+    }
+
+    fn oi_class_name(oi: u16) -> &'static str {
+        match oi >> 12 {
+            0x0 => "电能量类对象",
+            0x1 => "最大需量类对象",
+            0x2 => "变量类对象",
+            0x3 => "事件类对象",
+            0x4 => "参变量类对象",
+            0x5 => "冻结类对象",
+            0x6 => "采集监控类对象",
+            0x7 => "集合类对象",
+            0x8 => "控制类对象",
+            0xF => "文件传输/ESAM/输入输出设备/显示类对象",
+            _ => "自定义对象",
+        }
+    }
+
+    pub fn dar_name(dar: u8) -> &'static str {
+        match dar {
+            0 => "成功",
+            1 => "硬件失效",
+            2 => "暂时失效",
+            3 => "拒绝读写",
+            4 => "对象未定义",
+            5 => "对象接口类不符合",
+            6 => "对象不存在",
+            7 => "类型不匹配",
+            8 => "越界",
+            9 => "数据块不可用",
+            10 => "分帧传输已取消",
+            11 => "不处于分帧传输状态",
+            12 => "块写取消",
+            13 => "不存在块写状态",
+            14 => "数据块序号无效",
+            15 => "密码错/未授权",
+            16 => "通信速率不能更改",
+            17 => "年时区数超",
+            18 => "日时段数超",
+            19 => "费率数超",
+            20 => "安全认证不匹配",
+            21 => "重复充值",
+            22 => "ESAM验证失败",
+            23 => "安全认证失败",
+            24 => "客户编号不匹配",
+            25 => "充值次数错误",
+            26 => "购电超囤积",
+            27 => "地址异常",
+            28 => "对称解密错误",
+            29 => "非对称解密错误",
+            30 => "签名错误",
+            31 => "电能表挂起",
+            32 => "时间标签无效",
+            33 => "请求超时",
+            34 => "ESAM的P1P2不正确",
+            35 => "ESAM的LC错误",
+            36 => "比对失败",
+            255 => "其它",
+            _ => "未知",
+        }
+    }
+
+    fn service_choice_name(service: u8, choice: u8) -> &'static str {
+        match (service, choice) {
+            (0x05, 1) => "读取一个对象属性(GetRequestNormal)",
+            (0x05, 2) => "读取若干个对象属性(GetRequestNormalList)",
+            (0x05, 3) => "读取一个记录型对象属性(GetRequestRecord)",
+            (0x05, 4) => "读取若干个记录型对象属性(GetRequestRecordList)",
+            (0x05, 5) => "读取分帧响应的下一个数据块(GetRequestNext)",
+            (0x05, 6) => "读取一个对象属性的MD5值(GetRequestMD5)",
+            (0x85, 1) => "读取一个对象属性的响应(GetResponseNormal)",
+            (0x85, 2) => "读取若干个对象属性的响应(GetResponseNormalList)",
+            (0x85, 3) => "读取一个记录型对象属性的响应(GetResponseRecord)",
+            (0x85, 4) => "读取若干个记录型对象属性的响应(GetResponseRecordList)",
+            (0x85, 5) => "分帧响应一个数据块(GetResponseNext)",
+            (0x85, 6) => "读取一个对象属性的MD5值的响应(GetResponseMD5)",
+            (0x06, 1) => "设置一个对象属性(SetRequestNormal)",
+            (0x06, 2) => "设置若干个对象属性(SetRequestNormalList)",
+            (0x06, 3) => "设置后读取若干个对象属性(SetThenGetRequestNormalList)",
+            (0x86, 1) => "设置一个对象属性的响应(SetResponseNormal)",
+            (0x86, 2) => "设置若干个对象属性的响应(SetResponseNormalList)",
+            (0x86, 3) => "设置后读取若干个对象属性的响应(SetThenGetResponseNormalList)",
+            (0x07, 1) => "操作一个对象方法(ActionRequest)",
+            (0x07, 2) => "操作若干个对象方法(ActionRequestList)",
+            (0x07, 3) => "操作若干个对象方法后读取若干个对象属性(ActionThenGetRequestNormalList)",
+            (0x87, 1) => "操作一个对象方法的响应(ActionResponseNormal)",
+            (0x87, 2) => "操作若干个对象方法的响应(ActionResponseNormalList)",
+            (0x87, 3) => {
+                "操作若干个对象方法后读取若干个对象属性的响应(ActionThenGetResponseNormalList)"
+            }
+            (0x88, 1) => "上报若干个对象属性(ReportNotificationList)",
+            (0x88, 2) => "上报若干个记录型对象属性(ReportNotificationRecordList)",
+            (0x88, 3) => "上报透明数据(ReportNotificationTransData)",
+            (0x08, 1) => "上报若干个对象属性的响应(ReportResponseList)",
+            (0x08, 2) => "上报若干个记录型对象属性的响应(ReportResponseRecordList)",
+            (0x08, 3) => "上报透明数据的响应(ReportResponseTransData)",
+            (0x09, 1) => "代理读取若干个服务器的若干个对象属性(ProxyGetRequestList)",
+            (0x09, 2) => "代理读取一个服务器的一个记录型对象属性(ProxyGetRequestRecord)",
+            (0x09, 3) => "代理设置若干个服务器的若干个对象属性(ProxySetRequestList)",
+            (0x09, 4) => "代理设置后读取若干个服务器的若干个对象属性(ProxySetThenGetRequestList)",
+            (0x09, 5) => "代理操作若干个服务器的若干个对象方法(ProxyActionRequestList)",
+            (0x09, 6) => "代理操作后读取若干个服务器的若干个对象方法和属性(ProxyActionThenGetRequestList)",
+            (0x09, 7) => "代理透明转发命令(ProxyTransCommandRequest)",
+            (0x89, 1) => "代理读取若干个服务器的若干个对象属性的响应(ProxyGetResponseList)",
+            (0x89, 2) => "代理读取一个服务器的一个记录型对象属性的响应(ProxyGetResponseRecord)",
+            (0x89, 3) => "代理设置若干个服务器的若干个对象属性的响应(ProxySetResponseList)",
+            (0x89, 4) => {
+                "代理设置后读取若干个服务器的若干个对象属性的响应(ProxySetThenGetResponseList)"
+            }
+            (0x89, 5) => "代理操作若干个服务器的若干个对象方法的响应(ProxyActionResponseList)",
+            (0x89, 6) => {
+                "代理操作后读取若干个服务器的若干个对象方法和属性的响应(ProxyActionThenGetResponseList)"
+            }
+            (0x89, 7) => "代理透明转发命令的响应(ProxyTransCommandResponse)",
+            _ => "未知",
+        }
+    }
+}
+
+/// 按A-XDR编码规则顺序读取APDU并生成解析结果
+struct ApduReader<'a> {
+    analyzer: &'a Analyzer,
+    reader: AxdrReader<'a>,
+    // 数据在整帧中的起始位置
+    offset: usize,
+    region: &'a str,
+}
+
+type FieldResult = Result<(String, Option<Vec<Value>>), String>;
+
+impl<'a> ApduReader<'a> {
+    fn new(analyzer: &'a Analyzer, data: &'a [u8], offset: usize, region: &'a str) -> Self {
+        ApduReader {
+            analyzer,
+            reader: AxdrReader::new(data),
+            offset,
+            region,
+        }
+    }
+
+    fn add(
+        &self,
+        list: &mut Vec<Value>,
+        name: &str,
+        start: usize,
+        description: String,
+        children: Option<Vec<Value>>,
+    ) {
+        FrameFun::add_data(
+            list,
+            name.to_string(),
+            FrameFun::get_data_str_with_space(self.reader.bytes(start)),
+            description,
+            vec![self.offset + start, self.offset + self.reader.position()],
+            children,
+            None,
+        );
+    }
+
+    /// 读取一个字段，value返回显示值和子项
+    fn field<F>(&mut self, list: &mut Vec<Value>, name: &str, value: F) -> Result<(), String>
+    where
+        F: FnOnce(&mut Self) -> FieldResult,
+    {
+        let start = self.reader.position();
+        let (value, children) = value(self)?;
+        self.add(
+            list,
+            name,
+            start,
+            format!("[{}]: {}", name, value),
+            children,
+        );
+        Ok(())
+    }
+
+    /// 读取由若干字段组成的结构，fields返回结构的描述
+    fn group<F>(&mut self, list: &mut Vec<Value>, name: &str, fields: F) -> Result<(), String>
+    where
+        F: FnOnce(&mut Self, &mut Vec<Value>) -> Result<String, String>,
+    {
+        let start = self.reader.position();
+        let mut children = Vec::new();
+        let description = fields(self, &mut children)?;
+        self.add(list, name, start, description, Some(children));
+        Ok(())
+    }
+
+    /// 读取SEQUENCE OF，item的最后一个参数为从1开始的序号
+    fn sequence<F>(&mut self, list: &mut Vec<Value>, name: &str, mut item: F) -> Result<(), String>
+    where
+        F: FnMut(&mut Self, &mut Vec<Value>, usize) -> Result<(), String>,
+    {
+        self.group(list, name, |reader, children| {
+            let count = reader.reader.read_length()?;
+            for i in 1..=count {
+                item(reader, children, i)?;
+            }
+            Ok(format!("[{}]: 共{}项", name, count))
+        })
+    }
+
+    fn apdu_with_error(&mut self, list: &mut Vec<Value>) {
+        if let Err(e) = self.apdu(list) {
+            let start = self.reader.position();
+            self.reader.skip_to_end();
+            self.add(
+                list,
+                "解析错误",
+                start,
+                format!("APDU解析失败：{}", e),
+                None,
+            );
+        } else if !self.reader.is_empty() {
+            let start = self.reader.position();
+            self.reader.skip_to_end();
+            self.add(
+                list,
+                "多余数据",
+                start,
+                "APDU之后的多余数据".to_string(),
+                None,
+            );
+        }
+    }
+
+    fn apdu(&mut self, list: &mut Vec<Value>) -> Result<(), String> {
+        let start = self.reader.position();
+        let tag = self.reader.read_u8()?;
+        let entry = self
+            .analyzer
+            .find_afn(ProtocolInfo::ProtocolDLT69845.name(), tag, None, None);
+        let name = entry
+            .as_ref()
+            .map(|entry| entry.name.clone())
+            .unwrap_or_else(|| "未知服务".to_string());
+        self.add(
+            list,
+            "应用层服务",
+            start,
+            format!("[应用层服务]: {:02X}-{}", tag, name),
+            None,
+        );
+
+        match entry.and_then(|entry| entry.handler).as_deref() {
+            Some("link_request") => self.link_request(list),
+            Some("link_response") => self.link_response(list),
+            Some("connect_request") => self.connect_request(list),
+            Some("connect_response") => self.connect_response(list),
+            Some("release_request") => {
+                self.piid(list, false)?;
+                self.time_tag(list)
+            }
+            Some("release_response") => {
+                self.piid(list, true)?;
+                self.field(list, "结果", |r| {
+                    let result = r.reader.read_u8()?;
+                    let desc = if result == 0 { "成功" } else { "失败" };
+                    Ok((format!("{}-{}", result, desc), None))
+                })?;
+                self.follow_report(list)?;
+                self.time_tag(list)
+            }
+            Some("release_notification") => {
+                self.piid(list, true)?;
+                self.field(list, "应用连接建立时间", |r| r.date_time_s_value())?;
+                self.field(list, "服务器当前时间", |r| r.date_time_s_value())?;
+                self.time_tag(list)
+            }
+            Some("get_request") => self.get_request(list, tag),
+            Some("get_response") => self.get_response(list, tag),
+            Some("set_request") => self.set_request(list, tag),
+            Some("set_response") => self.set_response(list, tag),
+            Some("action_request") => self.action_request(list, tag),
+            Some("action_response") => self.action_response(list, tag),
+            Some("report_notification") => self.report_notification(list, tag),
+            Some("report_response") => self.report_response(list, tag),
+            Some("proxy_request") => self.proxy_request(list, tag),
+            Some("proxy_response") => self.proxy_response(list, tag),
+            Some("security_request") => self.security_request(list),
+            Some("security_response") => self.security_response(list),
+            Some("error_response") => {
+                self.piid(list, true)?;
+                self.field(list, "服务错误", |r| {
+                    let error = r.reader.read_u8()?;
+                    let desc = match error {
+                        1 => "APDU无法解析",
+                        2 => "服务不支持",
+                        _ => "其他",
+                    };
+                    Ok((format!("{}-{}", error, desc), None))
+                })
+            }
+            _ => {
+                let start = self.reader.position();
+                self.reader.skip_to_end();
+                self.add(
+                    list,
+                    "服务数据",
+                    start,
+                    "未定义解析方式的服务数据".to_string(),
+                    None,
+                );
+                Ok(())
+            }
+        }
+    }
+
+    fn sub_service(&mut self, list: &mut Vec<Value>, tag: u8) -> Result<u8, String> {
+        let start = self.reader.position();
+        let choice = self.reader.read_u8()?;
+        self.add(
+            list,
+            "服务子类型",
+            start,
+            format!(
+                "[服务子类型]: {}-{}",
+                choice,
+                Frame698::service_choice_name(tag, choice)
+            ),
+            None,
+        );
+        Ok(choice)
+    }
+
+    fn unknown_choice(&mut self, list: &mut Vec<Value>, choice: u8) -> Result<(), String> {
+        let start = self.reader.position();
+        self.reader.skip_to_end();
+        self.add(
+            list,
+            "服务数据",
+            start,
+            format!("未知的服务子类型{}", choice),
+            None,
+        );
+        Ok(())
+    }
+
+    fn piid(&mut self, list: &mut Vec<Value>, acd: bool) -> Result<(), String> {
+        let name = if acd { "PIID-ACD" } else { "PIID" };
+        self.field(list, name, |r| {
+            let piid = r.reader.read_u8()?;
+            let priority = if piid & 0x80 != 0 { "高" } else { "一般" };
+            let mut desc = format!("服务序号{}，服务优先级{}", piid & 0x3F, priority);
+            if acd && piid & 0x40 != 0 {
+                desc.push_str("，请求访问ACD");
+            }
+            Ok((desc, None))
+        })
+    }
+
+    fn time_tag(&mut self, list: &mut Vec<Value>) -> Result<(), String> {
+        if self.reader.is_empty() {
+            return Ok(());
+        }
+        let start = self.reader.position();
+        if self.reader.read_u8()? == 0 {
+            self.add(list, "时间标签", start, "[时间标签]: 无".to_string(), None);
+            return Ok(());
+        }
+        self.reader.set_position(start);
+        self.group(list, "时间标签", |r, children| {
+            r.reader.read_u8()?;
+            r.field(children, "发送时标", |r| r.date_time_s_value())?;
+            r.field(children, "允许传输延时时间", |r| r.ti_value())?;
+            Ok("[时间标签]: 有".to_string())
+        })
+    }
+
+    fn follow_report(&mut self, list: &mut Vec<Value>) -> Result<(), String> {
+        if self.reader.is_empty() {
+            return Ok(());
+        }
+        let start = self.reader.position();
+        if self.reader.read_u8()? == 0 {
+            self.add(
+                list,
+                "跟随上报信息域",
+                start,
+                "[跟随上报信息域]: 无".to_string(),
+                None,
+            );
+            return Ok(());
+        }
+        self.reader.set_position(start);
+        self.group(list, "跟随上报信息域", |r, children| {
+            r.reader.read_u8()?;
+            match r.reader.read_u8()? {
+                1 => r.sequence(children, "若干个对象属性及其数据", |r, l, i| {
+                    r.result_normal(l, &format!("第{}个对象属性", i))
+                })?,
+                2 => r.sequence(
+                    children,
+                    "若干个记录型对象属性及其数据",
+                    |r, l, i| r.result_record(l, &format!("第{}个记录型对象属性", i)),
+                )?,
+                choice => return Err(format!("跟随上报信息域的类型{}无效", choice)),
+            }
+            Ok("[跟随上报信息域]: 有".to_string())
+        })
+    }
+
+    fn link_request(&mut self, list: &mut Vec<Value>) -> Result<(), String> {
+        self.piid(list, true)?;
+        self.field(list, "请求类型", |r| {
+            let kind = r.reader.read_u8()?;
+            let desc = match kind {
+                0 => "登录",
+                1 => "心跳",
+                2 => "退出登录",
+                _ => "未知",
+            };
+            Ok((format!("{}-{}", kind, desc), None))
+        })?;
+        self.field(list, "心跳周期", |r| {
+            Ok((format!("{}秒", r.reader.read_u16()?), None))
+        })?;
+        self.field(list, "请求时间", |r| r.date_time_value())
+    }
+
+    fn link_response(&mut self, list: &mut Vec<Value>) -> Result<(), String> {
+        self.piid(list, false)?;
+        self.field(list, "结果", |r| {
+            let result = r.reader.read_u8()?;
+            let desc = match result & 0x07 {
+                0 => "成功",
+                1 => "地址重复",
+                2 => "非法设备",
+                3 => "容量不足",
+                _ => "保留",
+            };
+            let clock = if result & 0x80 != 0 {
+                "时钟可信"
+            } else {
+                "时钟不可信"
+            };
+            Ok((format!("{}，{}", desc, clock), None))
+        })?;
+        self.field(list, "请求时间", |r| r.date_time_value())?;
+        self.field(list, "收到时间", |r| r.date_time_value())?;
+        self.field(list, "响应时间", |r| r.date_time_value())
+    }
+
+    fn connect_request(&mut self, list: &mut Vec<Value>) -> Result<(), String> {
+        self.piid(list, false)?;
+        self.field(list, "期望的应用层协议版本号", |r| {
+            Ok((format!("{}", r.reader.read_u16()?), None))
+        })?;
+        self.connect_negotiation(list, "客户机")?;
+        self.field(list, "期望的应用连接超时时间", |r| {
+            Ok((format!("{}秒", r.reader.read_u32()?), None))
+        })?;
+        self.group(list, "认证请求对象", |r, children| {
+            let mechanism = r.reader.read_u8()?;
+            let desc = match mechanism {
+                0 => "公共连接",
+                1 => {
+                    r.field(children, "一般密码", |r| r.visible_string_value())?;
+                    "一般密码"
+                }
+                2 => {
+                    r.field(children, "密文1", |r| r.octet_string_value())?;
+                    r.field(children, "客户机签名1", |r| r.octet_string_value())?;
+                    "对称加密"
+                }
+                3 => {
+                    r.field(children, "密文2", |r| r.octet_string_value())?;
+                    r.field(children, "客户机签名2", |r| r.octet_string_value())?;
+                    "数字签名"
+                }
+                _ => return Err(format!("认证机制{}无效", mechanism)),
+            };
+            Ok(format!("[认证请求对象]: {}-{}", mechanism, desc))
+        })?;
+        self.time_tag(list)
+    }
+
+    fn connect_response(&mut self, list: &mut Vec<Value>) -> Result<(), String> {
+        self.piid(list, true)?;
+        self.group(list, "服务器厂商版本信息", |r, children| {
+            for (name, length) in [
+                ("厂商代码", 4),
+                ("软件版本号", 4),
+                ("软件版本日期", 6),
+                ("硬件版本号", 4),
+                ("硬件版本日期", 6),
+                ("厂家扩展信息", 8),
+            ] {
+                r.field(children, name, |r| {
+                    Ok((FrameFun::ascii_to_str(r.reader.take(length)?), None))
+                })?;
+            }
+            Ok("[服务器厂商版本信息]".to_string())
+        })?;
+        self.field(list, "商定的应用层协议版本号", |r| {
+            Ok((format!("{}", r.reader.read_u16()?), None))
+        })?;
+        self.connect_negotiation(list, "服务器")?;
+        self.field(list, "商定的应用连接超时时间", |r| {
+            Ok((format!("{}秒", r.reader.read_u32()?), None))
+        })?;
+        self.group(list, "连接响应对象", |r, children| {
+            r.field(children, "认证结果", |r| {
+                let result = r.reader.read_u8()?;
+                let desc = match result {
+                    0 => "允许建立应用连接",
+                    1 => "密码错误",
+                    2 => "对称解密错误",
+                    3 => "非对称解密错误",
+                    4 => "签名错误",
+                    5 => "协议版本不匹配",
+                    255 => "其他错误",
+                    _ => "未知",
+                };
+                Ok((format!("{}-{}", result, desc), None))
+            })?;
+            if r.reader.read_u8()? != 0 {
+                r.field(children, "服务器随机数", |r| r.octet_string_value())?;
+                r.field(children, "服务器签名信息", |r| {
+                    r.octet_string_value()
+                })?;
+            }
+            Ok("[连接响应对象]".to_string())
+        })?;
+        self.follow_report(list)?;
+        self.time_tag(list)
+    }
+
+    // 协议一致性块、功能一致性块及帧尺寸协商
+    fn connect_negotiation(&mut self, list: &mut Vec<Value>, side: &str) -> Result<(), String> {
+        self.field(list, "协议一致性块", |r| {
+            Ok((FrameFun::get_data_str_order(r.reader.take(8)?), None))
+        })?;
+        self.field(list, "功能一致性块", |r| {
+            Ok((FrameFun::get_data_str_order(r.reader.take(16)?), None))
+        })?;
+        self.field(list, &format!("{}发送帧最大尺寸", side), |r| {
+            Ok((format!("{}字节", r.reader.read_u16()?), None))
+        })?;
+        self.field(list, &format!("{}接收帧最大尺寸", side), |r| {
+            Ok((format!("{}字节", r.reader.read_u16()?), None))
+        })?;
+        self.field(list, &format!("{}接收帧最大窗口尺寸", side), |r| {
+            Ok((format!("{}", r.reader.read_u8()?), None))
+        })?;
+        self.field(list, &format!("{}最大可处理APDU尺寸", side), |r| {
+            Ok((format!("{}字节", r.reader.read_u16()?), None))
+        })
+    }
+
+    fn get_request(&mut self, list: &mut Vec<Value>, tag: u8) -> Result<(), String> {
+        let choice = self.sub_service(list, tag)?;
+        self.piid(list, false)?;
+        match choice {
+            1 | 6 => {
+                self.oad_field(list, "对象属性描述符")?;
+            }
+            2 => self.sequence(list, "对象属性描述符列表", |r, l, _| {
+                r.oad_field(l, "对象属性描述符").map(|_| ())
+            })?,
+            3 => self.get_record(list, "读取一个记录型对象属性")?,
+            4 => self.sequence(list, "读取若干个记录型对象属性", |r, l, i| {
+                r.get_record(l, &format!("第{}个记录型对象属性", i))
+            })?,
+            5 => self.field(list, "最近一次接收的分帧序号", |r| {
+                Ok((format!("{}", r.reader.read_u16()?), None))
+            })?,
+            _ => return self.unknown_choice(list, choice),
+        }
+        self.time_tag(list)
+    }
+
+    fn get_response(&mut self, list: &mut Vec<Value>, tag: u8) -> Result<(), String> {
+        let choice = self.sub_service(list, tag)?;
+        self.piid(list, true)?;
+        match choice {
+            1 => self.result_normal(list, "对象属性及其数据")?,
+            2 => self.sequence(list, "若干个对象属性及其数据", |r, l, i| {
+                r.result_normal(l, &format!("第{}个对象属性", i))
+            })?,
+            3 => self.result_record(list, "记录型对象属性及其数据")?,
+            4 => self.sequence(
+                list,
+                "若干个记录型对象属性及其数据",
+                |r, l, i| r.result_record(l, &format!("第{}个记录型对象属性", i)),
+            )?,
+            5 => {
+                self.field(list, "末帧标志", |r| {
+                    Ok(((r.reader.read_u8()? != 0).to_string(), None))
+                })?;
+                self.field(list, "分帧序号", |r| {
+                    Ok((format!("{}", r.reader.read_u16()?), None))
+                })?;
+                match self.reader.read_u8()? {
+                    0 => self.dar_field(list, "错误信息")?,
+                    1 => self.sequence(list, "若干个对象属性及其数据", |r, l, i| {
+                        r.result_normal(l, &format!("第{}个对象属性", i))
+                    })?,
+                    2 => self.sequence(
+                        list,
+                        "若干个记录型对象属性及其数据",
+                        |r, l, i| r.result_record(l, &format!("第{}个记录型对象属性", i)),
+                    )?,
+                    kind => return Err(format!("分帧响应的数据类型{}无效", kind)),
+                }
+            }
+            6 => {
+                self.oad_field(list, "对象属性描述符")?;
+                match self.reader.read_u8()? {
+                    0 => self.dar_field(list, "错误信息")?,
+                    _ => self.field(list, "MD5值", |r| r.octet_string_value())?,
+                }
+            }
+            _ => return self.unknown_choice(list, choice),
+        }
+        self.follow_report(list)?;
+        self.time_tag(list)
+    }
+
+    fn set_request(&mut self, list: &mut Vec<Value>, tag: u8) -> Result<(), String> {
+        let choice = self.sub_service(list, tag)?;
+        self.piid(list, false)?;
+        match choice {
+            1 => self.set_item(list)?,
+            2 => self.sequence(list, "若干个对象属性", |r, l, i| {
+                r.group(l, &format!("第{}个对象属性", i), |r, l| {
+                    r.set_item(l)?;
+                    Ok(format!("[第{}个对象属性]", i))
+                })
+            })?,
+            3 => self.sequence(list, "若干个设置后读取对象属性", |r, l, i| {
+                r.group(l, &format!("第{}个对象属性", i), |r, l| {
+                    r.set_item(l)?;
+                    r.get_after(l)?;
+                    Ok(format!("[第{}个对象属性]", i))
+                })
+            })?,
+            _ => return self.unknown_choice(list, choice),
+        }
+        self.time_tag(list)
+    }
+
+    fn set_response(&mut self, list: &mut Vec<Value>, tag: u8) -> Result<(), String> {
+        let choice = self.sub_service(list, tag)?;
+        self.piid(list, true)?;
+        match choice {
+            1 => {
+                self.oad_field(list, "对象属性描述符")?;
+                self.dar_field(list, "设置执行结果")?;
+            }
+            2 => self.sequence(list, "若干个对象属性设置结果", |r, l, i| {
+                r.group(l, &format!("第{}个对象属性", i), |r, l| {
+                    r.oad_field(l, "对象属性描述符")?;
+                    r.dar_field(l, "设置执行结果")?;
+                    Ok(format!("[第{}个对象属性]", i))
+                })
+            })?,
+            3 => self.sequence(
+                list,
+                "若干个对象属性设置后读取结果",
+                |r, l, i| {
+                    r.group(l, &format!("第{}个对象属性", i), |r, l| {
+                        r.oad_field(l, "对象属性描述符")?;
+                        r.dar_field(l, "设置执行结果")?;
+                        let oad = r.oad_field(l, "读取的对象属性描述符")?;
+                        r.get_result(l, oad)?;
+                        Ok(format!("[第{}个对象属性]", i))
+                    })
+                },
+            )?,
+            _ => return self.unknown_choice(list, choice),
+        }
+        self.follow_report(list)?;
+        self.time_tag(list)
+    }
+
+    fn action_request(&mut self, list: &mut Vec<Value>, tag: u8) -> Result<(), String> {
+        let choice = self.sub_service(list, tag)?;
+        self.piid(list, false)?;
+        match choice {
+            1 => self.action_item(list)?,
+            2 => self.sequence(list, "若干个对象方法", |r, l, i| {
+                r.group(l, &format!("第{}个对象方法", i), |r, l| {
+                    r.action_item(l)?;
+                    Ok(format!("[第{}个对象方法]", i))
+                })
+            })?,
+            3 => self.sequence(list, "若干个操作后读取对象方法", |r, l, i| {
+                r.group(l, &format!("第{}个对象方法", i), |r, l| {
+                    r.action_item(l)?;
+                    r.get_after(l)?;
+                    Ok(format!("[第{}个对象方法]", i))
+                })
+            })?,
+            _ => return self.unknown_choice(list, choice),
+        }
+        self.time_tag(list)
+    }
+
+    fn action_response(&mut self, list: &mut Vec<Value>, tag: u8) -> Result<(), String> {
+        let choice = self.sub_service(list, tag)?;
+        self.piid(list, true)?;
+        match choice {
+            1 => self.action_result(list)?,
+            2 => self.sequence(list, "若干个对象方法操作结果", |r, l, i| {
+                r.group(l, &format!("第{}个对象方法", i), |r, l| {
+                    r.action_result(l)?;
+                    Ok(format!("[第{}个对象方法]", i))
+                })
+            })?,
+            3 => self.sequence(
+                list,
+                "若干个对象方法操作后读取结果",
+                |r, l, i| {
+                    r.group(l, &format!("第{}个对象方法", i), |r, l| {
+                        r.action_result(l)?;
+                        let oad = r.oad_field(l, "读取的对象属性描述符")?;
+                        r.get_result(l, oad)?;
+                        Ok(format!("[第{}个对象方法]", i))
+                    })
+                },
+            )?,
+            _ => return self.unknown_choice(list, choice),
+        }
+        self.follow_report(list)?;
+        self.time_tag(list)
+    }
+
+    fn report_notification(&mut self, list: &mut Vec<Value>, tag: u8) -> Result<(), String> {
+        let choice = self.sub_service(list, tag)?;
+        self.piid(list, true)?;
+        match choice {
+            1 => self.sequence(list, "若干个对象属性及其数据", |r, l, i| {
+                r.result_normal(l, &format!("第{}个对象属性", i))
+            })?,
+            2 => self.sequence(
+                list,
+                "若干个记录型对象属性及其数据",
+                |r, l, i| r.result_record(l, &format!("第{}个记录型对象属性", i)),
+            )?,
+            3 => {
+                self.oad_field(list, "数据来源端口")?;
+                self.sequence(list, "透明数据", |r, l, i| {
+                    r.frame_field(l, &format!("第{}个透明数据", i))
+                })?;
+            }
+            _ => return self.unknown_choice(list, choice),
+        }
+        self.time_tag(list)
+    }
+
+    fn report_response(&mut self, list: &mut Vec<Value>, tag: u8) -> Result<(), String> {
+        let choice = self.sub_service(list, tag)?;
+        self.piid(list, false)?;
+        match choice {
+            1 | 2 => self.sequence(list, "对应上报的对象属性描述符", |r, l, _| {
+                r.oad_field(l, "对象属性描述符").map(|_| ())
+            })?,
+            3 => {
+                self.oad_field(list, "数据来源端口")?;
+            }
+            _ => return self.unknown_choice(list, choice),
+        }
+        self.time_tag(list)
+    }
+
+    fn proxy_request(&mut self, list: &mut Vec<Value>, tag: u8) -> Result<(), String> {
+        let choice = self.sub_service(list, tag)?;
+        self.piid(list, false)?;
+        if choice == 7 {
+            self.oad_field(list, "数据端口")?;
+            self.field(list, "端口通信控制块", |r| r.comdcb_value())?;
+            self.field(list, "接收等待报文超时时间", |r| {
+                Ok((format!("{}秒", r.reader.read_u16()?), None))
+            })?;
+            self.field(list, "接收等待字节超时时间", |r| {
+                Ok((format!("{}毫秒", r.reader.read_u16()?), None))
+            })?;
+            self.frame_field(list, "透明转发命令")?;
+            return self.time_tag(list);
+        }
+        if !(1..=6).contains(&choice) {
+            return self.unknown_choice(list, choice);
+        }
+        self.field(list, "代理整个请求的超时时间", |r| {
+            Ok((format!("{}秒", r.reader.read_u16()?), None))
+        })?;
+        if choice == 2 {
+            self.field(list, "目标服务器地址", |r| r.tsa_value())?;
+            self.get_record(list, "读取一个记录型对象属性")?;
+            return self.time_tag(list);
+        }
+        self.sequence(list, "代理的服务器", |r, l, i| {
+            r.group(l, &format!("第{}个服务器", i), |r, l| {
+                r.field(l, "目标服务器地址", |r| r.tsa_value())?;
+                r.field(l, "代理一个服务器的超时时间", |r| {
+                    Ok((format!("{}秒", r.reader.read_u16()?), None))
+                })?;
+                r.sequence(l, "对象列表", |r, l, j| match choice {
+                    1 => r.oad_field(l, "对象属性描述符").map(|_| ()),
+                    _ => r.group(l, &format!("第{}个对象", j), |r, l| {
+                        match choice {
+                            3 => r.set_item(l)?,
+                            4 => {
+                                r.set_item(l)?;
+                                r.get_after(l)?;
+                            }
+                            5 => r.action_item(l)?,
+                            _ => {
+                                r.action_item(l)?;
+                                r.get_after(l)?;
+                            }
+                        }
+                        Ok(format!("[第{}个对象]", j))
+                    }),
+                })?;
+                Ok(format!("[第{}个服务器]", i))
+            })
+        })?;
+        self.time_tag(list)
+    }
+
+    fn proxy_response(&mut self, list: &mut Vec<Value>, tag: u8) -> Result<(), String> {
+        let choice = self.sub_service(list, tag)?;
+        self.piid(list, true)?;
+        match choice {
+            2 => {
+                self.field(list, "目标服务器地址", |r| r.tsa_value())?;
+                self.result_record(list, "记录型对象属性及其数据")?;
+            }
+            7 => {
+                self.oad_field(list, "数据端口")?;
+                match self.reader.read_u8()? {
+                    0 => self.dar_field(list, "错误信息")?,
+                    _ => self.frame_field(list, "透明转发命令返回结果")?,
+                }
+            }
+            1 | 3..=6 => self.sequence(list, "代理的服务器", |r, l, i| {
+                r.group(l, &format!("第{}个服务器", i), |r, l| {
+                    r.field(l, "目标服务器地址", |r| r.tsa_value())?;
+                    r.sequence(l, "对象列表", |r, l, j| match choice {
+                        1 => r.result_normal(l, &format!("第{}个对象属性", j)),
+                        _ => r.group(l, &format!("第{}个对象", j), |r, l| {
+                            match choice {
+                                3 => {
+                                    r.oad_field(l, "对象属性描述符")?;
+                                    r.dar_field(l, "设置执行结果")?;
+                                }
+                                4 => {
+                                    r.oad_field(l, "对象属性描述符")?;
+                                    r.dar_field(l, "设置执行结果")?;
+                                    let oad = r.oad_field(l, "读取的对象属性描述符")?;
+                                    r.get_result(l, oad)?;
+                                }
+                                5 => r.action_result(l)?,
+                                _ => {
+                                    r.action_result(l)?;
+                                    let oad = r.oad_field(l, "读取的对象属性描述符")?;
+                                    r.get_result(l, oad)?;
+                                }
+                            }
+                            Ok(format!("[第{}个对象]", j))
+                        }),
+                    })?;
+                    Ok(format!("[第{}个服务器]", i))
+                })
+            })?,
+            _ => return self.unknown_choice(list, choice),
+        }
+        self.follow_report(list)?;
+        self.time_tag(list)
+    }
+
+    fn security_request(&mut self, list: &mut Vec<Value>) -> Result<(), String> {
+        match self.reader.read_u8()? {
+            0 => self.plain_apdu_field(list, "明文应用数据单元")?,
+            _ => self.field(list, "密文应用数据单元", |r| r.octet_string_value())?,
+        }
+        self.group(list, "数据验证信息", |r, children| {
+            let kind = r.reader.read_u8()?;
+            let desc = match kind {
+                0 => {
+                    r.field(children, "数据验证码", |r| r.sid_mac_value())?;
+                    "数据验证码(SID_MAC)"
+                }
+                1 => {
+                    r.field(children, "随机数", |r| r.octet_string_value())?;
+                    "随机数(RN)"
+                }
+                2 => {
+                    r.field(children, "随机数", |r| r.octet_string_value())?;
+                    r.field(children, "数据MAC", |r| r.octet_string_value())?;
+                    "随机数+数据MAC(RN_MAC)"
+                }
+                3 => {
+                    r.field(children, "安全标识", |r| r.sid_value())?;
+                    "安全标识(SID)"
+                }
+                _ => return Err(format!("数据验证信息类型{}无效", kind)),
+            };
+            Ok(format!("[数据验证信息]: {}", desc))
+        })
+    }
+
+    fn security_response(&mut self, list: &mut Vec<Value>) -> Result<(), String> {
+        match self.reader.read_u8()? {
+            0 => self.plain_apdu_field(list, "明文应用数据单元")?,
+            1 => self.field(list, "密文应用数据单元", |r| r.octet_string_value())?,
+            _ => self.dar_field(list, "异常错误")?,
+        }
+        if self.reader.is_empty() {
+            return Ok(());
+        }
+        if self.reader.read_u8()? == 0 {
+            return Ok(());
+        }
+        self.group(list, "数据验证信息", |r, children| {
+            r.reader.read_u8()?;
+            r.field(children, "数据MAC", |r| r.octet_string_value())?;
+            Ok("[数据验证信息]: 数据MAC".to_string())
+        })
+    }
+
+    // 安全传输中的明文APDU按完整APDU解析
+    fn plain_apdu_field(&mut self, list: &mut Vec<Value>, name: &str) -> Result<(), String> {
+        self.group(list, name, |r, children| {
+            let length = r.reader.read_length()?;
+            let start = r.reader.position();
+            let apdu = r.reader.take(length)?;
+            let mut reader = ApduReader::new(r.analyzer, apdu, r.offset + start, r.region);
+            reader.apdu_with_error(children);
+            Ok(format!("[{}]: {}字节", name, length))
+        })
+    }
+
+    // 透明转发的报文，能识别的电表规约报文展开解析
+    fn frame_field(&mut self, list: &mut Vec<Value>, name: &str) -> Result<(), String> {
+        let start = self.reader.position();
+        let length = self.reader.read_length()?;
+        let frame_start = self.reader.position();
+        let frame = self.reader.take(length)?;
+        let mut children = Vec::new();
+        let index = self.offset + frame_start;
+        let protocol = if Frame645::is_dlt645_frame(frame) {
+            Frame645::analysic_645_frame_by_afn(
+                self.analyzer,
+                frame,
+                &mut children,
+                index,
+                self.region,
+            );
+            Some("645规约报文")
+        } else if Frame698::is_698_frame(frame) {
+            Frame698::analysic_698_frame(self.analyzer, frame, &mut children, index, self.region);
+            Some("698.45规约报文")
+        } else {
+            None
+        };
+        let description = match protocol {
+            Some(protocol) => format!("[{}]: {}", name, protocol),
+            None => format!("[{}]: {}", name, FrameFun::get_data_str_order(frame)),
+        };
+        self.add(
+            list,
+            name,
+            start,
+            description,
+            (!children.is_empty()).then_some(children),
+        );
+        Ok(())
+    }
+
+    fn oad_field(&mut self, list: &mut Vec<Value>, name: &str) -> Result<u32, String> {
+        let start = self.reader.position();
+        let oad = self.reader.read_u32()?;
+        self.add(
+            list,
+            name,
+            start,
+            format!(
+                "[{}]: {}",
+                name,
+                Frame698::oad_desc(self.analyzer, oad, self.region)
+            ),
+            None,
+        );
+        Ok(oad)
+    }
+
+    fn omd_field(&mut self, list: &mut Vec<Value>, name: &str) -> Result<u32, String> {
+        let start = self.reader.position();
+        let omd = self.reader.read_u32()?;
+        self.add(
+            list,
+            name,
+            start,
+            format!(
+                "[{}]: {}",
+                name,
+                Frame698::omd_desc(self.analyzer, omd, self.region)
+            ),
+            None,
+        );
+        Ok(omd)
+    }
+
+    fn dar_field(&mut self, list: &mut Vec<Value>, name: &str) -> Result<(), String> {
+        self.field(list, name, |r| r.dar_value())
+    }
+
+    fn data_field(
+        &mut self,
+        list: &mut Vec<Value>,
+        name: &str,
+        scale: Option<&OadScale>,
+    ) -> Result<(), String> {
+        self.field(list, name, |r| r.data_value(scale))
+    }
+
+    fn oad_data_field(&mut self, list: &mut Vec<Value>, oad: u32) -> Result<(), String> {
+        let (name, scale) = Frame698::get_oad_info(self.analyzer, oad, self.region);
+        self.data_field(list, &name, scale.as_ref())
+    }
+
+    // OAD + Data
+    fn set_item(&mut self, list: &mut Vec<Value>) -> Result<(), String> {
+        let oad = self.oad_field(list, "对象属性描述符")?;
+        self.oad_data_field(list, oad)
+    }
+
+    // OMD + Data
+    fn action_item(&mut self, list: &mut Vec<Value>) -> Result<(), String> {
+        self.omd_field(list, "对象方法描述符")?;
+        self.data_field(list, "方法参数", None)
+    }
+
+    // OMD + DAR + Data OPTIONAL
+    fn action_result(&mut self, list: &mut Vec<Value>) -> Result<(), String> {
+        self.omd_field(list, "对象方法描述符")?;
+        self.dar_field(list, "操作执行结果")?;
+        self.field(list, "操作返回数据", |r| {
+            match r.reader.read_u8()? {
+                0 => Ok(("无".to_string(), None)),
+                _ => r.data_value(None),
+            }
+        })
+    }
+
+    // 设置或操作后读取的OAD和延时
+    fn get_after(&mut self, list: &mut Vec<Value>) -> Result<(), String> {
+        self.oad_field(list, "读取的对象属性描述符")?;
+        self.field(list, "延时读取时间", |r| {
+            Ok((format!("{}秒", r.reader.read_u8()?), None))
+        })
+    }
+
+    // Get-Result：DAR或Data
+    fn get_result(&mut self, list: &mut Vec<Value>, oad: u32) -> Result<(), String> {
+        let (name, scale) = Frame698::get_oad_info(self.analyzer, oad, self.region);
+        let name = if self.reader.peek() == Ok(0) {
+            "错误信息"
+        } else {
+            name.as_str()
+        };
+        self.field(list, name, |r| match r.reader.read_u8()? {
+            0 => r.dar_value(),
+            _ => r.data_value(scale.as_ref()),
+        })
+    }
+
+    // A-ResultNormal
+    fn result_normal(&mut self, list: &mut Vec<Value>, name: &str) -> Result<(), String> {
+        self.group(list, name, |r, children| {
+            let oad = r.oad_field(children, "对象属性描述符")?;
+            r.get_result(children, oad)?;
+            Ok(format!(
+                "[{}]: {}",
+                name,
+                Frame698::oad_desc(self.analyzer, oad, r.region)
+            ))
+        })
+    }
+
+    // A-ResultRecord：OAD + RCSD + (DAR或M条记录)
+    fn result_record(&mut self, list: &mut Vec<Value>, name: &str) -> Result<(), String> {
+        self.group(list, name, |r, children| {
+            let oad = r.oad_field(children, "对象属性描述符")?;
+            let columns = r.rcsd_field(children, "记录型对象属性描述符")?;
+            match r.reader.read_u8()? {
+                0 => r.dar_field(children, "错误信息")?,
+                _ => r.sequence(children, "记录", |r, l, i| {
+                    r.group(l, &format!("第{}条记录", i), |r, l| {
+                        for column in &columns {
+                            let (column_name, scale) = match column {
+                                (oad, false) => {
+                                    Frame698::get_oad_info(self.analyzer, *oad, r.region)
+                                }
+                                (oad, true) => (
+                                    Frame698::get_oad_info(self.analyzer, *oad, r.region).0,
+                                    None,
+                                ),
+                            };
+                            r.data_field(l, &column_name, scale.as_ref())?;
+                        }
+                        Ok(format!("[第{}条记录]", i))
+                    })
+                })?,
+            }
+            Ok(format!(
+                "[{}]: {}",
+                name,
+                Frame698::oad_desc(self.analyzer, oad, r.region)
+            ))
+        })
+    }
+
+    // GetRecord：OAD + RSD + RCSD
+    fn get_record(&mut self, list: &mut Vec<Value>, name: &str) -> Result<(), String> {
+        self.group(list, name, |r, children| {
+            let oad = r.oad_field(children, "对象属性描述符")?;
+            r.field(children, "行选择描述符", |r| r.rsd_value())?;
+            r.rcsd_field(children, "列选择描述符")?;
+            Ok(format!(
+                "[{}]: {}",
+                name,
+                Frame698::oad_desc(self.analyzer, oad, r.region)
+            ))
+        })
+    }
+
+    // RCSD，返回各列的OAD，ROAD列标记为true
+    fn rcsd_field(
+        &mut self,
+        list: &mut Vec<Value>,
+        name: &str,
+    ) -> Result<Vec<(u32, bool)>, String> {
+        let mut columns = Vec::new();
+        self.group(list, name, |r, children| {
+            let count = r.reader.read_length()?;
+            for i in 1..=count {
+                columns.push(r.csd_field(children, &format!("第{}列", i))?);
+            }
+            Ok(format!("[{}]: 共{}列", name, count))
+        })?;
+        Ok(columns)
+    }
+
+    fn csd_field(&mut self, list: &mut Vec<Value>, name: &str) -> Result<(u32, bool), String> {
+        let start = self.reader.position();
+        match self.reader.read_u8()? {
+            0 => {
+                let oad = self.reader.read_u32()?;
+                self.add(
+                    list,
+                    name,
+                    start,
+                    format!(
+                        "[{}]: {}",
+                        name,
+                        Frame698::oad_desc(self.analyzer, oad, self.region)
+                    ),
+                    None,
+                );
+                Ok((oad, false))
+            }
+            1 => {
+                let (value, children, oad) = self.road_value()?;
+                self.add(
+                    list,
+                    name,
+                    start,
+                    format!("[{}]: {}", name, value),
+                    children,
+                );
+                Ok((oad, true))
+            }
+            kind => Err(format!("CSD类型{}无效", kind)),
+        }
+    }
+
+    /// Data：类型标签 + 数据内容，带子项的类型逐项展开，其余按A-XDR解码
+    fn data_value(&mut self, scale: Option<&OadScale>) -> FieldResult {
+        let tag = self.reader.read_u8()?;
+        let type_name = Axdr::type_name(tag);
+        let value = match tag {
+            DT_ARRAY | DT_STRUCTURE => {
+                let count = self.reader.read_length()?;
+                let mut children = Vec::new();
+                for i in 1..=count {
+                    let name = if tag == DT_ARRAY {
+                        format!("第{}个元素", i)
+                    } else {
+                        format!("第{}个成员", i)
+                    };
+                    self.data_field(&mut children, &name, scale)?;
+                }
+                return Ok((format!("{}[{}]", type_name, count), Some(children)));
+            }
+            DT_ROAD => {
+                let (value, children, _) = self.road_value()?;
+                return Ok((value, children));
+            }
+            DT_REGION => return self.region_value(),
+            DT_RSD => return self.rsd_value(),
+            DT_CSD => {
+                let mut children = Vec::new();
+                let (oad, _) = self.csd_field(&mut children, "列选择描述符")?;
+                return Ok((
+                    Frame698::oad_desc(self.analyzer, oad, self.region),
+                    Some(children),
+                ));
+            }
+            DT_MS => return self.ms_value(),
+            DT_SID => return self.sid_value(),
+            DT_SID_MAC => return self.sid_mac_value(),
+            DT_RCSD => {
+                let mut children = Vec::new();
+                let columns = self.rcsd_field(&mut children, "记录型对象属性描述符")?;
+                return Ok((format!("共{}列", columns.len()), Some(children)));
+            }
+            _ => match self.reader.read_value(tag)? {
+                AxdrData::Oi(oi) => {
+                    let (name, _) =
+                        Frame698::get_oad_info(self.analyzer, (oi as u32) << 16, self.region);
+                    format!("{:04X} - {}", oi, name)
+                }
+                AxdrData::Oad(oad) => Frame698::oad_desc(self.analyzer, oad, self.region),
+                AxdrData::Omd(omd) => Frame698::omd_desc(self.analyzer, omd, self.region),
+                value => match (value.integer(), scale) {
+                    (Some(number), Some(scale)) => scale.format(number),
+                    _ => value.to_string(),
+                },
+            },
+        };
+        // 字符串、时间及地址类的值本身可辨认，不再附加类型名
+        match tag {
+            DT_OCTET_STRING | DT_VISIBLE_STRING | DT_DATE_TIME | DT_DATE_TIME_S | DT_TI
+            | DT_TSA | DT_MAC | DT_RN | DT_COMDCB => Ok((value, None)),
+            _ => Ok((format!("{} ({})", value, type_name), None)),
+        }
+    }
+
+    fn date_time_value(&mut self) -> FieldResult {
+        Ok((self.reader.read_date_time()?.to_string(), None))
+    }
+
+    fn date_time_s_value(&mut self) -> FieldResult {
+        Ok((self.reader.read_date_time_s()?.to_string(), None))
+    }
+
+    fn octet_string_value(&mut self) -> FieldResult {
+        let data = self.reader.read_octet_string()?;
+        Ok((FrameFun::get_data_str_order(data), None))
+    }
+
+    fn visible_string_value(&mut self) -> FieldResult {
+        let data = self.reader.read_octet_string()?;
+        Ok((FrameFun::ascii_to_str(data), None))
+    }
+
+    fn dar_value(&mut self) -> FieldResult {
+        let dar = self.reader.read_u8()?;
+        Ok((format!("{}-{}", dar, Frame698::dar_name(dar)), None))
+    }
+
+    fn ti_value(&mut self) -> FieldResult {
+        Ok((self.reader.read_ti()?.to_string(), None))
+    }
+
+    fn tsa_value(&mut self) -> FieldResult {
+        Ok((self.reader.read_tsa()?.to_string(), None))
+    }
+
+    fn road_value(&mut self) -> Result<(String, Option<Vec<Value>>, u32), String> {
+        let mut children = Vec::new();
+        let oad = self.oad_field(&mut children, "对象属性描述符")?;
+        self.sequence(&mut children, "关联对象属性描述符", |r, l, _| {
+            r.oad_field(l, "对象属性描述符").map(|_| ())
+        })?;
+        Ok((
+            Frame698::oad_desc(self.analyzer, oad, self.region),
+            Some(children),
+            oad,
+        ))
+    }
+
+    fn region_value(&mut self) -> FieldResult {
+        let kind = self.reader.read_u8()?;
+        let mut children = Vec::new();
+        self.data_field(&mut children, "起始值", None)?;
+        self.data_field(&mut children, "结束值", None)?;
+        Ok((Region::kind_name(kind).to_string(), Some(children)))
+    }
+
+    fn rsd_value(&mut self) -> FieldResult {
+        let selector = self.reader.read_u8()?;
+        let mut children = Vec::new();
+        let desc = match selector {
+            0 => "不选择",
+            1 => {
+                self.oad_field(&mut children, "对象属性描述符")?;
+                self.data_field(&mut children, "数值", None)?;
+                "选择方法1：指定对象指定值"
+            }
+            2 => {
+                self.selector2(&mut children)?;
+                "选择方法2：指定对象区间内连续间隔值"
+            }
+            3 => {
+                self.sequence(&mut children, "若干个选择方法2", |r, l, i| {
+                    r.group(l, &format!("第{}个选择方法2", i), |r, l| {
+                        r.selector2(l)?;
+                        Ok(format!("[第{}个选择方法2]", i))
+                    })
+                })?;
+                "选择方法3：多个指定对象连续间隔值"
+            }
+            4 | 5 => {
+                let name = if selector == 4 {
+                    "采集启动时间"
+                } else {
+                    "采集存储时间"
+                };
+                self.field(&mut children, name, |r| r.date_time_s_value())?;
+                self.field(&mut children, "电能表集合", |r| r.ms_value())?;
+                if selector == 4 {
+                    "选择方法4：指定电能表集合、指定采集启动时间"
+                } else {
+                    "选择方法5：指定电能表集合、指定采集存储时间"
+                }
+            }
+            6..=8 => {
+                let name = match selector {
+                    6 => "采集启动时间",
+                    7 => "采集存储时间",
+                    _ => "采集成功时间",
+                };
+                self.field(&mut children, &format!("{}起始值", name), |r| {
+                    r.date_time_s_value()
+                })?;
+                self.field(&mut children, &format!("{}结束值", name), |r| {
+                    r.date_time_s_value()
+                })?;
+                self.field(&mut children, "时间间隔", |r| r.ti_value())?;
+                self.field(&mut children, "电能表集合", |r| r.ms_value())?;
+                match selector {
+                    6 => "选择方法6：指定电能表集合、指定采集启动时间区间内连续间隔值",
+                    7 => "选择方法7：指定电能表集合、指定采集存储时间区间内连续间隔值",
+                    _ => "选择方法8：指定电能表集合、指定采集到时间区间内连续间隔值",
+                }
+            }
+            9 => {
+                self.field(&mut children, "上第n次记录", |r| {
+                    Ok((format!("{}", r.reader.read_u8()?), None))
+                })?;
+                "选择方法9：指定选取上第n次记录"
+            }
+            10 => {
+                self.field(&mut children, "上n条记录", |r| {
+                    Ok((format!("{}", r.reader.read_u8()?), None))
+                })?;
+                self.field(&mut children, "电能表集合", |r| r.ms_value())?;
+                "选择方法10：指定选取最新的n条记录"
+            }
+            _ => return Err(format!("行选择方法{}无效", selector)),
+        };
+        Ok((desc.to_string(), Some(children)))
+    }
+
+    fn selector2(&mut self, list: &mut Vec<Value>) -> Result<(), String> {
+        self.oad_field(list, "对象属性描述符")?;
+        self.data_field(list, "起始值", None)?;
+        self.data_field(list, "结束值", None)?;
+        self.data_field(list, "数据间隔", None)
+    }
+
+    fn ms_value(&mut self) -> FieldResult {
+        let kind = self.reader.read_u8()?;
+        let mut children = Vec::new();
+        let desc = match kind {
+            0 => "无电能表",
+            1 => "全部用户地址",
+            2 => {
+                self.sequence(&mut children, "用户类型", |r, l, i| {
+                    r.field(l, &format!("第{}个用户类型", i), |r| {
+                        Ok((format!("{}", r.reader.read_u8()?), None))
+                    })
+                })?;
+                "一组用户类型"
+            }
+            3 => {
+                self.sequence(&mut children, "用户地址", |r, l, i| {
+                    r.field(l, &format!("第{}个用户地址", i), |r| r.tsa_value())
+                })?;
+                "一组用户地址"
+            }
+            4 => {
+                self.sequence(&mut children, "配置序号", |r, l, i| {
+                    r.field(l, &format!("第{}个配置序号", i), |r| {
+                        Ok((format!("{}", r.reader.read_u16()?), None))
+                    })
+                })?;
+                "一组配置序号"
+            }
+            5..=7 => {
+                self.sequence(&mut children, "区间", |r, l, i| {
+                    r.field(l, &format!("第{}个区间", i), |r| r.region_value())
+                })?;
+                match kind {
+                    5 => "一组用户类型区间",
+                    6 => "一组用户地址区间",
+                    _ => "一组配置序号区间",
+                }
+            }
+            _ => return Err(format!("电能表集合类型{}无效", kind)),
+        };
+        Ok((desc.to_string(), Some(children)))
+    }
+
+    fn sid_value(&mut self) -> FieldResult {
+        let mut children = Vec::new();
+        self.field(&mut children, "标识", |r| {
+            Ok((format!("{:08X}", r.reader.read_u32()?), None))
+        })?;
+        self.field(&mut children, "附加数据", |r| r.octet_string_value())?;
+        Ok(("安全标识".to_string(), Some(children)))
+    }
+
+    fn sid_mac_value(&mut self) -> FieldResult {
+        let mut children = Vec::new();
+        self.field(&mut children, "安全标识", |r| r.sid_value())?;
+        self.field(&mut children, "数据MAC", |r| r.octet_string_value())?;
+        Ok(("安全标识+数据MAC".to_string(), Some(children)))
+    }
+
+    fn comdcb_value(&mut self) -> FieldResult {
+        Ok((self.reader.read_comdcb()?.to_string(), None))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::basefunc::test_util::find;
+
+    // 读取通信地址40010200，通配地址AAAAAAAAAAAA
+    const GOLDEN: &str = "6817004345AAAAAAAAAAAA005B4F0501004001020000ED0316";
+
+    #[test]
+    fn golden_frame_checksums() {
+        let frame = FrameFun::get_frame_list_from_str(GOLDEN);
+        assert!(Frame698::is_698_frame(&frame));
+        assert!(Frame698::is_check_ok(&frame));
+        assert_eq!(FrameFun::calculate_fcs16(&frame[1..12]), 0x4F5B);
+        assert_eq!(FrameFun::calculate_fcs16(&frame[1..22]), 0x03ED);
+    }
+
+    #[test]
+    fn build_golden_frame() {
+        let apdu = FrameFun::get_frame_list_from_str("0501004001020000");
+        let frame = Frame698::build_frame(0x43, 1, "AAAAAAAAAAAA", 0x00, &apdu).unwrap();
+        assert_eq!(FrameFun::get_data_str_order(&frame), GOLDEN);
+    }
+
+    #[test]
+    fn parse_golden_frame() {
+        let analyzer = Analyzer::with_builtin();
+        let frame = FrameFun::get_frame_list_from_str(GOLDEN);
+        let (protocol, result) = analyzer.process_frame(&frame, "南网");
+        assert_eq!(protocol, "DLT/698.45");
+        let hcs = find(&result, "帧头校验HCS").unwrap();
+        assert_eq!(hcs["data"], "5B 4F");
+        assert_eq!(hcs["description"], "帧头校验正确");
+        let fcs = find(&result, "帧校验FCS").unwrap();
+        assert_eq!(fcs["data"], "ED 03");
+        assert_eq!(fcs["description"], "帧校验正确");
+        assert_eq!(
+            find(&result, "对象属性描述符").unwrap()["data"],
+            "40 01 02 00"
+        );
+    }
+
+    #[test]
+    fn corrupted_fcs() {
+        let analyzer = Analyzer::with_builtin();
+        let mut frame = FrameFun::get_frame_list_from_str(GOLDEN);
+        frame[22] = 0x00;
+        // 帧头校验正确时仍按698解析，显示帧校验错误
+        assert!(Frame698::is_698_frame(&frame));
+        assert!(!Frame698::is_check_ok(&frame));
+        let (_, result) = analyzer.process_frame(&frame, "南网");
+        assert_eq!(
+            find(&result, "帧校验FCS").unwrap()["description"],
+            "帧校验错误，应为：ED 03"
+        );
+    }
+}
@@ -1,7 +1,7 @@
+use crate::analyzer::Analyzer;
 use crate::basefunc::frame_csg::FrameCsg;
 use crate::basefunc::frame_fun::FrameFun;
 use crate::basefunc::protocol::{FrameAnalisyic, ProtocolInfo};
-use crate::config::xmlconfig::ProtocolConfigManager;
 use serde_json::Value;
 use tracing::info;
 const FRAME_START: u8 = 0x68;
@@ -146,47 +146,29 @@ impl FrameCCO {
         );
     }
 
-    pub fn get_afn_info(dir_type: u8, _prm: u8, afn: u8) -> &'static str {
-        match dir_type {
-            0xE8 => match afn {
-                0x00 => "确认/否认",
-                0x01 => "初始化模块",
-                0x02 => "管理任务",
-                0x03 => "读参数",
-                0x04 => "写参数",
-                0x05 => "上报信息",
-                0x06 => "请求信息",
-                0x07 => "传输文件",
-                0x10 => "维护命令",
-                0xF0 => "维护模块",
-                _ => "未知",
-            },
-            _ => match afn {
-                0x00 => "确认/否认",
-                0x21 => "管理电表",
-                0x22 => "转发数据",
-                0x23 => "读参数",
-                0x24 => "传输文件",
-                0x25 => "请求信息",
-                0x31 => "管理映射表表计",
-                _ => "未知",
-            },
-        }
+    pub fn get_afn_info(analyzer: &Analyzer, dir_type: u8, _prm: u8, afn: u8) -> String {
+        let protocol = ProtocolInfo::ProtocolCSG16.name();
+        analyzer
+            .find_afn(protocol, afn, Some(dir_type), None)
+            .map(|entry| entry.name)
+            .unwrap_or_else(|| "未知".to_string())
     }
 
     pub fn analysic_cco_frame_by_afn(
+        analyzer: &Analyzer,
         frame: &[u8],
         result_list: &mut Vec<Value>,
         index: usize,
         region: &str,
     ) {
         let (dir, prm, add, afn, pos, mut user_result) =
-            Self::analysic_cco_head_frame(frame, result_list, index);
+            Self::analysic_cco_head_frame(analyzer, frame, result_list, index);
 
         let app_data = &frame[pos..frame.len() - 2];
         let mut app_data_result = Vec::new();
         let protocol = ProtocolInfo::ProtocolCSG16.name().to_string();
         Self::analysic_cco_appdata_frame(
+            analyzer,
             app_data,
             &mut app_data_result,
             dir,
@@ -224,6 +206,7 @@ impl FrameCCO {
     }
 
     fn analysic_cco_head_frame(
+        analyzer: &Analyzer,
         frame: &[u8],
         result_list: &mut Vec<Value>,
         index: usize,
@@ -291,7 +274,7 @@ impl FrameCCO {
 
         let afn = frame[pos];
         let dir_type = frame[pos + 5];
-        let afn_str = Self::get_afn_info(dir_type, prm, afn);
+        let afn_str = Self::get_afn_info(analyzer, dir_type, prm, afn);
 
         FrameFun::add_data(
             &mut user_result,
@@ -333,7 +316,7 @@ impl FrameCCO {
         }
     }
 
-    fn analysic_cco_di_data(di: &[u8], result: &mut Vec<Value>, index: usize) {
+    fn analysic_cco_di_data(analyzer: &Analyzer, di: &[u8], result: &mut Vec<Value>, index: usize) {
         let di0 = di[0];
         let di1 = di[1];
         let di2 = di[2];
@@ -342,7 +325,7 @@ impl FrameCCO {
         let di0_str = "功能码子类型".to_string();
         let di1_str = format!(
             "功能码类型定义,与AFN值保持一致：{}",
-            Self::get_afn_info(di3, 1, di1)
+            Self::get_afn_info(analyzer, di3, 1, di1)
         );
         let di2_str = format!("报文上下行类型{}", Self::get_direction_str(di2));
         let di3_str = format!(
@@ -394,6 +377,7 @@ impl FrameCCO {
     }
 
     fn analysic_cco_appdata_frame(
+        analyzer: &Analyzer,
         data_content: &[u8],
         result: &mut Vec<Value>,
         dir: u8,
@@ -404,13 +388,13 @@ impl FrameCCO {
         let di = &data_content[0..4];
         let mut di_result = Vec::new();
 
-        Self::analysic_cco_di_data(di, &mut di_result, index);
+        Self::analysic_cco_di_data(analyzer, di, &mut di_result, index);
 
         let di_data = &data_content[4..];
         let data_item = FrameFun::get_data_str_reverser(di);
 
         if let Some(mut data_item_elem) =
-            ProtocolConfigManager::get_config_xml(&data_item, protocol, region, Some(dir))
+            analyzer.get_config_xml(&data_item, protocol, region, Some(dir))
         {
             let pos: usize = 0;
 
@@ -419,6 +403,7 @@ impl FrameCCO {
             let sub_length = if let Some(length_text) = length_ele {
                 match length_text.to_uppercase().as_str() {
                     "UNKNOWN" => FrameCsg::calculate_item_length(
+                        analyzer,
                         &mut data_item_elem,
                         di_data,
                         protocol,
@@ -435,6 +420,7 @@ impl FrameCCO {
             let sub_datament = &di_data[pos..pos + sub_length];
             data_item_elem.update_value("length", sub_length.to_string());
             let item_data = FrameAnalisyic::prase_data(
+                analyzer,
                 &mut data_item_elem,
                 protocol,
                 region,
@@ -477,7 +463,7 @@ impl FrameCCO {
         }
     }
 
-    fn analysic_cco_end_frame(
+    pub fn analysic_cco_end_frame(
         data_content: &[u8],
         result: &mut Vec<Value>,
         _dir: u8,
@@ -1,17 +1,18 @@
+use crate::analyzer::Analyzer;
+use crate::basefunc::csg_items::CsgItemEngine;
 use crate::basefunc::frame_645::Frame645;
-use crate::basefunc::frame_err::CustomError;
 use crate::basefunc::frame_fun::FrameFun;
-use crate::basefunc::protocol::{AnalysicErr, FrameAnalisyic, ProtocolInfo};
-use crate::config::xmlconfig::{ProtocolConfigManager, XmlElement}; // 引入 FrameFun 模块
+use crate::basefunc::protocol::{FrameAnalisyic, ProtocolInfo};
+use crate::basefunc::length_expr::{evaluate_length_rule, FieldValues, LengthExpr};
+use crate::basefunc::switch_case::SwitchCase;
+use crate::config::xmlconfig::XmlElement; // 引入 FrameFun 模块
 use chrono::{DateTime, Datelike, NaiveDate, NaiveDateTime, NaiveTime};
-use lazy_static::lazy_static;
 use regex::Regex;
 use serde_json::Value;
 use std::backtrace::Backtrace;
 use std::collections::HashMap;
 use std::error::Error;
-use std::sync::Mutex;
-use tracing::info;
+use tracing::{info, warn};
 const ITEM_ACK_NAK: u32 = 0xE0000000;
 const MASK_FIR: u8 = 0x40;
 const MASK_FIN: u8 = 0x20;
@@ -35,21 +36,9 @@ pub enum FramePos {
 const ACK: u8 = 0x00;
 const NAK: u8 = 0x01;
 
-lazy_static! {
-    static ref GLOBAL_VAR: Mutex<u8> = Mutex::new(0);
-    static ref LOCK: Mutex<()> = Mutex::new(());
-}
-
 pub struct FrameCsg;
 
 impl FrameCsg {
-    pub fn get_csg_pseq() -> u8 {
-        let mut global_var = GLOBAL_VAR.lock().unwrap();
-        let current_value = *global_var;
-        *global_var = (current_value + 1) % 16;
-        current_value
-    }
-
     pub fn init_frame(ctrl: u8, afn: u8, adress: &[u8], msa: u8, seq: u8, frame: &mut [u8]) {
         frame[FramePos::PosStart0 as usize] = 0x68;
         frame[FramePos::PosStart1 as usize] = 0x68;
@@ -80,13 +69,14 @@ impl FrameCsg {
         len
     }
 
-    pub fn get_frame_seq(tpv: u8, fir: u8, fin: u8, con: u8) -> u8 {
+    /// 帧序号由调用方维护，取低4位
+    pub fn get_frame_seq(tpv: u8, fir: u8, fin: u8, con: u8, pseq: u8) -> u8 {
         let mut value = 0;
         value |= (tpv & 0x01) << 7;
         value |= (fir & 0x01) << 6;
         value |= (fin & 0x01) << 5;
         value |= (con & 0x01) << 4;
-        value |= Self::get_csg_pseq() & 0x0F;
+        value |= pseq & 0x0F;
         value
     }
 
@@ -333,6 +323,7 @@ impl FrameCsg {
     }
 
     pub fn analysic_csg_frame_by_afn(
+        analyzer: &Analyzer,
         frame: &[u8],
         result_list: &mut Vec<Value>,
         index: usize,
@@ -343,6 +334,7 @@ impl FrameCsg {
                 Self::analysic_csg_custom_head_frame(frame, result_list, index)?;
                 let new_frame = frame[84..].to_vec();
                 return Self::analysic_csg_frame_by_afn(
+                    analyzer,
                     &new_frame,
                     result_list,
                     index + 84,
@@ -355,125 +347,26 @@ impl FrameCsg {
         let (dir, prm) = Self::analysic_csg_head_frame(frame, result_list, index);
         info!("dir: {:?}, prm: {:?}", dir, prm);
         let protocol = ProtocolInfo::ProtocolCSG13.name().to_string();
-        match afn {
-            0x00 => Self::analysic_csg_ack_frame(
-                frame,
-                dir,
-                prm,
-                result_list,
-                index,
-                &protocol,
-                region,
-            )?,
-            0x02 => Self::analysic_csg_link_frame(
-                frame,
-                dir,
-                prm,
-                result_list,
-                index,
-                &protocol,
-                region,
-            )?,
-            0x04 => Self::analysic_csg_write_frame(
-                frame,
-                dir,
-                prm,
-                result_list,
-                index,
-                &protocol,
-                region,
-            )?,
-            0x06 => Self::analysic_csg_security_frame(
-                frame,
-                dir,
-                prm,
-                result_list,
-                index,
-                &protocol,
-                region,
-            )?,
-            0x0C => Self::analysic_csg_read_cur_frame(
-                frame,
-                dir,
-                prm,
-                result_list,
-                index,
-                &protocol,
-                region,
-            )?,
-            0x0D => Self::analysic_csg_read_history_frame(
-                frame,
-                dir,
-                prm,
-                result_list,
-                index,
-                &protocol,
-                region,
-            )?,
-            0x0A => Self::analysic_csg_read_param_frame(
-                frame,
-                dir,
-                prm,
-                result_list,
-                index,
-                &protocol,
-                region,
-            )?,
-            0x10 => Self::analysic_csg_relay_frame(
-                frame,
-                dir,
-                prm,
-                result_list,
-                index,
-                &protocol,
-                region,
-            )?,
-            0x12 => Self::analysic_csg_read_task_frame(
-                frame,
-                dir,
-                prm,
-                result_list,
-                index,
-                &protocol,
-                region,
-            )?,
-            0x13 => Self::analysic_csg_read_alarm_frame(
-                frame,
-                dir,
-                prm,
-                result_list,
-                index,
-                &protocol,
-                region,
-            )?,
-            0x0E => Self::analysic_csg_read_event_frame(
-                frame,
-                dir,
-                prm,
-                result_list,
-                index,
-                &protocol,
-                region,
-            )?,
-            0x0F => Self::analysic_csg_filetrans_frame(
-                frame,
-                dir,
-                prm,
-                result_list,
-                index,
-                &protocol,
-                region,
-            )?,
-            0x23 => Self::analysic_csg_topo_frame(
-                frame,
-                dir,
-                prm,
-                result_list,
-                index,
-                &protocol,
-                region,
-            )?,
-            _ => (),
+        // 功能码的解析方式来自功能码表，厂家自定义AFN可在配置中指定
+        let handler = analyzer
+            .find_afn(&protocol, afn, None, None)
+            .and_then(|entry| entry.handler);
+        match handler.as_deref() {
+            Some(handler) => match CsgItemEngine::layout(handler) {
+                Some(layout) => CsgItemEngine::analysic(
+                    analyzer,
+                    frame,
+                    dir,
+                    prm,
+                    &layout,
+                    result_list,
+                    index,
+                    &protocol,
+                    region,
+                )?,
+                None => warn!("AFN:{:02X}的解析方式[{}]不支持", afn, handler),
+            },
+            None => (),
         }
 
         Self::analyze_csg_end_frame(frame, result_list, index);
@@ -839,30 +732,31 @@ impl FrameCsg {
         )
     }
 
-    pub fn get_afn_and_seq_result(data: &[u8], index: usize, result_list: &mut Vec<Value>) -> bool {
+    pub fn get_afn_and_seq_result(
+        analyzer: &Analyzer,
+        data: &[u8],
+        index: usize,
+        result_list: &mut Vec<Value>,
+    ) -> bool {
+        let protocol = ProtocolInfo::ProtocolCSG13.name();
+        Self::get_afn_and_seq_result_with(analyzer, protocol, data, index, result_list)
+    }
+
+    /// AFN和SEQ的解析，AFN名称取自指定规约的功能码表(1376.1与南网13的SEQ定义相同)
+    pub fn get_afn_and_seq_result_with(
+        analyzer: &Analyzer,
+        protocol: &str,
+        data: &[u8],
+        index: usize,
+        result_list: &mut Vec<Value>,
+    ) -> bool {
         let afn = data[0];
         let seq = data[1];
 
-        let afn_str = match afn {
-            0x00 => "确认/否定".to_string(),
-            0x02 => "链路接口检测".to_string(),
-            0x04 => "写参数".to_string(),
-            0x06 => "安全认证".to_string(),
-            0x0A => "读参数".to_string(),
-            0x0C => "读当前数据".to_string(),
-            0x0D => "读历史数据".to_string(),
-            0x0E => "读事件记录".to_string(),
-            0x0F => "文件传输".to_string(),
-            0x10 => "中继转发".to_string(),
-            0x12 => "读任务数据".to_string(),
-            0x13 => "读告警数据".to_string(),
-            0x14 => "级联命令".to_string(),
-            0x15 => "用户自定义数据".to_string(),
-            0x16 => "数据安全传输".to_string(),
-            0x17 => "数据转加密".to_string(),
-            0x23 => "主站中转报文".to_string(),
-            _ => "备用".to_string(),
-        };
+        let afn_str = analyzer
+            .find_afn(protocol, afn, None, None)
+            .map(|entry| entry.name)
+            .unwrap_or_else(|| "备用".to_string());
         let binary_array = FrameFun::get_bit_array(seq);
         let tpv = binary_array[0];
         let fir = binary_array[1];
@@ -1039,131 +933,12 @@ impl FrameCsg {
         points.iter().map(|&point| Self::to_da(point)).collect()
     }
 
-    pub fn judge_is_exit_pw(
-        data_segment: &[u8],
-        item_element: Option<XmlElement>,
-        data_time: Option<&[u8]>,
-        with_time: bool,
-        protocol: &str,
-        region: &str,
-        dir: Option<u8>,
-    ) -> bool {
-        if let Some(item_element) = item_element {
-            if Self::guest_next_data_is_cur_item_data(
-                Some(item_element),
-                data_segment,
-                data_time,
-                protocol,
-                region,
-                dir,
-            ) {
-                return false;
-            } else {
-                return Self::judge_is_exit_pw(
-                    data_segment,
-                    None,
-                    None,
-                    with_time,
-                    protocol,
-                    region,
-                    dir,
-                );
-            }
-        }
-        let total_len = data_segment.len();
-        let mut pos = 0;
-        let left_length = if with_time { total_len - 6 } else { total_len };
-        while total_len > pos {
-            if pos + 4 > total_len {
-                info!("ddddd pos + 4:{:?} total_len {:?}", pos + 4,total_len);
-                if pos != 0 {
-                    return true;
-                }
-                break;
-            }
-            let item = &data_segment[2 + pos..6 + pos];
-            let data_item = &FrameFun::get_data_str_reverser(item);
-            let data_item_elem =
-                ProtocolConfigManager::get_config_xml(data_item, protocol, region, dir);
-            if let Some(mut data_item_elem) = data_item_elem {
-                let sub_length_cont = data_item_elem.get_child_text("length");
-                if let Some(sub_length_cont) = sub_length_cont {
-                    let sub_length = if sub_length_cont.to_uppercase() == "UNKNOWN" {
-                        Self::calculate_item_length(
-                            &mut data_item_elem,
-                            &data_segment[6..],
-                            protocol,
-                            region,
-                            dir,
-                            None,
-                        )
-                    } else {
-                        sub_length_cont.parse::<usize>().unwrap()
-                    };
-                    info!("sub_length:{:?} pos{:?} with_time{:?}", sub_length,pos,with_time);
-                    pos += sub_length + 6;
-                    pos += 5;
-                    if (left_length - 6) % (sub_length + 5) == 0 {
-                        return false;
-                    }
-                    
-                } else {
-                    info!("aaaaa");
-                    return true;
-                }
-            } else {
-                info!("bbbbbbb");
-                return true;
-            }
-        }
-        info!("ccccccc");
-        false
-    }
-
-    pub fn guest_is_exit_pw(
-        length: usize,
-        data_segment: &[u8],
-        data_item_elem: Option<XmlElement>,
-        data_time: Option<&[u8]>,
-        with_time: bool,
-        protocol: &str,
-        region: &str,
-        dir: Option<u8>,
-    ) -> bool {
-        info!(
-            "guest_is_exit_pw length{:?} pw_data{:?}",
-            length, data_segment
-        );
-        if length <= 16 {
-            return false;
-        }
-        if data_segment.len() < 16 {
-            return false;
-        }
-        if FrameFun::is_array_all_zeros(&data_segment) {
-            return true;
-        } else {
-            return Self::judge_is_exit_pw(
-                &data_segment,
-                data_item_elem,
-                data_time,
-                with_time,
-                protocol,
-                region,
-                dir,
-            );
-        }
-    }
-
     pub fn is_valid_expression(s: &str) -> bool {
-        // Define the regex pattern
-        let pattern = Regex::new(r"^\s*\d+\s*[\+\-\*/]\s*(\d+|[^\s]*)\s*$").unwrap();
-
-        // Match the input string against the pattern
-        pattern.is_match(s)
+        LengthExpr::parse(s).and_then(|expr| expr.check()).is_ok()
     }
 
     pub fn calculate_item_length(
+        analyzer: &Analyzer,
         sub_element: &mut XmlElement,
         data_segment: &[u8],
         protocol: &str,
@@ -1172,6 +947,7 @@ impl FrameCsg {
         all_length_items: Option<&[XmlElement]>,
     ) -> usize {
         Self::execute_calculation(
+            analyzer,
             sub_element,
             data_segment,
             protocol,
@@ -1182,6 +958,7 @@ impl FrameCsg {
     }
 
     pub fn execute_calculation(
+        analyzer: &Analyzer,
         element: &mut XmlElement,
         data: &[u8],
         protocol: &str,
@@ -1206,6 +983,7 @@ impl FrameCsg {
                     if Self::is_valid_expression(&rules) {
                         info!("rules:{:?}", rules);
                         return Self::calculate_unknown_length(
+                            analyzer,
                             element,
                             data,
                             &mut length_map,
@@ -1220,11 +998,12 @@ impl FrameCsg {
                     if let Some(data_type) = template_element.get_value().map(|s| s.to_uppercase())
                     {
                         if !["BCD", "BIN", "ASCII", "BIN_BE"].contains(&data_type.as_str()) {
-                            if let Some(mut template) = ProtocolConfigManager::get_template_element(
-                                &data_type, protocol, region, dir,
-                            ) {
+                            if let Some(mut template) =
+                                analyzer.get_template_element(&data_type, protocol, region, dir)
+                            {
                                 let template_items_cloned: Vec<XmlElement> = template.get_items("splitByLength");
                                 return Self::execute_calculation(
+                                    analyzer,
                                     &mut template,
                                     data,
                                     protocol,
@@ -1239,6 +1018,18 @@ impl FrameCsg {
             }
         } else {
             for (i, data_subitem_elem) in all_items.iter_mut().enumerate() {
+                if SwitchCase::has_switch(data_subitem_elem) {
+                    let values =
+                        Self::sibling_values(analyzer, &length_map, data, protocol, region, dir);
+                    match SwitchCase::resolve(data_subitem_elem, &values) {
+                        Ok(Some(resolved)) => *data_subitem_elem = resolved,
+                        Ok(None) => continue,
+                        Err(e) => {
+                            warn!("{}", e);
+                            break;
+                        }
+                    }
+                }
                 let subitem_name_item = data_subitem_elem.get_child("name");
                 let sub_length_content = data_subitem_elem.get_child_text("length");
 
@@ -1259,6 +1050,7 @@ impl FrameCsg {
                 let subitem_length = if let Some(sub_length_content) = sub_length_content {
                     if sub_length_content.to_uppercase() == "UNKNOWN" {
                         let length = Self::calculate_unknown_length(
+                            analyzer,
                             data_subitem_elem,
                             data,
                             &mut length_map,
@@ -1276,6 +1068,7 @@ impl FrameCsg {
                     }
                 } else {
                     let length = FrameFun::calculate_item_box_length(
+                        analyzer,
                         data_subitem_elem,
                         protocol,
                         region,
@@ -1296,7 +1089,32 @@ impl FrameCsg {
         length
     }
 
+    // 同级已计算长度的子项，作为lengthrule和switch的取值来源
+    fn sibling_values<'a>(
+        analyzer: &'a Analyzer,
+        length_map: &'a HashMap<String, (usize, usize, &XmlElement)>,
+        data_segment: &'a [u8],
+        protocol: &'a str,
+        region: &'a str,
+        dir: Option<u8>,
+    ) -> FieldValues<'a> {
+        FieldValues {
+            analyzer,
+            siblings: length_map
+                .iter()
+                .filter(|(_, (end, length, _))| *end <= data_segment.len() && *length <= *end)
+                .map(|(name, (end, length, element))| {
+                    (name.clone(), &data_segment[end - length..*end], *element)
+                })
+                .collect(),
+            protocol,
+            region,
+            dir,
+        }
+    }
+
     pub fn calculate_unknown_length(
+        analyzer: &Analyzer,
         data_subitem_elem: &mut XmlElement,
         data_segment: &[u8],
         length_map: &HashMap<String, (usize, usize, &XmlElement)>,
@@ -1310,7 +1128,6 @@ impl FrameCsg {
         let rules = data_subitem_elem
             .get_child_text("lengthrule")
             .unwrap_or_default();
-        let operator_mapping = HashMap::from([("+", '+'), ("-", '-'), ("*", '*'), ("/", '/')]);
         let mut sub_length = 0;
         let pattern = Regex::new(r"^RANGE\(([^)]+)\)$").unwrap();
         info!(
@@ -1334,115 +1151,23 @@ impl FrameCsg {
                         }
                     }
                 }
-            }
-
-            // Split the rule using regex
-            // 使用正则表达式解析规则，以处理不同格式的规则
-            let number_part: &str;
-            let operator_part: &str;
-            let text_part: &str;
-            let operator: char;
-            let rule_regex = Regex::new(r"(\d+)\s*([+\-*/])\s*(.+)").unwrap();
-            if let Some(captures) = rule_regex.captures(&rules) {
-                number_part = captures.get(1).unwrap().as_str();
-                operator_part = captures.get(2).unwrap().as_str();
-                text_part = captures.get(3).unwrap().as_str().trim();
-
-                operator = match operator_part {
-                    "+" => '+',
-                    "-" => '-',
-                    "*" => '*',
-                    "/" => '/',
-                    _ => *operator_mapping.get(operator_part).unwrap_or(&'?'),
-                };
-
-                info!(
-                    "number_part:{:?} operator_part:{:?} text_part:{:?}",
-                    number_part, operator_part, text_part
-                );
-            } else {
-                info!("Invalid rule format");
+                warn!("未找到长度规则[{}]的分隔字段或分隔符", rules);
                 return sub_length;
             }
-            info!("length_map:{:?}", length_map);
-            let sub_value = if text_part.chars().all(char::is_numeric) {
-                text_part.parse::<usize>().unwrap_or(0)
-            } else {
-                if let Some(vaule) = length_map.get(text_part) {
-                    info!(
-                        "value {:?} vaule.0 {:?} vaule.1 {:?} data_segment {:?}",
-                        vaule, vaule.0, vaule.1, data_segment
-                    );
-                    if vaule.0 - (vaule.0 - vaule.1) > data_segment.len() {
-                        return 0;
-                    }
-                    let vaule_data = &data_segment[(vaule.0 - vaule.1)..vaule.0];
-                    let mut value_element = vaule.2.clone();
-
-                    let result = FrameAnalisyic::prase_data_item(
-                        &mut value_element,
-                        vaule_data,
-                        0,
-                        false,
-                        protocol,
-                        region,
-                        dir,
-                    );
-                    let target_result = FrameFun::find_frame_in_data_list(&result, text_part);
-                    let mut sub_value_str = String::new();
-                    info!("target_result:{:?}", target_result);
-                    if let Some(target_results) = target_result {
-                        for item in target_results {
-                            if let Some(sub_value) = item.get("description") {
-                                if let Some(sub_value_str_temp) = sub_value.as_str() {
-                                    sub_value_str = sub_value_str_temp.to_string();
-                                } else {
-                                    info!("Description is not a string.");
-                                }
-                            } else {
-                                info!("No description found in item.");
-                            }
-                        }
-                    } else {
-                        info!("No matching frames found.");
-                    }
 
-                    // Extract number from sub_value using regex
-                    let match_re = Regex::new(r"(\d+)").unwrap();
-                    if let Some(captures) = match_re.captures(&sub_value_str) {
-                        captures
-                            .get(1)
-                            .unwrap()
-                            .as_str()
-                            .parse::<usize>()
-                            .unwrap_or(0)
-                    } else {
-                        sub_value_str.parse::<usize>().unwrap_or(0)
-                    }
-                } else {
+            // 按表达式计算长度，字段先从同级已计算长度的子项中取，再从已解析的上级字段中取
+            let values =
+                Self::sibling_values(analyzer, length_map, data_segment, protocol, region, dir);
+            sub_length = match evaluate_length_rule(data_subitem_elem, &rules, &values) {
+                Ok(length) => length,
+                Err(e) => {
+                    warn!("{}", e);
                     0
                 }
             };
-
-            // Parse number_part to usize
-            let decimal_number = number_part.parse::<usize>().unwrap_or_else(|_| {
-                info!("Failed to convert to integer: {}", number_part);
-                0
-            });
-            info!(
-                "decimal_number:{:?} sub_value:{:?}",
-                decimal_number, sub_value
-            );
-            // Perform the operation based on the operator
-            match operator {
-                '+' => sub_length = decimal_number + sub_value,
-                '-' => sub_length = decimal_number - sub_value,
-                '*' => sub_length = decimal_number * sub_value,
-                '/' => sub_length = decimal_number / sub_value,
-                _ => sub_length = 0,
-            }
         } else {
             sub_length = Self::execute_calculation(
+                analyzer,
                 data_subitem_elem,
                 data_segment,
                 protocol,
@@ -1455,56 +1180,8 @@ impl FrameCsg {
         sub_length
     }
 
-    pub fn guest_next_data_is_cur_item_data(
-        item_element: Option<XmlElement>,
-        data_segment: &[u8],
-        data_time: Option<&[u8]>,
-        protocol: &str,
-        region: &str,
-        dir: Option<u8>,
-    ) -> bool {
-        if item_element.is_none() {
-            return false;
-        }
-        let mut item_element: XmlElement = item_element.unwrap();
-        let (length, new_data) =
-            Self::recalculate_sub_length(&mut item_element, data_segment, protocol, region, dir);
-
-        if (length + 6) > data_segment.len() {
-            info!(
-                "length:{:?} data_segment.len():{:?}",
-                length,
-                data_segment.len()
-            );
-            return false;
-        }
-        info!(
-            "data_segment[length..length + 6]:{:?} data_time:{:?}",
-            &data_segment[length..length + 6],
-            data_time
-        );
-        if Self::is_valid_bcd_time(&data_segment[length..length + 6]) {
-            if let Some(data_time) = data_time {
-                if Self::is_within_one_month(&data_segment[length..length + 6], data_time) {
-                    return true;
-                }
-            }
-        }
-        let next_item = FrameFun::get_data_str_reverser(&data_segment[2..6]);
-        if Some(next_item.clone()) == item_element.get_attribute("id").cloned() {
-            info!("next_item:{:?} item_element:{:?}", next_item, item_element);
-            return false;
-        }
-        let data_item_elem =
-            ProtocolConfigManager::get_config_xml(&next_item, protocol, region, dir);
-        if let Some(data_item_elem) = data_item_elem {
-            info!("data_item_elem:{:?}", data_item_elem);
-            return false;
-        }
-        false
-    }
-
     pub fn recalculate_sub_length<'a>(
+        analyzer: &Analyzer,
         data_item_elem: &mut XmlElement,
         data_segment: &'a [u8],
         protocol: &str,
@@ -1518,6 +1195,7 @@ impl FrameCsg {
         // info!("bt:{:?}", bt);
         if sub_length_cont.to_uppercase() == "UNKNOWN" {
             sub_length = Self::calculate_item_length(
+                analyzer,
                 data_item_elem,
                 data_segment,
                 protocol,
@@ -1805,14 +1483,14 @@ impl FrameCsg {
     }
 
     pub fn try_get_item_and_point(
+        analyzer: &Analyzer,
         item: &[u8],
         protocol: &str,
         region: &str,
         dir: Option<u8>,
     ) -> (Option<XmlElement>, String) {
         let data_item = FrameFun::get_data_str_reverser(item);
-        let data_item_elem =
-            ProtocolConfigManager::get_config_xml(&data_item, protocol, region, dir);
+        let data_item_elem = analyzer.get_config_xml(&data_item, protocol, region, dir);
         (data_item_elem, data_item)
     }
 
@@ -1830,6 +1508,9 @@ impl FrameCsg {
         }
     }
 
+    // 剩余数据恰为PW长度时按终端设置判断；auto时不能作为一组DA+DI(+数据)+数据时间
+    // 完整解析的视为PW
+    #[allow(clippy::too_many_arguments)]
     pub fn get_relay_type(relay_type: u8) -> &'static str {
         match relay_type {
             0x00 => "普通中继",
@@ -1932,3366 +1613,4 @@ impl FrameCsg {
 
         Ok(())
     }
-
-    pub fn analysic_csg_ack_frame(
-        frame: &[u8],
-        dir: u8,
-        prm: u8,
-        result_list: &mut Vec<Value>,
-        start_pos: usize,
-        protocol: &str,
-        region: &str,
-    ) -> Result<(), Box<dyn Error>> {
-        let data_segment = &frame[16..frame.len() - 2];
-        let tpv = Self::get_afn_and_seq_result(&frame[14..16], start_pos + 14, result_list);
-        let mut length = data_segment.len();
-        let total_length = frame.len();
-        let mut pos = 0;
-        let index = 16 + start_pos;
-        let mut num = 0;
-        let mut sub_result = Vec::new();
-        let mut tpv_data: &[u8] = &[];
-        let empty_data: &[u8] = &[];
-
-        let (pw_data, pw_pos) = if tpv {
-            length -= 5;
-            tpv_data = &frame[frame.len() - 7..frame.len() - 2];
-            if data_segment.len() < 21 {
-                (empty_data, [0, 0])
-            } else {
-                let pw_data = &data_segment[data_segment.len() - 21..data_segment.len() - 5];
-                (pw_data, [total_length - 23, total_length - 7])
-            }
-        } else {
-            if data_segment.len() < 16 {
-                (empty_data, [0, 0])
-            } else {
-                let pw_data = &data_segment[data_segment.len() - 16..];
-                (pw_data, [total_length - 18, total_length - 2])
-            }
-        };
-
-        let data_segment = &data_segment[..length];
-        let mut pw = false;
-
-        while pos < length {
-            let da = &data_segment[pos..pos + 2];
-            let item = &data_segment[pos + 2..pos + 6];
-            let point_str = Self::prase_da_data([da[0], da[1]]);
-
-            let data_item = FrameFun::get_data_str_reverser(item);
-
-            FrameFun::add_data(
-                &mut sub_result,
-                format!("<第{}组>信息点标识DA", num + 1),
-                FrameFun::get_data_str_with_space(da),
-                point_str,
-                vec![index + pos, index + pos + 2],
-                None,
-                None,
-            );
-            pos += 2;
-
-            let data_item_elem =
-                ProtocolConfigManager::get_config_xml(&data_item, protocol, region, Some(dir));
-            let mut item_data: Vec<Value> = Vec::new();
-
-            let dis_data_identifier: String;
-            let mut sub_datamen: &[u8];
-            let sub_length: usize;
-            let sub_datament: &[u8];
-
-            if let Some(mut data_item_elem) = data_item_elem {
-                sub_length = if let Some(sublength) = data_item_elem.get_child_text("length") {
-                    sublength.parse::<usize>().unwrap()
-                } else {
-                    data_segment[pos + 4..].len()
-                };
-                sub_datament = &data_segment[pos + 4..pos + 4 + sub_length];
-                data_item_elem.update_value("length", sub_length.to_string());
-                item_data = FrameAnalisyic::prase_data(
-                    &mut data_item_elem,
-                    protocol,
-                    region,
-                    sub_datament,
-                    index + pos + 4,
-                    Some(dir),
-                );
-                let name = data_item_elem.get_child_text("name").unwrap();
-                dis_data_identifier = format!("数据标识编码：[{}]-{}", data_item, name);
-            } else {
-                sub_datament = &data_segment[pos + 4..];
-                sub_length = sub_datament.len();
-                dis_data_identifier = format!("数据标识编码：[{}]", data_item);
-            };
-
-            let result_str: String = format!("数据标识[{}]数据内容：", data_item).to_string();
-
-            let description: String = format!(
-                "{}{}",
-                result_str,
-                FrameFun::get_data_str(data_segment, false, true, false)
-            );
-            FrameFun::add_data(
-                &mut sub_result,
-                format!("<第{}组>数据标识编码DI", num + 1),
-                FrameFun::get_data_str_with_space(item),
-                dis_data_identifier,
-                vec![index + pos, index + pos + 4],
-                None,
-                None,
-            );
-            FrameFun::add_data(
-                &mut sub_result,
-                format!("<第{}组>数据标识内容", num + 1),
-                FrameFun::get_data_str_with_space(sub_datament),
-                description,
-                vec![index + pos + 4, index + pos + 4 + sub_length],
-                Some(item_data),
-                None,
-            );
-
-            pos += sub_length + 4;
-            num += 1;
-            if length - pos == 16 {
-                if pos + 6 < data_segment.len() {
-                    let m_item = &data_segment[pos + 2..pos + 6];
-                    let m_data_item = FrameFun::get_data_str_reverser(m_item);
-                    let m_data_item_elem = ProtocolConfigManager::get_config_xml(&m_data_item, protocol, region, Some(dir));
-                    pw = Self::guest_is_exit_pw(
-                        length,
-                        pw_data,
-                        m_data_item_elem,
-                        None,
-                        false,
-                        protocol,
-                        region,
-                        Some(dir),
-                    );
-                    if pw {
-                        length -= 16;
-                    }
-                }
-            }
-        }
-
-        if pw {
-            let pw_str = "PW由16个字节组成，是由主站按系统约定的认证算法产生，并在主站发送的报文中下发给终端，由终端进行校验认证。".to_string();
-            FrameFun::add_data(
-                &mut sub_result,
-                "消息验证码Pw".to_string(),
-                FrameFun::get_data_str_with_space(pw_data),
-                pw_str,
-                pw_pos.to_vec(),
-                None,
-                None,
-            );
-        }
-        if tpv {
-            let tpv_str = Self::prase_tpv_data(tpv_data);
-            FrameFun::add_data(
-                &mut sub_result,
-                "时间标签Tp".to_string(),
-                FrameFun::get_data_str_with_space(tpv_data),
-                tpv_str,
-                vec![total_length - 7, total_length - 2],
-                None,
-                None,
-            );
-        }
-        FrameFun::add_data(
-            result_list,
-            "信息体".to_string(),
-            FrameFun::get_data_str_with_space(&frame[16..frame.len() - 2]),
-            "".to_string(),
-            vec![16, total_length - 2],
-            Some(sub_result),
-            None,
-        );
-        Ok(())
-    }
-
-    pub fn analysic_csg_link_frame(
-        frame: &[u8],
-        dir: u8,
-        prm: u8,
-        result_list: &mut Vec<Value>,
-        start_pos: usize,
-        protocol: &str,
-        region: &str,
-    ) -> Result<(), Box<dyn Error>> {
-        let total_length = frame.len();
-        let valid_data_segment = &frame[16..frame.len() - 2];
-        let tpv = Self::get_afn_and_seq_result(&frame[14..16], start_pos + 14, result_list);
-        let mut length = valid_data_segment.len();
-        let mut pos = 0;
-        let index = 16 + start_pos;
-        let mut num = 0;
-        let mut sub_result = Vec::new();
-        let mut tpv_data: &[u8] = &[];
-        let empty_data: &[u8] = &[];
-        let (pw_data, pw_pos) = if tpv {
-            length -= 5;
-            tpv_data = &frame[frame.len() - 7..frame.len() - 2];
-            if valid_data_segment.len() < 21 {
-                (empty_data, [0, 0])
-            } else {
-                let pw_data = &valid_data_segment
-                    [valid_data_segment.len() - 21..valid_data_segment.len() - 5];
-                (pw_data, [total_length - 23, total_length - 7])
-            }
-        } else {
-            if valid_data_segment.len() < 16 {
-                (empty_data, [0, 0])
-            } else {
-                let pw_data = &valid_data_segment[valid_data_segment.len() - 16..];
-                (pw_data, [total_length - 18, total_length - 2])
-            }
-        };
-
-        let data_segment = &valid_data_segment[..length];
-        let mut pw = false;
-        let mut sub_datament: &[u8];
-        while pos < length {
-            let da = &data_segment[pos..pos + 2];
-            let item = &data_segment[pos + 2..pos + 6];
-
-            let point_str = Self::prase_da_data([da[0], da[1]]);
-            let data_item = FrameFun::get_data_str_reverser(item);
-
-            FrameFun::add_data(
-                &mut sub_result,
-                format!("<第{}组>信息点标识DA", num + 1),
-                FrameFun::get_data_str_with_space(da),
-                point_str,
-                vec![index + pos, index + pos + 2],
-                None,
-                None,
-            );
-            pos += 2;
-
-            let data_item_elem =
-                ProtocolConfigManager::get_config_xml(&data_item, protocol, region, Some(dir));
-            let mut item_data = Vec::new();
-            let sub_length: usize;
-            let dis_data_identifier: String;
-
-            if let Some(mut data_item_elem) = data_item_elem {
-                (sub_length, sub_datament) = if dir == 1 && prm == 0 {
-                    (1, &data_segment[pos + 4..pos + 4 + 1])
-                } else {
-                    let sub_length_cont = data_item_elem.get_child_text("length");
-                    let mut sub_length = if let Some(sub_length_cont) = sub_length_cont {
-                        if sub_length_cont.to_uppercase() == "UNKNOWN" {
-                            Self::calculate_item_length(
-                                &mut data_item_elem,
-                                &data_segment[pos + 4..],
-                                protocol,
-                                region,
-                                Some(dir),
-                                None,
-                            )
-                        } else {
-                            sub_length_cont.parse::<usize>().unwrap()
-                        }
-                    } else {
-                        data_segment[pos + 4..].len()
-                    };
-                    info!(
-                        "sub_length: {:?} data_segment: {:?}",
-                        sub_length, data_segment
-                    );
-                    if sub_length > data_segment[pos + 4..].len() {
-                        sub_length = data_segment[pos + 4..].len();
-                    }
-                    let sub_datament = &data_segment[pos + 4..pos + 4 + sub_length];
-                    (sub_length, sub_datament)
-                };
-                data_item_elem.update_value("length", sub_length.to_string());
-                item_data = FrameAnalisyic::prase_data(
-                    &mut data_item_elem,
-                    protocol,
-                    region,
-                    sub_datament,
-                    index + pos + 4,
-                    Some(dir),
-                );
-
-                let name = data_item_elem.get_child_text("name").unwrap();
-                dis_data_identifier = format!("数据标识编码：[{}]-{}", data_item, name);
-            } else {
-                (sub_length, sub_datament) = if dir == 1 && prm == 0 {
-                    (1, &data_segment[pos + 4..pos + 4 + 1])
-                } else {
-                    let err_str =
-                        format!("未查找到数据标识：{},请检查配置文件！", data_item).to_string();
-                    let err = CustomError::new(1, err_str);
-                    break;
-                };
-                dis_data_identifier = format!("数据标识编码：[{}]", data_item);
-            };
-
-            let result_str = if dir == 1 && prm == 0 {
-                format!(
-                    "写参数返回结果：{}-{}",
-                    FrameFun::get_data_str_reverser(sub_datament),
-                    Self::prase_err_code_result(sub_datament[0])
-                )
-            } else {
-                format!(
-                    "数据标识[{}]数据内容：{}",
-                    data_item,
-                    FrameFun::get_data_str_reverser(sub_datament)
-                )
-            };
-            FrameFun::add_data(
-                &mut sub_result,
-                format!("<第{}组>数据标识编码DI", num + 1),
-                FrameFun::get_data_str_with_space(item),
-                dis_data_identifier,
-                vec![index + pos, index + pos + 4],
-                None,
-                None,
-            );
-            if dir == 1 && prm == 0 {
-                FrameFun::add_data(
-                    &mut sub_result,
-                    format!("<第{}组>ERR", num + 1),
-                    FrameFun::get_data_str_with_space(sub_datament),
-                    result_str,
-                    vec![index + pos + 4, index + pos + 4 + sub_length],
-                    Some(item_data),
-                    None,
-                );
-            } else {
-                if !item_data.is_empty() {
-                    FrameFun::add_data(
-                        &mut sub_result,
-                        format!("<第{}组>数据标识内容", num + 1),
-                        FrameFun::get_data_str_with_space(sub_datament),
-                        result_str,
-                        vec![index + pos + 4, index + pos + 4 + sub_length],
-                        Some(item_data),
-                        None,
-                    );
-                }
-            }
-
-            pos += sub_length + 4;
-            num += 1;
-            if length - pos == 16 {
-                if pos + 6 < data_segment.len() {
-                    let m_item = &data_segment[pos + 2..pos + 6];
-                    let m_data_item = FrameFun::get_data_str_reverser(m_item);
-                    let m_data_item_elem = ProtocolConfigManager::get_config_xml(&m_data_item, protocol, region, Some(dir));
-                    pw = Self::guest_is_exit_pw(
-                        length,
-                        pw_data,
-                        m_data_item_elem,
-                        None,
-                        false,
-                        protocol,
-                        region,
-                        Some(dir),
-                    );
-                    if pw {
-                        length -= 16;
-                    }
-                }
-            }
-        }
-
-        if pw {
-            let pw_str = "PW由16个字节组成，是由主站按系统约定的认证算法产生，并在主站发送的报文中下发给终端，由终端进行校验认证。".to_string();
-            FrameFun::add_data(
-                &mut sub_result,
-                "消息验证码Pw".to_string(),
-                FrameFun::get_data_str_with_space(pw_data),
-                pw_str,
-                pw_pos.to_vec(),
-                None,
-                None,
-            );
-        }
-        if tpv {
-            let tpv_str = Self::prase_tpv_data(tpv_data);
-            FrameFun::add_data(
-                &mut sub_result,
-                "时间标签Tp".to_string(),
-                FrameFun::get_data_str_with_space(tpv_data),
-                tpv_str,
-                vec![total_length - 7, total_length - 2],
-                None,
-                None,
-            );
-        }
-        FrameFun::add_data(
-            result_list,
-            "信息体".to_string(),
-            FrameFun::get_data_str_with_space(&frame[16..frame.len() - 2]),
-            "".to_string(),
-            vec![16, total_length - 2],
-            Some(sub_result),
-            None,
-        );
-
-        Ok(())
-    }
-
-    pub fn analysic_csg_write_frame(
-        frame: &[u8],
-        dir: u8,
-        prm: u8,
-        result_list: &mut Vec<Value>,
-        start_pos: usize,
-        protocol: &str,
-        region: &str,
-    ) -> Result<(), Box<dyn Error>> {
-        let total_length = frame.len();
-        let valid_data_segment = &frame[16..frame.len() - 2];
-        let tpv = Self::get_afn_and_seq_result(&frame[14..16], start_pos + 14, result_list);
-        let mut length = valid_data_segment.len();
-        let mut pos = 0;
-        let index = 16 + start_pos;
-        let mut num = 0;
-        let mut sub_result = Vec::new();
-        let mut tpv_data: &[u8] = &[];
-        let pw_data: &[u8] = &[];
-        let empty_data: &[u8] = &[];
-        info!("dir {} prm {}", dir, prm);
-        let (pw_data, pw_pos) = if tpv {
-            length -= 5;
-            tpv_data = &frame[frame.len() - 7..frame.len() - 2];
-            if valid_data_segment.len() < 21 {
-                (empty_data, [0, 0])
-            } else {
-                let pw_data = &valid_data_segment
-                    [valid_data_segment.len() - 21..valid_data_segment.len() - 5];
-                (pw_data, [total_length - 23, total_length - 7])
-            }
-        } else {
-            if valid_data_segment.len() < 16 {
-                (empty_data, [0, 0])
-            } else {
-                let pw_data = &valid_data_segment[valid_data_segment.len() - 16..];
-                (pw_data, [total_length - 18, total_length - 2])
-            }
-        };
-
-        let data_segment = &valid_data_segment[..length];
-        let mut pw = false;
-        info!("write_csg_frame:---------------");
-        while pos < length {
-            let da = &data_segment[pos..pos + 2];
-            let item = &data_segment[pos + 2..pos + 6];
-
-            let point_str = Self::prase_da_data([da[0], da[1]]);
-            let data_item = FrameFun::get_data_str_reverser(item);
-
-            FrameFun::add_data(
-                &mut sub_result,
-                format!("<第{}组>信息点标识DA", num + 1),
-                FrameFun::get_data_str_with_space(da),
-                point_str,
-                vec![index + pos, index + pos + 2],
-                None,
-                None,
-            );
-            pos += 2;
-
-            let data_item_elem =
-                ProtocolConfigManager::get_config_xml(&data_item, protocol, region, Some(dir));
-            let mut item_data = Vec::new();
-            let sub_length: usize;
-            let sub_datament: &[u8];
-            let dis_data_identifier: String;
-            if let Some(mut data_item_elem) = data_item_elem {
-                (sub_length, sub_datament) = if dir == 1 && prm == 0 {
-                    (1, &data_segment[pos + 4..pos + 4 + 1])
-                } else {
-                    let sub_length_cont = data_item_elem.get_child_text("length");
-                    let mut sub_length = if let Some(sub_length_cont) = sub_length_cont {
-                        if sub_length_cont.to_uppercase() == "UNKNOWN" {
-                            Self::calculate_item_length(
-                                &mut data_item_elem,
-                                &data_segment[pos + 4..],
-                                protocol,
-                                region,
-                                Some(dir),
-                                None,
-                            )
-                        } else {
-                            sub_length_cont.parse::<usize>().unwrap()
-                        }
-                    } else {
-                        data_segment[pos + 4..].len()
-                    };
-                    if sub_length > data_segment[pos + 4..].len() {
-                        sub_length = data_segment[pos + 4..].len();
-                    }
-                    let sub_datament = &data_segment[pos + 4..pos + 4 + sub_length];
-                    (sub_length, sub_datament)
-                };
-                data_item_elem.update_value("length", sub_length.to_string());
-                // info!("sub_datament: {:?}", data_item_elem);
-                if dir == 0 || prm == 1 {
-                    item_data = FrameAnalisyic::prase_data(
-                        &mut data_item_elem,
-                        protocol,
-                        region,
-                        sub_datament,
-                        index + pos + 4,
-                        Some(dir),
-                    );
-                }
-                let name = data_item_elem.get_child_text("name").unwrap();
-                dis_data_identifier = format!("数据标识编码：[{}]-{}", data_item, name);
-            } else {
-                (sub_length, sub_datament) = if dir == 1 && prm == 0 {
-                    (1, &data_segment[pos + 4..pos + 4 + 1])
-                } else {
-                    let err_str =
-                        format!("未查找到数据标识：{},请检查配置文件！", data_item).to_string();
-                    let err = CustomError::new(1, err_str);
-                    break;
-                };
-                dis_data_identifier = format!("数据标识编码：[{}]", data_item);
-            };
-
-            let result_str = if dir == 1 && prm == 0 {
-                format!(
-                    "写参数返回结果：{}-{}",
-                    FrameFun::get_data_str_reverser(sub_datament),
-                    Self::prase_err_code_result(sub_datament[0])
-                )
-            } else {
-                format!(
-                    "数据标识[{}]数据内容：{}",
-                    data_item,
-                    FrameFun::get_data_str_reverser(sub_datament)
-                )
-            };
-            FrameFun::add_data(
-                &mut sub_result,
-                format!("<第{}组>数据标识编码DI", num + 1),
-                FrameFun::get_data_str_with_space(item),
-                dis_data_identifier,
-                vec![index + pos, index + pos + 4],
-                None,
-                None,
-            );
-            if dir == 1 && prm == 0 {
-                FrameFun::add_data(
-                    &mut sub_result,
-                    format!("<第{}组>ERR", num + 1),
-                    FrameFun::get_data_str_with_space(sub_datament),
-                    result_str,
-                    vec![index + pos + 4, index + pos + 4 + sub_length],
-                    None,
-                    None,
-                );
-            } else {
-                if !item_data.is_empty() {
-                    FrameFun::add_data(
-                        &mut sub_result,
-                        format!("<第{}组>数据标识内容", num + 1),
-                        FrameFun::get_data_str_with_space(sub_datament),
-                        result_str,
-                        vec![index + pos + 4, index + pos + 4 + sub_length],
-                        Some(item_data),
-                        None,
-                    );
-                }
-            }
-
-            pos += sub_length + 4;
-            num += 1;
-            if length - pos == 16 {
-                if pos + 6 < data_segment.len() {
-                    let m_item = &data_segment[pos + 2..pos + 6];
-                    let m_data_item = FrameFun::get_data_str_reverser(m_item);
-                    let m_data_item_elem = ProtocolConfigManager::get_config_xml(&m_data_item, protocol, region, Some(dir));
-                    pw = Self::guest_is_exit_pw(
-                        length,
-                        pw_data,
-                        m_data_item_elem,
-                        None,
-                        false,
-                        protocol,
-                        region,
-                        Some(dir),
-                    );
-                    if pw {
-                        length -= 16;
-                    }
-                }
-            }
-        }
-
-        if pw {
-            let pw_str = "PW由16个字节组成，是由主站按系统约定的认证算法产生，并在主站发送的报文中下发给终端，由终端进行校验认证。".to_string();
-            FrameFun::add_data(
-                &mut sub_result,
-                "消息验证码Pw".to_string(),
-                FrameFun::get_data_str_with_space(pw_data),
-                pw_str,
-                pw_pos.to_vec(),
-                None,
-                None,
-            );
-        }
-        if tpv {
-            let tpv_str = Self::prase_tpv_data(tpv_data);
-            FrameFun::add_data(
-                &mut sub_result,
-                "时间标签Tp".to_string(),
-                FrameFun::get_data_str_with_space(tpv_data),
-                tpv_str,
-                vec![total_length - 7, total_length - 2],
-                None,
-                None,
-            );
-        }
-        FrameFun::add_data(
-            result_list,
-            "信息体".to_string(),
-            FrameFun::get_data_str_with_space(&frame[16..frame.len() - 2]),
-            "".to_string(),
-            vec![16, total_length - 2],
-            Some(sub_result),
-            None,
-        );
-
-        Ok(())
-    }
-
-    pub fn analysic_csg_security_frame(
-        frame: &[u8],
-        dir: u8,
-        prm: u8,
-        result_list: &mut Vec<Value>,
-        start_pos: usize,
-        protocol: &str,
-        region: &str,
-    ) -> Result<(), Box<dyn Error>> {
-        let valid_data_segment = &frame[16..frame.len() - 2];
-        let tpv = Self::get_afn_and_seq_result(&frame[14..16], start_pos + 14, result_list);
-        let mut length = valid_data_segment.len();
-        let mut pos = 0;
-        let index = 16 + start_pos;
-        let mut num = 0;
-        let mut sub_result = vec![];
-        let total_length = frame.len();
-        let empty_data: &[u8] = &[];
-
-        let (tpv_data, pw_data, pw_pos, length_ref) = if tpv {
-            (
-                &frame[frame.len() - 7..frame.len() - 2],
-                if valid_data_segment.len() > 21 {
-                    &valid_data_segment[valid_data_segment.len() - 21..valid_data_segment.len() - 5]
-                } else {
-                    empty_data
-                },
-                [total_length - 23, total_length - 7],
-                &mut (length - 5),
-            )
-        } else {
-            (
-                empty_data,
-                if valid_data_segment.len() > 16 {
-                    &valid_data_segment[valid_data_segment.len() - 16..]
-                } else {
-                    empty_data
-                },
-                [total_length - 18, total_length - 2],
-                &mut length,
-            )
-        };
-
-        // 取消引用 `&mut length_ref` 以便进行切片
-        length = *length_ref;
-        let mut pw = false;
-        let data_segment = &valid_data_segment[..length];
-        let mut sub_length: usize = 0;
-        let mut new_datament: &[u8] = &[];
-        while pos < length {
-            let result = (|| -> Result<(), Box<dyn Error>> {
-                let da = &data_segment[pos..pos + 2];
-                let item = &data_segment[pos + 2..pos + 6];
-                let point_str = Self::prase_da_data([da[0], da[1]]).clone();
-                let data_item = FrameFun::get_data_str_reverser(item);
-                FrameFun::add_data(
-                    &mut sub_result,
-                    format!("<第{}组>信息点标识DA", num + 1),
-                    FrameFun::get_data_str_with_space(da),
-                    point_str.clone(),
-                    vec![index + pos, index + pos + 2],
-                    None,
-                    None,
-                );
-                pos += 2;
-
-                let data_item_elem =
-                    ProtocolConfigManager::get_config_xml(&data_item, protocol, region, Some(dir));
-
-                let mut item_data: Vec<Value> = Vec::new();
-
-                if let Some(mut data_item_elem) = data_item_elem {
-                    if dir == 1 && prm == 0 {
-                        let sub_length_cont = data_item_elem.get_child_text("length");
-                        (sub_length, new_datament) = if let Some(sub_length_cont) = sub_length_cont
-                        {
-                            if sub_length_cont.to_uppercase() == "UNKNOWN" {
-                                let new_sub_length = Self::calculate_item_length(
-                                    &mut data_item_elem,
-                                    &data_segment[pos + 4..],
-                                    protocol,
-                                    region,
-                                    Some(dir),
-                                    None,
-                                );
-                                let sub_datament = &data_segment[pos + 4..pos + 4 + new_sub_length];
-                                (new_sub_length, sub_datament)
-                            } else {
-                                // 解析 sub_length_cont 为 usize
-                                let mut sub_length = sub_length_cont.parse::<usize>()?;
-                                if sub_length > data_segment.len() - pos - 4 {
-                                    sub_length = data_segment.len() - pos - 4;
-                                }
-                                let sub_datament = &data_segment[pos + 4..pos + 4 + sub_length];
-                                // 重新计算长度并获取新的数据段
-                                let (new_sub_length, new_datament) = Self::recalculate_sub_length(
-                                    &mut data_item_elem,
-                                    sub_datament,
-                                    protocol,
-                                    region,
-                                    Some(dir),
-                                );
-                                (new_sub_length, new_datament)
-                            }
-                        } else {
-                            // 如果 sub_length_cont 为 None，则返回默认的值
-                            let sub_datament = &data_segment[pos + 4..];
-                            (0 as usize, sub_datament)
-                        };
-                        data_item_elem.update_value("length", sub_length.to_string());
-                        item_data = FrameAnalisyic::prase_data(
-                            &mut data_item_elem,
-                            protocol,
-                            region,
-                            &new_datament,
-                            index + pos + 4,
-                            Some(dir),
-                        );
-                    } else {
-                        let sub_length = 0;
-                    };
-
-                    let name = data_item_elem.get_child_text("name").unwrap();
-                    let dis_data_identifier =
-                        format!("数据标识编码：[{}]-{}", data_item, name).to_string();
-
-                    FrameFun::add_data(
-                        &mut sub_result,
-                        format!("<第{}组>数据标识编码DI", num + 1),
-                        FrameFun::get_data_str_with_space(item),
-                        dis_data_identifier.clone(),
-                        vec![index + pos, index + pos + 4],
-                        None,
-                        None,
-                    );
-
-                    if dir == 1 && prm == 0 {
-                        let new_point_str = point_str.replace("Pn=", ""); // 使用新的变量保存结果
-                        let dis_data_identifier = format!("[{}]-{}", data_item, name).to_string();
-
-                        FrameFun::add_data(
-                            &mut sub_result,
-                            format!("<第{}组>数据内容", num + 1),
-                            FrameFun::get_data_str_with_space(new_datament),
-                            format!("{}-{}", new_point_str.clone(), dis_data_identifier.clone()),
-                            vec![index + pos + 4, index + pos + 4 + sub_length],
-                            Some(item_data),
-                            None,
-                        );
-                    }
-
-                    pos += sub_length + 4;
-                    num += 1;
-
-                    if length - pos == 16 {
-                        if pos + 6 < data_segment.len() {
-                            let m_item = &data_segment[pos + 2..pos + 6];
-                            let m_data_item = FrameFun::get_data_str_reverser(m_item);
-                            let m_data_item_elem = ProtocolConfigManager::get_config_xml(&m_data_item, protocol, region, Some(dir));
-                            pw = Self::guest_is_exit_pw(
-                                length,
-                                pw_data,
-                                m_data_item_elem,
-                                None,
-                                false,
-                                protocol,
-                                region,
-                                Some(dir),
-                            );
-                            if pw {
-                                length -= 16;
-                            }
-                        }
-                    }
-                } else {
-                    if dir == 1 && prm == 0 {
-                        if pos + 6 < data_segment.len() {
-                            let m_item = &data_segment[pos + 2..pos + 6];
-                            let m_data_item = FrameFun::get_data_str_reverser(m_item);
-                            let m_data_item_elem = ProtocolConfigManager::get_config_xml(&m_data_item, protocol, region, Some(dir));
-                            pw = Self::guest_is_exit_pw(
-                                length,
-                                pw_data,
-                                m_data_item_elem,
-                                None,
-                                false,
-                                protocol,
-                                region,
-                                Some(dir),
-                            );
-                        }
-                        let err_str =
-                            format!("未查找到数据标识：{},请检查配置文件！", data_item).to_string();
-                        let err = CustomError::new(1, err_str);
-                        return Err(Box::new(err));
-                    } else {
-                        let sub_length = 0;
-                    }
-
-                    let dis_data_identifier = format!("数据标识编码：[{}]", data_item);
-                    FrameFun::add_data(
-                        &mut sub_result,
-                        format!("<第{}组>数据标识编码DI", num + 1),
-                        FrameFun::get_data_str_with_space(item),
-                        dis_data_identifier,
-                        vec![index + pos, index + pos + 4],
-                        None,
-                        None,
-                    );
-                }
-                Ok(())
-            })();
-
-            match result {
-                Ok(_) => {}
-                Err(e) => {
-                    let err_str = format!("数据解析失败!").to_string();
-                    let err = CustomError::new(1, err_str);
-                    break;
-                }
-            }
-        }
-
-        if pw {
-            let pw_str = "PW由16个字节组成，是由主站按系统约定的认证算法产生，并在主站发送的报文中下发给终端，由终端进行校验认证。".to_string();
-            FrameFun::add_data(
-                &mut sub_result,
-                "消息验证码Pw".to_string(),
-                FrameFun::get_data_str_with_space(pw_data),
-                pw_str,
-                pw_pos.to_vec(),
-                None,
-                None,
-            );
-        }
-
-        if tpv {
-            let tpv_str = Self::prase_tpv_data(tpv_data);
-            FrameFun::add_data(
-                &mut sub_result,
-                "时间标签Tp".to_string(),
-                FrameFun::get_data_str_with_space(tpv_data),
-                tpv_str,
-                vec![total_length - 7, total_length - 2],
-                None,
-                None,
-            );
-        }
-
-        FrameFun::add_data(
-            result_list,
-            "信息体".to_string(),
-            FrameFun::get_data_str_with_space(&frame[16..total_length - 2]),
-            "".to_string(),
-            vec![16, total_length - 2],
-            Some(sub_result),
-            None,
-        );
-
-        Ok(())
-    }
-
-    pub fn analysic_csg_read_cur_frame(
-        frame: &[u8],
-        dir: u8,
-        prm: u8,
-        result_list: &mut Vec<Value>,
-        start_pos: usize,
-        protocol: &str,
-        region: &str,
-    ) -> Result<(), Box<dyn Error>> {
-        let valid_data_segment = &frame[16..frame.len() - 2];
-        let tpv = Self::get_afn_and_seq_result(&frame[14..16], start_pos + 14, result_list);
-        let length = valid_data_segment.len();
-        let mut pos = 0;
-        let index = 16 + start_pos;
-        let mut num = 0;
-        let mut sub_result = vec![];
-        let total_length = frame.len();
-        let empty_data: &[u8] = &[];
-        let (tpv_data, pw_data, pw_pos, cur_length) = if tpv {
-            (
-                &frame[frame.len() - 7..frame.len() - 2],
-                if valid_data_segment.len() > 21 {
-                    &valid_data_segment[valid_data_segment.len() - 21..valid_data_segment.len() - 5]
-                } else {
-                    empty_data
-                },
-                [total_length - 23, total_length - 7],
-                length - 5,
-            )
-        } else {
-            (
-                empty_data,
-                if valid_data_segment.len() > 16 {
-                    &valid_data_segment[valid_data_segment.len() - 16..]
-                } else {
-                    empty_data
-                },
-                [total_length - 18, total_length - 2],
-                length,
-            )
-        };
-
-        let mut pw = false;
-        let data_segment = &valid_data_segment[..cur_length];
-        let mut length = cur_length;
-        while pos < length {
-            let result = (|| -> Result<(), CustomError> {
-                let da: &[u8] = &data_segment[pos..pos + 2];
-                let item = &data_segment[pos + 2..pos + 6];
-                let point_str = Self::prase_da_data([da[0], da[1]]);
-                let data_item = FrameFun::get_data_str_reverser(item);
-                FrameFun::add_data(
-                    &mut sub_result,
-                    format!("<第{}组>信息点标识DA", num + 1),
-                    FrameFun::get_data_str_with_space(da),
-                    point_str.clone(),
-                    vec![index + pos, index + pos + 2],
-                    None,
-                    None,
-                );
-                pos += 2;
-
-                let data_item_elem =
-                    ProtocolConfigManager::get_config_xml(&data_item, protocol, region, Some(dir));
-
-                let mut item_data: Vec<Value> = Vec::new();
-                let mut sub_length = 0;
-                let mut sub_datament: &[u8] = &[];
-                if let Some(mut data_item_elem) = data_item_elem {
-                    if dir == 1 && prm == 0 {
-                        let sub_length_cont = data_item_elem.get_child_text("length").unwrap();
-                        (sub_length, sub_datament) = if sub_length_cont.to_uppercase() == "UNKNOWN"
-                        {
-                            let new_sub_length = Self::calculate_item_length(
-                                &mut data_item_elem,
-                                &data_segment[pos + 4..],
-                                protocol,
-                                region,
-                                Some(dir),
-                                None,
-                            );
-                            let new_sub_datament = &data_segment[pos + 4..pos + 4 + new_sub_length];
-                            info!(
-                                "new_sub_datament:{:?} sub_length:{:?}",
-                                new_sub_datament, new_sub_length
-                            );
-                            (new_sub_length, new_sub_datament)
-                        } else {
-                            let mut sub_length = sub_length_cont.parse::<usize>().unwrap();
-                            if sub_length > data_segment.len() - pos - 4 {
-                                sub_length = data_segment.len() - pos - 4;
-                            }
-                            let sub_datament = &data_segment[pos + 4..pos + 4 + sub_length];
-                            let (new_sub_length, new_datament) = Self::recalculate_sub_length(
-                                &mut data_item_elem,
-                                sub_datament,
-                                protocol,
-                                region,
-                                Some(dir),
-                            );
-                            (new_sub_length, new_datament)
-                        };
-
-                        item_data = FrameAnalisyic::prase_data(
-                            &mut data_item_elem,
-                            protocol,
-                            region,
-                            sub_datament,
-                            index + pos + 4,
-                            Some(dir),
-                        );
-                    } else {
-                        let sub_length = 0;
-                    }
-
-                    let name = data_item_elem.get_child_text("name").unwrap();
-                    let dis_data_identifier = format!("数据标识编码：[{}]-{}", data_item, name);
-
-                    FrameFun::add_data(
-                        &mut sub_result,
-                        format!("<第{}组>数据标识编码DI", num + 1),
-                        FrameFun::get_data_str_with_space(item),
-                        dis_data_identifier.clone(),
-                        vec![index + pos, index + pos + 4],
-                        None,
-                        None,
-                    );
-
-                    if dir == 1 && prm == 0 {
-                        FrameFun::add_data(
-                            &mut sub_result,
-                            format!("<第{}组>数据内容", num + 1),
-                            FrameFun::get_data_str_with_space(sub_datament),
-                            format!(
-                                "{}-{}",
-                                &point_str["Pn=".len()..],
-                                &dis_data_identifier["数据标识编码：".len()..]
-                            ),
-                            vec![index + pos + 4, index + pos + 4 + sub_length],
-                            Some(item_data),
-                            None,
-                        );
-                    }
-
-                    pos += sub_length + 4;
-                    num += 1;
-
-                    if length - pos == 16 {
-                        if pos + 6 < data_segment.len() {
-                            let m_item = &data_segment[pos + 2..pos + 6];
-                            let m_data_item = FrameFun::get_data_str_reverser(m_item);
-                            let m_data_item_elem = ProtocolConfigManager::get_config_xml(&m_data_item, protocol, region, Some(dir));
-                            pw = Self::guest_is_exit_pw(
-                                length,
-                                pw_data,
-                                m_data_item_elem,
-                                None,
-                                false,
-                                protocol,
-                                region,
-                                Some(dir),
-                            );
-                            if pw {
-                                length -= 16;
-                            }
-                        }
-                    }
-                } else {
-                    if dir == 1 && prm == 0 {
-                        if pos + 6 < data_segment.len() {
-                            let m_item = &data_segment[pos + 2..pos + 6];
-                            let m_data_item = FrameFun::get_data_str_reverser(m_item);
-                            let m_data_item_elem = ProtocolConfigManager::get_config_xml(&m_data_item, protocol, region, Some(dir));
-                            pw = Self::guest_is_exit_pw(
-                                length,
-                                pw_data,
-                                m_data_item_elem,
-                                None,
-                                false,
-                                protocol,
-                                region,
-                                Some(dir),
-                            );
-                        }
-                        let err_str = format!("数据解析失败!").to_string();
-                        let err = CustomError::new(1, err_str);
-                        return Err(err);
-                    } else {
-                        let sub_length = 0;
-                    }
-
-                    let dis_data_identifier = format!("数据标识编码：[{}]", data_item);
-                    FrameFun::add_data(
-                        &mut sub_result,
-                        format!("<第{}组>数据标识编码DI", num + 1),
-                        FrameFun::get_data_str_with_space(item),
-                        dis_data_identifier,
-                        vec![index + pos, index + pos + 4],
-                        None,
-                        None,
-                    );
-                }
-                Ok(())
-            })();
-
-            match result {
-                Ok(_) => {}
-                Err(e) => {
-                    let err_str = format!("数据解析失败!").to_string();
-                    let err = CustomError::new(1, err_str);
-                    break;
-                }
-            }
-        }
-
-        if pw {
-            let pw_str = "PW由16个字节组成，是由主站按系统约定的认证算法产生，并在主站发送的报文中下发给终端，由终端进行校验认证。".to_string();
-            FrameFun::add_data(
-                &mut sub_result,
-                "消息验证码Pw".to_string(),
-                FrameFun::get_data_str_with_space(pw_data),
-                pw_str,
-                pw_pos.to_vec(),
-                None,
-                None,
-            );
-        }
-
-        if tpv {
-            let tpv_str = Self::prase_tpv_data(tpv_data);
-            FrameFun::add_data(
-                &mut sub_result,
-                "时间标签Tp".to_string(),
-                FrameFun::get_data_str_with_space(tpv_data),
-                tpv_str,
-                vec![total_length - 7, total_length - 2],
-                None,
-                None,
-            );
-        }
-
-        FrameFun::add_data(
-            result_list,
-            "信息体".to_string(),
-            FrameFun::get_data_str_with_space(&frame[16..frame.len() - 2]),
-            "".to_string(),
-            vec![16, total_length - 2],
-            Some(sub_result),
-            None,
-        );
-
-        Ok(())
-    }
-
-    pub fn analysic_csg_read_history_frame(
-        frame: &[u8],
-        dir: u8,
-        prm: u8,
-        result_list: &mut Vec<Value>,
-        start_pos: usize,
-        protocol: &str,
-        region: &str,
-    ) -> Result<(), Box<dyn Error>> {
-        let valid_data_segment = &frame[16..frame.len() - 2];
-        let tpv = Self::get_afn_and_seq_result(&frame[14..16], start_pos + 14, result_list);
-        let mut length = valid_data_segment.len();
-        let mut pos = 0;
-        let index = 16 + start_pos;
-        let mut num = 0;
-        let mut sub_result = vec![];
-        let total_length = frame.len();
-        let empty_data: &[u8] = &[];
-
-        let (tpv_data, pw_data, pw_pos, cur_length) = if tpv {
-            (
-                &frame[frame.len() - 7..frame.len() - 2],
-                if valid_data_segment.len() > 21 {
-                    &valid_data_segment[valid_data_segment.len() - 21..valid_data_segment.len() - 5]
-                } else {
-                    empty_data
-                },
-                [total_length - 23, total_length - 7],
-                length - 5,
-            )
-        } else {
-            (
-                empty_data,
-                if valid_data_segment.len() > 16 {
-                    &valid_data_segment[valid_data_segment.len() - 16..]
-                } else {
-                    empty_data
-                },
-                [total_length - 18, total_length - 2],
-                length,
-            )
-        };
-
-        length = cur_length;
-        let mut pw = false;
-        let data_segment = &valid_data_segment[..length];
-        let mut data_item_elem: Option<XmlElement> = None;
-        let sub_length: usize;
-        let mut last_data_time: Option<&[u8]> = None;
-        let sub_pos: usize;
-        let mut point_str: String = String::new();
-        let mut dis_data_identifier: String = String::new();
-        while pos < length {
-            let result = (|| -> Result<(), CustomError> {
-                if !Self::guest_next_data_is_cur_item_data(
-                    data_item_elem.clone(),
-                    &data_segment[pos..],
-                    last_data_time.as_deref(),
-                    protocol,
-                    region,
-                    Some(dir),
-                ) {
-                    let da = &data_segment[pos..pos + 2];
-                    let item = &data_segment[pos + 2..pos + 6];
-                    point_str = Self::prase_da_data([da[0], da[1]]);
-                    let (data_item_elem_opt, data_item) =
-                        Self::try_get_item_and_point(item, protocol, region, Some(dir));
-                    data_item_elem = data_item_elem_opt.clone();
-
-                    dis_data_identifier = if let Some(data_item_elem) = data_item_elem.clone() {
-                        let name = data_item_elem.get_child_text("name").unwrap();
-                        format!("数据标识编码：[{}]-{}", data_item, name)
-                    } else {
-                        format!("数据标识编码：[{}]", data_item)
-                    };
-
-                    FrameFun::add_data(
-                        &mut sub_result,
-                        format!("<第{}组>信息点标识DA", num + 1),
-                        FrameFun::get_data_str_with_space(da),
-                        point_str.clone(),
-                        vec![index + pos, index + pos + 2],
-                        None,
-                        None,
-                    );
-                    pos += 2;
-                    FrameFun::add_data(
-                        &mut sub_result,
-                        format!("<第{}组>数据标识编码DI", num + 1),
-                        FrameFun::get_data_str_with_space(item),
-                        dis_data_identifier.clone(),
-                        vec![index + pos, index + pos + 4],
-                        None,
-                        None,
-                    );
-                    pos += 4;
-                    if dir == 1 && prm == 0 && region == "海南" {
-                        let data_count = data_segment[pos];
-                        let identifier = format!("数据时间个数: {:02o}", data_count);
-                        FrameFun::add_data(
-                            &mut sub_result,
-                            format!("<第{}组>数据时间个数", num + 1),
-                            FrameFun::get_data_str_with_space(&data_segment[pos..pos + 1]),
-                            identifier,
-                            vec![index + pos, index + pos + 1],
-                            None,
-                            None,
-                        );
-                        pos += 1;
-                    }
-                }
-
-                let mut item_data: Vec<Value> = Vec::new();
-                let mut sub_length = 0;
-                let mut sub_datament: &[u8] = &[];
-
-                if let Some(mut item_elem) = data_item_elem.clone() {
-                    if dir == 1 && prm == 0 {
-                        let sub_length_cont = item_elem.get_child_text("length").unwrap();
-                        (sub_length, sub_datament) = if sub_length_cont.to_uppercase() == "UNKNOWN"
-                        {
-                            let sub_length = Self::calculate_item_length(
-                                &mut item_elem,
-                                &data_segment[pos..],
-                                protocol,
-                                region,
-                                Some(dir),
-                                None,
-                            );
-                            let sub_datament = &data_segment[pos..pos + sub_length];
-                            (sub_length, sub_datament)
-                        } else {
-                            let mut sub_length = sub_length_cont.parse::<usize>().unwrap();
-                            if sub_length > data_segment.len() - pos {
-                                sub_length = data_segment.len() - pos;
-                            }
-                            let sub_datament = &data_segment[pos..pos + sub_length];
-                            let (new_sub_length, new_datament) = Self::recalculate_sub_length(
-                                &mut item_elem,
-                                sub_datament,
-                                protocol,
-                                region,
-                                Some(dir),
-                            );
-                            (new_sub_length, new_datament)
-                        };
-                        item_elem.update_value("length", sub_length.to_string());
-                        item_data = FrameAnalisyic::prase_data(
-                            &mut item_elem,
-                            protocol,
-                            region,
-                            sub_datament,
-                            index + pos,
-                            Some(dir),
-                        );
-                    } else {
-                        sub_length = 0;
-                    }
-                } else {
-                    if dir == 1 && prm == 0 {
-                        let sub_length = length;
-                        pw = Self::guest_is_exit_pw(
-                            length,
-                            &pw_data,
-                            data_item_elem.clone(),
-                            last_data_time,
-                            true,
-                            protocol,
-                            region,
-                            Some(dir),
-                        );
-                        let err_str = format!("数据解析失败!").to_string();
-                        let err = CustomError::new(1, err_str);
-                        return Err(err);
-                    } else {
-                        let sub_length = 0;
-                    }
-                }
-
-                if dir == 1 {
-                    let new_point_str = point_str.clone().replace("Pn=", "");
-                    let new_dis_str: String =
-                        dis_data_identifier.clone().replace("数据标识编码：", "");
-                    FrameFun::add_data(
-                        &mut sub_result,
-                        format!("<第{}组>数据内容", num + 1),
-                        FrameFun::get_data_str_with_space(sub_datament),
-                        format!("{}-{}", new_point_str, new_dis_str),
-                        vec![index + pos, index + pos + sub_length + 6],
-                        Some(item_data),
-                        None,
-                    );
-                    let data_time = &data_segment[pos + sub_length..pos + sub_length + 6];
-                    last_data_time = Some(data_time);
-                    let time_str = FrameFun::parse_time_data(data_time, "CCYYMMDDhhmm", false);
-                    FrameFun::add_data(
-                        &mut sub_result,
-                        format!("<第{}组>数据时间", num + 1),
-                        FrameFun::get_data_str_with_space(data_time),
-                        format!("数据时间：{}", time_str),
-                        vec![index + pos + sub_length, index + pos + sub_length + 6],
-                        None,
-                        None,
-                    );
-                    pos += 6;
-                } else {
-                    let start_time = &data_segment[pos..pos + 6];
-                    let end_time = &data_segment[pos + 6..pos + 12];
-                    let data_dinsty = data_segment[pos + 12];
-                    let start_time_str =
-                        FrameFun::parse_time_data(start_time, "CCYYMMDDhhmm", false);
-                    let end_time_str = FrameFun::parse_time_data(end_time, "CCYYMMDDhhmm", false);
-                    let data_dinsty_str = Self::get_data_dinsty(data_dinsty);
-                    FrameFun::add_data(
-                        &mut sub_result,
-                        format!("<第{}组>数据起始时间", num + 1),
-                        FrameFun::get_data_str_with_space(start_time),
-                        start_time_str,
-                        vec![index + pos, index + pos + 6],
-                        None,
-                        None,
-                    );
-                    FrameFun::add_data(
-                        &mut sub_result,
-                        format!("<第{}组>数据结束时间", num + 1),
-                        FrameFun::get_data_str_with_space(end_time),
-                        end_time_str,
-                        vec![index + pos + 6, index + pos + 12],
-                        None,
-                        None,
-                    );
-                    FrameFun::add_data(
-                        &mut sub_result,
-                        format!("<第{}组>数据密度", num + 1),
-                        format!("{:02X}", data_dinsty),
-                        format!("数据间隔时间：{}", data_dinsty_str),
-                        vec![index + pos + 12, index + pos + 13],
-                        None,
-                        None,
-                    );
-                    pos += 13;
-                }
-
-                pos += sub_length;
-                num += 1;
-
-                if length - pos == 16 {
-                    let left_data = &data_segment[pos..pos + 16];
-                    if left_data == pw_data {
-                        pw = Self::guest_is_exit_pw(
-                            length,
-                            pw_data,
-                            data_item_elem.clone(),
-                            last_data_time,
-                            true,
-                            protocol,
-                            region,
-                            Some(dir),
-                        );
-                    }
-                    if pw {
-                        length -= 16;
-                    }
-                }
-                Ok(())
-            })();
-            match result {
-                Ok(_) => {}
-                Err(e) => {
-                    let err_str = format!("数据解析失败!").to_string();
-                    let err = CustomError::new(1, err_str);
-                    break;
-                }
-            }
-        }
-        if pw {
-            let pw_str = "PW由16个字节组成，是由主站按系统约定的认证算法产生，并在主站发送的报文中下发给终端，由终端进行校验认证。".to_string();
-            FrameFun::add_data(
-                &mut sub_result,
-                "消息验证码Pw".to_string(),
-                FrameFun::get_data_str_with_space(pw_data),
-                pw_str,
-                pw_pos.to_vec(),
-                None,
-                None,
-            );
-        }
-
-        if tpv {
-            let tpv_str = Self::prase_tpv_data(tpv_data);
-            FrameFun::add_data(
-                &mut sub_result,
-                "时间标签Tp".to_string(),
-                FrameFun::get_data_str_with_space(tpv_data),
-                tpv_str,
-                vec![total_length - 7, total_length - 2],
-                None,
-                None,
-            );
-        }
-
-        FrameFun::add_data(
-            result_list,
-            "信息体".to_string(),
-            FrameFun::get_data_str_with_space(&frame[16..frame.len() - 2]),
-            "".to_string(),
-            vec![16, total_length - 2],
-            Some(sub_result),
-            None,
-        );
-
-        Ok(())
-    }
-
-    pub fn analysic_csg_read_param_frame(
-        frame: &[u8],
-        dir: u8,
-        prm: u8,
-        result_list: &mut Vec<Value>,
-        start_pos: usize,
-        protocol: &str,
-        region: &str,
-    ) -> Result<(), Box<dyn Error>> {
-        let valid_data_segment = &frame[16..frame.len() - 2];
-        let tpv = Self::get_afn_and_seq_result(&frame[14..16], start_pos + 14, result_list);
-        let mut length = valid_data_segment.len();
-        let mut pos = 0;
-        let index = 16 + start_pos;
-        let mut num = 0;
-        let mut sub_result = vec![];
-        let total_length = frame.len();
-        let empty_data: &[u8] = &[];
-        let (tpv_data, pw_data, pw_pos, cur_length) = if tpv {
-            (
-                &frame[frame.len() - 7..frame.len() - 2],
-                if valid_data_segment.len() > 21 {
-                    &valid_data_segment[valid_data_segment.len() - 21..valid_data_segment.len() - 5]
-                } else {
-                    empty_data
-                },
-                [total_length - 23, total_length - 7],
-                length - 5,
-            )
-        } else {
-            (
-                empty_data,
-                if valid_data_segment.len() > 16 {
-                    &valid_data_segment[valid_data_segment.len() - 16..]
-                } else {
-                    empty_data
-                },
-                [total_length - 18, total_length - 2],
-                length,
-            )
-        };
-        length = cur_length;
-        let mut pw = false;
-        let data_segment = &valid_data_segment[..length];
-
-        while pos < length {
-            let result = (|| -> Result<(), CustomError> {
-                let da = &data_segment[pos..pos + 2];
-                let item = &data_segment[pos + 2..pos + 6];
-                let point_str = Self::prase_da_data([da[0], da[1]]);
-                let data_item = FrameFun::get_data_str_reverser(item);
-                FrameFun::add_data(
-                    &mut sub_result,
-                    format!("<第{}组>信息点标识DA", num + 1),
-                    FrameFun::get_data_str_with_space(da),
-                    point_str.clone(),
-                    vec![index + pos, index + pos + 2],
-                    None,
-                    None,
-                );
-                pos += 2;
-
-                let data_item_elem =
-                    ProtocolConfigManager::get_config_xml(&data_item, protocol, region, Some(dir));
-                let mut item_data: Vec<Value> = Vec::new();
-                let sub_length: usize;
-                let mut sub_datament: &[u8] = &[];
-
-                if let Some(mut data_item_elem) = data_item_elem {
-                    if dir == 1 && prm == 0 {
-                        let sub_length_cont = data_item_elem.get_child_text("length").unwrap();
-                        (sub_length, sub_datament) = if sub_length_cont.to_uppercase() == "UNKNOWN"
-                        {
-                            let sub_length = Self::calculate_item_length(
-                                &mut data_item_elem,
-                                &data_segment[pos + 4..],
-                                protocol,
-                                region,
-                                Some(dir),
-                                None,
-                            );
-                            let sub_datament = &data_segment[pos + 4..pos + 4 + sub_length];
-                            (sub_length, sub_datament)
-                        } else {
-                            let mut sub_length = sub_length_cont.parse::<usize>().unwrap();
-                            if sub_length > data_segment.len() - pos - 4 {
-                                sub_length = data_segment.len() - pos - 4;
-                            }
-                            let sub_datament = &data_segment[pos + 4..pos + 4 + sub_length];
-                            let (new_sub_length, new_datament) = Self::recalculate_sub_length(
-                                &mut data_item_elem,
-                                sub_datament,
-                                protocol,
-                                region,
-                                Some(dir),
-                            );
-                            (new_sub_length, new_datament)
-                        };
-                        data_item_elem.update_value("length", sub_length.to_string());
-                        info!("read_param:{:?}", sub_datament);
-                        item_data = FrameAnalisyic::prase_data(
-                            &mut data_item_elem,
-                            protocol,
-                            region,
-                            sub_datament,
-                            index + pos + 4,
-                            Some(dir),
-                        );
-                    } else {
-                        sub_length = 0;
-                    }
-
-                    let name = data_item_elem.get_child_text("name").unwrap();
-                    let dis_data_identifier = format!("数据标识编码：[{}]-{}", data_item, name);
-
-                    FrameFun::add_data(
-                        &mut sub_result,
-                        format!("<第{}组>数据标识编码DI", num + 1),
-                        FrameFun::get_data_str_with_space(item),
-                        dis_data_identifier.clone(),
-                        vec![index + pos, index + pos + 4],
-                        None,
-                        None,
-                    );
-
-                    if dir == 1 && prm == 0 {
-                        let new_point_str = point_str.clone().replace("Pn=", "");
-                        let new_dis_str: String =
-                            dis_data_identifier.clone().replace("数据标识编码：", "");
-                        FrameFun::add_data(
-                            &mut sub_result,
-                            format!("<第{}组>数据内容", num + 1),
-                            FrameFun::get_data_str_with_space(sub_datament),
-                            format!("{}-{}", new_point_str, new_dis_str),
-                            vec![index + pos + 4, index + pos + 4 + sub_length],
-                            Some(item_data),
-                            None,
-                        );
-                    }
-
-                    pos += sub_length + 4;
-                    num += 1;
-
-                    if length - pos == 16 {
-                        let m_item = &data_segment[pos + 2..pos + 6];
-                        let m_data_item = FrameFun::get_data_str_reverser(m_item);
-                        let m_data_item_elem = ProtocolConfigManager::get_config_xml(&m_data_item, protocol, region, Some(dir));
-                        pw = Self::guest_is_exit_pw(
-                            length,
-                            pw_data,
-                            m_data_item_elem,
-                            None,
-                            false,
-                            protocol,
-                            region,
-                            Some(dir),
-                        );
-                        if pw {
-                            length -= 16;
-                        }
-                    }
-                } else {
-                    if dir == 1 && prm == 0 {
-                        pw = Self::guest_is_exit_pw(
-                            length,
-                            &pw_data,
-                            None,
-                            None,
-                            false,
-                            protocol,
-                            region,
-                            Some(dir),
-                        );
-                        let err_str = format!("数据解析失败!").to_string();
-                        let err = CustomError::new(1, err_str);
-                        return Err(err);
-                    } else {
-                        sub_length = 0;
-                    }
-
-                    let dis_data_identifier = format!("数据标识编码：[{}]", data_item);
-                    FrameFun::add_data(
-                        &mut sub_result,
-                        format!("<第{}组>数据标识编码DI", num + 1),
-                        FrameFun::get_data_str_with_space(item),
-                        dis_data_identifier,
-                        vec![index + pos, index + pos + 4],
-                        None,
-                        None,
-                    );
-                    pos += sub_length + 4;
-                    num += 1;
-
-                    if length - pos == 16 {
-                        let m_item = &data_segment[pos + 2..pos + 6];
-                        let m_data_item = FrameFun::get_data_str_reverser(m_item);
-                        let m_data_item_elem = ProtocolConfigManager::get_config_xml(&m_data_item, protocol, region, Some(dir));
-                        pw = Self::guest_is_exit_pw(
-                            length,
-                            pw_data,
-                            m_data_item_elem,
-                            None,
-                            false,
-                            protocol,
-                            region,
-                            Some(dir),
-                        );
-                        if pw {
-                            length -= 16;
-                        }
-                    }
-                }
-                Ok(())
-            })();
-            match result {
-                Ok(_) => {}
-                Err(e) => {
-                    let err_str = format!("数据解析失败!").to_string();
-                    let err = CustomError::new(1, err_str);
-                    break;
-                }
-            }
-        }
-
-        if pw {
-            let pw_str = "PW由16个字节组成，是由主站按系统约定的认证算法产生，并在主站发送的报文中下发给终端，由终端进行校验认证。".to_string();
-            FrameFun::add_data(
-                &mut sub_result,
-                "消息验证码Pw".to_string(),
-                FrameFun::get_data_str_with_space(pw_data),
-                pw_str,
-                pw_pos.to_vec(),
-                None,
-                None,
-            );
-        }
-
-        if tpv {
-            let tpv_str = Self::prase_tpv_data(tpv_data);
-            FrameFun::add_data(
-                &mut sub_result,
-                "时间标签Tp".to_string(),
-                FrameFun::get_data_str_with_space(tpv_data),
-                tpv_str,
-                vec![total_length - 7, total_length - 2],
-                None,
-                None,
-            );
-        }
-
-        FrameFun::add_data(
-            result_list,
-            "信息体".to_string(),
-            FrameFun::get_data_str_with_space(&frame[16..frame.len() - 2]),
-            "".to_string(),
-            vec![16, total_length - 2],
-            Some(sub_result),
-            None,
-        );
-
-        Ok(())
-    }
-
-    pub fn analysic_csg_read_task_frame(
-        frame: &[u8],
-        dir: u8,
-        prm: u8,
-        result_list: &mut Vec<Value>,
-        start_pos: usize,
-        protocol: &str,
-        region: &str,
-    ) -> Result<(), Box<dyn Error>> {
-        let valid_data_segment = &frame[16..frame.len() - 2];
-        let tpv = Self::get_afn_and_seq_result(&frame[14..16], start_pos + 14, result_list);
-        let mut length = valid_data_segment.len();
-        let mut pos = 0;
-        let mut num = 0;
-        let mut sub_result = vec![];
-        let mut task_result = vec![];
-        let total_length = frame.len();
-        let empty_data: &[u8] = &[];
-
-        let (tpv_data, pw_data, pw_pos, cur_length) = if tpv {
-            (
-                &frame[frame.len() - 7..frame.len() - 2],
-                if valid_data_segment.len() > 21 {
-                    &valid_data_segment[valid_data_segment.len() - 21..valid_data_segment.len() - 5]
-                } else {
-                    &empty_data
-                },
-                [start_pos + total_length - 23, start_pos + total_length - 7],
-                length - 5,
-            )
-        } else {
-            (
-                &Vec::<u8>::new()[..],
-                if valid_data_segment.len() > 16 {
-                    &valid_data_segment[valid_data_segment.len() - 16..]
-                } else {
-                    &Vec::<u8>::new()[..]
-                },
-                [start_pos + total_length - 18, start_pos + total_length - 2],
-                length,
-            )
-        };
-        length = cur_length;
-        let mut data_item: String = String::new();
-        let mut data_segment = valid_data_segment;
-        let mut pncount = 0;
-        let mut item_count = 0;
-
-        let mut task_name = String::new();
-        let mut index = 16 + 9 + start_pos;
-
-        if dir == 1 {
-            let da = &frame[16..18];
-            let item = &frame[18..22];
-            let point_str = Self::prase_da_data([da[0], da[1]]);
-            let (data_item_elem, cur_data_item) =
-                Self::try_get_item_and_point(item, protocol, region, Some(dir));
-
-            task_name = if let Some(data_item_elem) = data_item_elem {
-                let name = data_item_elem.get_child_text("name").unwrap();
-                format!("{}号： {}", name, cur_data_item)
-            } else {
-                format!("任务号：{}", cur_data_item)
-            };
-
-            FrameFun::add_data(
-                &mut task_result,
-                "信息点标识DA".to_string(),
-                FrameFun::get_data_str_with_space(da),
-                point_str.clone(),
-                vec![start_pos + 16, start_pos + 18],
-                None,
-                None,
-            );
-            FrameFun::add_data(
-                &mut task_result,
-                "数据标识编码DI".to_string(),
-                FrameFun::get_data_str_with_space(item),
-                task_name.clone(),
-                vec![start_pos + 18, start_pos + 22],
-                None,
-                None,
-            );
-
-            let task_kind = frame[22];
-            let task_kind_str = match task_kind {
-                0 => "自描述方式",
-                1 => "任务模板",
-                2 => "补上报数据",
-                _ => "未知",
-            };
-            FrameFun::add_data(
-                &mut sub_result,
-                "数据结构方式".to_string(),
-                FrameFun::get_data_str_with_space(&frame[22..23]),
-                task_kind_str.to_string(),
-                vec![start_pos + 22, start_pos + 23],
-                None,
-                None,
-            );
-
-            let expire_item = &frame[27..31];
-            info!("expire_item:{:?}", expire_item);
-            let expire_data_item = FrameFun::get_data_str_reverser(expire_item);
-            let data_item_elem = ProtocolConfigManager::get_config_xml(
-                &expire_data_item,
-                protocol,
-                region,
-                Some(dir),
-            );
-            info!("data_item_elem:{:?}", data_item_elem);
-            if let Some(data_item_elem) = data_item_elem {
-                pncount = frame[23];
-                item_count = frame[24];
-                FrameFun::add_data(
-                    &mut sub_result,
-                    "数据组数".to_string(),
-                    FrameFun::get_data_str_with_space(&frame[23..25]),
-                    format!(
-                        "信息点标识数{},数据标识编码数{},共有{}个数据组数",
-                        pncount,
-                        item_count,
-                        pncount * item_count
-                    ),
-                    vec![start_pos + 23, start_pos + 25],
-                    None,
-                    None,
-                );
-
-                data_segment = &valid_data_segment[9..];
-                length -= 9;
-                data_item = cur_data_item;
-                index = 16 + 9 + start_pos;
-            } else {
-                pncount = frame[23];
-                item_count = 1;
-                FrameFun::add_data(
-                    &mut sub_result,
-                    "数据组数".to_string(),
-                    FrameFun::get_data_str_with_space(&frame[23..24]),
-                    format!("共有{}个数据组数", pncount),
-                    vec![23, 24],
-                    None,
-                    None,
-                );
-
-                data_segment = &valid_data_segment[8..];
-                length -= 8;
-                data_item = cur_data_item;
-                index = 16 + 8 + start_pos;
-            }
-        }
-
-        let mut pw = false;
-        let data_segment = &data_segment[..length];
-        let mut data_item_elem: Option<XmlElement> = None;
-        let data_time: Option<&[u8]> = None;
-        let mut point_str: String = String::new();
-        let mut dis_data_identifier: String = String::new();
-
-        while pos < length {
-            let result = (|| -> Result<AnalysicErr, Box<dyn Error>> {
-                if !Self::guest_next_data_is_cur_item_data(
-                    data_item_elem.clone(),
-                    &data_segment[pos..],
-                    data_time,
-                    protocol,
-                    region,
-                    Some(dir),
-                ) {
-                    let da = &data_segment[pos..pos + 2];
-                    let item = &data_segment[pos + 2..pos + 6];
-                    point_str = Self::prase_da_data([da[0], da[1]]);
-                    let (data_item_elem_opt, cur_data_item) =
-                        Self::try_get_item_and_point(item, protocol, region, Some(dir));
-
-                    // info!(
-                    //     "data_item:{:?} {:?} {:?}",
-                    //     data_item_elem_opt, data_segment, item
-                    // );
-                    data_item_elem = data_item_elem_opt.clone();
-                    dis_data_identifier = if data_item_elem_opt.is_some() {
-                        let name = data_item_elem_opt.unwrap().get_child_text("name").unwrap();
-                        format!("数据标识编码：[{}]-{}", cur_data_item, name)
-                    } else {
-                        format!("数据标识编码：[{}]", cur_data_item)
-                    };
-                    data_item = cur_data_item;
-                    FrameFun::add_data(
-                        &mut sub_result,
-                        format!("<第{}组>信息点标识DA", num + 1),
-                        FrameFun::get_data_str_with_space(da),
-                        point_str.clone(),
-                        vec![index + pos, index + pos + 2],
-                        None,
-                        None,
-                    );
-                    pos += 2;
-                    FrameFun::add_data(
-                        &mut sub_result,
-                        format!("<第{}组>数据标识编码DI", num + 1),
-                        FrameFun::get_data_str_with_space(item),
-                        dis_data_identifier.clone(),
-                        vec![index + pos, index + pos + 4],
-                        None,
-                        None,
-                    );
-                    pos += 4;
-                }
-
-                let mut item_data: Vec<Value> = Vec::new();
-                let mut sub_length = 0;
-                let mut sub_datament: &[u8] = &[];
-                // info!("dir {:} item{:?}", dir, data_item_elem);
-                if let Some(mut item_elem) = data_item_elem.clone() {
-                    if dir == 1 {
-                        let sub_length_cont = item_elem.get_child_text("length").unwrap();
-                        (sub_length, sub_datament) = if sub_length_cont.to_uppercase() == "UNKNOWN"
-                        {
-                            let sub_length = Self::calculate_item_length(
-                                &mut item_elem,
-                                &data_segment[pos..],
-                                protocol,
-                                region,
-                                Some(dir),
-                                None,
-                            );
-                            let sub_datament = &data_segment[pos..pos + sub_length];
-                            (sub_length, sub_datament)
-                        } else {
-                            let mut sub_length = sub_length_cont.parse::<usize>().unwrap();
-                            // info!("sub_length {:?} pos{:?} data_len {:?}", sub_length, pos, data_segment.len());
-                            if sub_length > data_segment[pos..].len() {
-                                sub_length = data_segment[pos..].len();
-                            }
-                            let sub_datament = &data_segment[pos..pos + sub_length];
-                            let (new_sub_length, new_datament) = Self::recalculate_sub_length(
-                                &mut item_elem,
-                                sub_datament,
-                                protocol,
-                                region,
-                                Some(dir),
-                            );
-                            (new_sub_length, new_datament)
-                        };
-                        item_elem.update_value("length", sub_length.to_string());
-                        // info!("length {:?} data:{:?} item_elem:{:?}", sub_length, sub_datament, item_elem);
-                        item_data = FrameAnalisyic::prase_data(
-                            &mut item_elem,
-                            protocol,
-                            region,
-                            sub_datament,
-                            index + pos,
-                            Some(dir),
-                        );
-                    } else {
-                        sub_length = 0;
-                    }
-                }
-
-                if dir == 1 {
-                    let new_point_str = point_str.clone().replace("Pn=", "");
-                    let new_dis_str: String =
-                        dis_data_identifier.clone().replace("数据标识编码：", "");
-                    FrameFun::add_data(
-                        &mut sub_result,
-                        format!("<第{}组>数据内容", num + 1),
-                        FrameFun::get_data_str_with_space(sub_datament),
-                        format!("{}-{}", new_point_str, new_dis_str),
-                        vec![index + pos, index + pos + sub_length],
-                        Some(item_data),
-                        None,
-                    );
-                    let data_time = &data_segment[pos + sub_length..pos + sub_length + 5];
-                    let time_str = FrameFun::parse_time_data(data_time, "YYMMDDhhmm", false);
-                    FrameFun::add_data(
-                        &mut sub_result,
-                        format!("<第{}组>数据时间", num + 1),
-                        FrameFun::get_data_str_with_space(data_time),
-                        format!("数据时间：{}", time_str),
-                        vec![index + pos + sub_length, index + pos + sub_length + 5],
-                        None,
-                        None,
-                    );
-                    pos += 5;
-                } else {
-                    let start_time = &data_segment[pos..pos + 6];
-                    let end_time = &data_segment[pos + 6..pos + 12];
-                    let data_dinsty = data_segment[pos + 12];
-                    let start_time_str =
-                        FrameFun::parse_time_data(start_time, "CCYYMMDDhhmm", false);
-                    let end_time_str = FrameFun::parse_time_data(end_time, "CCYYMMDDhhmm", false);
-                    let data_dinsty_str = Self::get_data_dinsty(data_dinsty);
-                    FrameFun::add_data(
-                        &mut task_result,
-                        format!("数据起始时间"),
-                        FrameFun::get_data_str_with_space(start_time),
-                        start_time_str,
-                        vec![index + pos, index + pos + 6],
-                        None,
-                        None,
-                    );
-                    FrameFun::add_data(
-                        &mut task_result,
-                        format!("数据结束时间"),
-                        FrameFun::get_data_str_with_space(end_time),
-                        end_time_str,
-                        vec![index + pos + 6, index + pos + 12],
-                        None,
-                        None,
-                    );
-                    FrameFun::add_data(
-                        &mut task_result,
-                        format!("数据密度"),
-                        format!("{:02X}", data_dinsty),
-                        format!("数据间隔时间：{}", data_dinsty_str),
-                        vec![index + pos + 12, index + pos + 13],
-                        None,
-                        None,
-                    );
-                    pos += 13;
-                }
-
-                pos += sub_length;
-                num += 1;
-                info!("num:{:?} length{:?} pos{:?} item_count * pncount{:?}", num, length, pos, item_count * pncount);
-                if ((length-pos==16) || (length - pos == 22)) && (num == item_count * pncount)
-                {
-                    info!("pw:{:?} pw_data:{:?}", pw, pw_data);
-                    pw = Self::guest_is_exit_pw(
-                        length,
-                        pw_data,
-                        data_item_elem.clone(),
-                        data_time,
-                        true,
-                        protocol,
-                        region,
-                        Some(dir),
-                    );
-                    if pw {
-                        length -= 16;
-                    }
-                }
-                if dir == 1 {
-                    if num >= (item_count * pncount) {
-                        return Ok(AnalysicErr::ErrLength);
-                    }
-                    if (length - pos == 6)
-                        && (!Self::guest_next_data_is_cur_item_data(
-                            data_item_elem.clone(),
-                            &data_segment[pos..],
-                            data_time,
-                            protocol,
-                            region,
-                            Some(dir),
-                        ))
-                    {
-                        return Ok(AnalysicErr::ErrLength);
-                    }
-                }
-                Ok(AnalysicErr::ErrOk)
-            })();
-
-            match result {
-                Ok(res) => match res {
-                    AnalysicErr::ErrOk => {}
-                    AnalysicErr::ErrLength => break,
-                    _ => {}
-                },
-                Err(e) => {
-                    let err_str = format!("数据解析失败!").to_string();
-                    let err = CustomError::new(1, err_str);
-                    break;
-                }
-            }
-        }
-
-        if dir == 1 {
-            FrameFun::add_data(
-                &mut task_result,
-                "任务数据内容".to_string(),
-                FrameFun::get_data_str_with_space(&valid_data_segment[6..]),
-                format!("{}数据内容", task_name),
-                vec![start_pos + 22, start_pos + frame.len() - 2],
-                Some(sub_result),
-                None,
-            );
-
-            if length - pos == 6 {
-                let data_time = &data_segment[pos..pos + 6];
-                let time_str = FrameFun::parse_time_data(data_time, "CCYYMMDDhhmm", false);
-                FrameFun::add_data(
-                    &mut task_result,
-                    "任务数据时间".to_string(),
-                    FrameFun::get_data_str_with_space(data_time),
-                    time_str,
-                    vec![index + pos, index + pos + 6],
-                    None,
-                    None,
-                );
-            }
-        }
-
-        FrameFun::add_data(
-            result_list,
-            "信息体".to_string(),
-            FrameFun::get_data_str_with_space(&frame[16..frame.len() - 2]),
-            "".to_string(),
-            vec![index + 16, index + total_length - 2],
-            Some(task_result),
-            None,
-        );
-
-        if pw {
-            let pw_str = "PW由16个字节组成，是由主站按系统约定的认证算法产生，并在主站发送的报文中下发给终端，由终端进行校验认证。".to_string();
-            FrameFun::add_data(
-                result_list,
-                "消息验证码Pw".to_string(),
-                FrameFun::get_data_str_with_space(pw_data),
-                pw_str,
-                pw_pos.to_vec(),
-                None,
-                None,
-            );
-        }
-
-        if tpv {
-            let tpv_str = Self::prase_tpv_data(tpv_data);
-            FrameFun::add_data(
-                result_list,
-                "时间标签Tp".to_string(),
-                FrameFun::get_data_str_with_space(tpv_data),
-                tpv_str,
-                vec![start_pos + total_length - 7, start_pos + total_length - 2],
-                None,
-                None,
-            );
-        }
-
-        Ok(())
-    }
-
-    pub fn analysic_csg_read_alarm_frame(
-        frame: &[u8],
-        dir: u8,
-        prm: u8,
-        result_list: &mut Vec<Value>,
-        start_pos: usize,
-        protocol: &str,
-        region: &str,
-    ) -> Result<(), Box<dyn Error>> {
-        let valid_data_segment = &frame[16..frame.len() - 2];
-        let tpv = Self::get_afn_and_seq_result(&frame[14..16], start_pos + 14, result_list);
-        let length = valid_data_segment.len();
-        let mut pos = 0;
-        let index = 16 + start_pos;
-        let mut num = 0;
-        let mut sub_result = vec![];
-        let total_length = frame.len();
-        let (tpv_data, pw_data, pw_pos, mut length) = if tpv {
-            (
-                frame[frame.len() - 7..frame.len() - 2].to_vec(),
-                if valid_data_segment.len() > 21 {
-                    valid_data_segment[valid_data_segment.len() - 21..valid_data_segment.len() - 5]
-                        .to_vec()
-                } else {
-                    [].to_vec()
-                },
-                [total_length - 23, total_length - 7],
-                length - 5,
-            )
-        } else {
-            (
-                vec![],
-                if valid_data_segment.len() > 16 {
-                    valid_data_segment[valid_data_segment.len() - 16..].to_vec()
-                } else {
-                    [].to_vec()
-                },
-                [total_length - 18, total_length - 2],
-                length,
-            )
-        };
-
-        let mut pw = false;
-        let data_segment = &valid_data_segment[..length];
-        let mut item_is_unknown = false;
-
-        while pos < length {
-            match (|| -> Result<(), CustomError> {
-                let da = &data_segment[pos..pos + 2];
-                let item = &data_segment[pos + 2..pos + 6];
-                info!("da: {:?}", da);
-                let point_str = Self::prase_da_data([da[0], da[1]]);
-                let data_item = FrameFun::get_data_str_reverser(item);
-
-                FrameFun::add_data(
-                    &mut sub_result,
-                    format!("<第{}组>信息点标识DA", num + 1),
-                    FrameFun::get_data_str_with_space(da),
-                    point_str.clone(),
-                    vec![index + pos, index + pos + 2],
-                    None,
-                    None,
-                );
-                pos += 2;
-
-                let data_item_elem =
-                    ProtocolConfigManager::get_config_xml(&data_item, protocol, region, Some(dir));
-                let mut item_data: Vec<Value> = Vec::new();
-                let mut sub_length = 0;
-                let mut sub_datament: &[u8] = &[];
-                let dis_data_identifier: String;
-                if let Some(mut data_item_elem) = data_item_elem {
-                    if dir == 1 {
-                        let sub_length_cont = data_item_elem.get_child_text("length").unwrap();
-                        (sub_length, sub_datament) = if sub_length_cont.to_uppercase() == "UNKNOWN"
-                        {
-                            let sub_length = Self::calculate_item_length(
-                                &mut data_item_elem,
-                                &data_segment[pos + 4..],
-                                protocol,
-                                region,
-                                Some(dir),
-                                None,
-                            );
-                            let sub_datament = &data_segment[pos + 4..pos + 4 + sub_length];
-                            (sub_length, sub_datament)
-                        } else {
-                            let mut sub_length = sub_length_cont.parse::<usize>().unwrap();
-                            if sub_length > data_segment.len() - pos - 4 {
-                                sub_length = data_segment.len() - pos - 4;
-                            }
-                            let new_sub_datament = &data_segment[pos + 4..pos + 4 + sub_length];
-                            let (new_sub_length, new_datament) = Self::recalculate_sub_length(
-                                &mut data_item_elem,
-                                new_sub_datament,
-                                protocol,
-                                region,
-                                Some(dir),
-                            );
-                            (new_sub_length, new_datament)
-                        };
-                        data_item_elem.update_value("length", sub_length.to_string());
-                        item_is_unknown = true;
-                        item_data = FrameAnalisyic::prase_data(
-                            &mut data_item_elem,
-                            protocol,
-                            region,
-                            &sub_datament,
-                            index + pos + 4,
-                            Some(dir),
-                        );
-                    } else {
-                        let sub_length = 0;
-                    }
-
-                    let name = data_item_elem.get_child_text("name").unwrap();
-                    dis_data_identifier = format!("数据标识编码：[{}]-{}", data_item, name);
-                } else {
-                    if dir == 1 {
-                        pw = Self::guest_is_exit_pw(
-                            length,
-                            &pw_data,
-                            None,
-                            None,
-                            false,
-                            protocol,
-                            region,
-                            Some(dir),
-                        );
-
-                        let err_str = format!("数据解析失败!").to_string();
-                        let err = CustomError::new(1, err_str);
-                        return Err(err);
-                    } else {
-                        let sub_length = 0;
-                    }
-                    dis_data_identifier = format!("数据标识编码：[{}]", data_item);
-                }
-
-                FrameFun::add_data(
-                    &mut sub_result,
-                    format!("<第{}组>数据标识编码DI", num + 1),
-                    FrameFun::get_data_str_with_space(item),
-                    dis_data_identifier.clone(),
-                    vec![index + pos, index + pos + 4],
-                    None,
-                    None,
-                );
-                pos += 4;
-
-                if dir == 1 {
-                    let new_point_str = point_str.clone().replace("Pn=", "");
-                    let new_dis_str: String =
-                        dis_data_identifier.clone().replace("数据标识编码：", "");
-                    FrameFun::add_data(
-                        &mut sub_result,
-                        format!("<第{}组>数据内容", num + 1),
-                        FrameFun::get_data_str_with_space(&sub_datament),
-                        format!("{}-{}", new_point_str, new_dis_str),
-                        vec![index + pos, index + pos + sub_length],
-                        Some(item_data),
-                        None,
-                    );
-                } else {
-                    let start_time = &data_segment[pos..pos + 6];
-                    let end_time = &data_segment[pos + 6..pos + 12];
-                    let start_time_str =
-                        FrameFun::parse_time_data(start_time, "CCYYMMDDhhmm", false);
-                    let end_time_str = FrameFun::parse_time_data(end_time, "CCYYMMDDhhmm", false);
-
-                    FrameFun::add_data(
-                        &mut sub_result,
-                        "数据起始时间".to_string(),
-                        FrameFun::get_data_str_with_space(start_time),
-                        start_time_str,
-                        vec![index + pos, index + pos + 6],
-                        None,
-                        None,
-                    );
-                    FrameFun::add_data(
-                        &mut sub_result,
-                        "数据结束时间".to_string(),
-                        FrameFun::get_data_str_with_space(end_time),
-                        end_time_str,
-                        vec![index + pos + 6, index + pos + 12],
-                        None,
-                        None,
-                    );
-                    pos += 12;
-                }
-
-                pos += sub_length;
-                num += 1;
-
-                if length - pos == 16 {
-                    let m_item = &data_segment[pos + 2..pos + 6];
-                    let m_data_item = FrameFun::get_data_str_reverser(m_item);
-                    let m_data_item_elem = ProtocolConfigManager::get_config_xml(&m_data_item, protocol, region, Some(dir));
-                    pw = Self::guest_is_exit_pw(
-                        length,
-                        &pw_data,
-                        m_data_item_elem,
-                        None,
-                        false,
-                        protocol,
-                        region,
-                        Some(dir),
-                    );
-                    if pw {
-                        length -= 16;
-                    }
-                }
-
-                Ok(())
-            })() {
-                Ok(_) => {}
-                Err(e) => {
-                    let err_str = format!("数据解析失败!").to_string();
-                    let err = CustomError::new(1, err_str);
-                    break;
-                }
-            }
-        }
-
-        if pw {
-            let pw_str = "PW由16个字节组成，是由主站按系统约定的认证算法产生，并在主站发送的报文中下发给终端，由终端进行校验认证。".to_string();
-            FrameFun::add_data(
-                &mut sub_result,
-                "消息验证码Pw".to_string(),
-                FrameFun::get_data_str_with_space(&pw_data),
-                pw_str,
-                pw_pos.to_vec(),
-                None,
-                None,
-            );
-        }
-
-        if tpv {
-            let tpv_str = Self::prase_tpv_data(&tpv_data);
-            FrameFun::add_data(
-                &mut sub_result,
-                "时间标签Tp".to_string(),
-                FrameFun::get_data_str_with_space(&tpv_data),
-                tpv_str,
-                vec![total_length - 7, total_length - 2],
-                None,
-                None,
-            );
-        }
-
-        FrameFun::add_data(
-            result_list,
-            "信息体".to_string(),
-            FrameFun::get_data_str_with_space(&frame[16..frame.len() - 2]),
-            "".to_string(),
-            vec![16, total_length - 2],
-            Some(sub_result),
-            None,
-        );
-
-        Ok(())
-    }
-
-    pub fn analysic_csg_read_event_frame(
-        frame: &[u8],
-        dir: u8,
-        prm: u8,
-        result_list: &mut Vec<Value>,
-        start_pos: usize,
-        protocol: &str,
-        region: &str,
-    ) -> Result<(), Box<dyn Error>> {
-        let valid_data_segment = &frame[16..frame.len() - 2];
-        let tpv = Self::get_afn_and_seq_result(&frame[14..16], start_pos + 14, result_list);
-        let mut length = valid_data_segment.len();
-        let mut pos = 0;
-        let index = 16 + start_pos;
-        let mut num = 0;
-        let mut sub_result: Vec<Value> = Vec::new();
-        let total_length = frame.len();
-        let mut tpv_data: &[u8] = &[];
-        let empty_data: &[u8] = &[];
-
-        let (pw_data, pw_pos) = if tpv {
-            length -= 5;
-            tpv_data = &frame[frame.len() - 7..frame.len() - 2];
-            if valid_data_segment.len() < 21 {
-                (empty_data, vec![0, 0])
-            } else {
-                let pw_data = &valid_data_segment[valid_data_segment.len() - 21..];
-                (pw_data, vec![total_length - 23, total_length - 7])
-            }
-        } else {
-            if valid_data_segment.len() < 16 {
-                (empty_data, vec![0, 0])
-            } else {
-                let pw_data = &valid_data_segment[valid_data_segment.len() - 16..];
-                (pw_data, vec![total_length - 18, total_length - 2])
-            }
-        };
-        let mut pw = false;
-
-        let data_segment = &valid_data_segment[..length];
-
-        while pos < length {
-            let da = &data_segment[pos..pos + 2];
-            let item = &data_segment[pos + 2..pos + 6];
-
-            let point_str = Self::prase_da_data([da[0], da[1]]);
-
-            let data_item = FrameFun::get_data_str_reverser(item);
-
-            FrameFun::add_data(
-                &mut sub_result,
-                format!("<第{}组>信息点标识DA", num + 1),
-                FrameFun::get_data_str_with_space(da),
-                point_str.clone(),
-                vec![index + pos, index + pos + 2],
-                None,
-                None,
-            );
-            pos += 2;
-
-            let data_item_elem =
-                ProtocolConfigManager::get_config_xml(&data_item, protocol, region, Some(dir));
-
-            let mut item_data: Vec<Value> = Vec::new();
-            let mut sub_length = 0;
-            let mut sub_datament: &[u8] = &[];
-            let dis_data_identifier: String;
-
-            if let Some(mut data_item_elem) = data_item_elem {
-                if dir == 1 && prm == 0 {
-                    // 上行回复
-                    let sub_length_cont = data_item_elem.get_child_text("length").unwrap();
-                    (sub_length, sub_datament) = if sub_length_cont.to_uppercase() == "UNKNOWN" {
-                        let sub_length = Self::calculate_item_length(
-                            &mut data_item_elem,
-                            &data_segment[pos + 4..],
-                            protocol,
-                            region,
-                            Some(dir),
-                            None,
-                        );
-                        let new_segment = &data_segment[pos + 4..pos + 4 + sub_length];
-                        (sub_length, new_segment)
-                    } else {
-                        let mut sub_length = sub_length_cont.parse::<usize>()?;
-                        if sub_length > data_segment[pos + 4..].len() {
-                            sub_length = data_segment[pos + 4..].len();
-                        }
-                        let sub_datament = &data_segment[pos + 4..pos + 4 + sub_length];
-                        (sub_length, sub_datament)
-                    };
-                    data_item_elem.update_value("length", sub_length.to_string());
-                    item_data = FrameAnalisyic::prase_data(
-                        &mut data_item_elem,
-                        protocol,
-                        region,
-                        sub_datament,
-                        index + pos + 4,
-                        Some(dir),
-                    );
-                } else {
-                    let sub_length = 0; // 下行读取报文
-                }
-                let name = data_item_elem.get_child_text("name").unwrap();
-                dis_data_identifier = format!("数据标识编码：[{}]-{}", data_item, name);
-            } else {
-                if dir == 1 && prm == 0 {
-                    pw = Self::guest_is_exit_pw(
-                        length,
-                        pw_data,
-                        None,
-                        None,
-                        false,
-                        protocol,
-                        region,
-                        Some(dir),
-                    );
-                    let err_str =
-                        format!("未找到数据标识{},请检查配置文件!", data_item).to_string();
-                    let err = CustomError::new(1, err_str);
-                    break;
-                } else {
-                    let sub_length = 0;
-                }
-                dis_data_identifier = format!("数据标识编码：[{}]", data_item);
-            }
-
-            FrameFun::add_data(
-                &mut sub_result,
-                format!("<第{}组>数据标识编码DI", num + 1),
-                FrameFun::get_data_str_with_space(item),
-                dis_data_identifier.clone(),
-                vec![index + pos, index + pos + 4],
-                None,
-                None,
-            );
-            pos += 4;
-            info!(
-                "point_str{:?} dis_data_identifier{:?}",
-                point_str, dis_data_identifier
-            );
-            if dir == 1 && prm == 0 {
-                // 找到"Pn="后面的内容
-                let point_suffix = point_str.strip_prefix("Pn=").unwrap_or(&point_str);
-
-                // 找到"数据标识编码："后面的内容
-                let dis_suffix = dis_data_identifier
-                    .strip_prefix("数据标识编码：")
-                    .unwrap_or(&dis_data_identifier);
-
-                FrameFun::add_data(
-                    &mut sub_result,
-                    format!("<第{}组>数据内容", num + 1),
-                    FrameFun::get_data_str_with_space(sub_datament),
-                    format!("{}-{}", point_suffix, dis_suffix),
-                    vec![index + pos, index + pos + sub_length],
-                    Some(item_data),
-                    None,
-                );
-            } else {
-                let start_time = &data_segment[pos..pos + 6];
-                let end_time = &data_segment[pos + 6..pos + 12];
-                let start_time_str = FrameFun::parse_time_data(start_time, "CCYYMMDDhhmm", false);
-                let end_time_str = FrameFun::parse_time_data(end_time, "CCYYMMDDhhmm", false);
-                FrameFun::add_data(
-                    &mut sub_result,
-                    "数据起始时间".to_string(),
-                    FrameFun::get_data_str_with_space(start_time),
-                    start_time_str,
-                    vec![index + pos, index + pos + 6],
-                    None,
-                    None,
-                );
-                FrameFun::add_data(
-                    &mut sub_result,
-                    "数据结束时间".to_string(),
-                    FrameFun::get_data_str_with_space(end_time),
-                    end_time_str,
-                    vec![index + pos + 6, index + pos + 12],
-                    None,
-                    None,
-                );
-                pos += 12;
-            }
-            pos += sub_length;
-            num += 1;
-
-            if length - pos == 16 {
-                let m_item = &data_segment[pos + 2..pos + 6];
-                let m_data_item = FrameFun::get_data_str_reverser(m_item);
-                let m_data_item_elem = ProtocolConfigManager::get_config_xml(&m_data_item, protocol, region, Some(dir));
-                pw = Self::guest_is_exit_pw(
-                    length,
-                    pw_data,
-                    m_data_item_elem,
-                    None,
-                    false,
-                    protocol,
-                    region,
-                    Some(dir),
-                );
-                if pw {
-                    length -= 16;
-                }
-            }
-        }
-        if pw {
-            let pw_str = "PW由16个字节组成，是由主站按系统约定的认证算法产生，并在主站发送的报文中下发给终端，由终端进行校验认证。".to_string();
-            FrameFun::add_data(
-                &mut sub_result,
-                "消息验证码Pw".to_string(),
-                FrameFun::get_data_str_with_space(pw_data),
-                pw_str,
-                pw_pos.to_vec(),
-                None,
-                None,
-            );
-        }
-        if tpv {
-            let tpv_str = Self::prase_tpv_data(tpv_data);
-            FrameFun::add_data(
-                &mut sub_result,
-                "时间标签Tp".to_string(),
-                FrameFun::get_data_str_with_space(tpv_data),
-                tpv_str,
-                vec![total_length - 7, total_length - 2],
-                None,
-                None,
-            );
-        }
-        FrameFun::add_data(
-            result_list,
-            "信息体".to_string(),
-            FrameFun::get_data_str_with_space(&frame[16..frame.len() - 2]),
-            "".to_string(),
-            vec![16, total_length - 2],
-            Some(sub_result),
-            None,
-        );
-
-        Ok(())
-    }
-
-    pub fn analysic_csg_relay_frame(
-        frame: &[u8],
-        dir: u8,
-        prm: u8,
-        result_list: &mut Vec<Value>,
-        start_pos: usize,
-        protocol: &str,
-        region: &str,
-    ) -> Result<(), Box<dyn Error>> {
-        let valid_data_segment = &frame[16..frame.len() - 2];
-        let tpv = Self::get_afn_and_seq_result(&frame[14..16], start_pos + 14, result_list);
-        let mut length = valid_data_segment.len();
-        let mut pos = 0;
-        let index = 16 + start_pos;
-        let mut num = 0;
-        let mut sub_result: Vec<Value> = Vec::new();
-        let total_length = frame.len();
-        let tmp_pw_data: &[u8] = &[];
-
-        let (tpv_data, pw_data, pw_pos) = if tpv {
-            let tpv_data = &frame[frame.len() - 7..frame.len() - 2];
-            length -= 5;
-            if valid_data_segment.len() < 21 {
-                (tpv_data, tmp_pw_data, vec![0, 0])
-            } else {
-                let pw_data = &valid_data_segment
-                    [valid_data_segment.len() - 21..valid_data_segment.len() - 5];
-                (tpv_data, pw_data, vec![total_length - 23, total_length - 7])
-            }
-        } else {
-            let pw_data = if valid_data_segment.len() > 16 {
-                &valid_data_segment[valid_data_segment.len() - 16..]
-            } else {
-                tmp_pw_data
-            };
-            (
-                &Vec::<u8>::new()[..],
-                pw_data,
-                vec![total_length - 18, total_length - 2],
-            )
-        };
-        let mut pw = false;
-
-        let data_segment = &valid_data_segment[..length];
-
-        while pos < length {
-            let da = &data_segment[pos..pos + 2];
-            let item = &data_segment[pos + 2..pos + 6];
-
-            let point_str = Self::prase_da_data([da[0], da[1]]);
-
-            let data_item = FrameFun::get_data_str_reverser(item);
-
-            FrameFun::add_data(
-                &mut sub_result,
-                format!("<第{}组>信息点标识DA", num + 1),
-                FrameFun::get_data_str_with_space(da),
-                point_str.clone(),
-                vec![index + pos, index + pos + 2],
-                None,
-                None,
-            );
-            pos += 2;
-
-            let data_item_elem =
-                ProtocolConfigManager::get_config_xml(&data_item, protocol, region, Some(dir));
-
-            let mut item_data: Vec<Value> = Vec::new();
-            let mut sub_length = 0;
-            let mut sub_datament: &[u8] = &[];
-            let mut dis_data_identifier: String;
-
-            if let Some(mut data_item_elem) = data_item_elem {
-                if dir == 1 && prm == 0 {
-                    let frame_result: Vec<String> = Vec::new();
-                    info!("dir:{:?} data_item_elem{:?} data_segment{:?}", dir,data_item_elem, data_segment);
-                    let sub_length_cont = data_item_elem.get_child_text("length").unwrap();
-                    (sub_length, sub_datament) = if sub_length_cont.to_uppercase() == "UNKNOWN" {
-                        let sub_length = Self::calculate_item_length(
-                            &mut data_item_elem,
-                            &data_segment[pos + 4..],
-                            protocol,
-                            region,
-                            Some(dir),
-                            None,
-                        );
-                        let sub_datament = &data_segment[pos + 4..pos + 4 + sub_length];
-                        (sub_length, sub_datament)
-                    } else {
-                        let mut sub_length = sub_length_cont.parse::<usize>()?;
-                        if sub_length > data_segment.len() - pos - 4 {
-                            sub_length = data_segment.len() - pos - 4;
-                        }
-                        let sub_datament = &data_segment[pos + 4..pos + 4 + sub_length];
-                        let (sub_length, new_datament) = Self::recalculate_sub_length(
-                            &mut data_item_elem,
-                            sub_datament,
-                            protocol,
-                            region,
-                            Some(dir),
-                        );
-                        (sub_length, new_datament)
-                    };
-                    info!("sub_length {:?} new_datament:{:?}", sub_length,sub_datament);
-                    data_item_elem.update_value("length", sub_length.to_string());
-                    item_data = FrameAnalisyic::prase_data(
-                        &mut data_item_elem,
-                        protocol,
-                        region,
-                        sub_datament,
-                        index + pos + 4,
-                        Some(dir),
-                    );
-                } else {
-                    let sub_length_cont = data_item_elem.get_child_text("length").unwrap();
-                    (sub_length, sub_datament) = if sub_length_cont.to_uppercase() == "UNKNOWN"
-                    {
-                        let sub_length = Self::calculate_item_length(
-                            &mut data_item_elem,
-                            &data_segment[pos + 4..],
-                            protocol,
-                            region,
-                            Some(dir),
-                            None,
-                        );
-                        let sub_datament = &data_segment[pos + 4..pos + 4 + sub_length];
-                        (sub_length, sub_datament)
-                    } else {
-                        let mut sub_length = sub_length_cont.parse::<usize>()?;
-                        if sub_length > data_segment.len() - pos - 4 {
-                            sub_length = data_segment.len() - pos - 4;
-                        }
-                        let sub_datament = &data_segment[pos + 4..pos + 4 + sub_length];
-                        let (sub_length, new_datament) = Self::recalculate_sub_length(
-                            &mut data_item_elem,
-                            sub_datament,
-                            protocol,
-                            region,
-                            Some(dir),
-                        );
-                        (sub_length, new_datament)
-                    };
-                    info!("sub_length {:?} new_datament:{:?}", sub_length,sub_datament);
-                    data_item_elem.update_value("length", sub_length.to_string());
-                    item_data = FrameAnalisyic::prase_data(
-                        &mut data_item_elem,
-                        protocol,
-                        region,
-                        sub_datament,
-                        index + pos + 4,
-                        Some(dir),
-                    );
-
-                }
-                let name = data_item_elem.get_child_text("name").unwrap();
-                let dis_data_identifier = format!("数据标识编码：[{}]-{}", data_item, name);
-                FrameFun::add_data(
-                    &mut sub_result,
-                    format!("<第{}组>数据标识编码DI", num + 1),
-                    FrameFun::get_data_str_with_space(item),
-                    dis_data_identifier.clone(),
-                    vec![index + pos, index + pos + 4],
-                    None,
-                    None,
-                );
-
-                FrameFun::add_data(
-                    &mut sub_result,
-                    format!("<第{}组>数据内容", num + 1),
-                    FrameFun::get_data_str_with_space(sub_datament),
-                    format!(
-                        "{}-{}",
-                        if point_str.len() > 3 {
-                            &point_str[3..]
-                        } else {
-                            &point_str
-                        },
-                        if dis_data_identifier.starts_with("数据标识编码：") {
-                            &dis_data_identifier[7 * 3..] // "数据标识编码：" 是7个中文字符，每个3字节
-                        } else {
-                            &dis_data_identifier
-                        }
-                    ),
-                    vec![index + pos + 4, index + pos + 4 + sub_length],
-                    Some(item_data),
-                    None,
-                );
-                pos += sub_length + 4;
-                num += 1;
-
-                if length - pos == 16 {
-                    let m_item = &data_segment[pos + 2..pos + 6];
-                    let m_data_item = FrameFun::get_data_str_reverser(m_item);
-                    let m_data_item_elem = ProtocolConfigManager::get_config_xml(&m_data_item, protocol, region, Some(dir));
-                    pw = Self::guest_is_exit_pw(
-                        length,
-                        pw_data,
-                        m_data_item_elem,
-                        None,
-                        false,
-                        protocol,
-                        region,
-                        Some(dir),
-                    );
-                    if pw {
-                        length -= 16;
-                    }
-                }
-            } else {
-                pw = Self::guest_is_exit_pw(
-                    length,
-                    pw_data,
-                    None,
-                    None,
-                    false,
-                    protocol,
-                    region,
-                    Some(dir),
-                );
-                let err_str = format!("未找到数据标识{},请检查配置文件!", data_item).to_string();
-                let err = CustomError::new(1, err_str);
-                break;
-            }
-        }
-        if pw {
-            let pw_str = "PW由16个字节组成，是由主站按系统约定的认证算法产生，并在主站发送的报文中下发给终端，由终端进行校验认证。".to_string();
-            FrameFun::add_data(
-                &mut sub_result,
-                "消息验证码Pw".to_string(),
-                FrameFun::get_data_str_with_space(pw_data),
-                pw_str,
-                pw_pos.to_vec(),
-                None,
-                None,
-            );
-        }
-        if tpv {
-            let tpv_str = Self::prase_tpv_data(tpv_data);
-            FrameFun::add_data(
-                &mut sub_result,
-                "时间标签Tp".to_string(),
-                FrameFun::get_data_str_with_space(tpv_data),
-                tpv_str,
-                vec![total_length - 7, total_length - 2],
-                None,
-                None,
-            );
-        }
-        FrameFun::add_data(
-            result_list,
-            "信息体".to_string(),
-            FrameFun::get_data_str_with_space(&frame[16..frame.len() - 2]),
-            "".to_string(),
-            vec![16, frame.len() - 2],
-            Some(sub_result),
-            None,
-        );
-        Ok(())
-    }
-
-    pub fn analysic_csg_topo_frame(
-        frame: &[u8],
-        dir: u8,
-        prm: u8,
-        result_list: &mut Vec<Value>,
-        start_pos: usize,
-        protocol: &str,
-        region: &str,
-    ) -> Result<(), Box<dyn Error>> {
-        let valid_data_segment = &frame[16..frame.len() - 2];
-        let tpv = Self::get_afn_and_seq_result(&frame[14..16], start_pos + 14, result_list);
-        let mut length = valid_data_segment.len();
-        let mut pos = 0;
-        let index = 16 + start_pos;
-        let mut num = 0;
-        let total_length = frame.len();
-        let tmp_pw_data: &[u8] = &[];
-        let (tpv_data, pw_data, pw_pos) = if tpv {
-            length -= 5;
-            let tpv_data = &frame[frame.len() - 7..frame.len() - 2];
-            if valid_data_segment.len() < 21 {
-                (tpv_data, tmp_pw_data, vec![0, 0])
-            } else {
-                let pw_data = &valid_data_segment
-                    [valid_data_segment.len() - 21..valid_data_segment.len() - 5];
-                (
-                    tpv_data,
-                    pw_data,
-                    vec![start_pos + frame.len() - 23, start_pos + frame.len() - 7],
-                )
-            }
-        } else {
-            let pw_data = if valid_data_segment.len() > 16 {
-                &valid_data_segment[valid_data_segment.len() - 16..]
-            } else {
-                tmp_pw_data
-            };
-            (
-                tmp_pw_data,
-                pw_data,
-                vec![start_pos + frame.len() - 18, start_pos + frame.len() - 2],
-            )
-        };
-        let mut pw = false;
-
-        let data_segment = &valid_data_segment[..length];
-        let mut sub_result: Vec<Value> = Vec::new();
-
-        while pos < length {
-            sub_result.clear();
-            let da: &[u8] = &data_segment[pos..pos + 2];
-            let item = &data_segment[pos + 2..pos + 6];
-
-            let point_str = Self::prase_da_data([da[0], da[1]]);
-
-            let data_item = FrameFun::get_data_str_reverser(item);
-            FrameFun::add_data(
-                &mut sub_result,
-                format!("<第{}组>信息点标识DA", num + 1),
-                FrameFun::get_data_str_with_space(da),
-                point_str.clone(),
-                vec![index + pos, index + pos + 2],
-                None,
-                None,
-            );
-            pos += 2;
-
-            let data_item_elem =
-                ProtocolConfigManager::get_config_xml(&data_item, protocol, region, Some(dir));
-
-            let item_data: Vec<Value>;
-            let dis_data_identifier: String;
-
-            if let Some(mut data_item_elem) = data_item_elem {
-                let sub_length_cont = data_item_elem.get_child_text("length").unwrap();
-                let (sub_length, new_datament) = if sub_length_cont.to_uppercase() == "UNKNOWN" {
-                    let sub_length = Self::calculate_item_length(
-                        &mut data_item_elem,
-                        &data_segment[pos + 4..],
-                        protocol,
-                        region,
-                        Some(dir),
-                        None,
-                    );
-                    let sub_datament = &data_segment[pos + 4..pos + 4 + sub_length];
-                    (sub_length, sub_datament)
-                } else {
-                    let mut sub_length = sub_length_cont.parse::<usize>()?;
-                    if sub_length > data_segment.len() - pos - 4 {
-                        sub_length = data_segment.len() - pos - 4;
-                    }
-                    let sub_datament = &data_segment[pos + 4..pos + 4 + sub_length];
-                    let (sub_length, new_datament) = Self::recalculate_sub_length(
-                        &mut data_item_elem,
-                        sub_datament,
-                        protocol,
-                        region,
-                        Some(dir),
-                    );
-                    (sub_length, new_datament)
-                };
-
-                item_data = FrameAnalisyic::prase_data(
-                    &mut data_item_elem,
-                    protocol,
-                    region,
-                    new_datament,
-                    index + pos + 4,
-                    Some(dir),
-                );
-                let name = data_item_elem.get_child_text("name").unwrap();
-                dis_data_identifier = format!("数据标识编码：[{}]-{}", data_item, name);
-                FrameFun::add_data(
-                    &mut sub_result,
-                    format!("<第{}组>数据标识编码DI", num + 1),
-                    FrameFun::get_data_str_with_space(item),
-                    dis_data_identifier.clone(),
-                    vec![index + pos, index + pos + 4],
-                    None,
-                    None,
-                );
-                FrameFun::add_data(
-                    &mut sub_result,
-                    format!("<第{}组>数据内容", num + 1),
-                    FrameFun::get_data_str_with_space(new_datament),
-                    point_str["Pn=".len()..].to_string()
-                        + "-"
-                        + &dis_data_identifier["数据标识编码：".len()..],
-                    vec![index + pos + 4, index + pos + 4 + sub_length],
-                    Some(item_data),
-                    None,
-                );
-                pos += sub_length + 4;
-                num += 1;
-
-                if length - pos == 16 {
-                    let m_item = &data_segment[pos + 2..pos + 6];
-                    let m_data_item = FrameFun::get_data_str_reverser(m_item);
-                    let m_data_item_elem = ProtocolConfigManager::get_config_xml(&m_data_item, protocol, region, Some(dir));
-                    pw = Self::guest_is_exit_pw(
-                        length,
-                        pw_data,
-                        m_data_item_elem,
-                        None,
-                        false,
-                        protocol,
-                        region,
-                        Some(dir),
-                    );
-                    if pw {
-                        length -= 16;
-                    }
-                }
-            } else {
-                if dir == 1 && prm == 0 {
-                    pw = Self::guest_is_exit_pw(
-                        length,
-                        pw_data,
-                        None,
-                        None,
-                        false,
-                        protocol,
-                        region,
-                        Some(dir),
-                    );
-                    let err_str =
-                        format!("未找到数据标识{},请检查配置文件!", data_item).to_string();
-                    let err = CustomError::new(1, err_str);
-                    break;
-                } else {
-                    let sub_length = 0;
-                    let dis_data_identifier = format!("数据标识编码：[{}]", data_item);
-                    FrameFun::add_data(
-                        &mut sub_result,
-                        format!("<第{}组>数据标识编码DI", num + 1),
-                        FrameFun::get_data_str_with_space(item),
-                        dis_data_identifier.clone(),
-                        vec![index + pos, index + pos + 4],
-                        None,
-                        None,
-                    );
-                    pos += sub_length + 4;
-                    num += 1;
-                }
-            }
-        }
-        if pw {
-            let pw_str = "PW由16个字节组成，是由主站按系统约定的认证算法产生，并在主站发送的报文中下发给终端，由终端进行校验认证。".to_string();
-            FrameFun::add_data(
-                &mut sub_result,
-                "消息验证码Pw".to_string(),
-                FrameFun::get_data_str_with_space(pw_data),
-                pw_str,
-                pw_pos.to_vec(),
-                None,
-                None,
-            );
-        }
-        if tpv {
-            let tpv_str = Self::prase_tpv_data(tpv_data);
-            FrameFun::add_data(
-                &mut sub_result,
-                "时间标签Tp".to_string(),
-                FrameFun::get_data_str_with_space(tpv_data),
-                tpv_str,
-                vec![start_pos + frame.len() - 7, start_pos + frame.len() - 2],
-                None,
-                None,
-            );
-        }
-        FrameFun::add_data(
-            result_list,
-            "信息体".to_string(),
-            FrameFun::get_data_str_with_space(&frame[16..frame.len() - 2]),
-            "".to_string(),
-            vec![index, start_pos + frame.len() - 2],
-            Some(sub_result),
-            None,
-        );
-        Ok(())
-    }
-
-    pub fn analysic_csg_filetrans_frame(
-        frame: &[u8],
-        dir: u8,
-        prm: u8,
-        result_list: &mut Vec<Value>,
-        start_pos: usize,
-        protocol: &str,
-        region: &str,
-    ) -> Result<(), Box<dyn Error>> {
-        let total_length = frame.len();
-        let valid_data_segment = &frame[16..frame.len() - 2];
-        let tpv = Self::get_afn_and_seq_result(&frame[14..16], start_pos + 14, result_list);
-        let mut length = valid_data_segment.len();
-        let mut pos = 0;
-        let index = 16 + start_pos;
-        let mut num = 0;
-        let mut sub_result = Vec::new();
-        let mut tpv_data: &[u8] = &[];
-        let pw_data: &[u8] = &[];
-        let empty_data: &[u8] = &[];
-        let (pw_data, pw_pos) = if tpv {
-            length -= 5;
-            tpv_data = &frame[frame.len() - 7..frame.len() - 2];
-            if valid_data_segment.len() < 21 {
-                (empty_data, [0, 0])
-            } else {
-                let pw_data = &valid_data_segment
-                    [valid_data_segment.len() - 21..valid_data_segment.len() - 5];
-                (pw_data, [total_length - 23, total_length - 7])
-            }
-        } else {
-            if valid_data_segment.len() < 16 {
-                (empty_data, [0, 0])
-            } else {
-                let pw_data = &valid_data_segment[valid_data_segment.len() - 16..];
-                (pw_data, [total_length - 18, total_length - 2])
-            }
-        };
-
-        let data_segment = &valid_data_segment[..length];
-        let mut pw = false;
-        info!(
-            "data_segment: {:?}, tpv: {:?}, pw_data: {:?}, length: {}",
-            data_segment, tpv, pw_data, length
-        );
-        while pos < length {
-            let da = &data_segment[pos..pos + 2];
-            let item = &data_segment[pos + 2..pos + 6];
-
-            let point_str = Self::prase_da_data([da[0], da[1]]);
-            let data_item = FrameFun::get_data_str_reverser(item);
-
-            FrameFun::add_data(
-                &mut sub_result,
-                format!("<第{}组>信息点标识DA", num + 1),
-                FrameFun::get_data_str_with_space(da),
-                point_str,
-                vec![index + pos, index + pos + 2],
-                None,
-                None,
-            );
-            pos += 2;
-
-            let data_item_elem =
-                ProtocolConfigManager::get_config_xml(&data_item, protocol, region, Some(dir));
-            let mut item_data = Vec::new();
-            let sub_length: usize;
-            let sub_datament: &[u8];
-            let dis_data_identifier: String;
-            if let Some(mut data_item_elem) = data_item_elem {
-                (sub_length, sub_datament) = if dir == 1 && prm == 0 {
-                    (1, &data_segment[pos + 4..pos + 4 + 1])
-                } else {
-                    let sub_length_cont = data_item_elem.get_child_text("length");
-                    let mut sub_length = if let Some(sub_length_cont) = sub_length_cont {
-                        if sub_length_cont.to_uppercase() == "UNKNOWN" {
-                            Self::calculate_item_length(
-                                &mut data_item_elem,
-                                &data_segment[pos + 4..],
-                                protocol,
-                                region,
-                                Some(dir),
-                                None,
-                            )
-                        } else {
-                            sub_length_cont.parse::<usize>().unwrap()
-                        }
-                    } else {
-                        data_segment[pos + 4..].len()
-                    };
-                    if sub_length > data_segment[pos + 4..].len() {
-                        sub_length = data_segment[pos + 4..].len();
-                    }
-                    let sub_datament = &data_segment[pos + 4..pos + 4 + sub_length];
-                    (sub_length, sub_datament)
-                };
-                data_item_elem.update_value("length", sub_length.to_string());
-                // info!("sub_datament: {:?}", data_item_elem);
-                item_data = FrameAnalisyic::prase_data(
-                    &mut data_item_elem,
-                    protocol,
-                    region,
-                    sub_datament,
-                    index + pos + 4,
-                    Some(dir),
-                );
-                let name = data_item_elem.get_child_text("name").unwrap();
-                dis_data_identifier = format!("数据标识编码：[{}]-{}", data_item, name);
-            } else {
-                (sub_length, sub_datament) = if dir == 1 && prm == 0 {
-                    (1, &data_segment[pos + 4..pos + 4 + 1])
-                } else {
-                    let err_str =
-                        format!("未查找到数据标识：{},请检查配置文件！", data_item).to_string();
-                    let err = CustomError::new(1, err_str);
-                    break;
-                };
-                dis_data_identifier = format!("数据标识编码：[{}]", data_item);
-            };
-
-            FrameFun::add_data(
-                &mut sub_result,
-                format!("<第{}组>数据标识编码DI", num + 1),
-                FrameFun::get_data_str_with_space(item),
-                dis_data_identifier,
-                vec![index + pos, index + pos + 4],
-                None,
-                None,
-            );
-            let result_str: String;
-            if sub_length > 0 {
-                result_str = format!(
-                    "数据标识[{}]数据内容：{}",
-                    data_item,
-                    FrameFun::get_data_str_reverser(sub_datament)
-                );
-                FrameFun::add_data(
-                    &mut sub_result,
-                    format!("<第{}组>数据标识内容", num + 1),
-                    FrameFun::get_data_str_with_space(sub_datament),
-                    result_str,
-                    vec![index + pos + 4, index + pos + 4 + sub_length],
-                    Some(item_data),
-                    None,
-                );
-            }
-            info!("pos: {}, sub_length: {}", pos, sub_length);
-            pos += sub_length + 4;
-            num += 1;
-            if length - pos == 16 {
-                let m_item = &data_segment[pos + 2..pos + 6];
-                let m_data_item = FrameFun::get_data_str_reverser(m_item);
-                let m_data_item_elem = ProtocolConfigManager::get_config_xml(&m_data_item, protocol, region, Some(dir));
-                pw = Self::guest_is_exit_pw(
-                    length,
-                    pw_data,
-                    m_data_item_elem,
-                    None,
-                    false,
-                    protocol,
-                    region,
-                    Some(dir),
-                );
-
-                if pw {
-                    length -= 16;
-                }
-            }
-        }
-
-        if pw {
-            let pw_str = "PW由16个字节组成，是由主站按系统约定的认证算法产生，并在主站发送的报文中下发给终端，由终端进行校验认证。".to_string();
-            FrameFun::add_data(
-                &mut sub_result,
-                "消息验证码Pw".to_string(),
-                FrameFun::get_data_str_with_space(pw_data),
-                pw_str,
-                pw_pos.to_vec(),
-                None,
-                None,
-            );
-        }
-        if tpv {
-            let tpv_str = Self::prase_tpv_data(tpv_data);
-            FrameFun::add_data(
-                &mut sub_result,
-                "时间标签Tp".to_string(),
-                FrameFun::get_data_str_with_space(tpv_data),
-                tpv_str,
-                vec![total_length - 7, total_length - 2],
-                None,
-                None,
-            );
-        }
-        FrameFun::add_data(
-            result_list,
-            "信息体".to_string(),
-            FrameFun::get_data_str_with_space(&frame[16..frame.len() - 2]),
-            "".to_string(),
-            vec![16, total_length - 2],
-            Some(sub_result),
-            None,
-        );
-
-        Ok(())
-    }
 }
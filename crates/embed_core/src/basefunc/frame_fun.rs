@@ -1,7 +1,8 @@
 use std::collections::HashMap;
 use std::error::Error;
 extern crate regex;
-use crate::config::xmlconfig::{ProtocolConfigManager, XmlElement};
+use crate::analyzer::Analyzer;
+use crate::config::xmlconfig::XmlElement;
 use regex::Regex;
 use serde_json::Value;
 pub struct FrameFun;
@@ -54,6 +55,35 @@ impl FrameFun {
         fcs
     }
 
+    /// HDLC帧的HCS/FCS：初值FFFF，计算结果取反，传输时低字节在前
+    pub fn calculate_fcs16(data: &[u8]) -> u16 {
+        Self::ppp_fcs16(0xFFFF, data) ^ 0xFFFF
+    }
+
+    /// check为data之后的2字节校验(低字节在前)
+    pub fn check_fcs16(data: &[u8], check: &[u8]) -> bool {
+        check.len() == 2 && Self::calculate_fcs16(data).to_le_bytes() == check
+    }
+
+    /// 校验结果的描述，错误时给出正确的校验值
+    pub fn fcs16_check_desc(name: &str, data: &[u8], check: &[u8]) -> String {
+        if Self::check_fcs16(data, check) {
+            format!("{}正确", name)
+        } else {
+            format!(
+                "{}错误，应为：{}",
+                name,
+                Self::get_data_str_with_space(&Self::calculate_fcs16(data).to_le_bytes())
+            )
+        }
+    }
+
+    /// 在frame末尾追加从start开始的数据的FCS16
+    pub fn append_fcs16(frame: &mut Vec<u8>, start: usize) {
+        let fcs = Self::calculate_fcs16(&frame[start.min(frame.len())..]);
+        frame.extend_from_slice(&fcs.to_le_bytes());
+    }
+
     pub fn bytes_to_decimal_list(byte_data: &[u8]) -> Result<Vec<u8>, Box<dyn Error>> {
         Ok(byte_data.to_vec())
     }
@@ -737,6 +767,9 @@ impl FrameFun {
             (1, vec![0xFFFF])
         } else if da1 == 0x00 && da2 == 0x00 {
             (1, vec![0])
+        } else if da2 == 0x00 {
+            // 信息点组从1开始，DA2为0时DA1应同为0
+            (0, Vec::new())
         } else {
             let set_bits_da1 = find_set_bits(da1);
             let info_point_group = da2 as usize;
@@ -780,7 +813,46 @@ impl FrameFun {
         }
     }
 
+    /// 信息类DT对应的Fn，DT1按位表示组内信息类，DT2为信息类组号，Fn=DT2*8+位号+1
+    pub fn calculate_fn(dt: &[u8]) -> (usize, Vec<u16>) {
+        let dt1 = dt[0];
+        let group = dt[1] as u16;
+        let fns: Vec<u16> = (0..8)
+            .filter(|&i| (dt1 >> i) & 1 == 1)
+            .map(|i| group * 8 + i + 1)
+            .collect();
+        (fns.len(), fns)
+    }
+
+    /// Fn对应的信息类DT(DT1, DT2)，同组的Fn可合并到一个DT中
+    pub fn to_dt(fns: &[u16]) -> Vec<(u8, u8)> {
+        let mut dts: Vec<(u8, u8)> = Vec::new();
+        for &fn_value in fns.iter().filter(|&&f| f > 0) {
+            let group = ((fn_value - 1) / 8) as u8;
+            let bit = 1u8 << ((fn_value - 1) % 8);
+            match dts.iter_mut().find(|(_, dt2)| *dt2 == group) {
+                Some((dt1, _)) => *dt1 |= bit,
+                None => dts.push((bit, group)),
+            }
+        }
+        dts
+    }
+
+    pub fn parse_dt_data(dt: &[u8]) -> String {
+        let (total_fn, fn_array) = Self::calculate_fn(dt);
+        if total_fn == 0 {
+            return "Fn解析失败".to_string();
+        }
+        let formatted_string = fn_array
+            .iter()
+            .map(|f| format!("F{}", f))
+            .collect::<Vec<_>>()
+            .join(", ");
+        format!("Fn={}", formatted_string)
+    }
+
     pub fn calculate_item_box_length(
+        analyzer: &Analyzer,
         item_ele: &XmlElement,
         protocol: &str,
         region: &str,
@@ -792,8 +864,7 @@ impl FrameFun {
         for item_elem in all_items {
             if let Some(item_id) = item_elem.get_value() {
                 let id: &str = &item_id.trim();
-                if let Some(item) = ProtocolConfigManager::get_config_xml(id, protocol, region, dir)
-                {
+                if let Some(item) = analyzer.get_config_xml(id, protocol, region, dir) {
                     let item_length = item
                         .get_child_text("length")
                         .and_then(|v| v.parse::<u64>().ok()) // 将 String 转换为 u64
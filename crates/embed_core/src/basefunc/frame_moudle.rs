@@ -1,7 +1,7 @@
+use crate::analyzer::Analyzer;
 use crate::basefunc::frame_csg::FrameCsg;
 use crate::basefunc::frame_fun::FrameFun;
 use crate::basefunc::protocol::{FrameAnalisyic, ProtocolInfo};
-use crate::config::xmlconfig::ProtocolConfigManager;
 use serde_json::Value;
 use tracing::info;
 const FRAME_START: u8 = 0x68;
@@ -34,18 +34,20 @@ impl FrameMoudle {
     }
 
     pub fn analysic_moudle_frame(
+        analyzer: &Analyzer,
         frame: &[u8],
         result_list: &mut Vec<Value>,
         index: usize,
         region: &str,
     ) {
         let (dir, prm, add, afn, pos, mut user_result) =
-            Self::analysic_moudle_head_frame(frame, result_list, index);
+            Self::analysic_moudle_head_frame(analyzer, frame, result_list, index);
 
         let app_data = &frame[pos..frame.len() - 2];
         let mut app_data_result = Vec::new();
         let protocol = ProtocolInfo::ProtocolMoudle.name().to_string();
         Self::analysic_moudle_appdata_frame(
+            analyzer,
             app_data,
             &mut app_data_result,
             dir,
@@ -83,6 +85,7 @@ impl FrameMoudle {
     }
 
     fn analysic_moudle_head_frame(
+        analyzer: &Analyzer,
         frame: &[u8],
         result_list: &mut Vec<Value>,
         index: usize,
@@ -134,7 +137,7 @@ impl FrameMoudle {
         let dir_type = frame[pos + 5];
         let item_data = frame[6..10].to_vec();
         let item = FrameFun::bintodecimal(&item_data);
-        let afn_str = Self::get_afn_info(afn, item);
+        let afn_str = Self::get_afn_info(analyzer, afn, item);
 
         FrameFun::add_data(
             &mut user_result,
@@ -163,30 +166,13 @@ impl FrameMoudle {
         (dir, prm, add, afn, pos, user_result)
     }
 
-    pub fn get_afn_info(afn: u8, item: u64) -> &'static str {
-        match afn {
-            0x00 => "确认/否认",
-            0x01 => "初始化模块",
-            0x03 => "读参数",
-            0x04 => "写参数",
-            0x05 => {
-                if item == 0xEC050501 {
-                    "上报信息"
-                } else {
-                    "电池管理"
-                }
-            }
-            0x06 => "请求信息",
-            0x07 => "传输文件",
-            0x10 => "维护命令",
-            0x41 => "遥信脉冲管理",
-            0x42 => "遥信脉冲上报",
-            0x43 => "遥控输出管理",
-            0x44 => "模拟量采集管理",
-            0x46 => "电池异常上报",
-            0xF0 => "维护模块",
-            _ => "未知",
-        }
+    pub fn get_afn_info(analyzer: &Analyzer, afn: u8, item: u64) -> String {
+        let protocol = ProtocolInfo::ProtocolMoudle.name();
+        let item = format!("{:08X}", item);
+        analyzer
+            .find_afn(protocol, afn, None, Some(&item))
+            .map(|entry| entry.name)
+            .unwrap_or_else(|| "未知".to_string())
     }
 
     pub fn get_control_code_str(
@@ -264,6 +250,7 @@ impl FrameMoudle {
     }
 
     fn analysic_moudle_appdata_frame(
+        analyzer: &Analyzer,
         data_content: &[u8],
         result: &mut Vec<Value>,
         dir: u8,
@@ -279,7 +266,7 @@ impl FrameMoudle {
             data_item, protocol, region, dir
         );
         if let Some(mut data_item_elem) =
-            ProtocolConfigManager::get_config_xml(&data_item, protocol, region, Some(dir))
+            analyzer.get_config_xml(&data_item, protocol, region, Some(dir))
         {
             let pos: usize = 0;
             let length_ele = data_item_elem.get_child_text("length");
@@ -291,6 +278,7 @@ impl FrameMoudle {
             let sub_length = if let Some(length_text) = length_ele {
                 match length_text.to_uppercase().as_str() {
                     "UNKNOWN" => FrameCsg::calculate_item_length(
+                        analyzer,
                         &mut data_item_elem,
                         di_data,
                         protocol,
@@ -307,6 +295,7 @@ impl FrameMoudle {
             let sub_datament = &di_data[pos..pos + sub_length];
             data_item_elem.update_value("length", sub_length.to_string());
             let item_data = FrameAnalisyic::prase_data(
+                analyzer,
                 &mut data_item_elem,
                 protocol,
                 region,
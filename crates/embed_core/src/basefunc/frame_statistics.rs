@@ -0,0 +1,474 @@
+use crate::basefunc::frame_13762::Frame13762;
+use crate::basefunc::frame_698::Frame698;
+use crate::basefunc::frame_csg::FrameCsg;
+use crate::basefunc::frame_fun::FrameFun;
+use crate::basefunc::protocol::{FrameAnalisyic, ProtocolInfo};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+
+/// 报文头部的统计信息
+#[derive(Debug, Clone, Default)]
+struct FrameHead {
+    address: Option<String>,
+    afn: Option<u8>,
+    seq: Option<u8>,
+    dir: Option<u8>,
+    cs_ok: Option<bool>,
+}
+
+/// 响应时延分布，单位毫秒
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LatencySummary {
+    pub count: usize,
+    pub min: Option<i64>,
+    pub max: Option<i64>,
+    pub avg: Option<f64>,
+    pub buckets: Vec<LatencyBucket>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LatencyBucket {
+    pub label: String,
+    pub count: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TalkerCount {
+    pub address: String,
+    pub count: usize,
+}
+
+/// 报文统计结果
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StatisticsSummary {
+    pub total_frames: usize,
+    pub parse_errors: usize,
+    pub parse_error_rate: f64,
+    pub checksum_errors: usize,
+    pub checksum_error_rate: f64,
+    pub avg_frame_length: f64,
+    pub protocol_distribution: BTreeMap<String, usize>,
+    pub afn_distribution: BTreeMap<String, usize>,
+    pub di_distribution: BTreeMap<String, usize>,
+    pub address_distribution: BTreeMap<String, usize>,
+    pub direction_distribution: BTreeMap<String, usize>,
+    pub latency: LatencySummary,
+    pub top_talkers: Vec<TalkerCount>,
+}
+
+// 时延分布区间上限(毫秒)
+const LATENCY_BUCKETS: [(i64, &str); 5] = [
+    (100, "<100ms"),
+    (500, "100-500ms"),
+    (1000, "500ms-1s"),
+    (3000, "1-3s"),
+    (10000, "3-10s"),
+];
+
+// 下行报文等待应答的最长时间(毫秒)，超时未应答的不再参与时延匹配
+const PENDING_TIMEOUT: i64 = 60_000;
+
+/// 报文统计器，逐帧累加，按需输出汇总结果
+#[derive(Debug, Default)]
+pub struct FrameStatistics {
+    total_frames: usize,
+    total_length: usize,
+    parse_errors: usize,
+    checksum_errors: usize,
+    protocol_count: HashMap<String, usize>,
+    afn_count: HashMap<String, usize>,
+    di_count: HashMap<String, usize>,
+    address_count: HashMap<String, usize>,
+    direction_count: HashMap<String, usize>,
+    latencies: Vec<i64>,
+    // (协议, 地址, AFN, SEQ) -> 下行报文时间
+    pending: HashMap<(String, String, u8, u8), i64>,
+}
+
+impl FrameStatistics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    /// 解析并统计一帧报文
+    ///
+    /// # Arguments
+    ///
+    /// * `frame` - 报文
+    /// * `region` - 省份
+    /// * `timestamp` - 报文收发时间(毫秒)，用于计算响应时延
+    pub fn add_frame(&mut self, frame: &[u8], region: &str, timestamp: Option<i64>) {
+        let (protocol, parsed_data) = FrameAnalisyic::process_frame(frame, region);
+        self.add_parsed_frame(frame, &protocol, &parsed_data, timestamp);
+    }
+
+    /// 统计已解析的报文
+    pub fn add_parsed_frame(
+        &mut self,
+        frame: &[u8],
+        protocol: &str,
+        parsed_data: &[Value],
+        timestamp: Option<i64>,
+    ) {
+        self.total_frames += 1;
+        self.total_length += frame.len();
+        *self.protocol_count.entry(protocol.to_string()).or_insert(0) += 1;
+
+        if parsed_data.is_empty() || protocol == "Unknown" {
+            self.parse_errors += 1;
+        }
+
+        let head = Self::get_frame_head(frame, protocol);
+        if head.cs_ok == Some(false) {
+            self.checksum_errors += 1;
+        }
+        if let Some(afn) = head.afn {
+            let key = format!("{}-{:02X}", protocol, afn);
+            *self.afn_count.entry(key).or_insert(0) += 1;
+        }
+        if let Some(address) = &head.address {
+            *self.address_count.entry(address.clone()).or_insert(0) += 1;
+        }
+        if let Some(dir) = head.dir {
+            let dir_str = if dir == 0 { "下行" } else { "上行" };
+            *self.direction_count.entry(dir_str.to_string()).or_insert(0) += 1;
+        }
+
+        let mut items = Vec::new();
+        Self::collect_data_items(parsed_data, protocol, head.afn, &mut items);
+        for item in items {
+            *self.di_count.entry(item).or_insert(0) += 1;
+        }
+
+        if let (Some(time), Some(address), Some(afn), Some(dir)) =
+            (timestamp, head.address, head.afn, head.dir)
+        {
+            self.pending
+                .retain(|_, send_time| time - *send_time <= PENDING_TIMEOUT);
+            let key = (protocol.to_string(), address, afn, head.seq.unwrap_or(0));
+            if dir == 0 {
+                self.pending.insert(key, time);
+            } else if let Some(send_time) = self.pending.remove(&key) {
+                if time >= send_time {
+                    self.latencies.push(time - send_time);
+                }
+            }
+        }
+    }
+
+    /// 输出统计汇总，top_n为通信最频繁的终端个数
+    pub fn summary(&self, top_n: usize) -> StatisticsSummary {
+        let mut talkers: Vec<TalkerCount> = self
+            .address_count
+            .iter()
+            .map(|(address, count)| TalkerCount {
+                address: address.clone(),
+                count: *count,
+            })
+            .collect();
+        talkers.sort_by(|a, b| b.count.cmp(&a.count).then(a.address.cmp(&b.address)));
+        talkers.truncate(top_n);
+
+        StatisticsSummary {
+            total_frames: self.total_frames,
+            parse_errors: self.parse_errors,
+            parse_error_rate: Self::percent(self.parse_errors, self.total_frames),
+            checksum_errors: self.checksum_errors,
+            checksum_error_rate: Self::percent(self.checksum_errors, self.total_frames),
+            avg_frame_length: if self.total_frames == 0 {
+                0.0
+            } else {
+                self.total_length as f64 / self.total_frames as f64
+            },
+            protocol_distribution: self.protocol_count.clone().into_iter().collect(),
+            afn_distribution: self.afn_count.clone().into_iter().collect(),
+            di_distribution: self.di_count.clone().into_iter().collect(),
+            address_distribution: self.address_count.clone().into_iter().collect(),
+            direction_distribution: self.direction_count.clone().into_iter().collect(),
+            latency: self.latency_summary(),
+            top_talkers: talkers,
+        }
+    }
+
+    fn latency_summary(&self) -> LatencySummary {
+        let mut buckets: Vec<LatencyBucket> = LATENCY_BUCKETS
+            .iter()
+            .map(|(_, label)| LatencyBucket {
+                label: label.to_string(),
+                count: 0,
+            })
+            .collect();
+        buckets.push(LatencyBucket {
+            label: ">=10s".to_string(),
+            count: 0,
+        });
+        for latency in &self.latencies {
+            let index = LATENCY_BUCKETS
+                .iter()
+                .position(|(limit, _)| latency < limit)
+                .unwrap_or(LATENCY_BUCKETS.len());
+            buckets[index].count += 1;
+        }
+
+        let count = self.latencies.len();
+        LatencySummary {
+            count,
+            min: self.latencies.iter().min().cloned(),
+            max: self.latencies.iter().max().cloned(),
+            avg: if count == 0 {
+                None
+            } else {
+                Some(self.latencies.iter().sum::<i64>() as f64 / count as f64)
+            },
+            buckets,
+        }
+    }
+
+    fn percent(count: usize, total: usize) -> f64 {
+        if total == 0 {
+            return 0.0;
+        }
+        (count as f64 * 10000.0 / total as f64).round() / 100.0
+    }
+
+    fn get_frame_head(frame: &[u8], protocol: &str) -> FrameHead {
+        let mut head = FrameHead::default();
+        if protocol == ProtocolInfo::ProtocolCSG13.name() {
+            let mut frame = frame;
+            if frame.len() > 84 && FrameCsg::is_contoine_custom_head(&frame[..84]) {
+                frame = &frame[84..];
+            }
+            if frame.len() < 18 {
+                return head;
+            }
+            let len = frame.len();
+            head.dir = Some(frame[6] >> 7);
+            head.address = Some(format!(
+                "{}{}",
+                FrameFun::get_data_str_reverser(&frame[7..10]),
+                FrameFun::get_data_str_reverser(&frame[10..13])
+            ));
+            head.afn = Some(frame[14]);
+            head.seq = Some(frame[15] & 0x0F);
+            head.cs_ok = Some(FrameFun::calculate_cs(&frame[6..len - 2]) == frame[len - 2]);
+        } else if protocol == ProtocolInfo::ProtocolGDW13761.name() {
+            if frame.len() < 16 {
+                return head;
+            }
+            let len = frame.len();
+            head.dir = Some(frame[6] >> 7);
+            head.address = Some(format!(
+                "{}{}",
+                FrameFun::get_data_str_reverser(&frame[7..9]),
+                FrameFun::get_data_str_reverser(&frame[9..11])
+            ));
+            head.afn = Some(frame[12]);
+            head.seq = Some(frame[13] & 0x0F);
+            head.cs_ok = Some(FrameFun::calculate_cs(&frame[6..len - 2]) == frame[len - 2]);
+        } else if protocol == ProtocolInfo::ProtocolGDW13762.name() {
+            let len = frame.len();
+            if len < 15 {
+                return head;
+            }
+            let mut pos = 10;
+            let address_length = Frame13762::address_length(frame[4]);
+            if address_length > 0 {
+                pos += address_length;
+                if len < pos + 5 {
+                    return head;
+                }
+                head.address = Some(FrameFun::get_data_str_reverser(&frame[pos - 6..pos]));
+            }
+            head.dir = Some(frame[3] >> 7);
+            head.afn = Some(frame[pos]);
+            head.seq = Some(frame[9]);
+            head.cs_ok = Some(FrameFun::calculate_cs(&frame[3..len - 2]) == frame[len - 2]);
+        } else if ProtocolInfo::is_dlt645(protocol) {
+            let pos = FrameFun::get_frame_fe_count(frame);
+            let len = frame.len();
+            if len < pos + 12 {
+                return head;
+            }
+            head.address = Some(FrameFun::get_data_str_reverser(&frame[pos + 1..pos + 7]));
+            head.dir = Some(frame[pos + 8] >> 7);
+            head.afn = Some(frame[pos + 8] & 0x1F);
+            head.cs_ok = Some(FrameFun::calculate_cs(&frame[pos..len - 2]) == frame[len - 2]);
+        } else if protocol == ProtocolInfo::ProtocolCSG16.name() {
+            let len = frame.len();
+            if len < 8 {
+                return head;
+            }
+            let control = frame[3];
+            let mut pos = 4;
+            if (control >> 5) & 0x01 == 1 {
+                if len < pos + 14 {
+                    return head;
+                }
+                head.address = Some(FrameFun::get_data_str_reverser(&frame[pos..pos + 6]));
+                pos += 12;
+            }
+            head.dir = Some(control >> 7);
+            head.afn = Some(frame[pos]);
+            head.seq = Some(frame[pos + 1]);
+            head.cs_ok = Some(FrameFun::calculate_cs(&frame[3..len - 2]) == frame[len - 2]);
+        } else if protocol == ProtocolInfo::ProtocolDLT69845.name() {
+            let frame = &frame[FrameFun::get_frame_fe_count(frame)..];
+            if frame.len() < 5 {
+                return head;
+            }
+            let control = frame[3];
+            let sa_length = (frame[4] & 0x0F) as usize + 1;
+            let mut pos = 8 + sa_length;
+            if control & 0x20 != 0 {
+                pos += 2;
+            }
+            if frame.len() < pos + 4 {
+                return head;
+            }
+            head.dir = Some(control >> 7);
+            head.address = Some(FrameFun::get_data_str_reverser(&frame[5..5 + sa_length]));
+            let tag = frame[pos];
+            head.afn = Some(tag & 0x7F);
+            // 预连接及应用连接类服务无服务子类型
+            head.seq = match tag & 0x7F {
+                0x01..=0x04 => Some(frame[pos + 1] & 0x3F),
+                0x05..=0x09 => Some(frame[pos + 2] & 0x3F),
+                _ => None,
+            };
+            head.cs_ok = Some(Frame698::is_check_ok(frame));
+        }
+        head
+    }
+
+    // 从解析结果的数据标识节点中按原始报文字节提取数据标识
+    fn collect_data_items(
+        parsed_data: &[Value],
+        protocol: &str,
+        afn: Option<u8>,
+        items: &mut Vec<String>,
+    ) {
+        for node in parsed_data {
+            let domain = node
+                .get("frameDomain")
+                .and_then(|v| v.as_str())
+                .unwrap_or("");
+            let data = || {
+                node.get("data")
+                    .and_then(|v| v.as_str())
+                    .and_then(|data| hex::decode(data.replace(' ', "")).ok())
+                    .unwrap_or_default()
+            };
+            if domain == "对象属性描述符" {
+                // 698.45以对象属性描述符作为数据标识
+                let data = data();
+                if data.len() == 4 {
+                    items.push(FrameFun::get_data_str_order(&data));
+                }
+            } else if domain.ends_with("数据标识编码DI") {
+                // 南网数据标识低字节在前
+                let data = data();
+                if data.len() == 4 {
+                    items.push(FrameFun::get_data_str_reverser(&data));
+                }
+            } else if domain == "数据标识编码" {
+                // 南网16及模块协议低字节在前，645报文(含转发中嵌套的645报文)按字节加33H
+                let data = data();
+                if protocol == ProtocolInfo::ProtocolCSG16.name()
+                    || protocol == ProtocolInfo::ProtocolMoudle.name()
+                {
+                    if data.len() == 4 {
+                        items.push(FrameFun::get_data_str_reverser(&data));
+                    }
+                } else if matches!(data.len(), 2 | 4) {
+                    items.push(FrameFun::get_data_str_delete_33h_reverse(&data));
+                }
+            } else if domain.ends_with("信息类DT") || domain.ends_with("数据单元标识DT") {
+                // 376.1/376.2以AFN+Fn作为数据标识
+                let data = data();
+                if let (Some(afn), 2) = (afn, data.len()) {
+                    let (_, fns) = FrameFun::calculate_fn(&data);
+                    items.extend(
+                        fns.iter()
+                            .map(|fn_value| format!("{:02X}F{}", afn, fn_value)),
+                    );
+                }
+            }
+            if let Some(children) = node.get("children").and_then(|v| v.as_array()) {
+                Self::collect_data_items(children, protocol, afn, items);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 南网读当前数据，DA=P0，DI=E0000130
+    fn csg_frame(control: u8, seq: u8) -> Vec<u8> {
+        let mut body = vec![control, 0x00, 0x44, 0x12, 0x34, 0x56, 0x00, 0x00, 0x0C, seq];
+        body.extend_from_slice(&[0x00, 0x00, 0x30, 0x01, 0x00, 0xE0]);
+        let [low, high] = (body.len() as u16).to_le_bytes();
+        let mut frame = vec![0x68, low, high, low, high, 0x68];
+        frame.extend_from_slice(&body);
+        frame.push(FrameFun::calculate_cs(&body));
+        frame.push(0x16);
+        frame
+    }
+
+    #[test]
+    fn latency_pairing() {
+        let mut statistics = FrameStatistics::new();
+        statistics.add_frame(&csg_frame(0x4A, 0x61), "南网", Some(1_000));
+        statistics.add_frame(&csg_frame(0x4A, 0x62), "南网", Some(1_100));
+        // 按SEQ配对，应答顺序与请求不同
+        statistics.add_frame(&csg_frame(0x88, 0x62), "南网", Some(1_150));
+        statistics.add_frame(&csg_frame(0x88, 0x61), "南网", Some(1_600));
+        // 没有对应请求的应答不计时延
+        statistics.add_frame(&csg_frame(0x88, 0x63), "南网", Some(1_700));
+
+        let summary = statistics.summary(5);
+        assert_eq!(summary.total_frames, 5);
+        assert_eq!(summary.parse_errors, 0);
+        assert_eq!(summary.direction_distribution["下行"], 2);
+        assert_eq!(summary.direction_distribution["上行"], 3);
+        assert_eq!(summary.di_distribution["E0000130"], 5);
+        assert_eq!(summary.latency.count, 2);
+        assert_eq!(summary.latency.min, Some(50));
+        assert_eq!(summary.latency.max, Some(600));
+        let bucket = |label: &str| {
+            summary
+                .latency
+                .buckets
+                .iter()
+                .find(|bucket| bucket.label == label)
+                .unwrap()
+                .count
+        };
+        assert_eq!(bucket("<100ms"), 1);
+        assert_eq!(bucket("500ms-1s"), 1);
+        assert!(statistics.pending.is_empty());
+    }
+
+    #[test]
+    fn pending_timeout_eviction() {
+        let mut statistics = FrameStatistics::new();
+        statistics.add_frame(&csg_frame(0x4A, 0x61), "南网", Some(0));
+        statistics.add_frame(&csg_frame(0x4A, 0x62), "南网", Some(PENDING_TIMEOUT));
+        assert_eq!(statistics.pending.len(), 2);
+
+        // 超时未应答的请求被清除，之后的应答不再配对
+        statistics.add_frame(&csg_frame(0x88, 0x61), "南网", Some(PENDING_TIMEOUT + 1));
+        assert_eq!(statistics.pending.len(), 1);
+        assert_eq!(statistics.summary(5).latency.count, 0);
+
+        statistics.add_frame(&csg_frame(0x88, 0x62), "南网", Some(PENDING_TIMEOUT + 10));
+        assert!(statistics.pending.is_empty());
+        assert_eq!(statistics.summary(5).latency.min, Some(10));
+    }
+}
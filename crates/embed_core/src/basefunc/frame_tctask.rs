@@ -1,11 +1,12 @@
+use crate::analyzer::Analyzer;
+use crate::basefunc::axdr::{AxdrReader, Ms, DT_ARRAY, DT_MS, DT_OAD, DT_STRUCTURE};
 use crate::basefunc::frame_err::CustomError;
 use crate::basefunc::frame_fun::FrameFun;
-use crate::config::oadmapconfig::TaskOadConfigManager;
-use crate::config::xmlconfig::ProtocolConfigManager;
 use crate::basefunc::frame_csg::FrameCsg;
 use crate::basefunc::protocol::{FrameAnalisyic, ProtocolInfo};
 use serde_json::Value;
 use tracing::info;
+const MS_TYPE_NONE: u8 = 0x00; //无电能表*/
 const MS_TYPE_ALL_USER: u8 = 0x01; //全部用户类型*/
 const MS_TYPE_A_SET_OF_USER: u8 = 0x02; //一组用户类型 */
 const MS_TYPE_A_SET_OF_ADDRESSES: u8 = 0x03; //一组用户地址*/
@@ -49,13 +50,17 @@ impl TCMeterTask {
         }
     }
 
-    pub fn get_sub_oad_info(master_oad: &str, sub_oad: &str, region: &str) -> (String, String) {
-        let result = TaskOadConfigManager::get_voad(master_oad, sub_oad);
+    pub fn get_sub_oad_info(
+        analyzer: &Analyzer,
+        master_oad: &str,
+        sub_oad: &str,
+        region: &str,
+    ) -> (String, String) {
+        let result = analyzer.get_voad(master_oad, sub_oad);
         if let Some(item) = result {
             let item_set = item.item_07.to_uppercase();
             let protocol = ProtocolInfo::ProtocolDLT64507.name().to_string();
-            let template_element =
-                ProtocolConfigManager::get_config_xml(&item_set, &protocol, region, None);
+            let template_element = analyzer.get_config_xml(&item_set, &protocol, region, None);
             if let Some(item_ele) = template_element {
                 if let Some(name) = item_ele.get_child_text("name") {
                     return (item_set, name);
@@ -77,23 +82,24 @@ impl TCMeterTask {
         }
     }
 
+    /// 按MS配置文件解析MS内容，未配置该MS类型或内容长度不足时返回None
     pub fn get_ms_len_new(
+        analyzer: &Analyzer,
         ms_type: u8,
         task_content: &[u8],
         start_pos: usize,
-    ) -> (usize, String, Vec<Value>) {
+    ) -> Option<(usize, String, Vec<Value>)> {
         info!("get_ms_len_new task_content{:?}", task_content);
         let region = "南网";
         let protocol = ProtocolInfo::ProtocolMS.name().to_string();
         let ms_str = format!("{:02X}", ms_type);
-        let data_item_elem = ProtocolConfigManager::get_config_xml(
+        let data_item_elem = analyzer.get_config_xml(
             &ms_str,
             &protocol,
             "南网",
             None,
         );
 
-        let mut item_data: Vec<Value> = Vec::new();
         let dis_data_identifier: String;
         let pos:usize = 0;
         if let Some(mut data_item_elem) = data_item_elem {
@@ -102,6 +108,7 @@ impl TCMeterTask {
             let name = data_item_elem.get_child_text("name").unwrap();
             let (sub_length, sub_datament) = if sub_length_cont.to_uppercase() == "UNKNOWN" {
                 let sub_length = FrameCsg::calculate_item_length(
+                    analyzer,
                     &mut data_item_elem,
                     &task_content[pos..],
                     &protocol,
@@ -109,6 +116,9 @@ impl TCMeterTask {
                     None,
                     None,
                 );
+                if pos + sub_length > task_content.len() {
+                    return None;
+                }
                 let new_segment = &task_content[pos..pos + sub_length];
                 (sub_length, new_segment)
             } else {
@@ -121,7 +131,8 @@ impl TCMeterTask {
             };
             data_item_elem.update_value("length", sub_length.to_string());
             info!("get_ms_len_new {:?} {:?}", sub_datament, sub_length);
-            item_data = FrameAnalisyic::prase_data(
+            let item_data = FrameAnalisyic::prase_data(
+                analyzer,
                 &mut data_item_elem,
                 &protocol,
                 region,
@@ -129,556 +140,322 @@ impl TCMeterTask {
                 start_pos + pos,
                 None,
             );
-            return (sub_length, name, item_data);
+            return Some((sub_length, name, item_data));
         }
-        return (0, "未知类型".to_string(), item_data);
+        None
     }
+    /// 按A-XDR解析MS内容，task_content从MS类型之后开始，返回内容长度及描述
     pub fn get_ms_len(
         ms_type: u8,
         task_content: &[u8],
         sub_result: &mut Vec<Value>,
         start_pos: usize,
-    ) -> (usize, String) {
-        let mut pos = 0;
-
-        match ms_type {
-            MS_TYPE_ALL_USER => {
-                return (0, "全部用户类型".to_string());
-            }
-            MS_TYPE_A_SET_OF_USER => {
-                pos += 1;
-                let mut ms_result = vec![];
-                for i in 0..task_content[0] {
-                    let dis_data_identifier = format!("用户类型:{}", task_content[pos]);
-                    FrameFun::add_data(
-                        &mut ms_result,
-                        format!("<第{}组>用户类型", i + 1),
-                        FrameFun::get_data_str_with_space(&task_content[pos..pos + 1]),
-                        dis_data_identifier,
-                        vec![start_pos + pos, start_pos + pos + 1],
-                        None,
-                        None,
-                    );
-                    pos += 1;
-                }
-                let dis_data_identifier = format!("用户类型个数:{}", task_content[0]);
-                FrameFun::add_data(
-                    sub_result,
-                    "用户类型个数".to_string(),
-                    FrameFun::get_data_str_with_space(&[task_content[0]]),
-                    dis_data_identifier,
-                    vec![start_pos, start_pos + 1],
-                    Some(ms_result),
-                    None,
-                );
-                return (pos, "一组用户类型".to_string());
-            }
-            MS_TYPE_A_SET_OF_ADDRESSES => {
-                pos += 1;
-                let mut ms_result = vec![];
-                for i in 0..task_content[0] {
-                    pos += 1;
-                    let len = (task_content[pos] + 1) as usize;
-                    pos += 1;
-                    let address = FrameFun::get_data_str_order(&task_content[pos..pos + len]);
-                    FrameFun::add_data(
-                        &mut ms_result,
-                        format!("<第{}组>用户地址", i + 1),
-                        FrameFun::get_data_str_with_space(&task_content[pos..pos + len]),
-                        address,
-                        vec![start_pos + pos, start_pos + pos + len],
-                        None,
-                        None,
-                    );
-                    pos += len;
-                }
-                let dis_data_identifier = format!("用户地址个数:{}", task_content[0]);
-                FrameFun::add_data(
-                    sub_result,
-                    "用户地址个数".to_string(),
-                    FrameFun::get_data_str_with_space(&[task_content[0]]),
-                    dis_data_identifier,
-                    vec![start_pos, start_pos + 1],
-                    Some(ms_result),
-                    None,
-                );
-                return (pos, "一组用户地址".to_string());
-            }
-            MS_TYPE_A_SET_OF_NUMBERS => {
-                pos += 1;
-                let mut ms_result = vec![];
-                for i in 0..task_content[0] {
-                    let range_type = Self::get_range_type(task_content[pos]);
-                    let spot_id = FrameFun::cosem_bin2_int32u(&task_content[pos..pos + 2]);
-                    let dis_data_identifier = format!("测量点号:{:04}", spot_id);
-                    FrameFun::add_data(
-                        &mut ms_result,
-                        format!("<第{}组>测量点号", i + 1),
-                        FrameFun::get_data_str_with_space(&task_content[pos..pos + 2]),
-                        dis_data_identifier,
-                        vec![start_pos + pos, start_pos + pos + 2],
-                        None,
-                        None,
-                    );
-                    pos += 2;
-                }
-                let dis_data_identifier = format!("测量点个数:{:02}", task_content[0]);
-                FrameFun::add_data(
-                    sub_result,
-                    "测量点个数".to_string(),
-                    FrameFun::get_data_str_with_space(&[task_content[0]]),
-                    dis_data_identifier,
-                    vec![start_pos, start_pos + 1],
-                    Some(ms_result),
-                    None,
-                );
-                return (pos, "一组配置序号".to_string());
-            }
-            MS_TYPE_A_RANGE_OF_USER_TYPES => {
-                pos += 1;
-                let mut ms_result = vec![];
-                for i in 0..task_content[0] {
-                    let range_type = Self::get_range_type(task_content[pos]);
-                    FrameFun::add_data(
-                        &mut ms_result,
-                        format!("<第{}组>区间类型", i + 1),
-                        FrameFun::get_data_str_with_space(&task_content[pos..pos + 1]),
-                        range_type.to_string(),
-                        vec![start_pos + pos, start_pos + pos + 1],
-                        None,
-                        None,
-                    );
-                    pos += 2;
-                    let user1 = format!("用户类型:{}", task_content[pos]);
-                    FrameFun::add_data(
-                        &mut ms_result,
-                        format!("<第{}组>用户类型", i + 1),
-                        FrameFun::get_data_str_with_space(&task_content[pos..pos + 1]),
-                        user1,
-                        vec![start_pos + pos, start_pos + pos + 1],
-                        None,
-                        None,
-                    );
-                    pos += 2;
-                    let user2 = format!("用户类型:{}", task_content[pos]);
-                    FrameFun::add_data(
-                        &mut ms_result,
-                        format!("<第{}组>用户类型", i + 1),
-                        FrameFun::get_data_str_with_space(&task_content[pos..pos + 1]),
-                        user2,
-                        vec![start_pos + pos, start_pos + pos + 1],
-                        None,
-                        None,
-                    );
-                    pos += 1;
-                }
-                let dis_data_identifier = format!("区间个数:{:02}", task_content[0]);
-                FrameFun::add_data(
-                    sub_result,
-                    "区间个数".to_string(),
-                    FrameFun::get_data_str_with_space(&[task_content[0]]),
-                    dis_data_identifier,
-                    vec![start_pos, start_pos + 1],
-                    Some(ms_result),
-                    None,
-                );
-                return (pos, "一组用户类型区间".to_string());
-            }
-            MS_TYPE_A_SET_OF_USER_ADDRESS_RANGES => {
-                pos += 1;
-                let mut ms_result = vec![];
-                for i in 0..task_content[0] {
-                    let range_type = Self::get_range_type(task_content[pos]);
-                    FrameFun::add_data(
-                        &mut ms_result,
-                        format!("<第{}组>区间类型", i + 1),
-                        FrameFun::get_data_str_with_space(&task_content[pos..pos + 1]),
-                        range_type.to_string(),
-                        vec![start_pos + pos, start_pos + pos + 1],
-                        None,
-                        None,
-                    );
-                    pos += 3;
-                    let len = (task_content[pos] + 1) as usize;
-                    pos += 1;
-                    let address = FrameFun::get_data_str_order(&task_content[pos..pos + len]);
-                    FrameFun::add_data(
-                        &mut ms_result,
-                        format!("<第{}组>用户地址", i + 1),
-                        FrameFun::get_data_str_with_space(&task_content[pos..pos + len]),
-                        address,
-                        vec![start_pos + pos, start_pos + pos + len],
-                        None,
-                        None,
-                    );
-                    pos += len;
-                    pos += 2;
-                    let len = (task_content[pos] + 1) as usize;
-                    pos += 1;
-                    let address = FrameFun::get_data_str_order(&task_content[pos..pos + len]);
-                    FrameFun::add_data(
-                        &mut ms_result,
-                        format!("<第{}组>用户地址", i + 1),
-                        FrameFun::get_data_str_with_space(&task_content[pos..pos + len]),
-                        address,
-                        vec![start_pos + pos, start_pos + pos + len],
-                        None,
-                        None,
-                    );
-                    pos += len;
-                }
-                let dis_data_identifier = format!("区间个数:{:02}", task_content[0]);
-                FrameFun::add_data(
-                    sub_result,
-                    "区间个数".to_string(),
-                    FrameFun::get_data_str_with_space(&[task_content[0]]),
-                    dis_data_identifier,
-                    vec![start_pos, start_pos + 1],
-                    Some(ms_result),
-                    None,
-                );
-                return (pos, "一组用户地址区间".to_string());
-            }
-            MS_TYPE_A_SET_OF_NUMBER_RANGES => {
-                pos += 1;
-                let mut ms_result = vec![];
-                for i in 0..task_content[0] {
-                    let range_type = Self::get_range_type(task_content[pos]);
-                    FrameFun::add_data(
-                        &mut ms_result,
-                        format!("<第{}组>区间类型", i + 1),
-                        FrameFun::get_data_str_with_space(&task_content[pos..pos + 1]),
-                        range_type.to_string(),
-                        vec![start_pos + pos, start_pos + pos + 1],
-                        None,
-                        None,
-                    );
-                    pos += 2;
-                    let spot_id = FrameFun::cosem_bin2_int32u(&task_content[pos..pos + 2]);
-                    let dis_data_identifier = format!("测量点号:{:04}", spot_id);
-                    FrameFun::add_data(
-                        &mut ms_result,
-                        format!("<第{}组>测量点号", i + 1),
-                        FrameFun::get_data_str_with_space(&task_content[pos..pos + 2]),
-                        dis_data_identifier,
-                        vec![start_pos + pos, start_pos + pos + 2],
-                        None,
-                        None,
-                    );
-                    pos += 2;
-                    pos += 1;
-                    let spot_id = FrameFun::cosem_bin2_int32u(&task_content[pos..pos + 2]);
-                    let dis_data_identifier = format!("测量点号:{:04}", spot_id);
-                    FrameFun::add_data(
-                        &mut ms_result,
-                        format!("<第{}组>测量点号", i + 1),
-                        FrameFun::get_data_str_with_space(&task_content[pos..pos + 2]),
-                        dis_data_identifier,
-                        vec![start_pos + pos, start_pos + pos + 2],
-                        None,
-                        None,
-                    );
-                    pos += 2;
-                }
-                let dis_data_identifier = format!("测量点个数:{:02}", task_content[0]);
-                FrameFun::add_data(
-                    sub_result,
-                    "测量点个数".to_string(),
-                    FrameFun::get_data_str_with_space(&[task_content[0]]),
-                    dis_data_identifier,
-                    vec![start_pos, start_pos + 1],
-                    Some(ms_result),
-                    None,
-                );
-                return (pos, "一组配置序号".to_string());
-            }
-            MS_TYPE_ALL_USER_WITHOUT_JC => {
-                return (0, "除交采外所有表".to_string());
-            }
-            MS_TYPE_A_SET_OF_VIP_USER_BY_PORT => {
-                pos += 1;
-                let mut ms_result = Vec::new();
-                for i in 0..task_content[0] {
-                    let dis_data_identifier = format!("用户类型:{}", task_content[pos]);
-                    FrameFun::add_data(
-                        &mut ms_result,
-                        format!("<第{}组>用户类型", i + 1),
-                        FrameFun::get_data_str_with_space(&task_content[pos..pos + 1]),
-                        dis_data_identifier.clone(),
-                        vec![start_pos + pos, start_pos + pos + 1],
-                        None,
-                        None,
-                    );
-                    pos += 1;
-                }
-                let dis_data_identifier = format!("用户类型个数:{}", task_content[0]);
-                FrameFun::add_data(
-                    sub_result,
-                    "用户类型个数".to_string(),
-                    FrameFun::get_data_str_with_space(&[task_content[0]]),
-                    dis_data_identifier,
-                    vec![start_pos, start_pos + 1],
-                    Some(ms_result),
-                    None,
-                );
-                let dis_data_identifier =
-                    "一组重点用户类型区分端口(300<=任务号<=500:载波，其他：非载波)".to_string();
-                return (pos, dis_data_identifier);
+    ) -> Option<(usize, String)> {
+        let mut reader = AxdrReader::new(task_content);
+        let name = match ms_type {
+            MS_TYPE_NONE | MS_TYPE_ALL_USER => Ms::kind_name(ms_type).to_string(),
+            MS_TYPE_A_SET_OF_USER => Self::read_ms_list(
+                &mut reader,
+                sub_result,
+                start_pos,
+                "用户类型",
+                |r| Ok(format!("用户类型:{}", r.read_u8()?)),
+            )
+            .map(|_| Ms::kind_name(ms_type).to_string())
+            .ok()?,
+            MS_TYPE_A_SET_OF_ADDRESSES => Self::read_ms_list(
+                &mut reader,
+                sub_result,
+                start_pos,
+                "用户地址",
+                |r| Ok(r.read_tsa()?.to_string()),
+            )
+            .map(|_| Ms::kind_name(ms_type).to_string())
+            .ok()?,
+            MS_TYPE_A_SET_OF_NUMBERS => Self::read_ms_list(
+                &mut reader,
+                sub_result,
+                start_pos,
+                "测量点号",
+                |r| Ok(format!("测量点号:{:04}", r.read_u16()?)),
+            )
+            .map(|_| Ms::kind_name(ms_type).to_string())
+            .ok()?,
+            MS_TYPE_A_RANGE_OF_USER_TYPES
+            | MS_TYPE_A_SET_OF_USER_ADDRESS_RANGES
+            | MS_TYPE_A_SET_OF_NUMBER_RANGES => Self::read_ms_list(
+                &mut reader,
+                sub_result,
+                start_pos,
+                "区间",
+                |r| Ok(r.read_region()?.to_string()),
+            )
+            .map(|_| Ms::kind_name(ms_type).to_string())
+            .ok()?,
+            MS_TYPE_ALL_USER_WITHOUT_JC | MS_TYPE_VIP_USER_TYPES => {
+                Self::ext_kind_name(ms_type).to_string()
             }
-            MS_TYPE_A_SET_OF_USER_BY_PORT => {
-                pos += 1;
-                let mut ms_result = Vec::new();
-                for i in 0..task_content[0] {
-                    let dis_data_identifier = format!("用户类型:{}", task_content[pos]);
-                    FrameFun::add_data(
-                        &mut ms_result,
-                        format!("<第{}组>用户类型", i + 1),
-                        FrameFun::get_data_str_with_space(&task_content[pos..pos + 1]),
-                        dis_data_identifier.clone(),
-                        vec![start_pos + pos, start_pos + pos + 1],
-                        None,
-                        None,
-                    );
-                    pos += 1;
-                }
-                let dis_data_identifier = format!("用户类型个数:{}", task_content[0]);
-                FrameFun::add_data(
-                    sub_result,
-                    "用户类型个数".to_string(),
-                    FrameFun::get_data_str_with_space(&[task_content[0]]),
-                    dis_data_identifier,
-                    vec![start_pos, start_pos + 1],
-                    Some(ms_result),
-                    None,
-                );
-                let dis_data_identifier =
-                    "一组用户类型区分端口(300<=任务号<=500:载波，其他：非载波)".to_string();
-                return (pos, dis_data_identifier);
+            MS_TYPE_A_SET_OF_VIP_USER_BY_PORT | MS_TYPE_A_SET_OF_USER_BY_PORT => {
+                Self::read_ms_list(&mut reader, sub_result, start_pos, "用户类型", |r| {
+                    Ok(format!("用户类型:{}", r.read_u8()?))
+                })
+                .map(|_| Self::ext_kind_name(ms_type).to_string())
+                .ok()?
             }
-
             MS_TYPE_A_GROUP_OF_VIP_USER_TYPES => {
-                let dis_data_identifier = format!("用户类型:{:02}", task_content[pos]);
+                let user_type = reader.read_u8().ok()?;
                 FrameFun::add_data(
                     sub_result,
                     "用户类型".to_string(),
-                    FrameFun::get_data_str_with_space(&[task_content[pos]]),
-                    dis_data_identifier,
-                    vec![start_pos + pos, start_pos + pos + 1],
+                    format!("{:02X}", user_type),
+                    format!("用户类型:{:02}", user_type),
+                    vec![start_pos, start_pos + 1],
                     None,
                     None,
                 );
-                pos += 1;
-                let dis_data_identifier = "一组重点用户类型".to_string();
-                return (pos, dis_data_identifier);
+                Self::ext_kind_name(ms_type).to_string()
             }
-
             MS_TYPE_A_SET_OF_USER_EVENT_LEVELS => {
-                pos += 1;
-                let mut ms_result = Vec::new();
-                for i in 0..task_content[0] {
-                    let dis_data_identifier = format!("事件等级:{:02}", task_content[pos]);
-                    FrameFun::add_data(
-                        &mut ms_result,
-                        format!("<第{}组>事件等级", i + 1),
-                        FrameFun::get_data_str_with_space(&[task_content[pos]]),
-                        dis_data_identifier.clone(),
-                        vec![start_pos + pos, start_pos + pos + 1],
-                        None,
-                        None,
-                    );
-                    pos += 1;
-                }
-                let dis_data_identifier = format!("事件等级个数:{:02}", task_content[0]);
-                FrameFun::add_data(
-                    sub_result,
-                    "事件等级个数".to_string(),
-                    FrameFun::get_data_str_with_space(&[task_content[0]]),
-                    dis_data_identifier,
-                    vec![start_pos, start_pos + 1],
-                    Some(ms_result),
-                    None,
-                );
-                let dis_data_identifier = "一组用户事件等级".to_string();
-                return (pos, dis_data_identifier);
-            }
-
-            MS_TYPE_VIP_USER_TYPES => {
-                let dis_data_identifier = "重点用户".to_string();
-                return (0, dis_data_identifier);
+                Self::read_ms_list(&mut reader, sub_result, start_pos, "事件等级", |r| {
+                    Ok(format!("事件等级:{:02}", r.read_u8()?))
+                })
+                .map(|_| Self::ext_kind_name(ms_type).to_string())
+                .ok()?
             }
-
             MS_TYPE_A_SET_OF_USER_PORT_NUMBERS => {
-                pos += 1;
-                let mut ms_result = Vec::new();
-                for i in 0..task_content[0] {
-                    let dis_data_identifier = format!("端口号:{:02X}", task_content[pos]);
-                    FrameFun::add_data(
-                        &mut ms_result,
-                        format!("<第{}组>端口号", i + 1),
-                        FrameFun::get_data_str_with_space(&[task_content[pos]]),
-                        dis_data_identifier.clone(),
-                        vec![start_pos + pos, start_pos + pos + 1],
-                        None,
-                        None,
-                    );
-                    pos += 1;
-                }
-                let dis_data_identifier = format!("端口号个数:{:02}", task_content[0]);
-                FrameFun::add_data(
-                    sub_result,
-                    "端口号个数".to_string(),
-                    FrameFun::get_data_str_with_space(&[task_content[0]]),
-                    dis_data_identifier,
-                    vec![start_pos, start_pos + 1],
-                    Some(ms_result),
-                    None,
-                );
-                let dis_data_identifier = "一组端口号".to_string();
-                return (pos, dis_data_identifier);
+                Self::read_ms_list(&mut reader, sub_result, start_pos, "端口号", |r| {
+                    Ok(format!("端口号:{:02X}", r.read_u8()?))
+                })
+                .map(|_| Self::ext_kind_name(ms_type).to_string())
+                .ok()?
             }
+            _ => return None,
+        };
+        Some((reader.position(), name))
+    }
 
-            _ => {
-                // 处理未知 ms_type 的情况
-                return (task_content.len(), "未知的 MS 类型".to_string());
+    // 扩展MS类型的名称
+    fn ext_kind_name(ms_type: u8) -> &'static str {
+        match ms_type {
+            MS_TYPE_ALL_USER_WITHOUT_JC => "除交采外的所有表",
+            MS_TYPE_A_SET_OF_VIP_USER_BY_PORT | MS_TYPE_A_SET_OF_USER_BY_PORT => {
+                "一组重点用户类型区分端口"
             }
+            MS_TYPE_A_GROUP_OF_VIP_USER_TYPES => "一组重点用户类型",
+            MS_TYPE_A_SET_OF_USER_EVENT_LEVELS => "一组用户事件等级",
+            MS_TYPE_VIP_USER_TYPES => "重点用户",
+            MS_TYPE_A_SET_OF_USER_PORT_NUMBERS => "一组端口号",
+            _ => "未知类型",
         }
     }
 
+    // SEQUENCE OF的MS内容：个数 + 若干项，返回个数的描述
+    fn read_ms_list<F>(
+        reader: &mut AxdrReader,
+        sub_result: &mut Vec<Value>,
+        start_pos: usize,
+        item_name: &str,
+        mut item: F,
+    ) -> Result<String, String>
+    where
+        F: FnMut(&mut AxdrReader) -> Result<String, String>,
+    {
+        let count = reader.read_length()?;
+        let count_data = reader.bytes(0);
+        let mut ms_result = vec![];
+        for i in 0..count {
+            let pos = reader.position();
+            let dis_data_identifier = item(reader)?;
+            FrameFun::add_data(
+                &mut ms_result,
+                format!("<第{}组>{}", i + 1, item_name),
+                FrameFun::get_data_str_with_space(reader.bytes(pos)),
+                dis_data_identifier,
+                vec![start_pos + pos, start_pos + reader.position()],
+                None,
+                None,
+            );
+        }
+        let dis_data_identifier = format!("{}个数:{}", item_name, count);
+        FrameFun::add_data(
+            sub_result,
+            format!("{}个数", item_name),
+            FrameFun::get_data_str_with_space(count_data),
+            dis_data_identifier.clone(),
+            vec![start_pos, start_pos + count_data.len()],
+            Some(ms_result),
+            None,
+        );
+        Ok(dis_data_identifier)
+    }
+
+    fn read_oad(reader: &mut AxdrReader, index: usize) -> Result<(u32, String, Vec<usize>), String> {
+        reader.expect_tag(DT_OAD)?;
+        let pos = reader.position();
+        let oad = reader.read_u32()?;
+        Ok((
+            oad,
+            FrameFun::get_data_str_with_space(reader.bytes(pos)),
+            vec![index + pos, index + reader.position()],
+        ))
+    }
+
     pub fn analysic_meter_task(
+        analyzer: &Analyzer,
         task_content: &[u8],
         result_list: &mut Vec<Value>,
         index: usize,
         region: &str,
     ) -> Result<(), CustomError> {
-        let oad_count = task_content[1];
+        let mut reader = AxdrReader::new(task_content);
+        let oad_count = reader
+            .expect_tag(DT_ARRAY)
+            .and_then(|_| reader.read_length())
+            .map_err(|e| CustomError::new(1, e))?;
         if oad_count == 0 {
             return Ok(());
         }
-        let mut pos = 1;
         let dis_data_identifier = format!("采集数据项个数：{}", oad_count);
         FrameFun::add_data(
             result_list,
             "数据项个数".to_string(),
-            FrameFun::get_data_str_with_space(&[oad_count]),
+            FrameFun::get_data_str_with_space(reader.bytes(1)),
             dis_data_identifier,
-            vec![index + pos, index + pos + 1],
+            vec![index + 1, index + reader.position()],
             None,
             None,
         );
-        pos = 2;
 
         for i in 0..oad_count {
-            let start_pos = pos;
+            let start_pos = reader.position();
             let mut sub_result = vec![];
-            pos += 3;
-
-            let (master_oad, len) = Self::get_oad(&task_content[pos..]);
-            let oad_info = Self::get_master_oad_info(master_oad);
-            let masterr_str = format!("{:08X}", master_oad);
-
-            let dis_data_identifier = if let Some(oad_str) = oad_info {
-                format!("主数据项:{}-{}", masterr_str, oad_str)
-            } else {
-                format!("主数据项:{}", masterr_str)
-            };
+            let master_oad =
+                Self::analysic_task_item(analyzer, &mut reader, &mut sub_result, index, region)
+                    .map_err(|e| CustomError::new(1, e))?;
 
+            let dis_data_identifier = format!("<第{}组>数据采集:{:08X}", i + 1, master_oad);
             FrameFun::add_data(
-                &mut sub_result,
-                "主数据项".to_string(),
-                FrameFun::get_data_str_with_space(&task_content[pos..pos + len]),
+                result_list,
+                format!("<第{}组>数据采集", i + 1),
+                FrameFun::get_data_str_with_space(reader.bytes(start_pos)),
                 dis_data_identifier,
-                vec![index + pos, index + pos + len],
-                None,
+                vec![index + start_pos, index + reader.position()],
+                Some(sub_result),
                 None,
             );
-            pos += len + 1;
+        }
 
-            let (sub_oad, len) = Self::get_oad(&task_content[pos..]);
-            let sud_oad_str = format!("{:08X}", sub_oad);
-            let (item, info) = Self::get_sub_oad_info(&masterr_str, &sud_oad_str, region);
-            let dis_data_identifier = if !item.is_empty() {
-                if !info.is_empty() {
-                    format!("分数据项:{}-{}:{}", sud_oad_str, item, info)
-                } else {
-                    format!("分数据项:{}-{}", sud_oad_str, item)
-                }
-            } else {
-                format!("分数据项:{}", sud_oad_str)
-            };
+        Ok(())
+    }
 
-            FrameFun::add_data(
-                &mut sub_result,
-                "分数据项".to_string(),
-                FrameFun::get_data_str_with_space(&task_content[pos..pos + len]),
-                dis_data_identifier,
-                vec![index + pos, index + pos + len],
-                None,
-                None,
-            );
-            pos += len + 1;
+    // 一组数据采集：structure{主OAD, 分OAD, 分OAD, ..., MS}
+    fn analysic_task_item(
+        analyzer: &Analyzer,
+        reader: &mut AxdrReader,
+        sub_result: &mut Vec<Value>,
+        index: usize,
+        region: &str,
+    ) -> Result<u32, String> {
+        reader.expect_tag(DT_STRUCTURE)?;
+        reader.read_length()?;
 
-            let (sub_oad, len) = Self::get_oad(&task_content[pos..]);
-            let dis_data_identifier = format!("分数据项:{:08X}", sub_oad);
-            FrameFun::add_data(
-                &mut sub_result,
-                "分数据项".to_string(),
-                FrameFun::get_data_str_with_space(&task_content[pos..pos + len]),
-                dis_data_identifier,
-                vec![index + pos, index + pos + len],
-                None,
-                None,
-            );
-            pos += len;
-            pos += 6; // 假设这是固定的偏移量
-            pos += 1;
+        let (master_oad, data, position) = Self::read_oad(reader, index)?;
+        let oad_info = Self::get_master_oad_info(master_oad);
+        let masterr_str = format!("{:08X}", master_oad);
+        let dis_data_identifier = if let Some(oad_str) = oad_info {
+            format!("主数据项:{}-{}", masterr_str, oad_str)
+        } else {
+            format!("主数据项:{}", masterr_str)
+        };
+        FrameFun::add_data(
+            sub_result,
+            "主数据项".to_string(),
+            data,
+            dis_data_identifier,
+            position,
+            None,
+            None,
+        );
 
-            let ms_type = task_content[pos];
-            pos += 1;
-            // let (len, me_info) =
-            //     Self::get_ms_len(ms_type, &task_content[pos..], &mut ms_data, pos + index);
+        let (sub_oad, data, position) = Self::read_oad(reader, index)?;
+        let sud_oad_str = format!("{:08X}", sub_oad);
+        let (item, info) = Self::get_sub_oad_info(analyzer, &masterr_str, &sud_oad_str, region);
+        let dis_data_identifier = if !item.is_empty() {
+            if !info.is_empty() {
+                format!("分数据项:{}-{}:{}", sud_oad_str, item, info)
+            } else {
+                format!("分数据项:{}-{}", sud_oad_str, item)
+            }
+        } else {
+            format!("分数据项:{}", sud_oad_str)
+        };
+        FrameFun::add_data(
+            sub_result,
+            "分数据项".to_string(),
+            data,
+            dis_data_identifier,
+            position,
+            None,
+            None,
+        );
 
-            let (len, me_info, ms_data) = Self::get_ms_len_new(ms_type, &task_content[pos..], pos + index);
-            FrameFun::add_data(
-                &mut sub_result,
-                "MS".to_string(),
-                FrameFun::get_data_str_with_space(&[ms_type]),
-                me_info,
-                vec![index + pos - 1, index + pos],
-                None,
-                None,
-            );
+        let (sub_oad, data, position) = Self::read_oad(reader, index)?;
+        let dis_data_identifier = format!("分数据项:{:08X}", sub_oad);
+        FrameFun::add_data(
+            sub_result,
+            "分数据项".to_string(),
+            data,
+            dis_data_identifier,
+            position,
+            None,
+            None,
+        );
 
-            if len > 0 {
-                let dis_data_identifier = format!(
-                    "MS内容:{}",
-                    FrameFun::get_data_str_order(&task_content[pos + index..pos + index + len])
-                );
-                FrameFun::add_data(
-                    &mut sub_result,
-                    "MS内容".to_string(),
-                    FrameFun::get_data_str_with_space(
-                        &task_content[pos + index..pos + index + len],
-                    ),
-                    dis_data_identifier,
-                    vec![pos + index, pos + index + len],
-                    Some(ms_data),
-                    None,
-                );
+        // MS之前的其余成员不展示
+        while reader.peek()? != DT_MS {
+            reader.read_data()?;
+        }
+        reader.read_u8()?;
+        let ms_type = reader.read_u8()?;
+        let pos = reader.position();
+
+        // 配置文件的解析结果带端口等说明，与A-XDR解析的长度一致时优先使用，
+        // 否则以A-XDR为准；A-XDR不支持的MS类型只按配置文件解析
+        let mut axdr_data = vec![];
+        let axdr = Self::get_ms_len(ms_type, reader.remaining(), &mut axdr_data, pos + index);
+        let config = Self::get_ms_len_new(analyzer, ms_type, reader.remaining(), pos + index);
+        let (len, me_info, ms_data) = match (config, axdr) {
+            (Some((len, me_info, data)), Some((axdr_len, _))) if len == axdr_len => {
+                (len, me_info, data)
             }
-            pos += len;
+            (_, Some((len, me_info))) => (len, me_info, axdr_data),
+            (Some(result), None) => result,
+            (None, None) => (0, "未知类型".to_string(), vec![]),
+        };
+        FrameFun::add_data(
+            sub_result,
+            "MS".to_string(),
+            FrameFun::get_data_str_with_space(&[ms_type]),
+            me_info,
+            vec![index + pos - 1, index + pos],
+            None,
+            None,
+        );
 
-            let dis_data_identifier = format!("<第{}组>数据采集:{:08X}", i + 1, master_oad);
+        let ms_content = reader.take(len)?;
+        if len > 0 {
+            let dis_data_identifier =
+                format!("MS内容:{}", FrameFun::get_data_str_order(ms_content));
             FrameFun::add_data(
-                result_list,
-                format!("<第{}组>数据采集", i + 1),
-                FrameFun::get_data_str_with_space(&task_content[index + start_pos..index + pos]),
+                sub_result,
+                "MS内容".to_string(),
+                FrameFun::get_data_str_with_space(ms_content),
                 dis_data_identifier,
-                vec![index + start_pos, index + pos],
-                Some(sub_result),
+                vec![pos + index, pos + index + len],
+                Some(ms_data),
                 None,
             );
         }
-
-        Ok(())
+        Ok(master_oad)
     }
 }
@@ -0,0 +1,780 @@
+use crate::analyzer::Analyzer;
+use crate::basefunc::frame_fun::FrameFun;
+use crate::basefunc::protocol::FrameAnalisyic;
+use crate::config::xmlconfig::XmlElement;
+use std::cell::RefCell;
+use tracing::info;
+
+/// lengthrule表达式
+///
+/// 支持的语法(优先级从低到高)：
+/// * 条件 `cond ? a : b`
+/// * 逻辑 `||` `&&` `!`
+/// * 位运算 `|` `^` `&` `~` `<<` `>>`
+/// * 比较 `==` `!=` `<` `<=` `>` `>=`
+/// * 算术 `+` `-` `*` `/` `%`，括号
+/// * 数字(十进制或0x开头的十六进制)，字段名(可含空格)，名称中含运算符时用`[名称]`引用
+/// * 函数 `bit(x, n)` `bits(x, start, end)` `len(字段)` `ItemLen(字段)` `min(a, b)` `max(a, b)`
+///
+/// 字段名引用同级或上级中已解析的字段，取值为字段按类型解码后的数值
+#[derive(Debug, Clone, PartialEq)]
+pub enum LengthExpr {
+    Number(i64),
+    Field(String),
+    Unary(char, Box<LengthExpr>),
+    Binary(&'static str, Box<LengthExpr>, Box<LengthExpr>),
+    Condition(Box<LengthExpr>, Box<LengthExpr>, Box<LengthExpr>),
+    Call(String, Vec<LengthExpr>),
+}
+
+/// 表达式中字段的取值来源
+pub trait FieldResolver {
+    /// 字段解码后的数值
+    fn value(&self, name: &str) -> Option<i64>;
+    /// 字段的字节长度
+    fn length(&self, name: &str) -> Option<i64>;
+    /// 以字段内容为数据标识，在配置中查到的数据项长度
+    fn item_length(&self, name: &str) -> Option<i64>;
+}
+
+const OPERATOR_CHARS: &str = "+-*/%()&|^~<>=!?:,[]";
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(i64),
+    Ident(String),
+    Op(&'static str),
+}
+
+// 按长度从长到短排列，保证优先匹配双字符运算符
+const OPERATORS: [&str; 24] = [
+    "<<", ">>", "<=", ">=", "==", "!=", "&&", "||", "+", "-", "*", "/", "%", "(", ")", "&", "|",
+    "^", "~", "<", ">", "!", "?", ":",
+];
+
+struct Parser {
+    tokens: Vec<(Token, usize)>,
+    pos: usize,
+}
+
+impl LengthExpr {
+    /// 解析表达式，错误信息包含出错位置
+    pub fn parse(source: &str) -> Result<LengthExpr, String> {
+        let tokens = Self::tokenize(source)?;
+        if tokens.is_empty() {
+            return Err("表达式为空".to_string());
+        }
+        let mut parser = Parser { tokens, pos: 0 };
+        let expr = parser.condition()?;
+        if let Some((token, offset)) = parser.tokens.get(parser.pos) {
+            return Err(format!("第{}个字符处多余的{:?}", offset + 1, token));
+        }
+        Ok(expr)
+    }
+
+    fn tokenize(source: &str) -> Result<Vec<(Token, usize)>, String> {
+        let chars: Vec<char> = source.chars().collect();
+        let mut tokens = Vec::new();
+        let mut i = 0;
+        while i < chars.len() {
+            let c = chars[i];
+            if c.is_whitespace() {
+                i += 1;
+                continue;
+            }
+            if c == ',' {
+                tokens.push((Token::Op(","), i));
+                i += 1;
+                continue;
+            }
+
+            if c.is_ascii_digit() {
+                let start = i;
+                while i < chars.len() && chars[i].is_ascii_alphanumeric() {
+                    i += 1;
+                }
+                let text: String = chars[start..i].iter().collect();
+                let number = match text.strip_prefix("0x").or(text.strip_prefix("0X")) {
+                    Some(hex) => i64::from_str_radix(hex, 16),
+                    None => text.parse::<i64>(),
+                }
+                .map_err(|_| format!("第{}个字符处的数字[{}]无效", start + 1, text))?;
+                tokens.push((Token::Number(number), start));
+                continue;
+            }
+
+            if c == '[' {
+                let start = i;
+                let end = chars[i..]
+                    .iter()
+                    .position(|&c| c == ']')
+                    .ok_or_else(|| format!("第{}个字符处的[缺少对应的]", start + 1))?;
+                let name: String = chars[i + 1..i + end].iter().collect();
+                tokens.push((Token::Ident(name.trim().to_string()), start));
+                i += end + 1;
+                continue;
+            }
+
+            if OPERATOR_CHARS.contains(c) {
+                let rest: String = chars[i..chars.len().min(i + 2)].iter().collect();
+                let op = OPERATORS
+                    .iter()
+                    .find(|op| rest.starts_with(*op))
+                    .ok_or_else(|| format!("第{}个字符处无法识别的符号[{}]", i + 1, c))?;
+                tokens.push((Token::Op(op), i));
+                i += op.chars().count();
+                continue;
+            }
+
+            // 字段名中间可以有空格，如"APP 数量"
+            let start = i;
+            let mut end = i;
+            while i < chars.len() && !OPERATOR_CHARS.contains(chars[i]) {
+                if !chars[i].is_whitespace() {
+                    end = i + 1;
+                }
+                i += 1;
+            }
+            i = end;
+            tokens.push((Token::Ident(chars[start..end].iter().collect()), start));
+        }
+        Ok(tokens)
+    }
+
+    /// 表达式引用的全部字段名
+    pub fn fields(&self) -> Vec<String> {
+        let mut fields = Vec::new();
+        self.collect_fields(&mut fields);
+        fields
+    }
+
+    fn collect_fields(&self, fields: &mut Vec<String>) {
+        match self {
+            LengthExpr::Number(_) => {}
+            LengthExpr::Field(name) => {
+                if !fields.contains(name) {
+                    fields.push(name.clone());
+                }
+            }
+            LengthExpr::Unary(_, expr) => expr.collect_fields(fields),
+            LengthExpr::Binary(_, left, right) => {
+                left.collect_fields(fields);
+                right.collect_fields(fields);
+            }
+            LengthExpr::Condition(cond, then, otherwise) => {
+                cond.collect_fields(fields);
+                then.collect_fields(fields);
+                otherwise.collect_fields(fields);
+            }
+            LengthExpr::Call(_, args) => {
+                for arg in args {
+                    arg.collect_fields(fields);
+                }
+            }
+        }
+    }
+
+    /// 检查函数名和参数个数
+    pub fn check(&self) -> Result<(), String> {
+        match self {
+            LengthExpr::Number(_) | LengthExpr::Field(_) => Ok(()),
+            LengthExpr::Unary(_, expr) => expr.check(),
+            LengthExpr::Binary(_, left, right) => {
+                left.check()?;
+                right.check()
+            }
+            LengthExpr::Condition(cond, then, otherwise) => {
+                cond.check()?;
+                then.check()?;
+                otherwise.check()
+            }
+            LengthExpr::Call(name, args) => {
+                let expected = match name.to_lowercase().as_str() {
+                    "bit" | "min" | "max" => 2,
+                    "bits" => 3,
+                    "len" | "itemlen" => 1,
+                    "range" => return Err("RANGE()只能单独作为长度规则使用".to_string()),
+                    _ => return Err(format!("未知函数[{}]", name)),
+                };
+                if args.len() != expected {
+                    return Err(format!(
+                        "函数[{}]需要{}个参数，实际为{}个",
+                        name,
+                        expected,
+                        args.len()
+                    ));
+                }
+                if expected == 1 && !matches!(args[0], LengthExpr::Field(_)) {
+                    return Err(format!("函数[{}]的参数必须是字段名", name));
+                }
+                args.iter().try_for_each(|arg| arg.check())
+            }
+        }
+    }
+
+    /// 计算表达式的值
+    pub fn eval(&self, resolver: &dyn FieldResolver) -> Result<i64, String> {
+        match self {
+            LengthExpr::Number(value) => Ok(*value),
+            LengthExpr::Field(name) => resolver
+                .value(name)
+                .ok_or_else(|| format!("字段[{}]未解析或无法取值", name)),
+            LengthExpr::Unary(op, expr) => {
+                let value = expr.eval(resolver)?;
+                Ok(match op {
+                    '-' => value.checked_neg().ok_or("数值溢出")?,
+                    '!' => (value == 0) as i64,
+                    _ => !value,
+                })
+            }
+            LengthExpr::Binary(op, left, right) => {
+                let left = left.eval(resolver)?;
+                // 逻辑运算短路求值
+                match *op {
+                    "&&" if left == 0 => return Ok(0),
+                    "||" if left != 0 => return Ok(1),
+                    _ => {}
+                }
+                let right = right.eval(resolver)?;
+                Self::binary(op, left, right)
+            }
+            LengthExpr::Condition(cond, then, otherwise) => {
+                if cond.eval(resolver)? != 0 {
+                    then.eval(resolver)
+                } else {
+                    otherwise.eval(resolver)
+                }
+            }
+            LengthExpr::Call(name, args) => self.call(name, args, resolver),
+        }
+    }
+
+    fn binary(op: &str, left: i64, right: i64) -> Result<i64, String> {
+        let overflow = || format!("{} {} {} 数值溢出", left, op, right);
+        Ok(match op {
+            "+" => left.checked_add(right).ok_or_else(overflow)?,
+            "-" => left.checked_sub(right).ok_or_else(overflow)?,
+            "*" => left.checked_mul(right).ok_or_else(overflow)?,
+            "/" | "%" if right == 0 => return Err(format!("{} {} 0 除数为0", left, op)),
+            "/" => left.checked_div(right).ok_or_else(overflow)?,
+            "%" => left.checked_rem(right).ok_or_else(overflow)?,
+            "<<" | ">>" if !(0..64).contains(&right) => {
+                return Err(format!("移位位数[{}]超出范围", right))
+            }
+            "<<" => left << right,
+            ">>" => left >> right,
+            "&" => left & right,
+            "|" => left | right,
+            "^" => left ^ right,
+            "==" => (left == right) as i64,
+            "!=" => (left != right) as i64,
+            "<" => (left < right) as i64,
+            "<=" => (left <= right) as i64,
+            ">" => (left > right) as i64,
+            ">=" => (left >= right) as i64,
+            "&&" => (left != 0 && right != 0) as i64,
+            "||" => (left != 0 || right != 0) as i64,
+            _ => return Err(format!("不支持的运算符[{}]", op)),
+        })
+    }
+
+    fn call(
+        &self,
+        name: &str,
+        args: &[LengthExpr],
+        resolver: &dyn FieldResolver,
+    ) -> Result<i64, String> {
+        self.check()?;
+        let field_name = || match &args[0] {
+            LengthExpr::Field(field) => field.clone(),
+            _ => String::new(),
+        };
+        match name.to_lowercase().as_str() {
+            "len" => resolver
+                .length(&field_name())
+                .ok_or_else(|| format!("字段[{}]未解析，无法取长度", field_name())),
+            "itemlen" => resolver
+                .item_length(&field_name())
+                .ok_or_else(|| format!("字段[{}]对应的数据项未配置固定长度", field_name())),
+            "bit" => {
+                let value = args[0].eval(resolver)?;
+                let bit = args[1].eval(resolver)?;
+                if !(0..64).contains(&bit) {
+                    return Err(format!("bit()的位序号[{}]超出范围", bit));
+                }
+                Ok((value >> bit) & 1)
+            }
+            "bits" => {
+                let value = args[0].eval(resolver)?;
+                let start = args[1].eval(resolver)?;
+                let end = args[2].eval(resolver)?;
+                if !(0..64).contains(&start) || !(start..64).contains(&end) {
+                    return Err(format!("bits()的位范围[{}, {}]无效", start, end));
+                }
+                let width = end - start + 1;
+                let mask = if width == 64 { -1 } else { (1i64 << width) - 1 };
+                Ok((value >> start) & mask)
+            }
+            "min" => Ok(args[0].eval(resolver)?.min(args[1].eval(resolver)?)),
+            "max" => Ok(args[0].eval(resolver)?.max(args[1].eval(resolver)?)),
+            _ => Err(format!("未知函数[{}]", name)),
+        }
+    }
+}
+
+impl Parser {
+    fn peek_op(&self) -> Option<&'static str> {
+        match self.tokens.get(self.pos) {
+            Some((Token::Op(op), _)) => Some(op),
+            _ => None,
+        }
+    }
+
+    fn offset(&self) -> usize {
+        self.tokens
+            .get(self.pos)
+            .or(self.tokens.last())
+            .map_or(0, |(_, offset)| offset + 1)
+    }
+
+    fn expect(&mut self, op: &str) -> Result<(), String> {
+        if self.peek_op() == Some(op) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            Err(format!("第{}个字符处缺少[{}]", self.offset(), op))
+        }
+    }
+
+    fn condition(&mut self) -> Result<LengthExpr, String> {
+        let cond = self.binary_level(0)?;
+        if self.peek_op() == Some("?") {
+            self.pos += 1;
+            let then = self.condition()?;
+            self.expect(":")?;
+            let otherwise = self.condition()?;
+            return Ok(LengthExpr::Condition(
+                Box::new(cond),
+                Box::new(then),
+                Box::new(otherwise),
+            ));
+        }
+        Ok(cond)
+    }
+
+    // 二元运算符按优先级从低到高分组
+    const LEVELS: [&'static [&'static str]; 10] = [
+        &["||"],
+        &["&&"],
+        &["|"],
+        &["^"],
+        &["&"],
+        &["==", "!="],
+        &["<", "<=", ">", ">="],
+        &["<<", ">>"],
+        &["+", "-"],
+        &["*", "/", "%"],
+    ];
+
+    fn binary_level(&mut self, level: usize) -> Result<LengthExpr, String> {
+        if level == Self::LEVELS.len() {
+            return self.unary();
+        }
+        let mut left = self.binary_level(level + 1)?;
+        while let Some(op) = self.peek_op().filter(|op| Self::LEVELS[level].contains(op)) {
+            self.pos += 1;
+            let right = self.binary_level(level + 1)?;
+            left = LengthExpr::Binary(op, Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn unary(&mut self) -> Result<LengthExpr, String> {
+        match self.peek_op() {
+            Some(op @ ("-" | "!" | "~")) => {
+                self.pos += 1;
+                let expr = self.unary()?;
+                Ok(LengthExpr::Unary(
+                    op.chars().next().unwrap(),
+                    Box::new(expr),
+                ))
+            }
+            _ => self.primary(),
+        }
+    }
+
+    fn primary(&mut self) -> Result<LengthExpr, String> {
+        let offset = self.offset();
+        let token = self.tokens.get(self.pos).map(|(token, _)| token.clone());
+        self.pos += 1;
+        match token {
+            Some(Token::Number(value)) => Ok(LengthExpr::Number(value)),
+            Some(Token::Ident(name)) => {
+                if self.peek_op() != Some("(") {
+                    return Ok(LengthExpr::Field(name));
+                }
+                self.pos += 1;
+                let mut args = Vec::new();
+                if self.peek_op() != Some(")") {
+                    loop {
+                        args.push(self.condition()?);
+                        if self.peek_op() == Some(",") {
+                            self.pos += 1;
+                        } else {
+                            break;
+                        }
+                    }
+                }
+                self.expect(")")?;
+                Ok(LengthExpr::Call(name, args))
+            }
+            Some(Token::Op("(")) => {
+                let expr = self.condition()?;
+                self.expect(")")?;
+                Ok(expr)
+            }
+            Some(token) => Err(format!("第{}个字符处不应出现{:?}", offset, token)),
+            None => Err("表达式不完整".to_string()),
+        }
+    }
+}
+
+/// 已解析的字段
+#[derive(Debug, Clone)]
+pub struct DecodedField {
+    pub name: String,
+    pub data: Vec<u8>,
+    pub element: XmlElement,
+    pub need_delete: bool,
+}
+
+thread_local! {
+    // 每层splitByLength解析时压入一层，记录本层已解析的字段
+    static FIELD_SCOPES: RefCell<Vec<Vec<DecodedField>>> = const { RefCell::new(Vec::new()) };
+}
+
+/// 字段作用域，离开作用域时自动弹出本层记录
+pub struct FieldScope;
+
+impl FieldScope {
+    pub fn enter() -> FieldScope {
+        FIELD_SCOPES.with(|scopes| scopes.borrow_mut().push(Vec::new()));
+        FieldScope
+    }
+
+    /// 记录当前层已解析的字段，按name和id都可以引用
+    pub fn record(element: &XmlElement, data: &[u8], need_delete: bool) {
+        let Some(name) = element
+            .get_child_text("name")
+            .or_else(|| element.get_attribute("id").cloned())
+        else {
+            return;
+        };
+        FIELD_SCOPES.with(|scopes| {
+            if let Some(scope) = scopes.borrow_mut().last_mut() {
+                scope.push(DecodedField {
+                    name,
+                    data: data.to_vec(),
+                    element: element.clone(),
+                    need_delete,
+                });
+            }
+        });
+    }
+
+    /// 从内层到外层查找最近解析的同名字段
+    pub fn lookup(name: &str) -> Option<DecodedField> {
+        FIELD_SCOPES.with(|scopes| {
+            scopes.borrow().iter().rev().find_map(|scope| {
+                scope
+                    .iter()
+                    .rev()
+                    .find(|field| {
+                        field.name == name
+                            || field.element.get_attribute("id").map(|id| id.as_str()) == Some(name)
+                    })
+                    .cloned()
+            })
+        })
+    }
+}
+
+impl Drop for FieldScope {
+    fn drop(&mut self) {
+        FIELD_SCOPES.with(|scopes| {
+            scopes.borrow_mut().pop();
+        });
+    }
+}
+
+/// 按字段配置的类型解码数值，先查同级字段，再查已解析的上级字段
+pub struct FieldValues<'a> {
+    pub analyzer: &'a Analyzer,
+    pub siblings: Vec<(String, &'a [u8], &'a XmlElement)>,
+    pub protocol: &'a str,
+    pub region: &'a str,
+    pub dir: Option<u8>,
+}
+
+impl<'a> FieldValues<'a> {
+    /// 只从已解析的上级字段中取值
+    pub fn from_scope(
+        analyzer: &'a Analyzer,
+        protocol: &'a str,
+        region: &'a str,
+        dir: Option<u8>,
+    ) -> FieldValues<'a> {
+        FieldValues {
+            analyzer,
+            siblings: Vec::new(),
+            protocol,
+            region,
+            dir,
+        }
+    }
+
+    /// 字段的原始报文，645报文已减33H
+    pub fn raw(&self, name: &str) -> Option<Vec<u8>> {
+        self.find(name).map(|(data, _)| data)
+    }
+
+    fn find(&self, name: &str) -> Option<(Vec<u8>, XmlElement)> {
+        if let Some((_, data, element)) = self.siblings.iter().rev().find(|(n, _, _)| n == name) {
+            return Some((data.to_vec(), (*element).clone()));
+        }
+        FieldScope::lookup(name).map(|field| {
+            let data = if field.need_delete {
+                FrameFun::frame_delete_33h(&field.data)
+            } else {
+                field.data
+            };
+            (data, field.element)
+        })
+    }
+
+    fn decode(&self, name: &str, data: &[u8], element: &XmlElement) -> Option<i64> {
+        if data.is_empty() || data.len() > 8 {
+            return None;
+        }
+        let data_type = element
+            .get_child_text("type")
+            .map(|t| t.to_uppercase())
+            .unwrap_or_else(|| "BIN".to_string());
+        match data_type.as_str() {
+            "BIN" | "BIN_FF" | "NORMAL" => Some(
+                data.iter()
+                    .rev()
+                    .fold(0i64, |value, &byte| (value << 8) | byte as i64),
+            ),
+            "BIN_BE" => Some(
+                data.iter()
+                    .fold(0i64, |value, &byte| (value << 8) | byte as i64),
+            ),
+            "BCD" => Some(FrameFun::bcd_to_int(data, false) as i64),
+            _ => {
+                // 其他类型按解析结果中的第一个数字取值
+                let mut value_element = element.clone();
+                let result = FrameAnalisyic::prase_data_item(
+                    self.analyzer,
+                    &mut value_element,
+                    data,
+                    0,
+                    false,
+                    self.protocol,
+                    self.region,
+                    self.dir,
+                );
+                let description = FrameFun::find_frame_in_data_list(&result, name)
+                    .and_then(|items| {
+                        items
+                            .iter()
+                            .rev()
+                            .find_map(|item| item.get("description")?.as_str().map(String::from))
+                    })
+                    .unwrap_or_default();
+                let digits: String = description
+                    .chars()
+                    .skip_while(|c| !c.is_ascii_digit())
+                    .take_while(|c| c.is_ascii_digit())
+                    .collect();
+                digits.parse::<i64>().ok()
+            }
+        }
+    }
+}
+
+impl FieldResolver for FieldValues<'_> {
+    fn value(&self, name: &str) -> Option<i64> {
+        let (data, element) = self.find(name)?;
+        let value = self.decode(name, &data, &element);
+        info!("lengthrule field {} = {:?}", name, value);
+        value
+    }
+
+    fn length(&self, name: &str) -> Option<i64> {
+        self.find(name).map(|(data, _)| data.len() as i64)
+    }
+
+    fn item_length(&self, name: &str) -> Option<i64> {
+        let (data, _) = self.find(name)?;
+        let item_id = FrameFun::get_data_str_reverser(&data);
+        self.analyzer
+            .get_config_xml(&item_id, self.protocol, self.region, self.dir)?
+            .get_child_text("length")?
+            .parse::<i64>()
+            .ok()
+    }
+}
+
+/// 计算数据项的lengthrule，错误信息带上数据项名称
+pub fn evaluate_length_rule(
+    element: &XmlElement,
+    rule: &str,
+    values: &FieldValues,
+) -> Result<usize, String> {
+    let label = element
+        .get_attribute("id")
+        .cloned()
+        .or_else(|| element.get_child_text("name"))
+        .unwrap_or_else(|| element.name.clone());
+    let value = LengthExpr::parse(rule)
+        .and_then(|expr| {
+            expr.check()?;
+            expr.eval(values)
+        })
+        .map_err(|e| format!("数据项[{}]的长度规则[{}]计算失败: {}", label, rule, e))?;
+    usize::try_from(value)
+        .map_err(|_| format!("数据项[{}]的长度规则[{}]结果为负数: {}", label, rule, value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    struct MapResolver(HashMap<&'static str, (i64, i64)>);
+
+    impl FieldResolver for MapResolver {
+        fn value(&self, name: &str) -> Option<i64> {
+            self.0.get(name).map(|(value, _)| *value)
+        }
+
+        fn length(&self, name: &str) -> Option<i64> {
+            self.0.get(name).map(|(_, length)| *length)
+        }
+
+        fn item_length(&self, name: &str) -> Option<i64> {
+            self.0.get(name).map(|(value, _)| *value * 2)
+        }
+    }
+
+    fn resolver() -> MapResolver {
+        MapResolver(HashMap::from([
+            ("n", (3, 1)),
+            ("APP 数量", (5, 2)),
+            ("a-b", (7, 1)),
+            ("flag", (0b1010_0110, 1)),
+        ]))
+    }
+
+    fn eval(source: &str) -> Result<i64, String> {
+        LengthExpr::parse(source)?.eval(&resolver())
+    }
+
+    #[test]
+    fn eval_operator_precedence() {
+        assert_eq!(eval("1 + 2 * 3"), Ok(7));
+        assert_eq!(eval("(1 + 2) * 3"), Ok(9));
+        assert_eq!(eval("10 - 4 - 3"), Ok(3));
+        assert_eq!(eval("1 << 2 + 1"), Ok(8));
+        assert_eq!(eval("0x10 | 1 & 3"), Ok(17));
+        assert_eq!(eval("-n + ~0"), Ok(-4));
+        assert_eq!(eval("n > 2 && !0 ? n * 4 : 1"), Ok(12));
+        assert_eq!(eval("n == 3 ? 1 : n == 4 ? 2 : 3"), Ok(1));
+    }
+
+    #[test]
+    fn eval_fields_and_functions() {
+        assert_eq!(eval("APP 数量 * 2"), Ok(10));
+        assert_eq!(eval("[a-b] - 1"), Ok(6));
+        assert_eq!(eval("len(APP 数量) + ItemLen(n)"), Ok(8));
+        assert_eq!(eval("bit(flag, 1) + bit(flag, 0)"), Ok(1));
+        assert_eq!(eval("bits(flag, 4, 7)"), Ok(0b1010));
+        assert_eq!(eval("min(n, 2) * max(n, 2)"), Ok(6));
+        assert_eq!(
+            LengthExpr::parse("n * [a-b] + len(n)").unwrap().fields(),
+            vec!["n".to_string(), "a-b".to_string()]
+        );
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(LengthExpr::parse(""), Err("表达式为空".to_string()));
+        assert_eq!(LengthExpr::parse("   "), Err("表达式为空".to_string()));
+        assert!(LengthExpr::parse("1 +").is_err());
+        assert!(LengthExpr::parse("(1 + 2").is_err());
+        assert!(LengthExpr::parse("n ? 1").is_err());
+        assert!(LengthExpr::parse("min(1, 2").is_err());
+        assert_eq!(
+            LengthExpr::parse("[abc + 1"),
+            Err("第1个字符处的[缺少对应的]".to_string())
+        );
+        assert_eq!(
+            LengthExpr::parse("0xZZ"),
+            Err("第1个字符处的数字[0xZZ]无效".to_string())
+        );
+        assert_eq!(
+            LengthExpr::parse("1 = 2"),
+            Err("第3个字符处无法识别的符号[=]".to_string())
+        );
+        assert!(LengthExpr::parse("1 2")
+            .unwrap_err()
+            .starts_with("第3个字符处多余的"));
+    }
+
+    #[test]
+    fn check_errors() {
+        let check = |source: &str| LengthExpr::parse(source).unwrap().check();
+        assert_eq!(check("foo(1)"), Err("未知函数[foo]".to_string()));
+        assert_eq!(
+            check("bit(n)"),
+            Err("函数[bit]需要2个参数，实际为1个".to_string())
+        );
+        assert_eq!(
+            check("len(1)"),
+            Err("函数[len]的参数必须是字段名".to_string())
+        );
+        assert!(check("range(1, 2)").is_err());
+        assert_eq!(check("bits(n, 0, 3) + len(n)"), Ok(()));
+    }
+
+    #[test]
+    fn eval_errors() {
+        assert_eq!(eval("n / 0"), Err("3 / 0 除数为0".to_string()));
+        assert_eq!(eval("n % (n - 3)"), Err("3 % 0 除数为0".to_string()));
+        assert_eq!(eval("m + 1"), Err("字段[m]未解析或无法取值".to_string()));
+        assert_eq!(eval("len(m)"), Err("字段[m]未解析，无法取长度".to_string()));
+        assert_eq!(eval("1 << 64"), Err("移位位数[64]超出范围".to_string()));
+        assert_eq!(
+            eval("bit(n, -1)"),
+            Err("bit()的位序号[-1]超出范围".to_string())
+        );
+        assert_eq!(
+            eval("bits(n, 5, 2)"),
+            Err("bits()的位范围[5, 2]无效".to_string())
+        );
+        assert!(eval("0x7FFFFFFFFFFFFFFF + 1")
+            .unwrap_err()
+            .ends_with("数值溢出"));
+        // 8字节字段80 00...解码为i64::MIN，除以-1溢出
+        for expr in [
+            "(-0x7FFFFFFFFFFFFFFF - 1) / -1",
+            "(-0x7FFFFFFFFFFFFFFF - 1) % -1",
+        ] {
+            assert!(eval(expr).unwrap_err().ends_with("数值溢出"));
+        }
+        // 短路求值时不计算右侧
+        assert_eq!(eval("0 && m"), Ok(0));
+        assert_eq!(eval("1 || n / 0"), Ok(1));
+    }
+}
@@ -1,10 +1,25 @@
 // basefunc/mod.rs
+pub mod axdr;
+pub mod csg_items;
+pub mod csg_security;
+pub mod data_completeness;
+pub mod file_transfer;
+pub mod frame_13761;
+pub mod frame_13762;
 pub mod frame_645;
+pub mod frame_645_97;
+pub mod frame_698;
 pub mod frame_cco;
 pub mod frame_csg;
 pub mod frame_err;
 pub mod frame_fun;
 pub mod frame_moudle;
 pub mod frame_speecial;
+pub mod frame_statistics;
 pub mod frame_tctask;
+pub mod length_expr;
 pub mod protocol;
+pub mod sm_crypto;
+pub mod switch_case;
+#[cfg(test)]
+pub(crate) mod test_util;
@@ -1,14 +1,21 @@
+use crate::analyzer::Analyzer;
+use crate::basefunc::frame_13761::Frame13761;
+use crate::basefunc::frame_13762::Frame13762;
 use crate::basefunc::frame_645::Frame645;
+use crate::basefunc::frame_645_97::Frame64597;
+use crate::basefunc::frame_698::Frame698;
 use crate::basefunc::frame_cco::FrameCCO;
 use crate::basefunc::frame_csg::FrameCsg;
 use crate::basefunc::frame_fun::FrameFun;
 use crate::basefunc::frame_moudle::FrameMoudle;
 use crate::basefunc::frame_speecial::SpcialFrame;
 use crate::basefunc::frame_tctask::TCMeterTask;
-use crate::config::xmlconfig::{ProtocolConfigManager, XmlElement};
+use crate::basefunc::length_expr::{FieldScope, FieldValues};
+use crate::basefunc::switch_case::SwitchCase;
+use crate::config::xmlconfig::XmlElement;
 use regex::Regex;
 use serde_json::{json, Value};
-use tracing::info;
+use tracing::{info, warn};
 
 #[derive(Debug)]
 pub enum AnalysicErr {
@@ -23,6 +30,10 @@ pub enum ProtocolInfo {
     ProtocolCSG13,
     ProtocolCSG16,
     ProtocolDLT64507,
+    ProtocolDLT64597,
+    ProtocolDLT69845,
+    ProtocolGDW13761,
+    ProtocolGDW13762,
     ProtocolMoudle,
     ProtocolMS,
     ProtocolHis,
@@ -34,45 +45,81 @@ impl ProtocolInfo {
             ProtocolInfo::ProtocolCSG13 => "CSG13",
             ProtocolInfo::ProtocolCSG16 => "CSG16",
             ProtocolInfo::ProtocolDLT64507 => "DLT/645-2007",
+            ProtocolInfo::ProtocolDLT64597 => "DLT/645-1997",
+            ProtocolInfo::ProtocolDLT69845 => "DLT/698.45",
+            ProtocolInfo::ProtocolGDW13761 => "Q/GDW1376.1",
+            ProtocolInfo::ProtocolGDW13762 => "Q/GDW1376.2",
             ProtocolInfo::ProtocolMoudle => "moudle",
             ProtocolInfo::ProtocolMS => "MS",
             ProtocolInfo::ProtocolHis => "His",
         }
     }
+
+    /// 是否为645规约(2007或1997)，645规约的数据域按字节加33H传输
+    pub fn is_dlt645(protocol: &str) -> bool {
+        protocol == ProtocolInfo::ProtocolDLT64507.name()
+            || protocol == ProtocolInfo::ProtocolDLT64597.name()
+    }
 }
 
 pub struct FrameAnalisyic;
 
 impl FrameAnalisyic {
+    /// 使用默认解析器实例识别协议并解析报文
     pub fn process_frame(frame: &[u8], region: &str) -> (String, Vec<Value>) {
+        Analyzer::global().process_frame(frame, region)
+    }
+
+    /// 使用指定解析器实例的配置识别协议并解析报文
+    pub fn process_frame_with(
+        analyzer: &Analyzer,
+        frame: &[u8],
+        region: &str,
+    ) -> (String, Vec<Value>) {
         let mut parsed_data: Vec<Value> = Vec::new();
         let mut protocol = String::from("Unknown");
         if FrameCsg::is_csg_frame(frame) {
-            let result = FrameCsg::analysic_csg_frame_by_afn(frame, &mut parsed_data, 0, region);
+            let result =
+                FrameCsg::analysic_csg_frame_by_afn(analyzer, frame, &mut parsed_data, 0, region);
             protocol = ProtocolInfo::ProtocolCSG13.name().to_string();
             match result {
                 Ok(_) => {}
                 Err(_) => {}
             }
+        } else if Frame13761::is_13761_frame(frame) {
+            protocol = ProtocolInfo::ProtocolGDW13761.name().to_string();
+            Frame13761::analysic_13761_frame(analyzer, frame, &mut parsed_data, 0, region);
+        } else if Frame64597::is_dlt645_97_frame(frame) {
+            protocol = ProtocolInfo::ProtocolDLT64597.name().to_string();
+            Frame64597::analysic_645_97_frame_by_afn(analyzer, frame, &mut parsed_data, 0, region);
         } else if Frame645::is_dlt645_frame(frame) {
             protocol = ProtocolInfo::ProtocolDLT64507.name().to_string();
-            let result = Frame645::analysic_645_frame_by_afn(frame, &mut parsed_data, 0, region);
+            let result =
+                Frame645::analysic_645_frame_by_afn(analyzer, frame, &mut parsed_data, 0, region);
+        } else if Frame698::is_698_frame(frame) {
+            protocol = ProtocolInfo::ProtocolDLT69845.name().to_string();
+            Frame698::analysic_698_frame(analyzer, frame, &mut parsed_data, 0, region);
+        } else if Frame13762::is_13762_frame(frame) {
+            protocol = ProtocolInfo::ProtocolGDW13762.name().to_string();
+            Frame13762::analysic_13762_frame(analyzer, frame, &mut parsed_data, 0, region);
         } else if FrameCCO::is_cco_frame(frame) {
             protocol = ProtocolInfo::ProtocolCSG16.name().to_string();
-            FrameCCO::analysic_cco_frame_by_afn(frame, &mut parsed_data, 0, region);
+            FrameCCO::analysic_cco_frame_by_afn(analyzer, frame, &mut parsed_data, 0, region);
         } else if FrameMoudle::is_moudle_frame(frame) {
             protocol = ProtocolInfo::ProtocolMoudle.name().to_string();
-            FrameMoudle::analysic_moudle_frame(frame, &mut parsed_data, 0, region);
+            FrameMoudle::analysic_moudle_frame(analyzer, frame, &mut parsed_data, 0, region);
         } else if TCMeterTask::is_meter_task(frame) {
             protocol = ProtocolInfo::ProtocolMS.name().to_string();
-            let result = TCMeterTask::analysic_meter_task(frame, &mut parsed_data, 0, region);
+            let result =
+                TCMeterTask::analysic_meter_task(analyzer, frame, &mut parsed_data, 0, region);
             match result {
                 Ok(_) => {}
                 Err(_) => {}
             }
-        } else if SpcialFrame::is_special_frame(frame, region) {
+        } else if SpcialFrame::is_special_frame(analyzer, frame, region) {
             protocol = ProtocolInfo::ProtocolHis.name().to_string();
-            let result = SpcialFrame::analysic_special_frame(frame, &mut parsed_data, 0, region);
+            let result =
+                SpcialFrame::analysic_special_frame(analyzer, frame, &mut parsed_data, 0, region);
             match result {
                 Ok(_) => {}
                 Err(_) => {}
@@ -82,6 +129,7 @@ impl FrameAnalisyic {
         (protocol, parsed_data)
     }
     pub fn prase_data(
+        analyzer: &Analyzer,
         data_item_elem: &mut XmlElement,
         protocol: &str,
         region: &str,
@@ -97,9 +145,10 @@ impl FrameAnalisyic {
             "prase_data data_item_elem: {:?} data_segment{:?}",
             data_item_elem, data_segment
         );
-        let need_delete = protocol == ProtocolInfo::ProtocolDLT64507.name();
+        let need_delete = ProtocolInfo::is_dlt645(protocol);
         info!("need_delete: {:?}", need_delete);
         parsed_data = Self::prase_data_item(
+            analyzer,
             data_item_elem,
             data_segment,
             index,
@@ -110,7 +159,9 @@ impl FrameAnalisyic {
         );
         parsed_data
     }
+    #[allow(clippy::too_many_arguments)]
     pub fn prase_data_item(
+        analyzer: &Analyzer,
         data_item_elem: &mut XmlElement,
         data_segment: &[u8],
         index: usize,
@@ -125,6 +176,18 @@ impl FrameAnalisyic {
             return result;
         }
 
+        if SwitchCase::has_switch(data_item_elem) {
+            let values = FieldValues::from_scope(analyzer, protocol, region, dir);
+            match SwitchCase::resolve(data_item_elem, &values) {
+                Ok(Some(resolved)) => *data_item_elem = resolved,
+                Ok(None) => return result,
+                Err(e) => {
+                    warn!("{}", e);
+                    return result;
+                }
+            }
+        }
+
         let data_item_id = data_item_elem.get_attribute("id");
         let data_item_name = data_item_elem.get_child_text("name");
 
@@ -150,6 +213,7 @@ impl FrameAnalisyic {
 
         if !sub_data_item.is_empty() {
             let (sub_result, length) = Self::process_all_item(
+                analyzer,
                 data_item_elem,
                 sub_data_segment,
                 index,
@@ -168,6 +232,7 @@ impl FrameAnalisyic {
             && data_item_elem.get_child("value").is_some()
         {
             let (cur_result, sub_result, length, cur_color) = Self::prase_value_item(
+                analyzer,
                 data_item_elem,
                 sub_data_segment,
                 index,
@@ -195,6 +260,7 @@ impl FrameAnalisyic {
             cur_length = length;
         } else if data_item_elem.get_child("value").is_some() {
             let (cur_result, sub_result, length, cur_color) = Self::prase_value_item(
+                analyzer,
                 data_item_elem,
                 sub_data_segment,
                 index,
@@ -234,6 +300,7 @@ impl FrameAnalisyic {
             cur_length = length;
         } else if data_item_elem.get_child("splitByLength").is_some() {
             let (sub_result, length) = Self::prase_split_by_length_item(
+                analyzer,
                 &data_item_elem,
                 sub_data_segment,
                 index,
@@ -246,6 +313,7 @@ impl FrameAnalisyic {
             cur_length = length;
         } else if data_item_elem.get_child("itembox").is_some() {
             let (sub_result, length) = Self::prase_item_box(
+                analyzer,
                 data_item_elem,
                 sub_data_segment,
                 index,
@@ -262,6 +330,7 @@ impl FrameAnalisyic {
             let length_elem = data_item_elem.find_child_by_attribute("len", &length_vaue);
             if let Some(length_elem) = length_elem {
                 let sub_result = Self::prase_data_item(
+                    analyzer,
                     &mut length_elem.clone(),
                     data_segment,
                     index,
@@ -282,6 +351,7 @@ impl FrameAnalisyic {
                     Some(sub_length_txt) => match sub_length_txt.to_uppercase().as_str() {
                         "UNKNOWN" => {
                             subitem_length = FrameCsg::calculate_item_length(
+                                analyzer,
                                 data_item_elem,
                                 &sub_data_segment,
                                 protocol,
@@ -297,8 +367,9 @@ impl FrameAnalisyic {
                     _ => {
                         let item_box = data_item_elem.get_items("item");
                         if item_box.len() > 0 {
-                            subitem_length =
-                                Self::caculate_item_box_length(&item_box, protocol, region, dir);
+                            subitem_length = Self::caculate_item_box_length(
+                                analyzer, &item_box, protocol, region, dir,
+                            );
                         }
                     }
                 }
@@ -311,6 +382,7 @@ impl FrameAnalisyic {
             );
 
             let (cur_result, sub_result, length) = Self::prase_type_item(
+                analyzer,
                 &data_item_elem,
                 sub_data_segment,
                 index,
@@ -350,7 +422,9 @@ impl FrameAnalisyic {
         result
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn process_all_item(
+        analyzer: &Analyzer,
         data_item_elem: &XmlElement,
         data_segment: &[u8],
         index: usize,
@@ -395,6 +469,7 @@ impl FrameAnalisyic {
                 }
 
                 let cur_result = Self::prase_data_item(
+                    analyzer,
                     &mut data_item.clone(),
                     &sub_item_data,
                     index + pos,
@@ -434,7 +509,9 @@ impl FrameAnalisyic {
         (sub_item_result, total_length) // Return both results
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn prase_value_item(
+        analyzer: &Analyzer,
         data_item_elem: &mut XmlElement,
         data_segment: &[u8],
         index: usize,
@@ -454,6 +531,7 @@ impl FrameAnalisyic {
         if let Some(item_length_content) = item_length_content {
             if item_length_content.to_uppercase() == "UNKNOWN" {
                 item_length = FrameCsg::calculate_item_length(
+                    analyzer,
                     data_item_elem,
                     data_segment,
                     protocol,
@@ -1024,7 +1102,9 @@ impl FrameAnalisyic {
         }
         name_str
     }
+    #[allow(clippy::too_many_arguments)]
     pub fn prase_split_by_length_item(
+        analyzer: &Analyzer,
         data_item_elem: &XmlElement,
         data_segment: &[u8],
         index: usize,
@@ -1046,6 +1126,7 @@ impl FrameAnalisyic {
         let mut color: Option<String> = None;
 
         let all_splitlength_items = data_item_elem.get_items("splitByLength");
+        let _field_scope = FieldScope::enter();
         let mut sub_data_segment = data_segment;
         let mut pos = 0;
         let mut sub_item_result: Option<Vec<Value>>;
@@ -1077,6 +1158,21 @@ impl FrameAnalisyic {
         }
 
         for splitlength_item in all_splitlength_items {
+            // 按已解析的字段展开switch，无匹配分支时该字段不存在
+            let switched = SwitchCase::has_switch(&splitlength_item);
+            let splitlength_item = if switched {
+                let values = FieldValues::from_scope(analyzer, protocol, region, dir);
+                match SwitchCase::resolve(&splitlength_item, &values) {
+                    Ok(Some(resolved)) => resolved,
+                    Ok(None) => continue,
+                    Err(e) => {
+                        warn!("{}", e);
+                        break;
+                    }
+                }
+            } else {
+                splitlength_item
+            };
             let mut splitlength_item_clone = splitlength_item.clone();
             let sub_item_id = splitlength_item.get_attribute("id");
             let sub_item_name = splitlength_item.get_child_text("name");
@@ -1091,6 +1187,7 @@ impl FrameAnalisyic {
                 Some(sub_item_length) => match sub_item_length.to_uppercase().as_str() {
                     "UNKNOWN" => {
                         subitem_length = FrameCsg::calculate_item_length(
+                            analyzer,
                             &mut splitlength_item_clone,
                             &sub_data_segment,
                             protocol,
@@ -1106,8 +1203,9 @@ impl FrameAnalisyic {
                 _ => {
                     let item_box = splitlength_item.get_items("item");
                     if item_box.len() > 0 {
-                        subitem_length =
-                            Self::caculate_item_box_length(&item_box, protocol, region, dir);
+                        subitem_length = Self::caculate_item_box_length(
+                            analyzer, &item_box, protocol, region, dir,
+                        );
                     }
                 }
             }
@@ -1116,6 +1214,9 @@ impl FrameAnalisyic {
                 sub_data_segment, subitem_length
             );
             splitlength_item_clone.update_value("length", subitem_length.to_string());
+            if switched && subitem_length == 0 {
+                continue;
+            }
 
             if subitem_length > sub_data_segment.len() {
                 info!(
@@ -1131,6 +1232,7 @@ impl FrameAnalisyic {
                 && splitlength_item.get_child("value").is_some()
             {
                 let (cur_result, sub_result, length, cur_color) = Self::prase_value_item(
+                    analyzer,
                     &mut splitlength_item.clone(),
                     subitem_content,
                     index + pos,
@@ -1165,6 +1267,7 @@ impl FrameAnalisyic {
                 );
             } else if splitlength_item.get_child("value").is_some() {
                 let (cur_result, sub_result, length, cur_color) = Self::prase_value_item(
+                    analyzer,
                     &mut splitlength_item.clone(),
                     subitem_content,
                     index + pos,
@@ -1209,6 +1312,7 @@ impl FrameAnalisyic {
                 result_str = "".to_string();
             } else if splitlength_item.get_child("splitByLength").is_some() {
                 let (sub_result, length) = Self::prase_split_by_length_item(
+                    analyzer,
                     &splitlength_item,
                     subitem_content,
                     index + pos,
@@ -1222,6 +1326,7 @@ impl FrameAnalisyic {
                 result_str = "".to_string();
             } else if splitlength_item.get_child("type").is_some() {
                 let (mut cur_result, sub_result, length) = Self::prase_type_item(
+                    analyzer,
                     &mut splitlength_item.clone(),
                     subitem_content,
                     index + pos,
@@ -1239,6 +1344,7 @@ impl FrameAnalisyic {
                 cur_length = length;
             } else if splitlength_item.get_child("item").is_some() {
                 let (sub_result, length) = Self::prase_item_box(
+                    analyzer,
                     &splitlength_item,
                     subitem_content,
                     index + pos,
@@ -1301,6 +1407,8 @@ impl FrameAnalisyic {
                     color.clone(),
                 );
             }
+            // 记录已解析的字段，供后续字段的lengthrule引用
+            FieldScope::record(&splitlength_item_clone, subitem_content, need_delete);
             pos += subitem_length;
             sub_data_segment = &sub_data_segment[subitem_length..];
             info!("cur_length:{:?}", cur_length);
@@ -1308,7 +1416,9 @@ impl FrameAnalisyic {
         (result, pos)
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn prase_item_box(
+        analyzer: &Analyzer,
         data_item_elem: &XmlElement,
         data_segment: &[u8],
         index: usize,
@@ -1334,14 +1444,14 @@ impl FrameAnalisyic {
             if let Some(item_id) = item_id {
                 item_result_name = item_id.clone();
                 info!("prase_item_box item_id: {:?}", item_id);
-                let item_element =
-                    ProtocolConfigManager::get_config_xml(&item_id, protocol, region, dir);
+                let item_element = analyzer.get_config_xml(&item_id, protocol, region, dir);
                 let mut cur_length = 0;
                 if let Some(mut item_element) = item_element {
                     let item_length = item_element.get_child_text("length");
                     if let Some(item_length) = item_length {
                         if item_length.to_uppercase().as_str() == "UNKNOWN" {
                             cur_length = FrameCsg::calculate_item_length(
+                                analyzer,
                                 &mut item_element,
                                 &subitem_content,
                                 protocol,
@@ -1355,6 +1465,7 @@ impl FrameAnalisyic {
                     }
                     let item_data = &subitem_content[..cur_length];
                     result = Self::prase_data_item(
+                        analyzer,
                         &mut item_element,
                         item_data,
                         index + pos,
@@ -1389,6 +1500,7 @@ impl FrameAnalisyic {
     }
 
     pub fn caculate_item_box_length(
+        analyzer: &Analyzer,
         all_items: &Vec<XmlElement>,
         protocol: &str,
         region: &str,
@@ -1401,9 +1513,7 @@ impl FrameAnalisyic {
             if let Some(item_id) = item_elem.get_value() {
                 // 调用 `ConfigManager` 获取与 item_id 相关的 XML 元素
                 info!("caculate_item_box_length item_id: {:?}", item_id);
-                if let Some(item) =
-                    ProtocolConfigManager::get_config_xml(&item_id, protocol, region, dir)
-                {
+                if let Some(item) = analyzer.get_config_xml(&item_id, protocol, region, dir) {
                     // 获取 `length` 子元素
                     if let Some(item_length) = item.get_child_text("length") {
                         if let Ok(item_length) = item_length.parse::<usize>() {
@@ -1417,6 +1527,7 @@ impl FrameAnalisyic {
     }
 
     pub fn prase_type_item(
+        analyzer: &Analyzer,
         item_element: &XmlElement,
         data_segment: &[u8],
         index: usize,
@@ -1479,6 +1590,7 @@ impl FrameAnalisyic {
                 }
                 "ITEM" => {
                     let result_vec = Self::prase_item_type(
+                        analyzer,
                         &data_content,
                         index,
                         singal_length as usize,
@@ -1491,6 +1603,7 @@ impl FrameAnalisyic {
                 "FRAME645" => {
                     let mut result_vec: Vec<Value> = Vec::new();
                     Frame645::analysic_645_frame_by_afn(
+                        analyzer,
                         &data_content,
                         &mut result_vec,
                         index,
@@ -1502,6 +1615,7 @@ impl FrameAnalisyic {
                 "FRAMECSG13" => {
                     let mut result_vec: Vec<Value> = Vec::new();
                     match FrameCsg::analysic_csg_frame_by_afn(
+                        analyzer,
                         &data_content,
                         &mut result_vec,
                         index,
@@ -1526,15 +1640,15 @@ impl FrameAnalisyic {
                     sub_item_result = Some(result_vec);
                 }
                 _ => {
-                    let template_element = ProtocolConfigManager::get_template_element(
-                        &sub_type, protocol, region, dir,
-                    );
+                    let template_element =
+                        analyzer.get_template_element(&sub_type, protocol, region, dir);
                     info!(
                         "template_element: {:?}, protocol: {:?}, region: {:?}, dir: {:?}",
                         template_element, protocol, region, dir
                     );
                     if let Some(template_element) = template_element {
                         let (cur_result, result_vec, length) = Self::prase_template_type(
+                            analyzer,
                             &template_element,
                             &data_content,
                             singal_length as usize,
@@ -1642,6 +1756,7 @@ impl FrameAnalisyic {
     }
 
     pub fn prase_item_type(
+        analyzer: &Analyzer,
         data_segment: &[u8],
         index: usize,
         item_len: usize,
@@ -1660,8 +1775,7 @@ impl FrameAnalisyic {
                 let item_id = FrameFun::get_data_str(sub_data, false, true, false);
                 let mut item_description: String = item_id.clone();
                 info!("prase_item_type item_id: {:?}", item_id);
-                if let Some(item_element) =
-                    ProtocolConfigManager::get_config_xml(&item_id, protocol, region, dir)
+                if let Some(item_element) = analyzer.get_config_xml(&item_id, protocol, region, dir)
                 {
                     if let Some(element_name) = item_element.get_child_text("name") {
                         item_description =
@@ -1733,6 +1847,7 @@ impl FrameAnalisyic {
     }
 
     pub fn prase_template_type(
+        analyzer: &Analyzer,
         item_element: &XmlElement,
         data_segment: &[u8],
         item_len: usize,
@@ -1762,6 +1877,7 @@ impl FrameAnalisyic {
         let subitem_length = if let Some(length) = length_ele {
             if length.to_uppercase() == "UNKNOWN" {
                 FrameCsg::calculate_item_length(
+                    analyzer,
                     &mut item_element_clone,
                     &data_segment,
                     protocol,
@@ -1774,6 +1890,7 @@ impl FrameAnalisyic {
             }
         } else {
             FrameCsg::calculate_item_length(
+                analyzer,
                 &mut item_element_clone,
                 &data_segment,
                 protocol,
@@ -1832,6 +1949,7 @@ impl FrameAnalisyic {
                     .unwrap_or_else(|| format!("第{}组数据内容", i + 1));
 
                 let item_value = Self::prase_data_item(
+                    analyzer,
                     &mut item_element_clone,
                     sub_data,
                     index + pos,
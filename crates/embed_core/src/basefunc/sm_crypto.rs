@@ -0,0 +1,331 @@
+// 国密SM4分组密码(GB/T 32907)和SM3杂凑算法(GB/T 32905)的软件实现
+
+pub const SM4_BLOCK_LENGTH: usize = 16;
+pub const SM3_DIGEST_LENGTH: usize = 32;
+
+const SM4_SBOX: [u8; 256] = [
+    0xD6, 0x90, 0xE9, 0xFE, 0xCC, 0xE1, 0x3D, 0xB7, 0x16, 0xB6, 0x14, 0xC2, 0x28, 0xFB, 0x2C, 0x05,
+    0x2B, 0x67, 0x9A, 0x76, 0x2A, 0xBE, 0x04, 0xC3, 0xAA, 0x44, 0x13, 0x26, 0x49, 0x86, 0x06, 0x99,
+    0x9C, 0x42, 0x50, 0xF4, 0x91, 0xEF, 0x98, 0x7A, 0x33, 0x54, 0x0B, 0x43, 0xED, 0xCF, 0xAC, 0x62,
+    0xE4, 0xB3, 0x1C, 0xA9, 0xC9, 0x08, 0xE8, 0x95, 0x80, 0xDF, 0x94, 0xFA, 0x75, 0x8F, 0x3F, 0xA6,
+    0x47, 0x07, 0xA7, 0xFC, 0xF3, 0x73, 0x17, 0xBA, 0x83, 0x59, 0x3C, 0x19, 0xE6, 0x85, 0x4F, 0xA8,
+    0x68, 0x6B, 0x81, 0xB2, 0x71, 0x64, 0xDA, 0x8B, 0xF8, 0xEB, 0x0F, 0x4B, 0x70, 0x56, 0x9D, 0x35,
+    0x1E, 0x24, 0x0E, 0x5E, 0x63, 0x58, 0xD1, 0xA2, 0x25, 0x22, 0x7C, 0x3B, 0x01, 0x21, 0x78, 0x87,
+    0xD4, 0x00, 0x46, 0x57, 0x9F, 0xD3, 0x27, 0x52, 0x4C, 0x36, 0x02, 0xE7, 0xA0, 0xC4, 0xC8, 0x9E,
+    0xEA, 0xBF, 0x8A, 0xD2, 0x40, 0xC7, 0x38, 0xB5, 0xA3, 0xF7, 0xF2, 0xCE, 0xF9, 0x61, 0x15, 0xA1,
+    0xE0, 0xAE, 0x5D, 0xA4, 0x9B, 0x34, 0x1A, 0x55, 0xAD, 0x93, 0x32, 0x30, 0xF5, 0x8C, 0xB1, 0xE3,
+    0x1D, 0xF6, 0xE2, 0x2E, 0x82, 0x66, 0xCA, 0x60, 0xC0, 0x29, 0x23, 0xAB, 0x0D, 0x53, 0x4E, 0x6F,
+    0xD5, 0xDB, 0x37, 0x45, 0xDE, 0xFD, 0x8E, 0x2F, 0x03, 0xFF, 0x6A, 0x72, 0x6D, 0x6C, 0x5B, 0x51,
+    0x8D, 0x1B, 0xAF, 0x92, 0xBB, 0xDD, 0xBC, 0x7F, 0x11, 0xD9, 0x5C, 0x41, 0x1F, 0x10, 0x5A, 0xD8,
+    0x0A, 0xC1, 0x31, 0x88, 0xA5, 0xCD, 0x7B, 0xBD, 0x2D, 0x74, 0xD0, 0x12, 0xB8, 0xE5, 0xB4, 0xB0,
+    0x89, 0x69, 0x97, 0x4A, 0x0C, 0x96, 0x77, 0x7E, 0x65, 0xB9, 0xF1, 0x09, 0xC5, 0x6E, 0xC6, 0x84,
+    0x18, 0xF0, 0x7D, 0xEC, 0x3A, 0xDC, 0x4D, 0x20, 0x79, 0xEE, 0x5F, 0x3E, 0xD7, 0xCB, 0x39, 0x48,
+];
+
+const SM4_FK: [u32; 4] = [0xA3B1BAC6, 0x56AA3350, 0x677D9197, 0xB27022DC];
+
+const SM3_IV: [u32; 8] = [
+    0x7380166F, 0x4914B2B9, 0x172442D7, 0xDA8A0600, 0xA96F30BC, 0x163138AA, 0xE38DEE4D, 0xB0FB0E4E,
+];
+
+/// SM4分组密码，按16字节密钥展开轮密钥
+pub struct Sm4 {
+    round_keys: [u32; 32],
+}
+
+impl Sm4 {
+    pub fn new(key: &[u8; SM4_BLOCK_LENGTH]) -> Self {
+        let mut k = [0u32; 36];
+        for i in 0..4 {
+            k[i] = u32::from_be_bytes([key[4 * i], key[4 * i + 1], key[4 * i + 2], key[4 * i + 3]])
+                ^ SM4_FK[i];
+        }
+        let mut round_keys = [0u32; 32];
+        for i in 0..32 {
+            k[i + 4] = k[i] ^ Self::key_transform(k[i + 1] ^ k[i + 2] ^ k[i + 3] ^ Self::ck(i));
+            round_keys[i] = k[i + 4];
+        }
+        Sm4 { round_keys }
+    }
+
+    pub fn encrypt_block(&self, block: &mut [u8; SM4_BLOCK_LENGTH]) {
+        Self::crypt_block(block, self.round_keys.iter());
+    }
+
+    pub fn decrypt_block(&self, block: &mut [u8; SM4_BLOCK_LENGTH]) {
+        Self::crypt_block(block, self.round_keys.iter().rev());
+    }
+
+    /// ECB加密，数据长度须为16的整数倍
+    pub fn encrypt_ecb(&self, data: &[u8]) -> Result<Vec<u8>, String> {
+        Self::check_length(data)?;
+        Ok(data
+            .chunks(SM4_BLOCK_LENGTH)
+            .flat_map(|chunk| {
+                let mut block = Self::to_block(chunk);
+                self.encrypt_block(&mut block);
+                block
+            })
+            .collect())
+    }
+
+    /// ECB解密，数据长度须为16的整数倍
+    pub fn decrypt_ecb(&self, data: &[u8]) -> Result<Vec<u8>, String> {
+        Self::check_length(data)?;
+        Ok(data
+            .chunks(SM4_BLOCK_LENGTH)
+            .flat_map(|chunk| {
+                let mut block = Self::to_block(chunk);
+                self.decrypt_block(&mut block);
+                block
+            })
+            .collect())
+    }
+
+    /// CBC加密，数据长度须为16的整数倍
+    pub fn encrypt_cbc(&self, iv: &[u8; SM4_BLOCK_LENGTH], data: &[u8]) -> Result<Vec<u8>, String> {
+        Self::check_length(data)?;
+        let mut result = Vec::with_capacity(data.len());
+        let mut chain = *iv;
+        for chunk in data.chunks(SM4_BLOCK_LENGTH) {
+            for (c, d) in chain.iter_mut().zip(chunk) {
+                *c ^= d;
+            }
+            self.encrypt_block(&mut chain);
+            result.extend_from_slice(&chain);
+        }
+        Ok(result)
+    }
+
+    /// CBC解密，数据长度须为16的整数倍
+    pub fn decrypt_cbc(&self, iv: &[u8; SM4_BLOCK_LENGTH], data: &[u8]) -> Result<Vec<u8>, String> {
+        Self::check_length(data)?;
+        let mut result = Vec::with_capacity(data.len());
+        let mut chain = *iv;
+        for chunk in data.chunks(SM4_BLOCK_LENGTH) {
+            let mut block = Self::to_block(chunk);
+            self.decrypt_block(&mut block);
+            for (b, c) in block.iter_mut().zip(chain.iter()) {
+                *b ^= c;
+            }
+            result.extend_from_slice(&block);
+            chain = Self::to_block(chunk);
+        }
+        Ok(result)
+    }
+
+    /// CBC-MAC：按pad_80补齐后CBC加密，取最后一个分组
+    pub fn cbc_mac(&self, iv: &[u8; SM4_BLOCK_LENGTH], data: &[u8]) -> [u8; SM4_BLOCK_LENGTH] {
+        let mut chain = *iv;
+        for chunk in Self::pad_80(data).chunks(SM4_BLOCK_LENGTH) {
+            for (c, d) in chain.iter_mut().zip(chunk) {
+                *c ^= d;
+            }
+            self.encrypt_block(&mut chain);
+        }
+        chain
+    }
+
+    /// 补0x80后补0x00至16的整数倍，数据已对齐时也补一个分组
+    pub fn pad_80(data: &[u8]) -> Vec<u8> {
+        let mut padded = data.to_vec();
+        padded.push(0x80);
+        while !padded.len().is_multiple_of(SM4_BLOCK_LENGTH) {
+            padded.push(0x00);
+        }
+        padded
+    }
+
+    /// 去掉pad_80补的数据，补位不符时返回错误
+    pub fn unpad_80(data: &[u8]) -> Result<&[u8], String> {
+        let tail_start = data.len().saturating_sub(SM4_BLOCK_LENGTH);
+        match data[tail_start..].iter().rposition(|&b| b != 0x00) {
+            Some(pos) if data[tail_start + pos] == 0x80 => Ok(&data[..tail_start + pos]),
+            _ => Err("补位数据错误，密钥可能不正确".to_string()),
+        }
+    }
+
+    fn crypt_block<'a>(
+        block: &mut [u8; SM4_BLOCK_LENGTH],
+        round_keys: impl Iterator<Item = &'a u32>,
+    ) {
+        let mut x = [0u32; 4];
+        for (i, word) in x.iter_mut().enumerate() {
+            *word = u32::from_be_bytes([
+                block[4 * i],
+                block[4 * i + 1],
+                block[4 * i + 2],
+                block[4 * i + 3],
+            ]);
+        }
+        for rk in round_keys {
+            let next = x[0] ^ Self::transform(x[1] ^ x[2] ^ x[3] ^ rk);
+            x = [x[1], x[2], x[3], next];
+        }
+        for (i, word) in x.iter().rev().enumerate() {
+            block[4 * i..4 * i + 4].copy_from_slice(&word.to_be_bytes());
+        }
+    }
+
+    fn tau(a: u32) -> u32 {
+        u32::from_be_bytes(a.to_be_bytes().map(|b| SM4_SBOX[b as usize]))
+    }
+
+    fn transform(a: u32) -> u32 {
+        let b = Self::tau(a);
+        b ^ b.rotate_left(2) ^ b.rotate_left(10) ^ b.rotate_left(18) ^ b.rotate_left(24)
+    }
+
+    fn key_transform(a: u32) -> u32 {
+        let b = Self::tau(a);
+        b ^ b.rotate_left(13) ^ b.rotate_left(23)
+    }
+
+    // CK第i个字的第j字节为(4i+j)*7 mod 256
+    fn ck(i: usize) -> u32 {
+        let byte = |j: usize| ((4 * i + j) * 7 % 256) as u8;
+        u32::from_be_bytes([byte(0), byte(1), byte(2), byte(3)])
+    }
+
+    fn to_block(chunk: &[u8]) -> [u8; SM4_BLOCK_LENGTH] {
+        let mut block = [0u8; SM4_BLOCK_LENGTH];
+        block.copy_from_slice(chunk);
+        block
+    }
+
+    fn check_length(data: &[u8]) -> Result<(), String> {
+        if !data.len().is_multiple_of(SM4_BLOCK_LENGTH) {
+            return Err(format!("数据长度{}不是16的整数倍", data.len()));
+        }
+        Ok(())
+    }
+}
+
+/// SM3杂凑算法
+pub struct Sm3;
+
+impl Sm3 {
+    pub fn hash(data: &[u8]) -> [u8; SM3_DIGEST_LENGTH] {
+        let mut state = SM3_IV;
+        let mut message = data.to_vec();
+        message.push(0x80);
+        while message.len() % 64 != 56 {
+            message.push(0x00);
+        }
+        message.extend_from_slice(&((data.len() as u64) * 8).to_be_bytes());
+        for block in message.chunks(64) {
+            Self::compress(&mut state, block);
+        }
+
+        let mut digest = [0u8; SM3_DIGEST_LENGTH];
+        for (i, word) in state.iter().enumerate() {
+            digest[4 * i..4 * i + 4].copy_from_slice(&word.to_be_bytes());
+        }
+        digest
+    }
+
+    fn compress(state: &mut [u32; 8], block: &[u8]) {
+        let mut w = [0u32; 68];
+        for i in 0..16 {
+            w[i] = u32::from_be_bytes([
+                block[4 * i],
+                block[4 * i + 1],
+                block[4 * i + 2],
+                block[4 * i + 3],
+            ]);
+        }
+        for j in 16..68 {
+            w[j] = Self::p1(w[j - 16] ^ w[j - 9] ^ w[j - 3].rotate_left(15))
+                ^ w[j - 13].rotate_left(7)
+                ^ w[j - 6];
+        }
+
+        let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = *state;
+        for j in 0..64 {
+            let t: u32 = if j < 16 { 0x79CC4519 } else { 0x7A879D8A };
+            let ss1 = a
+                .rotate_left(12)
+                .wrapping_add(e)
+                .wrapping_add(t.rotate_left(j as u32 % 32))
+                .rotate_left(7);
+            let ss2 = ss1 ^ a.rotate_left(12);
+            let (ff, gg) = if j < 16 {
+                (a ^ b ^ c, e ^ f ^ g)
+            } else {
+                ((a & b) | (a & c) | (b & c), (e & f) | (!e & g))
+            };
+            let tt1 = ff
+                .wrapping_add(d)
+                .wrapping_add(ss2)
+                .wrapping_add(w[j] ^ w[j + 4]);
+            let tt2 = gg.wrapping_add(h).wrapping_add(ss1).wrapping_add(w[j]);
+            d = c;
+            c = b.rotate_left(9);
+            b = a;
+            a = tt1;
+            h = g;
+            g = f.rotate_left(19);
+            f = e;
+            e = Self::p0(tt2);
+        }
+
+        for (s, v) in state.iter_mut().zip([a, b, c, d, e, f, g, h]) {
+            *s ^= v;
+        }
+    }
+
+    fn p0(x: u32) -> u32 {
+        x ^ x.rotate_left(9) ^ x.rotate_left(17)
+    }
+
+    fn p1(x: u32) -> u32 {
+        x ^ x.rotate_left(15) ^ x.rotate_left(23)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bytes<const N: usize>(text: &str) -> [u8; N] {
+        <[u8; N]>::try_from(hex::decode(text).unwrap().as_slice()).unwrap()
+    }
+
+    // GB/T 32907附录A示例1
+    #[test]
+    fn sm4_known_answer() {
+        let key = bytes::<16>("0123456789ABCDEFFEDCBA9876543210");
+        let sm4 = Sm4::new(&key);
+        let mut block = key;
+        sm4.encrypt_block(&mut block);
+        assert_eq!(block, bytes::<16>("681EDF34D206965E86B3E94F536E4246"));
+        sm4.decrypt_block(&mut block);
+        assert_eq!(block, key);
+    }
+
+    #[test]
+    fn sm4_cbc_roundtrip() {
+        let sm4 = Sm4::new(&bytes::<16>("0123456789ABCDEFFEDCBA9876543210"));
+        let iv = [0x5A; SM4_BLOCK_LENGTH];
+        let plain = Sm4::pad_80(b"0123456789ABCDEF01");
+        assert_eq!(plain.len(), 32);
+        let cipher = sm4.encrypt_cbc(&iv, &plain).unwrap();
+        assert_ne!(cipher, plain);
+        let decrypted = sm4.decrypt_cbc(&iv, &cipher).unwrap();
+        assert_eq!(Sm4::unpad_80(&decrypted).unwrap(), b"0123456789ABCDEF01");
+        assert!(sm4.encrypt_cbc(&iv, &plain[..31]).is_err());
+    }
+
+    // GB/T 32905附录A示例1、示例2
+    #[test]
+    fn sm3_known_answer() {
+        assert_eq!(
+            Sm3::hash(b"abc"),
+            bytes::<32>("66C7F0F462EEEDD9D1F2D46BDC10E4E24167C4875CF2F7A2297DA02B8F4BA8E0")
+        );
+        assert_eq!(
+            Sm3::hash("abcd".repeat(16).as_bytes()),
+            bytes::<32>("DEBE9FF92275B8A138604889C18E5A4D6FDB70E5387E5765293DCBA39C0C5732")
+        );
+    }
+}
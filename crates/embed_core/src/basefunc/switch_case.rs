@@ -0,0 +1,181 @@
+use crate::basefunc::frame_fun::FrameFun;
+use crate::basefunc::length_expr::{FieldValues, LengthExpr};
+use crate::config::xmlconfig::XmlElement;
+use tracing::info;
+
+pub const SWITCH_NODE: &str = "switch";
+pub const CASE_NODE: &str = "case";
+pub const DEFAULT_NODE: &str = "default";
+
+/// 按已解析字段选择数据项结构
+///
+/// ```xml
+/// <splitByLength>
+///     <name>数据内容</name>
+///     <switch on="数据类型">
+///         <case value="1,2"><length>4</length><type>BCD</type></case>
+///         <case raw="FFFF"><length>0</length></case>
+///         <default><length>unknown</length>...</default>
+///     </switch>
+/// </splitByLength>
+/// ```
+///
+/// `on`为lengthrule表达式，`value`按表达式的值匹配(逗号分隔，支持0x十六进制和`a..b`区间)，
+/// `raw`按`on`引用字段的原始报文(十六进制，逗号分隔)匹配。
+/// 选中分支的子元素替换数据项中同名的子元素，splitByLength追加在后。
+pub struct SwitchCase;
+
+impl SwitchCase {
+    pub fn has_switch(element: &XmlElement) -> bool {
+        element.get_child(SWITCH_NODE).is_some()
+    }
+
+    /// 展开数据项中的switch，没有分支匹配且无default时返回None，表示该数据项不存在
+    pub fn resolve(
+        element: &XmlElement,
+        values: &FieldValues,
+    ) -> Result<Option<XmlElement>, String> {
+        let Some(switch) = element.get_child(SWITCH_NODE) else {
+            return Ok(Some(element.clone()));
+        };
+        let label = element
+            .get_attribute("id")
+            .cloned()
+            .or_else(|| element.get_child_text("name"))
+            .unwrap_or_else(|| element.name.clone());
+        let case = Self::select_case(switch, values)
+            .map_err(|e| format!("数据项[{}]的switch选择失败: {}", label, e))?;
+        let Some(case) = case else {
+            info!("数据项[{}]的switch无匹配分支", label);
+            return Ok(None);
+        };
+
+        let mut resolved = element.clone();
+        resolved.children.retain(|child| child.name != SWITCH_NODE);
+        for child in &case.children {
+            let existing = resolved
+                .children
+                .iter()
+                .position(|c| c.name == child.name && child.name != "splitByLength");
+            match existing {
+                Some(index) => resolved.children[index] = child.clone(),
+                None => resolved.children.push(child.clone()),
+            }
+        }
+        // 分支内可以嵌套switch
+        Self::resolve(&resolved, values)
+    }
+
+    fn select_case<'a>(
+        switch: &'a XmlElement,
+        values: &FieldValues,
+    ) -> Result<Option<&'a XmlElement>, String> {
+        let on = switch.get_attribute("on").ok_or("缺少on属性")?;
+        let expr = LengthExpr::parse(on)?;
+        expr.check()?;
+        // 只有raw分支时不要求字段能按数值解码
+        let value = expr.eval(values);
+        let raw = match &expr {
+            LengthExpr::Field(name) => values
+                .raw(name)
+                .map(|data| FrameFun::get_data_str(&data, false, false, false)),
+            _ => None,
+        };
+
+        for case in switch.children.iter().filter(|c| c.name == CASE_NODE) {
+            if let Some(pattern) = case.get_attribute("value") {
+                let value = value.clone()?;
+                if Self::match_value(pattern, value)? {
+                    return Ok(Some(case));
+                }
+            }
+            if let Some(pattern) = case.get_attribute("raw") {
+                let raw = raw.as_ref().ok_or("raw匹配要求on为已解析的字段名")?;
+                if pattern
+                    .split(',')
+                    .any(|p| p.trim().replace(' ', "").eq_ignore_ascii_case(raw))
+                {
+                    return Ok(Some(case));
+                }
+            }
+        }
+        Ok(switch.children.iter().find(|c| c.name == DEFAULT_NODE))
+    }
+
+    /// 分支取值是否匹配，取值格式有误时返回错误
+    pub fn match_value(pattern: &str, value: i64) -> Result<bool, String> {
+        for part in pattern.split(',').map(|p| p.trim()) {
+            let matched = match part.split_once("..") {
+                Some((start, end)) => {
+                    (Self::parse_number(start)?..=Self::parse_number(end)?).contains(&value)
+                }
+                None => Self::parse_number(part)? == value,
+            };
+            if matched {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    fn parse_number(text: &str) -> Result<i64, String> {
+        let text = text.trim();
+        match text.strip_prefix("0x").or(text.strip_prefix("0X")) {
+            Some(hex) => i64::from_str_radix(hex, 16),
+            None => text.parse::<i64>(),
+        }
+        .map_err(|_| format!("分支取值[{}]不是数字", text))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn match_single_and_list() {
+        assert_eq!(SwitchCase::match_value("3", 3), Ok(true));
+        assert_eq!(SwitchCase::match_value("3", 4), Ok(false));
+        assert_eq!(SwitchCase::match_value("1, 2,5", 5), Ok(true));
+        assert_eq!(SwitchCase::match_value("0x1F,0X20", 32), Ok(true));
+        assert_eq!(SwitchCase::match_value("-1", -1), Ok(true));
+    }
+
+    #[test]
+    fn match_range() {
+        // 区间包含两端
+        assert_eq!(SwitchCase::match_value("1..4", 1), Ok(true));
+        assert_eq!(SwitchCase::match_value("1..4", 4), Ok(true));
+        assert_eq!(SwitchCase::match_value("1..4", 0), Ok(false));
+        assert_eq!(SwitchCase::match_value("1..4", 5), Ok(false));
+        assert_eq!(SwitchCase::match_value("0x10..0x1F", 0x18), Ok(true));
+        assert_eq!(SwitchCase::match_value(" 2 .. 3 ", 3), Ok(true));
+        assert_eq!(SwitchCase::match_value("-5..-1", -3), Ok(true));
+        assert_eq!(SwitchCase::match_value("5..5", 5), Ok(true));
+        // 起点大于终点的区间为空
+        assert_eq!(SwitchCase::match_value("4..1", 2), Ok(false));
+        assert_eq!(SwitchCase::match_value("1..2,7..9,12", 8), Ok(true));
+        assert_eq!(SwitchCase::match_value("1..2,7..9,12", 10), Ok(false));
+    }
+
+    #[test]
+    fn match_invalid_pattern() {
+        assert_eq!(
+            SwitchCase::match_value("a", 1),
+            Err("分支取值[a]不是数字".to_string())
+        );
+        assert_eq!(
+            SwitchCase::match_value("1..", 1),
+            Err("分支取值[]不是数字".to_string())
+        );
+        assert_eq!(
+            SwitchCase::match_value("1..0xG", 1),
+            Err("分支取值[0xG]不是数字".to_string())
+        );
+        assert_eq!(
+            SwitchCase::match_value("1,,2", 2),
+            Err("分支取值[]不是数字".to_string())
+        );
+        assert!(SwitchCase::match_value("1...3", 2).is_err());
+    }
+}
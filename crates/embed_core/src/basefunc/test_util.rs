@@ -0,0 +1,15 @@
+// basefunc/test_util.rs
+// 单元测试共用的辅助函数
+use serde_json::Value;
+
+// 在解析结果树中按帧域名称深度优先查找第一个节点
+pub(crate) fn find<'a>(nodes: &'a [Value], domain: &str) -> Option<&'a Value> {
+    nodes.iter().find_map(|node| {
+        if node["frameDomain"] == domain {
+            return Some(node);
+        }
+        node["children"]
+            .as_array()
+            .and_then(|children| find(children, domain))
+    })
+}
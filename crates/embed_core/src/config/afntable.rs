@@ -0,0 +1,598 @@
+use serde::{Deserialize, Serialize};
+
+use crate::config::xmlconfig::XmlNode;
+
+// 功能码表，形如<afnTable><afn code="0C" name="读当前数据" handler="read_cur"/></afnTable>
+pub const AFN_TABLE_NODE: &str = "afnTable";
+pub const AFN_NODE: &str = "afn";
+// 内置功能码表所在的配置层
+pub const BUILTIN_LAYER: &str = "builtin";
+
+/// 南网13规约按AFN解析报文的方式
+///
+/// 厂家自定义AFN的报文结构与标准AFN一致时，选用对应的解析方式即可：
+/// cascade/user_defined上下行均为DA+DI+数据，下行优先取配置中以dir="0"定义的数据标识，未定义时取通用定义
+/// cascade/user_defined上行DA+DI+数据，下行仅配置中以dir="0"定义的数据标识带数据
+pub const CSG13_HANDLERS: &[&str] = &[
+    "ack",
+    "link",
+    "write",
+    "security",
+    "read_param",
+    "read_cur",
+    "read_history",
+    "read_event",
+    "filetrans",
+    "relay",
+    "read_task",
+    "read_alarm",
+    "topo",
+    "cascade",
+    "user_defined",
+];
+
+/// 645规约按控制码解析报文的方式
+pub const DLT645_HANDLERS: &[&str] = &[
+    "read",
+    "read_response",
+    "read_err",
+    "read_subsequent",
+    "read_subsequent_response",
+    "write",
+    "read_address",
+    "write_address",
+    "broadcast_time",
+    "frozen_time",
+    "baud_rate",
+    "password",
+    "password_response",
+    "demand_reset",
+    "meter_reset",
+    "event_reset",
+];
+
+/// 645-1997规约按控制码解析报文的方式
+pub const DLT645_97_HANDLERS: &[&str] = &[
+    "read",
+    "read_response",
+    "read_err",
+    "reread",
+    "write",
+    "write_response",
+    "broadcast_time",
+    "write_address",
+    "baud_rate",
+    "password",
+    "password_response",
+    "demand_reset",
+];
+
+/// 698.45规约按应用层服务标签解析报文的方式
+pub const DLT698_HANDLERS: &[&str] = &[
+    "link_request",
+    "link_response",
+    "connect_request",
+    "connect_response",
+    "release_request",
+    "release_response",
+    "release_notification",
+    "get_request",
+    "get_response",
+    "set_request",
+    "set_response",
+    "action_request",
+    "action_response",
+    "report_notification",
+    "report_response",
+    "proxy_request",
+    "proxy_response",
+    "security_request",
+    "security_response",
+    "error_response",
+];
+
+/// 1376.1规约按AFN解析报文的方式
+///
+/// 数据单元均为DA+DT+数据，command的下行报文在数据单元后附带16字节消息认证码PW
+pub const GDW13761_HANDLERS: &[&str] = &["ack", "request", "command"];
+
+/// 功能码(AFN)或控制码的定义
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct AfnEntry {
+    pub code: u8,
+    pub name: String,
+    // 解析方式，未设置时只显示名称
+    pub handler: Option<String>,
+    // 本地通信报文的通信双方类型(DI3)，未设置时对所有类型生效
+    pub kind: Option<u8>,
+    // 仅对该数据标识生效
+    pub item: Option<String>,
+    pub layer: Option<String>,
+}
+
+impl AfnEntry {
+    fn new(code: u8, name: &str, handler: Option<&str>) -> Self {
+        AfnEntry {
+            code,
+            name: name.to_string(),
+            handler: handler.map(|h| h.to_string()),
+            kind: None,
+            item: None,
+            layer: Some(BUILTIN_LAYER.to_string()),
+        }
+    }
+
+    fn with_kind(mut self, kind: u8) -> Self {
+        self.kind = Some(kind);
+        self
+    }
+
+    fn with_item(mut self, item: &str) -> Self {
+        self.item = Some(item.to_string());
+        self
+    }
+
+    /// 从<afn>节点读取定义，code和type按十六进制解析
+    pub fn from_node(node: &XmlNode) -> Result<Self, String> {
+        let code = node.get_attribute("code").ok_or("功能码缺少code属性")?;
+        let name = node
+            .get_attribute("name")
+            .map(|name| name.trim())
+            .filter(|name| !name.is_empty())
+            .ok_or_else(|| format!("功能码[{}]缺少name属性", code))?;
+        let kind = node
+            .get_attribute("type")
+            .map(|kind| Self::parse_hex(kind))
+            .transpose()?;
+        Ok(AfnEntry {
+            code: Self::parse_hex(code)?,
+            name: name.to_string(),
+            handler: node
+                .get_attribute("handler")
+                .map(|handler| handler.trim().to_string())
+                .filter(|handler| !handler.is_empty()),
+            kind,
+            item: node
+                .get_attribute("item")
+                .map(|item| item.trim().to_uppercase()),
+            layer: None,
+        })
+    }
+
+    fn parse_hex(text: &str) -> Result<u8, String> {
+        let text = text.trim();
+        let digits = text
+            .strip_prefix("0x")
+            .or(text.strip_prefix("0X"))
+            .unwrap_or(text);
+        u8::from_str_radix(digits, 16).map_err(|_| format!("[{}]不是一个字节的十六进制数", text))
+    }
+
+    fn matches(&self, code: u8, kind: Option<u8>, item: Option<&str>) -> bool {
+        self.code == code
+            && self.kind.is_none_or(|k| Some(k) == kind)
+            && self
+                .item
+                .as_deref()
+                .is_none_or(|i| item.is_some_and(|item| i.eq_ignore_ascii_case(item)))
+    }
+
+    // 限定了数据标识或通信双方类型的定义优先
+    fn specificity(&self) -> u8 {
+        (self.item.is_some() as u8) * 2 + self.kind.is_some() as u8
+    }
+}
+
+pub struct AfnTable;
+
+impl AfnTable {
+    /// 内置功能码表，配置中的<afnTable>在此基础上追加或覆盖
+    pub fn builtin(protocol: &str) -> Vec<AfnEntry> {
+        let protocol = protocol.to_uppercase();
+        match protocol.as_str() {
+            p if p.contains("CSG13") => Self::builtin_csg13(),
+            p if p.contains("DLT/645-1997") => Self::builtin_645_97(),
+            p if p.contains("DLT/645") => Self::builtin_645(),
+            p if p.contains("DLT/698") => Self::builtin_698(),
+            p if p.contains("1376.1") => Self::builtin_gdw13761(),
+            p if p.contains("1376.2") => Self::builtin_gdw13762(),
+            p if p.contains("CSG16") => Self::builtin_csg16(),
+            p if p.contains("MODULE") || p.contains("MOUDLE") => Self::builtin_moudle(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// 查找功能码定义，同等限定条件下后定义的覆盖先定义的
+    pub fn find<'a>(
+        entries: &'a [AfnEntry],
+        code: u8,
+        kind: Option<u8>,
+        item: Option<&str>,
+    ) -> Option<&'a AfnEntry> {
+        entries
+            .iter()
+            .filter(|entry| entry.matches(code, kind, item))
+            .max_by_key(|entry| entry.specificity())
+    }
+
+    pub fn is_known_handler(handler: &str) -> bool {
+        CSG13_HANDLERS.contains(&handler)
+            || DLT645_HANDLERS.contains(&handler)
+            || DLT645_97_HANDLERS.contains(&handler)
+            || DLT698_HANDLERS.contains(&handler)
+            || GDW13761_HANDLERS.contains(&handler)
+    }
+
+    fn builtin_csg13() -> Vec<AfnEntry> {
+        [
+            (0x00, "确认/否定", Some("ack")),
+            (0x02, "链路接口检测", Some("link")),
+            (0x04, "写参数", Some("write")),
+            (0x06, "安全认证", Some("security")),
+            (0x0A, "读参数", Some("read_param")),
+            (0x0C, "读当前数据", Some("read_cur")),
+            (0x0D, "读历史数据", Some("read_history")),
+            (0x0E, "读事件记录", Some("read_event")),
+            (0x0F, "文件传输", Some("filetrans")),
+            (0x10, "中继转发", Some("relay")),
+            (0x12, "读任务数据", Some("read_task")),
+            (0x13, "读告警数据", Some("read_alarm")),
+            (0x14, "级联命令", Some("cascade")),
+            (0x15, "用户自定义数据", Some("user_defined")),
+            (0x16, "数据安全传输", None),
+            (0x17, "数据转加密", None),
+            (0x23, "主站中转报文", Some("topo")),
+        ]
+        .into_iter()
+        .map(|(code, name, handler)| AfnEntry::new(code, name, handler))
+        .collect()
+    }
+
+    fn builtin_645() -> Vec<AfnEntry> {
+        [
+            (0x08, "广播校时", "broadcast_time"),
+            (0x11, "读数据", "read"),
+            (0x91, "读数据正常应答", "read_response"),
+            (0xB1, "读数据正常应答(有后续数据)", "read_response"),
+            (0xD1, "读数据异常应答", "read_err"),
+            (0x12, "读后续数据", "read_subsequent"),
+            (0x92, "读后续数据正常应答", "read_subsequent_response"),
+            (
+                0xB2,
+                "读后续数据正常应答(有后续数据)",
+                "read_subsequent_response",
+            ),
+            (0xD2, "读后续数据异常应答", "read_err"),
+            (0x93, "读通信地址正常应答", "read_address"),
+            (0x14, "写数据", "write"),
+            (0xD4, "写数据异常应答", "read_err"),
+            (0x15, "写通信地址", "write_address"),
+            (0x16, "冻结命令", "frozen_time"),
+            (0xD6, "冻结命令异常应答", "read_err"),
+            (0x17, "更改通信速率", "baud_rate"),
+            (0x97, "更改通信速率正常应答", "baud_rate"),
+            (0xD7, "更改通信速率异常应答", "read_err"),
+            (0x18, "修改密码", "password"),
+            (0x98, "修改密码正常应答", "password_response"),
+            (0x19, "最大需量清零", "demand_reset"),
+            (0xD9, "最大需量清零异常应答", "read_err"),
+            (0x1A, "电表清零", "meter_reset"),
+            (0xDA, "电表清零异常应答", "read_err"),
+            (0x1B, "事件清零", "event_reset"),
+            (0xDB, "事件清零异常应答", "read_err"),
+        ]
+        .into_iter()
+        .map(|(code, name, handler)| AfnEntry::new(code, name, Some(handler)))
+        .collect()
+    }
+
+    fn builtin_645_97() -> Vec<AfnEntry> {
+        [
+            (0x01, "读数据", "read"),
+            (0x81, "读数据正常应答", "read_response"),
+            (0xA1, "读数据正常应答(有后续数据)", "read_response"),
+            (0xC1, "读数据异常应答", "read_err"),
+            (0x02, "读后续数据", "read"),
+            (0x82, "读后续数据正常应答", "read_response"),
+            (0xA2, "读后续数据正常应答(有后续数据)", "read_response"),
+            (0xC2, "读后续数据异常应答", "read_err"),
+            (0x03, "重读数据", "reread"),
+            (0x83, "重读数据正常应答", "read_response"),
+            (0xA3, "重读数据正常应答(有后续数据)", "read_response"),
+            (0xC3, "重读数据异常应答", "read_err"),
+            (0x04, "写数据", "write"),
+            (0x84, "写数据正常应答", "write_response"),
+            (0xC4, "写数据异常应答", "read_err"),
+            (0x08, "广播校时", "broadcast_time"),
+            (0x0A, "写设备地址", "write_address"),
+            (0x8A, "写设备地址正常应答", "write_response"),
+            (0x0C, "更改通信速率", "baud_rate"),
+            (0x8C, "更改通信速率正常应答", "baud_rate"),
+            (0xCC, "更改通信速率异常应答", "read_err"),
+            (0x0F, "修改密码", "password"),
+            (0x8F, "修改密码正常应答", "password_response"),
+            (0xCF, "修改密码异常应答", "read_err"),
+            (0x10, "最大需量清零", "demand_reset"),
+            (0x90, "最大需量清零正常应答", "write_response"),
+            (0xD0, "最大需量清零异常应答", "read_err"),
+        ]
+        .into_iter()
+        .map(|(code, name, handler)| AfnEntry::new(code, name, Some(handler)))
+        .collect()
+    }
+
+    fn builtin_698() -> Vec<AfnEntry> {
+        [
+            (0x01, "预连接请求(LINK-Request)", "link_request"),
+            (0x81, "预连接响应(LINK-Response)", "link_response"),
+            (0x02, "建立应用连接请求(CONNECT-Request)", "connect_request"),
+            (
+                0x82,
+                "建立应用连接响应(CONNECT-Response)",
+                "connect_response",
+            ),
+            (0x03, "断开应用连接请求(RELEASE-Request)", "release_request"),
+            (
+                0x83,
+                "断开应用连接响应(RELEASE-Response)",
+                "release_response",
+            ),
+            (
+                0x84,
+                "断开应用连接通知(RELEASE-Notification)",
+                "release_notification",
+            ),
+            (0x05, "读取请求(GET-Request)", "get_request"),
+            (0x85, "读取响应(GET-Response)", "get_response"),
+            (0x06, "设置请求(SET-Request)", "set_request"),
+            (0x86, "设置响应(SET-Response)", "set_response"),
+            (0x07, "操作请求(ACTION-Request)", "action_request"),
+            (0x87, "操作响应(ACTION-Response)", "action_response"),
+            (0x88, "上报通知(REPORT-Notification)", "report_notification"),
+            (0x08, "上报应答(REPORT-Response)", "report_response"),
+            (0x09, "代理请求(PROXY-Request)", "proxy_request"),
+            (0x89, "代理响应(PROXY-Response)", "proxy_response"),
+            (0x10, "安全传输请求(SECURITY-Request)", "security_request"),
+            (0x90, "安全传输响应(SECURITY-Response)", "security_response"),
+            (0xEE, "异常响应(ERROR-Response)", "error_response"),
+        ]
+        .into_iter()
+        .map(|(code, name, handler)| AfnEntry::new(code, name, Some(handler)))
+        .collect()
+    }
+
+    fn builtin_gdw13761() -> Vec<AfnEntry> {
+        [
+            (0x00, "确认/否认", "ack"),
+            (0x01, "复位", "command"),
+            (0x02, "链路接口检测", "request"),
+            (0x03, "中继站命令", "request"),
+            (0x04, "设置参数", "command"),
+            (0x05, "控制命令", "command"),
+            (0x06, "身份认证及密钥协商", "command"),
+            (0x08, "请求被级联终端主动上报", "request"),
+            (0x09, "请求终端配置", "request"),
+            (0x0A, "查询参数", "request"),
+            (0x0B, "请求任务数据", "request"),
+            (0x0C, "请求1类数据", "request"),
+            (0x0D, "请求2类数据", "request"),
+            (0x0E, "请求3类数据", "request"),
+            (0x0F, "文件传输", "command"),
+            (0x10, "数据转发", "command"),
+        ]
+        .into_iter()
+        .map(|(code, name, handler)| AfnEntry::new(code, name, Some(handler)))
+        .collect()
+    }
+
+    fn builtin_gdw13762() -> Vec<AfnEntry> {
+        [
+            (0x00, "确认/否认"),
+            (0x01, "初始化"),
+            (0x02, "数据转发"),
+            (0x03, "查询数据"),
+            (0x04, "链路接口检测"),
+            (0x05, "控制命令"),
+            (0x06, "主动上报"),
+            (0x10, "路由查询"),
+            (0x11, "路由设置"),
+            (0x12, "路由控制"),
+            (0x13, "路由数据转发"),
+            (0x14, "路由数据抄读"),
+            (0x15, "文件传输"),
+            (0xF0, "内部调试"),
+        ]
+        .into_iter()
+        .map(|(code, name)| AfnEntry::new(code, name, None))
+        .collect()
+    }
+
+    fn builtin_csg16() -> Vec<AfnEntry> {
+        let concentrator = [
+            (0x00, "确认/否认"),
+            (0x01, "初始化模块"),
+            (0x02, "管理任务"),
+            (0x03, "读参数"),
+            (0x04, "写参数"),
+            (0x05, "上报信息"),
+            (0x06, "请求信息"),
+            (0x07, "传输文件"),
+            (0x10, "维护命令"),
+            (0xF0, "维护模块"),
+        ]
+        .into_iter()
+        .map(|(code, name)| AfnEntry::new(code, name, None).with_kind(0xE8));
+        let collector = [
+            (0x00, "确认/否认"),
+            (0x21, "管理电表"),
+            (0x22, "转发数据"),
+            (0x23, "读参数"),
+            (0x24, "传输文件"),
+            (0x25, "请求信息"),
+            (0x31, "管理映射表表计"),
+        ]
+        .into_iter()
+        .map(|(code, name)| AfnEntry::new(code, name, None));
+        concentrator.chain(collector).collect()
+    }
+
+    fn builtin_moudle() -> Vec<AfnEntry> {
+        let mut entries: Vec<AfnEntry> = [
+            (0x00, "确认/否认"),
+            (0x01, "初始化模块"),
+            (0x03, "读参数"),
+            (0x04, "写参数"),
+            (0x05, "电池管理"),
+            (0x06, "请求信息"),
+            (0x07, "传输文件"),
+            (0x10, "维护命令"),
+            (0x41, "遥信脉冲管理"),
+            (0x42, "遥信脉冲上报"),
+            (0x43, "遥控输出管理"),
+            (0x44, "模拟量采集管理"),
+            (0x46, "电池异常上报"),
+            (0xF0, "维护模块"),
+        ]
+        .into_iter()
+        .map(|(code, name)| AfnEntry::new(code, name, None))
+        .collect();
+        entries.push(AfnEntry::new(0x05, "上报信息", None).with_item("EC050501"));
+        entries
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::xmlconfig::QframeConfig;
+
+    fn handler(entries: &[AfnEntry], code: u8) -> Option<&str> {
+        AfnTable::find(entries, code, None, None).and_then(|entry| entry.handler.as_deref())
+    }
+
+    fn configured(xml: &str) -> Vec<AfnEntry> {
+        let config = QframeConfig::new();
+        config.load_from_str(xml).unwrap();
+        config.afn_entries()
+    }
+
+    #[test]
+    fn builtin_tables_per_protocol() {
+        let csg13 = AfnTable::builtin("CSG13");
+        assert_eq!(handler(&csg13, 0x0D), Some("read_history"));
+        assert_eq!(handler(&csg13, 0x12), Some("read_task"));
+        assert_eq!(handler(&csg13, 0x16), None);
+        assert_eq!(
+            AfnTable::find(&csg13, 0x16, None, None).unwrap().name,
+            "数据安全传输"
+        );
+        assert!(AfnTable::find(&csg13, 0x30, None, None).is_none());
+
+        // 1997和2007的控制码不同，按协议名称区分
+        assert_eq!(
+            handler(&AfnTable::builtin("DLT/645-2007"), 0x11),
+            Some("read")
+        );
+        assert_eq!(handler(&AfnTable::builtin("DLT/645-1997"), 0x11), None);
+        assert_eq!(
+            handler(&AfnTable::builtin("DLT/645-1997"), 0x01),
+            Some("read")
+        );
+        assert!(!AfnTable::builtin("Q/GDW1376.1").is_empty());
+        assert!(!AfnTable::builtin("moudle").is_empty());
+        assert!(AfnTable::builtin("MS").is_empty());
+    }
+
+    #[test]
+    fn specific_entries_take_precedence() {
+        // 南网16规约按通信双方类型区分功能码
+        let csg16 = AfnTable::builtin("CSG16");
+        assert_eq!(
+            AfnTable::find(&csg16, 0x03, Some(0xE8), None).unwrap().name,
+            "读参数"
+        );
+        assert!(AfnTable::find(&csg16, 0x03, Some(0x01), None).is_none());
+        assert_eq!(
+            AfnTable::find(&csg16, 0x00, Some(0xE8), None).unwrap().name,
+            "确认/否认"
+        );
+
+        // 限定数据标识的定义优先，数据标识不区分大小写
+        let moudle = AfnTable::builtin("moudle");
+        assert_eq!(
+            AfnTable::find(&moudle, 0x05, None, Some("ec050501"))
+                .unwrap()
+                .name,
+            "上报信息"
+        );
+        assert_eq!(
+            AfnTable::find(&moudle, 0x05, None, Some("EC050502"))
+                .unwrap()
+                .name,
+            "电池管理"
+        );
+        assert_eq!(
+            AfnTable::find(&moudle, 0x05, None, None).unwrap().name,
+            "电池管理"
+        );
+    }
+
+    #[test]
+    fn configured_entries_extend_builtin() {
+        let mut entries = AfnTable::builtin("CSG13");
+        entries.extend(configured(
+            r#"<config>
+    <afnTable>
+        <afn code="0x0C" name="厂商读当前数据" handler="read_cur"/>
+        <afn code="F1" name="厂商私有" handler="read_param"/>
+        <afn code="F2" name="只显示名称" handler=" "/>
+        <afn code="F3" type="E8" item="e0000001" name="限定"/>
+    </afnTable>
+</config>"#,
+        ));
+
+        // 同等限定条件下后定义的覆盖内置定义
+        assert_eq!(
+            AfnTable::find(&entries, 0x0C, None, None).unwrap().name,
+            "厂商读当前数据"
+        );
+        assert_eq!(handler(&entries, 0xF1), Some("read_param"));
+        assert_eq!(
+            AfnTable::find(&entries, 0xF2, None, None).unwrap().handler,
+            None
+        );
+        let limited = AfnTable::find(&entries, 0xF3, Some(0xE8), Some("E0000001")).unwrap();
+        assert_eq!(limited.item.as_deref(), Some("E0000001"));
+        assert!(AfnTable::find(&entries, 0xF3, None, Some("E0000001")).is_none());
+        assert!(AfnTable::find(&entries, 0xF3, Some(0xE8), None).is_none());
+    }
+
+    #[test]
+    fn invalid_entries_are_skipped() {
+        let entries = configured(
+            r#"<config>
+    <afnTable>
+        <afn code="100" name="超出一个字节"/>
+        <afn code="0G" name="不是十六进制"/>
+        <afn code="F4"/>
+        <afn code="F5" type="XX" name="类型无效"/>
+        <afn code="F6" name="有效"/>
+    </afnTable>
+</config>"#,
+        );
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].code, 0xF6);
+        assert_eq!(
+            entries[0].layer.as_deref(),
+            Some(crate::config::xmlconfig::BASE_LAYER)
+        );
+    }
+
+    #[test]
+    fn known_handlers() {
+        assert!(AfnTable::is_known_handler("read_history"));
+        assert!(AfnTable::is_known_handler("broadcast_time"));
+        assert!(AfnTable::is_known_handler("command"));
+        assert!(!AfnTable::is_known_handler("unknown"));
+    }
+}
@@ -1,41 +1,173 @@
-#[cfg(feature = "desktop")]
+use crate::analyzer::Analyzer;
 use crate::config::constants;
-#[cfg(feature = "desktop")]
-use lazy_static::lazy_static;
 
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-#[cfg(feature = "desktop")]
+#[cfg(not(target_arch = "wasm32"))]
 use std::fs;
-#[cfg(feature = "desktop")]
+#[cfg(not(target_arch = "wasm32"))]
 use std::path::{Path, PathBuf};
-#[cfg(feature = "desktop")]
-use std::sync::Mutex;
+use std::sync::{Arc, RwLock};
+
+/// 应用配置的存储后端，整份配置为一个JSON对象
+pub trait ConfigStore: Send + Sync {
+    /// 读取整份配置，尚未保存过时返回None
+    fn load(&self) -> Result<Option<Value>, String>;
+
+    /// 保存整份配置
+    fn save(&self, content: &Value) -> Result<(), String>;
+}
+
+/// 保存在JSON文件中的配置
+#[cfg(not(target_arch = "wasm32"))]
+pub struct FileConfigStore {
+    path: PathBuf,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl FileConfigStore {
+    pub fn new<P: AsRef<Path>>(path: P) -> Self {
+        FileConfigStore {
+            path: path.as_ref().to_path_buf(),
+        }
+    }
+
+    /// 系统配置目录下的应用配置文件
+    #[cfg(any(target_os = "windows", target_os = "macos", target_os = "linux"))]
+    pub fn app_default() -> Self {
+        Self::new(get_config_dir(constants::APP_NAME).join(constants::APP_CONFIG))
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl ConfigStore for FileConfigStore {
+    fn load(&self) -> Result<Option<Value>, String> {
+        if !self.path.exists() {
+            return Ok(None);
+        }
+        let contents = fs::read_to_string(&self.path).map_err(|e| format!("{}", e))?;
+        let content = serde_json::from_str(&contents).map_err(|e| format!("{}", e))?;
+        Ok(Some(content))
+    }
+
+    fn save(&self, content: &Value) -> Result<(), String> {
+        if let Some(dir_path) = self.path.parent() {
+            if !dir_path.exists() {
+                fs::create_dir_all(dir_path)
+                    .map_err(|e| format!("Failed to create directory: {}", e))?;
+            }
+        }
+        let json_string = serde_json::to_string_pretty(content).map_err(|e| format!("{}", e))?;
+        fs::write(&self.path, json_string).map_err(|e| format!("{}", e))
+    }
+}
+
+/// 保存在浏览器localStorage中的配置，整份配置存为一个键
+#[cfg(feature = "wasm")]
+pub struct LocalStorageStore {
+    key: String,
+}
+
+#[cfg(feature = "wasm")]
+impl LocalStorageStore {
+    pub fn new(key: &str) -> Self {
+        LocalStorageStore {
+            key: key.to_string(),
+        }
+    }
+
+    pub fn app_default() -> Self {
+        Self::new(&format!("{}/{}", constants::APP_NAME, constants::APP_CONFIG))
+    }
+
+    // Storage不能跨线程持有，每次使用时重新获取
+    fn storage() -> Result<web_sys::Storage, String> {
+        web_sys::window()
+            .ok_or("当前环境没有window对象")?
+            .local_storage()
+            .map_err(|e| format!("无法访问localStorage: {:?}", e))?
+            .ok_or_else(|| "localStorage不可用".to_string())
+    }
+}
+
+#[cfg(feature = "wasm")]
+impl ConfigStore for LocalStorageStore {
+    fn load(&self) -> Result<Option<Value>, String> {
+        let contents = Self::storage()?
+            .get_item(&self.key)
+            .map_err(|e| format!("读取localStorage失败: {:?}", e))?;
+        contents
+            .map(|contents| serde_json::from_str(&contents).map_err(|e| format!("{}", e)))
+            .transpose()
+    }
+
+    fn save(&self, content: &Value) -> Result<(), String> {
+        let json_string = serde_json::to_string(content).map_err(|e| format!("{}", e))?;
+        Self::storage()?
+            .set_item(&self.key, &json_string)
+            .map_err(|e| format!("写入localStorage失败: {:?}", e))
+    }
+}
+
+/// 只保存在内存中的配置，进程结束后丢失
+#[derive(Default)]
+pub struct MemoryConfigStore {
+    content: RwLock<Option<Value>>,
+}
+
+impl MemoryConfigStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_content(content: Value) -> Self {
+        MemoryConfigStore {
+            content: RwLock::new(Some(content)),
+        }
+    }
+}
+
+impl ConfigStore for MemoryConfigStore {
+    fn load(&self) -> Result<Option<Value>, String> {
+        Ok(self.content.read().unwrap().clone())
+    }
+
+    fn save(&self, content: &Value) -> Result<(), String> {
+        *self.content.write().unwrap() = Some(content.clone());
+        Ok(())
+    }
+}
+
+/// 默认配置存储：desktop使用配置目录下的文件，wasm使用localStorage，其他情况只保存在内存中
+pub fn default_store() -> Arc<dyn ConfigStore> {
+    #[cfg(feature = "desktop")]
+    return Arc::new(FileConfigStore::app_default());
+    #[cfg(all(feature = "wasm", not(feature = "desktop")))]
+    return Arc::new(LocalStorageStore::app_default());
+    #[cfg(not(any(feature = "desktop", feature = "wasm")))]
+    return Arc::new(MemoryConfigStore::new());
+}
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Config {
-    file_path: String,
     content: Value,
 }
 
-#[cfg(feature = "desktop")]
 impl Config {
-    pub fn new<P: AsRef<Path>>(file_path: P) -> Result<Self, Box<dyn std::error::Error>> {
-        let file_path = file_path.as_ref().to_str().unwrap().to_string();
-        let contents = fs::read_to_string(&file_path)?;
-        let content: Value = serde_json::from_str(&contents)?;
-        Ok(Config { file_path, content })
+    /// 从存储读取配置，尚未保存过时为空配置
+    pub fn load(store: &dyn ConfigStore) -> Result<Self, String> {
+        let content = store
+            .load()?
+            .unwrap_or_else(|| Value::Object(serde_json::Map::new()));
+        Ok(Config { content })
     }
 
-    // Save config to file
-    pub fn save(&self, file_path: &str) -> Result<(), Box<dyn std::error::Error>> {
-        if !Path::new(file_path).exists() {
-            fs::create_dir_all(Path::new(file_path).parent().unwrap())?;
-        }
-
-        let json_string = serde_json::to_string_pretty(&self.content)?;
-        fs::write(file_path, json_string)?;
-        Ok(())
+    pub fn save(&self, store: &dyn ConfigStore) -> Result<(), String> {
+        store.save(&self.content)
     }
 
     pub fn get_value(&self, path: &[&str]) -> Option<&Value> {
@@ -70,7 +202,6 @@ impl Config {
         if let Some(last_key) = path.last() {
             if let Value::Object(map) = current {
                 map.insert(last_key.to_string(), value);
-                self.save(self.file_path.as_str())?;
             } else {
                 return Err("Invalid path structure".into());
             }
@@ -80,14 +211,14 @@ impl Config {
     }
 }
 
-#[cfg(all(feature = "desktop", target_os = "windows"))]
+#[cfg(all(not(target_arch = "wasm32"), target_os = "windows"))]
 fn get_config_dir(app_name: &str) -> PathBuf {
     let base_dir = std::env::var("APPDATA")
         .unwrap_or_else(|_| "C:\\Users\\Default\\AppData\\Roaming".to_string());
     PathBuf::from(base_dir).join(app_name)
 }
 
-#[cfg(all(feature = "desktop", target_os = "macos"))]
+#[cfg(all(not(target_arch = "wasm32"), target_os = "macos"))]
 fn get_config_dir(app_name: &str) -> PathBuf {
     let base_dir = dirs_next::home_dir()
         .unwrap()
@@ -96,139 +227,213 @@ fn get_config_dir(app_name: &str) -> PathBuf {
     base_dir.join(app_name)
 }
 
-#[cfg(all(feature = "desktop", target_os = "linux"))]
+#[cfg(all(not(target_arch = "wasm32"), target_os = "linux"))]
 fn get_config_dir(app_name: &str) -> PathBuf {
     let base_dir = std::env::var("XDG_CONFIG_HOME")
         .unwrap_or_else(|_| format!("{}/.config", dirs_next::home_dir().unwrap().display()));
     PathBuf::from(base_dir).join(app_name)
 }
 
+// key为空时取整个section
+pub(crate) fn value_path<'a>(section: &'a str, key: &'a str) -> Vec<&'a str> {
+    if key.is_empty() {
+        vec![section]
+    } else {
+        vec![section, key]
+    }
+}
+
 #[cfg(feature = "desktop")]
 use tauri::command;
 
-#[cfg(feature = "desktop")]
-#[command]
+/// 读取默认解析器实例的配置值，key为空时返回整个section
+pub fn get_config_value(section: &str, key: &str) -> Result<Option<Value>, String> {
+    Analyzer::global().get_config_value(section, key)
+}
+
+#[cfg_attr(feature = "desktop", command)]
 pub async fn get_config_value_async(section: &str, key: &str) -> Result<Option<Value>, String> {
-    // Build the path to the config file
-    let path = get_config_dir(constants::APP_NAME).join(constants::APP_CONFIG);
-
-    // Load the config
-    let config = Config::new(path.to_str().unwrap()).map_err(|e| format!("{}", e))?;
-    // 读取 window.theme 字段
-    let sectionvalue = if key.is_empty() {
-        config.get_value(&[section]).cloned()
-    } else {
-        config.get_value(&[section, key]).cloned()
-    };
-    // Retrieve the value from the config
-    Ok(sectionvalue)
+    get_config_value(section, key)
 }
 
-#[cfg(feature = "desktop")]
-#[command]
+#[cfg_attr(feature = "desktop", command)]
 pub async fn set_config_value_async(section: &str, key: &str, value: &str) -> Result<(), String> {
-    // Build the path to the config file
-    let path = get_config_dir(constants::APP_NAME).join(constants::APP_CONFIG);
-    let dir_path = path.parent().unwrap(); // Get the directory path
-                                          // Create the directory if it doesn't exist
-    if !dir_path.exists() {
-        if let Err(e) = fs::create_dir_all(dir_path) {
-            return Err(format!("Failed to create directory: {}", e));
-        }
-    }
+    set_config_value(section, key, value)
+}
 
-    let value_json: serde_json::Value =
-        serde_json::from_str(value).map_err(|e| format!("Failed to parse JSON: {}", e))?;
-    // 读取 window.theme 字段
-    let config = Config::new(path.to_str().unwrap()).map_err(|e| format!("{}", e));
-    if let Ok(mut config) = config {
-        if key.is_empty() {
-            config
-                .set_value(&[section], value_json)
-                .map_err(|e| format!("{}", e))?;
-        } else {
-            config
-                .set_value(&[section, key], value_json)
-                .map_err(|e| format!("{}", e))?;
-        }
-    } else {
-        let mut newconfig = Config {
-            file_path: path.to_str().unwrap().to_string(),
-            content: Value::Object(serde_json::Map::new()),
-        };
-        if key.is_empty() {
-            newconfig
-                .set_value(&[section], value_json)
-                .map_err(|e| format!("{}", e))?;
-        } else {
-            newconfig
-                .set_value(&[section, key], value_json)
-                .map_err(|e| format!("{}", e))?;
-        }
-    }
+/// 读取配置值，读取失败时返回None
+pub fn load_config_value(section: &str, key: &str) -> Option<Value> {
+    get_config_value(section, key).ok().flatten()
+}
 
-    Ok(())
+/// 写入默认解析器实例的配置值，value为JSON文本，key为空时替换整个section
+pub fn set_config_value(section: &str, key: &str, value: &str) -> Result<(), String> {
+    Analyzer::global().set_config_value(section, key, value)
 }
 
-// Desktop implementation of get/set helpers
-#[cfg(feature = "desktop")]
-pub fn load_config_value(section: &str, key: &str) -> Option<Value> {
-    // Build the path to the config file
-    let path = get_config_dir(constants::APP_NAME).join(constants::APP_CONFIG);
-
-    // Load the config
-    let config = Config::new(path.to_str().unwrap()).map_err(|e| format!("{}", e));
-    if let Ok(config) = config {
-        // 读取 window.theme 字段
-        let sectionvalue = config.get_value(&[section, key]);
-        // Retrieve the value from the config
-        sectionvalue.cloned()
-    } else {
-        None
+/// 协议配置文件的来源
+pub enum ProtocolFileSource {
+    // 直接保存在配置中的XML内容
+    Content(String),
+    Path(String),
+    Embedded,
+}
+
+/// 按protocolsetting.protocolfile.<key>选择协议配置文件
+///
+/// content优先于path；都未设置时desktop使用default_path，其他情况使用内嵌配置。
+/// 只有desktop能读取文件，其他情况忽略path。
+pub fn select_protocol_file(
+    store: &dyn ConfigStore,
+    key: &str,
+    default_path: &str,
+) -> ProtocolFileSource {
+    let setting = Config::load(store).ok().and_then(|config| {
+        config
+            .get_value(&["protocolsetting", "protocolfile", key])
+            .cloned()
+    });
+    let field = |name: &str| {
+        setting
+            .as_ref()
+            .and_then(|protocol| protocol.get(name))
+            .and_then(|value| value.as_str())
+            .map(String::from)
+    };
+
+    if let Some(content) = field("content") {
+        return ProtocolFileSource::Content(content);
     }
+    if cfg!(feature = "desktop") {
+        return ProtocolFileSource::Path(field("path").unwrap_or(default_path.to_string()));
+    }
+    ProtocolFileSource::Embedded
 }
 
-#[cfg(feature = "desktop")]
-pub fn set_config_value(section: &str, key: &str, value: &str) -> Result<(), String> {
-    // Build the path to the config file
-    let path = get_config_dir(constants::APP_NAME).join(constants::APP_CONFIG);
-    let dir_path = path.parent().unwrap(); // Get the directory path
-
-    // Create the directory if it doesn't exist
-    if !dir_path.exists() {
-        if let Err(e) = fs::create_dir_all(dir_path) {
-            return Err(format!("Failed to create directory: {}", e));
-        }
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir()
+            .join(format!("embed_core_appconfig_{}", std::process::id()))
+            .join(name)
     }
 
-    let value_json: serde_json::Value =
-        serde_json::from_str(value).map_err(|e| format!("Failed to parse JSON: {}", e))?;
+    #[test]
+    fn file_store_round_trip() {
+        let path = temp_path("nested/config.json");
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+        let store = FileConfigStore::new(&path);
+        assert_eq!(store.path(), path.as_path());
+        assert_eq!(store.load().unwrap(), None);
 
-    let config = Config::new(path.to_str().unwrap()).map_err(|e| format!("{}", e));
-    if let Ok(mut config) = config {
-        if key.is_empty() {
-            config
-                .set_value(&[section], value_json)
-                .map_err(|e| format!("{}", e))?;
-        } else {
-            config
-                .set_value(&[section, key], value_json)
-                .map_err(|e| format!("{}", e))?;
-        }
-    } else {
-        let mut newconfig = Config {
-            file_path: path.to_str().unwrap().to_string(),
-            content: Value::Object(serde_json::Map::new()),
-        };
-        if key.is_empty() {
-            newconfig
-                .set_value(&[section], value_json)
-                .map_err(|e| format!("{}", e))?;
+        // 保存时创建不存在的目录
+        let content = json!({"protocolsetting": {"region": "广东"}});
+        store.save(&content).unwrap();
+        assert_eq!(store.load().unwrap(), Some(content));
+
+        fs::write(&path, "{").unwrap();
+        assert!(store.load().is_err());
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn memory_store_round_trip() {
+        let store = MemoryConfigStore::new();
+        assert_eq!(store.load().unwrap(), None);
+        store.save(&json!({"a": 1})).unwrap();
+        assert_eq!(store.load().unwrap(), Some(json!({"a": 1})));
+
+        let store = MemoryConfigStore::with_content(json!({"b": 2}));
+        let config = Config::load(&store).unwrap();
+        assert_eq!(config.get_value(&["b"]), Some(&json!(2)));
+        assert_eq!(config.get_value(&["b", "c"]), None);
+    }
+
+    #[test]
+    fn config_set_value_creates_sections() {
+        let mut config = Config::load(&MemoryConfigStore::new()).unwrap();
+        config
+            .set_value(&["section", "key"], json!("value"))
+            .unwrap();
+        assert_eq!(config.get_value(&["section", "key"]), Some(&json!("value")));
+        // 路径中间不是对象时报错
+        assert!(config
+            .set_value(&["section", "key", "sub"], json!(1))
+            .is_err());
+    }
+
+    #[test]
+    fn values_follow_analyzer_store() {
+        let path = temp_path("switch.json");
+        let _ = fs::remove_file(&path);
+        let file: Arc<dyn ConfigStore> = Arc::new(FileConfigStore::new(&path));
+        let memory = Analyzer::new();
+        let filed = Analyzer::with_store(file.clone());
+
+        memory
+            .set_config_value("testsection", "key", "\"内存\"")
+            .unwrap();
+        assert_eq!(
+            memory.get_config_value("testsection", "key").unwrap(),
+            Some(json!("内存"))
+        );
+        assert!(memory.set_config_value("testsection", "key", "{").is_err());
+
+        assert_eq!(filed.get_config_value("testsection", "key").unwrap(), None);
+        filed
+            .set_config_value("testsection", "key", "\"文件\"")
+            .unwrap();
+        // key为空时替换整个section
+        filed
+            .set_config_value("testsection", "", r#"{"key": "文件", "other": 1}"#)
+            .unwrap();
+        assert_eq!(
+            filed.get_config_value("testsection", "").unwrap(),
+            Some(json!({"key": "文件", "other": 1}))
+        );
+
+        assert_eq!(
+            memory.get_config_value("testsection", "key").unwrap(),
+            Some(json!("内存"))
+        );
+        assert_eq!(
+            memory.get_config_value("testsection", "other").unwrap(),
+            None
+        );
+        assert_eq!(
+            file.load().unwrap(),
+            Some(json!({"testsection": {"key": "文件", "other": 1}}))
+        );
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn select_protocol_file_per_store() {
+        let store = MemoryConfigStore::with_content(json!({
+            "protocolsetting": {
+                "protocolfile": {
+                    "test_content": {"path": "/tmp/CSG13.xml", "content": "<config/>"},
+                    "test_path": {"path": "/tmp/CSG16.xml"}
+                }
+            }
+        }));
+        // content优先于path
+        assert!(matches!(
+            select_protocol_file(&store, "test_content", "default.xml"),
+            ProtocolFileSource::Content(content) if content == "<config/>"
+        ));
+        let path = select_protocol_file(&store, "test_path", "default.xml");
+        let unset = select_protocol_file(&store, "test_unset", "default.xml");
+        if cfg!(feature = "desktop") {
+            assert!(matches!(path, ProtocolFileSource::Path(path) if path == "/tmp/CSG16.xml"));
+            assert!(matches!(unset, ProtocolFileSource::Path(path) if path == "default.xml"));
         } else {
-            newconfig
-                .set_value(&[section, key], value_json)
-                .map_err(|e| format!("{}", e))?;
+            // 非desktop不能读取文件，忽略path
+            assert!(matches!(path, ProtocolFileSource::Embedded));
+            assert!(matches!(unset, ProtocolFileSource::Embedded));
         }
     }
-    Ok(())
-}
\ No newline at end of file
+}
@@ -0,0 +1,108 @@
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+pub const KEY_LENGTH: usize = 16;
+
+/// 未单独配置密钥的终端使用此地址下的密钥
+pub const ANY_TERMINAL: &str = "*";
+
+/// 密钥用途
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum KeyUsage {
+    /// 计算、校验消息验证码PW/MAC
+    Mac,
+    /// 加解密数据单元
+    Data,
+}
+
+impl KeyUsage {
+    pub fn parse(usage: &str) -> Option<Self> {
+        match usage.trim().to_lowercase().as_str() {
+            "mac" => Some(KeyUsage::Mac),
+            "data" => Some(KeyUsage::Data),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            KeyUsage::Mac => "PW/MAC",
+            KeyUsage::Data => "数据加密",
+        }
+    }
+}
+
+/// 终端密钥的来源，密钥由测试台等外部提供，不随程序和配置保存
+pub trait KeyStore: Send + Sync {
+    /// 按终端逻辑地址和用途取密钥
+    fn get_key(&self, terminal: &str, usage: KeyUsage) -> Option<[u8; KEY_LENGTH]>;
+
+    /// 设置密钥，key为None时删除
+    fn set_key(
+        &self,
+        terminal: &str,
+        usage: KeyUsage,
+        key: Option<[u8; KEY_LENGTH]>,
+    ) -> Result<(), String>;
+}
+
+/// 只保存在内存中的密钥，进程结束后丢失
+#[derive(Default)]
+pub struct MemoryKeyStore {
+    keys: RwLock<HashMap<(String, KeyUsage), [u8; KEY_LENGTH]>>,
+}
+
+impl MemoryKeyStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl KeyStore for MemoryKeyStore {
+    fn get_key(&self, terminal: &str, usage: KeyUsage) -> Option<[u8; KEY_LENGTH]> {
+        let keys = self.keys.read().unwrap();
+        keys.get(&(terminal.to_string(), usage))
+            .or_else(|| keys.get(&(ANY_TERMINAL.to_string(), usage)))
+            .copied()
+    }
+
+    fn set_key(
+        &self,
+        terminal: &str,
+        usage: KeyUsage,
+        key: Option<[u8; KEY_LENGTH]>,
+    ) -> Result<(), String> {
+        let mut keys = self.keys.write().unwrap();
+        let id = (terminal.to_string(), usage);
+        match key {
+            Some(key) => {
+                keys.insert(id, key);
+            }
+            None => {
+                keys.remove(&id);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 按十六进制文本设置密钥，key为空时删除
+pub fn set_key_hex(
+    store: &dyn KeyStore,
+    terminal: &str,
+    usage: &str,
+    key: &str,
+) -> Result<(), String> {
+    let usage = KeyUsage::parse(usage).ok_or_else(|| format!("未知的密钥用途：{}", usage))?;
+    let key = key.replace(' ', "");
+    let key = if key.is_empty() {
+        None
+    } else {
+        let bytes = hex::decode(&key).map_err(|e| format!("密钥格式错误: {}", e))?;
+        Some(
+            <[u8; KEY_LENGTH]>::try_from(bytes.as_slice())
+                .map_err(|_| format!("密钥长度应为{}字节", KEY_LENGTH))?,
+        )
+    };
+    store.set_key(terminal, usage, key)
+}
@@ -1,5 +1,8 @@
+pub mod afntable;
 pub mod appconfig;
 pub mod constants;
+pub mod keystore;
 pub mod oadmapconfig;
 pub mod xmlconfig;
+pub mod xmlvalidator;
 pub use xmlconfig::ProtocolConfigManager;
@@ -1,4 +1,3 @@
-use lazy_static::lazy_static;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 #[cfg(feature = "desktop")]
@@ -10,6 +9,8 @@ use std::path::Path;
 
 use tracing::info;
 
+use crate::analyzer::Analyzer;
+
 #[derive(Debug, Deserialize, Serialize)]
 struct MainConfig {
     oad_list: Vec<OadItem>,
@@ -46,7 +47,7 @@ pub struct CompleteConfig {
 }
 
 impl CompleteConfig {
-    fn default() -> Self {
+    pub(crate) fn default() -> Self {
         CompleteConfig {
             main_config: MainConfig { oad_list: vec![] },
             sub_configs: HashMap::new(),
@@ -54,7 +55,7 @@ impl CompleteConfig {
     }
 
     #[cfg(feature = "desktop")]
-    fn new(config_path: &Path) -> Result<Self, Box<dyn std::error::Error>> {
+    pub(crate) fn new(config_path: &Path) -> Result<Self, Box<dyn std::error::Error>> {
         let main_config = Self::load_main_config(config_path)?;
         let mut sub_configs = HashMap::new();
 
@@ -74,8 +75,7 @@ impl CompleteConfig {
         })
     }
 
-    #[cfg(not(feature = "desktop"))]
-    fn new_from_strs(
+    pub(crate) fn new_from_strs(
         main_yaml: &str,
         sub_yaml_map: &HashMap<String, String>,
     ) -> Result<Self, Box<dyn std::error::Error>> {
@@ -126,90 +126,26 @@ impl CompleteConfig {
             }
         })
     }
-}
 
-#[cfg(feature = "desktop")]
-lazy_static! {
-    static ref TASK_OAD_CONFIG: CompleteConfig = {
-        match CompleteConfig::new(Path::new("./resources/taskoadconfig/oad_list.yml")) {
-            Ok(config) => config,
-            Err(e) => {
-                eprintln!("{}", e);
-                panic!("读取配置文件失败")
-            }
+    pub(crate) fn get_voad(&self, master_oad: &str, v_oad: &str) -> Option<ListItem> {
+        let sub_config = self.get_config_by_master_oad(master_oad);
+        if sub_config.is_none() {
+            eprintln!("找不到对应的配置列表: {}", master_oad);
+            return None;
         }
-    };
-}
-
-#[cfg(not(feature = "desktop"))]
-lazy_static! {
-    static ref TASK_OAD_CONFIG: std::sync::RwLock<CompleteConfig> =
-        std::sync::RwLock::new(CompleteConfig::default());
-}
-
-pub struct TaskOadConfigManager;
-
-impl TaskOadConfigManager {
-    pub fn get_voad(master_oad: &str, v_oad: &str) -> Option<ListItem> {
-        #[cfg(feature = "desktop")]
-        {
-            let config = TASK_OAD_CONFIG.get_config_by_master_oad(master_oad);
-            if config.is_none() {
-                eprintln!("找不到对应的配置列表: {}", master_oad);
-                return None;
-            }
-            let config = config.unwrap();
-            for (list_name, items) in &config.lists {
-                for item in items {
-                    if item.v_oad.to_lowercase() == v_oad.to_lowercase() {
-                        return Some(item.clone());
-                    }
+        let sub_config = sub_config.unwrap();
+        for items in sub_config.lists.values() {
+            for item in items {
+                if item.v_oad.to_lowercase() == v_oad.to_lowercase() {
+                    return Some(item.clone());
                 }
             }
-            None
-        }
-
-        #[cfg(not(feature = "desktop"))]
-        {
-            let config = TASK_OAD_CONFIG.read().unwrap();
-            let sub_config = config.get_config_by_master_oad(master_oad);
-            if sub_config.is_none() {
-                eprintln!("找不到对应的配置列表: {}", master_oad);
-                return None;
-            }
-            let sub_config = sub_config.unwrap();
-            for (list_name, items) in &sub_config.lists {
-                for item in items {
-                    if item.v_oad.to_lowercase() == v_oad.to_lowercase() {
-                        return Some(item.clone());
-                    }
-                }
-            }
-            None
         }
+        None
     }
 
-    #[cfg(not(feature = "desktop"))]
-    pub fn init(
-        main_yaml: &str,
-        sub_yaml_map: &HashMap<String, String>,
-    ) -> Result<(), Box<dyn std::error::Error>> {
-        let new_config = CompleteConfig::new_from_strs(main_yaml, sub_yaml_map)?;
-        let mut config = TASK_OAD_CONFIG.write().unwrap();
-        *config = new_config;
-        Ok(())
-    }
-
-    #[cfg(feature = "desktop")]
-    pub fn init(
-        _main_yaml: &str,
-        _sub_yaml_map: &HashMap<String, String>,
-    ) -> Result<(), Box<dyn std::error::Error>> {
-        // Desktop 模式下配置在编译时已经加载，不需要运行时初始化
-        Ok(())
-    }
-
-    pub fn default() -> Result<(), Box<dyn std::error::Error>> {
+    // 编译时内嵌的OAD映射
+    fn embedded_yamls() -> (&'static str, HashMap<String, String>) {
         let main_yaml = include_str!("../../../../public/config/oad_list.yml");
         let mut sub_yaml_map = HashMap::new();
 
@@ -225,6 +161,83 @@ impl TaskOadConfigManager {
             "Vir_50060200_List".to_string(),
             include_str!("../../../../public/config/50060200_list.yml").to_string(),
         );
+        (main_yaml, sub_yaml_map)
+    }
+
+    /// 内置OAD映射，desktop从资源目录加载，其他情况使用内嵌内容
+    #[cfg(feature = "desktop")]
+    pub(crate) fn builtin() -> Self {
+        match CompleteConfig::new(Path::new("./resources/taskoadconfig/oad_list.yml")) {
+            Ok(config) => config,
+            Err(e) => {
+                eprintln!("读取配置文件失败: {}", e);
+                CompleteConfig::default()
+            }
+        }
+    }
+
+    /// 内置OAD映射，desktop从资源目录加载，其他情况使用内嵌内容
+    #[cfg(not(feature = "desktop"))]
+    pub(crate) fn builtin() -> Self {
+        let (main_yaml, sub_yaml_map) = Self::embedded_yamls();
+        CompleteConfig::new_from_strs(main_yaml, &sub_yaml_map).unwrap_or_else(|e| {
+            eprintln!("读取内置OAD映射失败: {}", e);
+            CompleteConfig::default()
+        })
+    }
+}
+
+pub struct TaskOadConfigManager;
+
+impl TaskOadConfigManager {
+    pub fn get_voad(master_oad: &str, v_oad: &str) -> Option<ListItem> {
+        Analyzer::global().get_voad(master_oad, v_oad)
+    }
+
+    /// 替换默认解析器实例的OAD映射
+    pub fn init(
+        main_yaml: &str,
+        sub_yaml_map: &HashMap<String, String>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        Analyzer::global().init_oad_map(main_yaml, sub_yaml_map)
+    }
+
+    pub fn default() -> Result<(), Box<dyn std::error::Error>> {
+        let (main_yaml, sub_yaml_map) = CompleteConfig::embedded_yamls();
         Self::init(main_yaml, &sub_yaml_map)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::basefunc::protocol::ProtocolInfo;
+    use crate::config::xmlconfig::{BuiltinProtocol, QframeConfig};
+
+    #[test]
+    fn dlt645_dictionary_covers_oad_lists() {
+        let (main_yaml, sub_yaml_map) = CompleteConfig::embedded_yamls();
+        let oad_config = CompleteConfig::new_from_strs(main_yaml, &sub_yaml_map).unwrap();
+        assert!(!oad_config.sub_configs.is_empty());
+
+        let dictionary = QframeConfig::new();
+        dictionary
+            .load_from_str(BuiltinProtocol::Dlt645.embedded())
+            .unwrap();
+        let protocol = ProtocolInfo::ProtocolDLT64507.name();
+        let mut missing = Vec::new();
+        for sub_config in oad_config.sub_configs.values() {
+            for item in sub_config.lists.values().flatten() {
+                if dictionary
+                    .get_item(&item.item_07, protocol, "南网", None)
+                    .is_none()
+                {
+                    missing.push(item.item_07.clone());
+                }
+            }
+        }
+        missing.sort();
+        missing.dedup();
+        assert!(missing.is_empty(), "{:?}", missing);
+    }
+}
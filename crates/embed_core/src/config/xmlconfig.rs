@@ -1,20 +1,20 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::error::Error;
-#[cfg(feature = "desktop")]
-use std::fs::File;
-#[cfg(feature = "desktop")]
-use std::io::BufReader;
 use std::path::{Path, PathBuf};
 use std::sync::{Arc, RwLock, RwLockReadGuard};
 
-use lazy_static::lazy_static;
-use quick_xml::events::Event;
+use quick_xml::events::{BytesStart, Event};
 use quick_xml::Reader;
 use serde::{Deserialize, Serialize};
 use tracing::info;
 
-#[cfg(feature = "desktop")]
-use crate::config::appconfig::load_config_value;
+use crate::analyzer::Analyzer;
+use crate::config::afntable::{AfnEntry, AFN_NODE, AFN_TABLE_NODE};
+use crate::config::appconfig::{select_protocol_file, ConfigStore, ProtocolFileSource};
+use crate::config::xmlvalidator::{DiagnosticLevel, XmlConfigValidator, XmlDiagnostic};
+
+// 注释节点名称
+pub const COMMENT_NODE: &str = "#comment";
 
 // 定义树节点结构
 #[derive(Clone, Debug)]
@@ -31,8 +31,49 @@ pub struct XmlNode {
     children: Vec<usize>,  // 子节点的索引列表
 
     // 用于快速查找的索引
-    depth: u32,   // 节点深度
-    path: String, // 从根节点到当前节点的路径
+    depth: u32,              // 节点深度
+    path: String,            // 从根节点到当前节点的路径
+    line: usize,             // 节点在XML文件中的行号，0表示未知
+    attr_order: Vec<String>, // 节点自身属性(不含继承属性)在文件中的顺序
+    layer: Option<String>,   // 顶层数据项来自的配置层
+}
+
+impl XmlNode {
+    pub fn id(&self) -> Option<&String> {
+        self.id.as_ref()
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn value(&self) -> Option<&String> {
+        self.value.as_ref()
+    }
+
+    pub fn get_attribute(&self, name: &str) -> Option<&String> {
+        self.attributes.get(name)
+    }
+
+    pub fn parent(&self) -> Option<usize> {
+        self.parent
+    }
+
+    pub fn children(&self) -> &[usize] {
+        &self.children
+    }
+
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    pub fn layer(&self) -> Option<&String> {
+        self.layer.as_ref()
+    }
+
+    pub fn depth(&self) -> u32 {
+        self.depth
+    }
 }
 
 // 定义树结构
@@ -43,6 +84,9 @@ pub struct XmlTree {
     id_index: HashMap<String, HashSet<usize>>,       // id -> 节点索引的映射
     protocol_index: HashMap<String, HashSet<usize>>, // protocol -> 节点索引的映射
     region_index: HashMap<String, HashSet<usize>>,   // region -> 节点索引的映射
+    region_parents: HashMap<String, String>,         // 地区 -> 上级地区，来自<regions>声明
+    root_region: String,                             // 根地区，来自<regions root>
+    afn_entries: Vec<AfnEntry>,                      // 来自<afnTable>声明的功能码，按声明顺序
 }
 
 impl XmlTree {
@@ -53,6 +97,9 @@ impl XmlTree {
             id_index: HashMap::new(),
             protocol_index: HashMap::new(),
             region_index: HashMap::new(),
+            region_parents: HashMap::new(),
+            root_region: DEFAULT_REGION.to_string(),
+            afn_entries: Vec::new(),
         }
     }
 
@@ -89,7 +136,134 @@ impl XmlTree {
         index
     }
 
-    // 快速查找指定ID的节点
+    // 读取根节点下<regions>中声明的地区继承关系和根地区，后声明的覆盖先声明的
+    fn index_regions(&mut self) {
+        self.region_parents.clear();
+        self.root_region = DEFAULT_REGION.to_string();
+        if self.nodes.is_empty() {
+            return;
+        }
+        let mut declarations = Vec::new();
+        for &index in &self.nodes[self.root].children {
+            if self.nodes[index].name != REGIONS_NODE {
+                continue;
+            }
+            if let Some(root) = self.nodes[index].attributes.get(REGION_ROOT_ATTR) {
+                let root = root.trim();
+                if !root.is_empty() {
+                    self.root_region = root.to_string();
+                }
+            }
+            for &child in &self.nodes[index].children {
+                let node = &self.nodes[child];
+                if node.name != REGION_NODE {
+                    continue;
+                }
+                if let (Some(name), Some(parent)) =
+                    (node.attributes.get("name"), node.attributes.get("parent"))
+                {
+                    declarations.push((name.trim().to_string(), parent.trim().to_string()));
+                }
+            }
+        }
+        self.region_parents.extend(declarations);
+    }
+
+    // 读取根节点下<afnTable>中声明的功能码，格式有误的由配置校验报告
+    fn index_afn_tables(&mut self) {
+        self.afn_entries.clear();
+        if self.nodes.is_empty() {
+            return;
+        }
+        for &index in &self.nodes[self.root].children {
+            let table = &self.nodes[index];
+            if table.name != AFN_TABLE_NODE {
+                continue;
+            }
+            for &child in &table.children {
+                let node = &self.nodes[child];
+                if node.name != AFN_NODE {
+                    continue;
+                }
+                match AfnEntry::from_node(node) {
+                    Ok(mut entry) => {
+                        entry.layer = table.layer.clone();
+                        self.afn_entries.push(entry);
+                    }
+                    Err(e) => info!("第{}行的功能码定义无效: {}", node.line, e),
+                }
+            }
+        }
+    }
+
+    /// 配置中声明的功能码，不含内置功能码表
+    pub fn afn_entries(&self) -> &[AfnEntry] {
+        &self.afn_entries
+    }
+
+    /// 未声明上级的地区最终回退到的地区，由<regions root="...">指定，默认为南网
+    pub fn root_region(&self) -> &str {
+        &self.root_region
+    }
+
+    /// 地区的上级地区，未声明时为根地区，根地区没有上级
+    pub fn region_parent(&self, region: &str) -> Option<&str> {
+        match self.region_parents.get(region) {
+            Some(parent) => Some(parent.as_str()),
+            None if region != self.root_region => Some(self.root_region.as_str()),
+            None => None,
+        }
+    }
+
+    /// 查找数据项时依次尝试的地区，从自身开始沿上级地区直到根地区
+    pub fn region_chain(&self, region: &str) -> Vec<String> {
+        let mut chain = vec![region.to_string()];
+        let mut current = region;
+        while let Some(parent) = self.region_parent(current) {
+            // 继承关系存在循环时截断，由配置校验报告
+            if chain.iter().any(|r| r == parent) {
+                break;
+            }
+            chain.push(parent.to_string());
+            current = parent;
+        }
+        chain
+    }
+
+    /// 配置中出现的所有地区及其继承链
+    pub fn regions(&self) -> Vec<Region> {
+        let mut names: Vec<String> = self
+            .region_index
+            .keys()
+            .flat_map(|region| region.split(','))
+            .map(|region| region.trim().to_string())
+            .filter(|region| !region.is_empty())
+            .chain(
+                self.region_parents
+                    .iter()
+                    .flat_map(|(name, parent)| [name.clone(), parent.clone()]),
+            )
+            .chain(std::iter::once(self.root_region.clone()))
+            .collect();
+        names.sort();
+        names.dedup();
+
+        names
+            .into_iter()
+            .map(|name| Region {
+                parent: self.region_parent(&name).map(|parent| parent.to_string()),
+                chain: self.region_chain(&name),
+                declared: self.region_parents.contains_key(&name),
+                name,
+            })
+            .collect()
+    }
+
+    // 快速查找指定ID的节点，沿地区继承链回退
+    // 多个节点同时满足条件时按以下优先级选取，保证结果稳定：
+    // 区域精确匹配优先于继承链上的上级地区(越近越优先，根地区最后)，
+    // 上级地区优先于未指定区域的定义；区域相同时指定dir的优先于未指定dir的，
+    // 其余相同时文件中靠后的定义优先于靠前的
     pub fn find_by_id(
         &self,
         id: &str,
@@ -97,54 +271,76 @@ impl XmlTree {
         region: &str,
         dir: Option<u8>,
     ) -> Option<&XmlNode> {
-        // 直接从索引中查找
-        if let Some(node_indices) = self.id_index.get(id) {
-            info!("Found {:?} nodes with id: {}", node_indices, id);
-            for &index in node_indices {
-                let node = &self.nodes[index];
-                if self.matches_criteria(
-                    node,
-                    protocol,
-                    region,
-                    dir,
-                    self.protocol_index.get(protocol),
-                    self.region_index.get(region),
-                    if region != "南网" {
-                        self.region_index.get("南网")
-                    } else {
-                        None
-                    },
-                ) {
-                    return Some(node);
-                }
-            }
-        }
-        None
+        let chain = self.region_chain(region);
+        self.find_best(id, protocol, &chain, dir, 0)
     }
 
-    // 检查节点是否满足查询条件
-    fn matches_criteria(
+    // 只查找区域精确匹配的节点，不回退到上级地区和未指定区域的定义
+    fn find_in_scope(
         &self,
-        node: &XmlNode,
+        id: &str,
         protocol: &str,
         region: &str,
         dir: Option<u8>,
-        _protocol_nodes: Option<&HashSet<usize>>,
-        _region_nodes: Option<&HashSet<usize>>,
-        _south_grid_nodes: Option<&HashSet<usize>>,
-    ) -> bool {
+    ) -> Option<&XmlNode> {
+        self.find_best(id, protocol, &[region.to_string()], dir, 1)
+    }
+
+    fn find_best(
+        &self,
+        id: &str,
+        protocol: &str,
+        chain: &[String],
+        dir: Option<u8>,
+        min_rank: usize,
+    ) -> Option<&XmlNode> {
+        let node_indices = self.id_index.get(id)?;
+        node_indices
+            .iter()
+            .filter(|&&index| self.matches_criteria(&self.nodes[index], protocol, dir))
+            .filter_map(|&index| {
+                let node = &self.nodes[index];
+                let region_rank = Self::region_rank(node, chain).filter(|&r| r >= min_rank)?;
+                let dir_specific = dir.is_some() && node.attributes.contains_key("dir");
+                Some(((region_rank, dir_specific, index), index))
+            })
+            .max_by_key(|&(precedence, _)| precedence)
+            .map(|(_, index)| &self.nodes[index])
+    }
+
+    // 节点区域在继承链中的优先级，值越大越优先：
+    // 精确匹配为chain.len()，上级地区依次递减，未指定区域为0，不在继承链上为None
+    fn region_rank(node: &XmlNode, chain: &[String]) -> Option<usize> {
+        let node_region = match node.attributes.get("region") {
+            Some(region) => region,
+            None => return Some(0),
+        };
+        chain.iter().enumerate().find_map(|(position, region)| {
+            let matched = if region.contains(',') {
+                // 传入的region包含逗号时进行完整匹配
+                node_region.trim().eq_ignore_ascii_case(region)
+            } else {
+                node_region
+                    .split(',')
+                    .map(|r| r.trim())
+                    .any(|r| r.eq_ignore_ascii_case(region))
+            };
+            matched.then_some(chain.len() - position)
+        })
+    }
+
+    // 检查节点的协议和方向是否满足查询条件，区域由region_rank判断
+    fn matches_criteria(&self, node: &XmlNode, protocol: &str, dir: Option<u8>) -> bool {
         let node_protocol = node.attributes.get("protocol");
-        let node_region = node.attributes.get("region");
         let node_dir = node
             .attributes
             .get("dir")
             .and_then(|d| d.parse::<u8>().ok());
 
         // 检查协议匹配
-        let protocol_match = node_protocol.map_or(false, |p| {
+        let protocol_match = node_protocol.is_some_and(|p| {
             if protocol.contains(',') {
                 // 如果传入的protocol包含逗号，直接进行完整匹配
-                info!("protocol: {}", protocol);
                 p.eq_ignore_ascii_case(protocol)
             } else {
                 // 否则按照原来的逻辑进行分割匹配
@@ -153,33 +349,14 @@ impl XmlTree {
                     .any(|s| s.eq_ignore_ascii_case(protocol))
             }
         });
-        info!("node_region {:?} region {:?} node_protocol {:?} protocol {:?}", node_region, region, node_protocol, protocol);
-        // 检查区域匹配
-        let region_match = node_region.map_or(false, |r| {
-            if region.contains(',') {
-                // 如果传入的region包含逗号，直接进行完整匹配
-                r.eq_ignore_ascii_case(region)
-            } else {
-                // 否则按照原来的逻辑进行分割匹配
-                r.split(',').map(|s| s.trim()).any(|s| {
-                    s.eq_ignore_ascii_case(region)
-                })
-            }
-        });
 
-        // 检查方向匹配，如果节点没有dir属性或者传入的dir为None，则认为匹配
-        let dir_match = if dir.is_none() {
-            // 如果没有指定dir，则不检查
-            true
-        } else if node_dir.is_none() {
-            // 如果节点没有dir属性，则不检查
-            true
-        } else {
-            // 如果都有，则必须匹配
-            dir.unwrap() == node_dir.unwrap()
+        // 检查方向匹配，节点没有dir属性或者传入的dir为None时认为匹配
+        let dir_match = match (dir, node_dir) {
+            (Some(dir), Some(node_dir)) => dir == node_dir,
+            _ => true,
         };
 
-        protocol_match && region_match && dir_match
+        protocol_match && dir_match
     }
 
     // 获取节点的完整路径
@@ -200,6 +377,142 @@ impl XmlTree {
     pub fn get_children(&self, index: usize) -> &[usize] {
         &self.nodes[index].children
     }
+
+    pub fn root(&self) -> usize {
+        self.root
+    }
+
+    pub fn get_node(&self, index: usize) -> Option<&XmlNode> {
+        self.nodes.get(index)
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    // 所有带id的节点id
+    pub fn ids(&self) -> impl Iterator<Item = &String> {
+        self.id_index.keys()
+    }
+
+    // 将节点还原为XML元素，只保留节点自身属性，包含注释节点
+    pub fn node_to_document(&self, index: usize) -> XmlElement {
+        let node = &self.nodes[index];
+        let attributes: HashMap<String, String> = node
+            .attr_order
+            .iter()
+            .filter_map(|key| {
+                node.attributes
+                    .get(key)
+                    .map(|value| (key.clone(), value.clone()))
+            })
+            .collect();
+        XmlElement {
+            name: node.name.clone(),
+            attributes,
+            value: node.value.clone(),
+            children: node
+                .children
+                .iter()
+                .map(|&child| self.node_to_document(child))
+                .collect(),
+            line: node.line,
+            attr_order: node.attr_order.clone(),
+            layer: node.layer.clone(),
+        }
+    }
+
+    // 将配置树写回XML字符串
+    pub fn to_xml_string(&self) -> String {
+        let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\" ?>\n");
+        if !self.nodes.is_empty() {
+            self.write_node(self.root, 0, &mut xml);
+        }
+        xml
+    }
+
+    fn write_node(&self, index: usize, depth: usize, xml: &mut String) {
+        let node = &self.nodes[index];
+        let indent = "\t".repeat(depth);
+        if node.name == COMMENT_NODE {
+            xml.push_str(&format!(
+                "{}<!--{}-->\n",
+                indent,
+                node.value.as_deref().unwrap_or_default()
+            ));
+            return;
+        }
+
+        xml.push_str(&indent);
+        xml.push('<');
+        xml.push_str(&node.name);
+        for key in &node.attr_order {
+            if let Some(value) = node.attributes.get(key) {
+                xml.push_str(&format!(" {}=\"{}\"", key, Self::escape_xml(value)));
+            }
+        }
+        xml.push('>');
+
+        if node.children.is_empty() {
+            if let Some(value) = XmlElement::trim_value(node.value.clone()) {
+                xml.push_str(&Self::escape_xml(&value));
+            }
+        } else {
+            xml.push('\n');
+            for &child in &node.children {
+                self.write_node(child, depth + 1, xml);
+            }
+            xml.push_str(&indent);
+        }
+        xml.push_str(&format!("</{}>\n", node.name));
+    }
+
+    fn escape_xml(value: &str) -> String {
+        value
+            .replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+            .replace('"', "&quot;")
+    }
+
+    // 节点在各级父节点中的位置，用于在还原的XML元素中定位
+    fn get_child_positions(&self, index: usize) -> Vec<usize> {
+        let path = self.get_node_path(index);
+        path.windows(2)
+            .map(|pair| {
+                self.nodes[pair[0]]
+                    .children
+                    .iter()
+                    .position(|&child| child == pair[1])
+                    .unwrap()
+            })
+            .collect()
+    }
+
+    // 指定id的所有节点，按文件中的顺序排列
+    pub fn find_all_by_id(&self, id: &str) -> Vec<usize> {
+        let mut indices: Vec<usize> = self
+            .id_index
+            .get(id)
+            .map(|indices| indices.iter().cloned().collect())
+            .unwrap_or_default();
+        indices.sort();
+        indices
+    }
+
+    // 获取节点下指定名称子节点的文本
+    pub fn get_child_text(&self, index: usize, name: &str) -> Option<String> {
+        self.nodes[index]
+            .children
+            .iter()
+            .map(|&child| &self.nodes[child])
+            .find(|child| child.name == name)
+            .and_then(|child| XmlElement::trim_value(child.value.clone()))
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -208,6 +521,12 @@ pub struct XmlElement {
     pub attributes: HashMap<String, String>,
     pub value: Option<String>,
     pub children: Vec<XmlElement>,
+    #[serde(skip)]
+    pub line: usize,
+    #[serde(skip)]
+    pub attr_order: Vec<String>,
+    #[serde(skip)]
+    pub layer: Option<String>,
 }
 
 impl XmlElement {
@@ -237,6 +556,28 @@ impl XmlElement {
         self.attributes.get(name)
     }
 
+    // 按文件中的顺序返回属性，未记录顺序的属性按名称排序追加在后
+    pub fn ordered_attributes(&self) -> Vec<(String, String)> {
+        let mut result: Vec<(String, String)> = self
+            .attr_order
+            .iter()
+            .filter_map(|key| {
+                self.attributes
+                    .get(key)
+                    .map(|value| (key.clone(), value.clone()))
+            })
+            .collect();
+        let mut rest: Vec<(String, String)> = self
+            .attributes
+            .iter()
+            .filter(|(key, _)| !self.attr_order.contains(key))
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect();
+        rest.sort();
+        result.extend(rest);
+        result
+    }
+
     pub fn get_child_text(&self, name: &str) -> Option<String> {
         self.get_child(name).and_then(|child| child.value.clone())
     }
@@ -272,6 +613,9 @@ impl XmlElement {
                 attributes: HashMap::new(),
                 value: Some(new_value),
                 children: Vec::new(),
+                line: 0,
+                attr_order: Vec::new(),
+                layer: None,
             };
             self.children.push(new_child);
         }
@@ -295,6 +639,19 @@ impl XmlElement {
         }
     }
 
+    // 名称、属性、值和子节点均相同，不比较行号和配置层
+    fn same_content(&self, other: &XmlElement) -> bool {
+        self.name == other.name
+            && self.attributes == other.attributes
+            && self.value == other.value
+            && self.children.len() == other.children.len()
+            && self
+                .children
+                .iter()
+                .zip(&other.children)
+                .all(|(a, b)| a.same_content(b))
+    }
+
     pub fn update_child(&mut self, new_child: &XmlElement) -> bool {
         for child in &mut self.children {
             if child.is_matching_structure(new_child) {
@@ -337,6 +694,7 @@ pub struct QframeConfig {
     config: RwLock<Option<XmlTree>>,
     config_cache: RwLock<Cache>,
     config_path: RwLock<Option<PathBuf>>,
+    layers: RwLock<ConfigLayers>,
 }
 
 impl QframeConfig {
@@ -345,6 +703,7 @@ impl QframeConfig {
             config: RwLock::new(None),
             config_cache: RwLock::new(Cache::new()),
             config_path: RwLock::new(None),
+            layers: RwLock::new(ConfigLayers::default()),
         }
     }
 
@@ -396,6 +755,13 @@ impl QframeConfig {
                 children: Vec::new(),
                 depth,
                 path: path.clone(),
+                line: element.line,
+                attr_order: element
+                    .ordered_attributes()
+                    .into_iter()
+                    .map(|(key, _)| key)
+                    .collect(),
+                layer: element.layer.clone(),
             };
 
             let node_index = tree.add_node(node);
@@ -425,42 +791,39 @@ impl QframeConfig {
         }
 
         tree.root = build_recursive(&mut tree, element, None, 0, String::new(), None, None);
+        tree.index_regions();
+        tree.index_afn_tables();
         tree
     }
 
-    #[cfg(feature = "desktop")]
-    pub fn load(&self, file_path: &Path) -> Result<(), Arc<dyn Error + Send + Sync>> {
-        let file =
-            File::open(file_path).map_err(|e| Arc::new(e) as Arc<dyn Error + Send + Sync>)?;
-        let reader = BufReader::new(file);
-        let mut xml_reader = Reader::from_reader(reader);
+    // 解析XML字符串，记录每个元素的起始行号
+    pub fn parse_xml_str(xml_str: &str) -> Result<XmlElement, Arc<dyn Error + Send + Sync>> {
+        let mut xml_reader = Reader::from_reader(xml_str.as_bytes());
         let mut buf = Vec::new();
         let mut root = XmlElement {
             name: String::new(),
             attributes: HashMap::new(),
             value: None,
             children: Vec::new(),
+            line: 0,
+            attr_order: Vec::new(),
+            layer: None,
         };
         let mut stack: Vec<XmlElement> = Vec::new();
+        let mut line = 1;
+        let mut last_pos = 0;
 
         loop {
+            let event_pos = xml_reader.buffer_position() as usize;
             match xml_reader.read_event_into(&mut buf) {
                 Ok(Event::Start(ref e)) => {
-                    let name = String::from_utf8_lossy(e.name().as_ref()).to_string();
-                    let mut attributes = HashMap::new();
-                    for attr in e.attributes() {
-                        if let Ok(attr) = attr {
-                            let key = String::from_utf8_lossy(attr.key.as_ref()).to_string();
-                            let value = String::from_utf8_lossy(&attr.value).to_string();
-                            attributes.insert(key, value);
-                        }
-                    }
-                    let element = XmlElement {
-                        name,
-                        attributes,
-                        value: None,
-                        children: Vec::new(),
-                    };
+                    line += xml_str.as_bytes()[last_pos..event_pos]
+                        .iter()
+                        .filter(|&&b| b == b'\n')
+                        .count();
+                    last_pos = event_pos;
+
+                    let element = Self::start_element(e, line);
                     if stack.is_empty() {
                         root = element.clone();
                     } else if let Some(parent) = stack.last_mut() {
@@ -469,6 +832,21 @@ impl QframeConfig {
                     }
                     stack.push(element);
                 }
+                Ok(Event::Empty(ref e)) => {
+                    line += xml_str.as_bytes()[last_pos..event_pos]
+                        .iter()
+                        .filter(|&&b| b == b'\n')
+                        .count();
+                    last_pos = event_pos;
+
+                    // 自闭合元素没有结束事件，直接加入父节点
+                    let element = Self::start_element(e, line);
+                    if let Some(parent) = stack.last_mut() {
+                        parent.children.push(element);
+                    } else {
+                        root = element;
+                    }
+                }
                 Ok(Event::Text(e)) => {
                     if let Some(element) = stack.last_mut() {
                         element.value = Some(
@@ -478,6 +856,20 @@ impl QframeConfig {
                         );
                     }
                 }
+                Ok(Event::Comment(e)) => {
+                    // 注释作为#comment节点保留，写回XML时输出
+                    if let Some(element) = stack.last_mut() {
+                        element.children.push(XmlElement {
+                            name: COMMENT_NODE.to_string(),
+                            attributes: HashMap::new(),
+                            value: Some(String::from_utf8_lossy(&e).to_string()),
+                            children: Vec::new(),
+                            line: 0,
+                            attr_order: Vec::new(),
+                            layer: None,
+                        });
+                    }
+                }
                 Ok(Event::End(_)) => {
                     if let Some(element) = stack.pop() {
                         if let Some(parent) = stack.last_mut() {
@@ -496,115 +888,510 @@ impl QframeConfig {
             buf.clear();
         }
 
-        let tree = self.build_tree(&root);
-        *self.config.write().unwrap() = Some(tree);
+        Ok(root)
+    }
+
+    fn start_element(e: &BytesStart, line: usize) -> XmlElement {
+        let name = String::from_utf8_lossy(e.name().as_ref()).to_string();
+        let mut attributes = HashMap::new();
+        let mut attr_order = Vec::new();
+        for attr in e.attributes().flatten() {
+            let key = String::from_utf8_lossy(attr.key.as_ref()).to_string();
+            let value = String::from_utf8_lossy(&attr.value).to_string();
+            attr_order.push(key.clone());
+            attributes.insert(key, value);
+        }
+        XmlElement {
+            name,
+            attributes,
+            value: None,
+            children: Vec::new(),
+            line,
+            attr_order,
+            layer: None,
+        }
+    }
+
+    #[cfg(feature = "desktop")]
+    pub fn load(&self, file_path: &Path) -> Result<(), Arc<dyn Error + Send + Sync>> {
+        let xml_str = std::fs::read_to_string(file_path)
+            .map_err(|e| Arc::new(e) as Arc<dyn Error + Send + Sync>)?;
+        let root = Self::parse_xml_str(&xml_str)?;
+
+        self.replace_base(root);
         *self.config_path.write().unwrap() = Some(file_path.to_path_buf());
 
         Ok(())
     }
 
     pub fn load_from_str(&self, xml_str: &str) -> Result<(), Arc<dyn Error + Send + Sync>> {
-        let mut xml_reader = Reader::from_reader(xml_str.as_bytes());
-        let mut buf = Vec::new();
-        let mut root = XmlElement {
-            name: String::new(),
-            attributes: HashMap::new(),
-            value: None,
-            children: Vec::new(),
+        let root = Self::parse_xml_str(xml_str)?;
+
+        self.replace_base(root);
+        *self.config_path.write().unwrap() = None;
+
+        Ok(())
+    }
+
+    // 替换基础配置，已加载的叠加层和修改层重新叠加到新的基础配置上
+    fn replace_base(&self, root: XmlElement) {
+        let mut layers = self.layers.write().unwrap();
+        layers.base = Some(root);
+        if let Some(merged) = layers.merge() {
+            let tree = self.build_tree(&merged);
+            self.replace_tree(tree);
+        }
+    }
+
+    /// 添加叠加层，同名叠加层已存在时原位替换
+    ///
+    /// 叠加层XML的顶层数据项按id、protocol、region、dir匹配基础配置中的数据项，
+    /// 未声明protocol、region时取叠加层根节点上的属性，
+    /// 匹配到时整体覆盖，未匹配到时新增；带`action="remove"`属性的数据项删除匹配到的数据项。
+    /// 叠加层变化后从基础配置重新合并，通过add_item等接口做的修改在修改层中保留，合并后重新应用。
+    pub fn add_overlay(&self, name: &str, xml_str: &str) -> Result<(), Box<dyn Error>> {
+        let root = Self::parse_xml_str(xml_str).map_err(|e| e.to_string())?;
+        ConfigLayers::check_overlay(name, &root)?;
+
+        let mut layers = self.layers.write().unwrap();
+        let overlay = ConfigOverlay {
+            name: name.to_string(),
+            root,
         };
-        let mut stack: Vec<XmlElement> = Vec::new();
+        match layers.overlays.iter().position(|o| o.name == name) {
+            Some(index) => layers.overlays[index] = overlay,
+            None => layers.overlays.push(overlay),
+        }
+        if let Some(merged) = layers.merge() {
+            let tree = self.build_tree(&merged);
+            self.replace_tree(tree);
+        }
+        Ok(())
+    }
 
-        loop {
-            match xml_reader.read_event_into(&mut buf) {
-                Ok(Event::Start(ref e)) => {
-                    let name = String::from_utf8_lossy(e.name().as_ref()).to_string();
-                    let mut attributes = HashMap::new();
-                    for attr in e.attributes() {
-                        if let Ok(attr) = attr {
-                            let key = String::from_utf8_lossy(attr.key.as_ref()).to_string();
-                            let value = String::from_utf8_lossy(&attr.value).to_string();
-                            attributes.insert(key, value);
-                        }
-                    }
-                    let element = XmlElement {
-                        name,
-                        attributes,
-                        value: None,
-                        children: Vec::new(),
-                    };
-                    if stack.is_empty() {
-                        root = element.clone();
-                    } else if let Some(parent) = stack.last_mut() {
-                        let parent: &mut XmlElement = parent;
-                        parent.children.push(element.clone());
-                    }
-                    stack.push(element);
-                }
-                Ok(Event::Text(e)) => {
-                    if let Some(element) = stack.last_mut() {
-                        element.value = Some(
-                            e.unescape()
-                                .map_err(|e| Arc::new(e) as Arc<dyn Error + Send + Sync>)?
-                                .into_owned(),
-                        );
-                    }
-                }
-                Ok(Event::End(_)) => {
-                    if let Some(element) = stack.pop() {
-                        if let Some(parent) = stack.last_mut() {
-                            if let Some(last) = parent.children.last_mut() {
-                                *last = element;
-                            }
-                        } else {
-                            root = element;
-                        }
-                    }
-                }
-                Ok(Event::Eof) => break,
-                Err(e) => return Err(Arc::new(e)),
-                _ => (),
-            }
-            buf.clear();
+    /// 移除叠加层，返回是否存在该叠加层
+    pub fn remove_overlay(&self, name: &str) -> bool {
+        let mut layers = self.layers.write().unwrap();
+        let count = layers.overlays.len();
+        layers.overlays.retain(|o| o.name != name);
+        if layers.overlays.len() == count {
+            return false;
         }
+        if let Some(merged) = layers.merge() {
+            let tree = self.build_tree(&merged);
+            self.replace_tree(tree);
+        }
+        true
+    }
 
-        let tree = self.build_tree(&root);
-        *self.config.write().unwrap() = Some(tree);
-        *self.config_path.write().unwrap() = None;
+    /// 丢弃通过add_item等接口做的修改，返回是否有修改
+    pub fn discard_edits(&self) -> bool {
+        let mut layers = self.layers.write().unwrap();
+        if layers.edits.is_empty() {
+            return false;
+        }
+        layers.edits.clear();
+        if let Some(merged) = layers.merge() {
+            let tree = self.build_tree(&merged);
+            self.replace_tree(tree);
+        }
+        true
+    }
 
-        Ok(())
+    /// 按叠加顺序列出叠加层名称
+    pub fn overlay_names(&self) -> Vec<String> {
+        let layers = self.layers.read().unwrap();
+        layers.overlays.iter().map(|o| o.name.clone()).collect()
     }
 
-    pub fn get_item(
+    /// 查询生效的数据项来自哪个配置层
+    pub fn get_item_layer(
         &self,
         item_id: &str,
         protocol: &str,
         region: &str,
         dir: Option<u8>,
-    ) -> Option<XmlElement> {
-        info!(
-            "item_id: {}, protocol: {}, region: {}, dir: {:?}",
-            item_id, protocol, region, dir
-        );
-        let cache_key = Self::generate_cache_key(item_id, protocol, region, dir);
-        {
-            let cache = self.config_cache.read().unwrap();
-            if let Some(cached_result) = cache.get(&cache_key) {
-                info!("get config from cache");
-                return cached_result.clone();
-            }
+    ) -> Option<String> {
+        let config = self.config.read().unwrap();
+        let tree = config.as_ref()?;
+        let node = tree.find_by_id(item_id, protocol, region, dir)?;
+        // 子数据项取所属顶层数据项的配置层
+        let mut current = node;
+        while current.depth > 1 {
+            current = &tree.nodes[current.parent?];
         }
+        current.layer.clone()
+    }
 
+    /// 配置中出现的所有地区及其继承链
+    pub fn regions(&self) -> Vec<Region> {
         let config = self.config.read().unwrap();
-        if let Some(tree) = config.as_ref() {
-            if let Some(node) = tree.find_by_id(item_id, protocol, region, dir) {
-                info!("find item from {:?}", region);
-                let result = Some(self.node_to_element(tree, node));
-                let mut cache = self.config_cache.write().unwrap();
-                cache.insert(cache_key, result.clone());
-                return result;
-            }
-            if let Some(node) = tree.find_by_id(item_id, protocol, "南网", dir) {
-                info!("use 南网 get item");
+        config
+            .as_ref()
+            .map(|tree| tree.regions())
+            .unwrap_or_default()
+    }
+
+    /// 配置中声明的功能码，不含内置功能码表
+    pub fn afn_entries(&self) -> Vec<AfnEntry> {
+        let config = self.config.read().unwrap();
+        config
+            .as_ref()
+            .map(|tree| tree.afn_entries().to_vec())
+            .unwrap_or_default()
+    }
+
+    /// 查找数据项时依次尝试的地区
+    pub fn region_chain(&self, region: &str) -> Vec<String> {
+        let config = self.config.read().unwrap();
+        match config.as_ref() {
+            Some(tree) => tree.region_chain(region),
+            None => XmlTree::new().region_chain(region),
+        }
+    }
+
+    /// 列出合并后所有顶层数据项及其来源配置层
+    pub fn item_layers(&self) -> Vec<ItemLayer> {
+        let config = self.config.read().unwrap();
+        let Some(tree) = config.as_ref() else {
+            return Vec::new();
+        };
+        tree.nodes[tree.root]
+            .children
+            .iter()
+            .map(|&index| &tree.nodes[index])
+            .filter_map(|node| {
+                Some(ItemLayer {
+                    id: node.id.clone()?,
+                    protocol: node.attributes.get("protocol").cloned(),
+                    region: node.attributes.get("region").cloned(),
+                    dir: node.attributes.get("dir").cloned(),
+                    layer: node.layer.clone(),
+                })
+            })
+            .collect()
+    }
+
+    // 替换配置树并清空缓存，持有配置写锁期间完成，查找不会读到旧缓存
+    fn replace_tree(&self, tree: XmlTree) {
+        let mut config = self.config.write().unwrap();
+        let mut cache = self.config_cache.write().unwrap();
+        cache.clear();
+        *config = Some(tree);
+    }
+
+    pub fn cache_stats(&self) -> CacheStats {
+        self.config_cache.read().unwrap().stats()
+    }
+
+    pub fn set_cache_capacity(&self, capacity: usize) {
+        self.config_cache.write().unwrap().set_capacity(capacity);
+    }
+
+    /// 将基础配置和修改层写回XML字符串，叠加层保存在各自的文件中，不写入
+    pub fn to_xml_string(&self) -> Option<String> {
+        let layers = self.layers.read().unwrap();
+        let document = layers.merge_layers(&[])?;
+        Some(self.build_tree(&document).to_xml_string())
+    }
+
+    /// 将基础配置和修改层保存到文件，未指定路径时写回加载时的文件
+    #[cfg(feature = "desktop")]
+    pub fn save(&self, file_path: Option<&Path>) -> Result<(), Box<dyn Error>> {
+        let path = match file_path {
+            Some(path) => path.to_path_buf(),
+            None => self
+                .config_path
+                .read()
+                .unwrap()
+                .clone()
+                .ok_or("配置未从文件加载，需要指定保存路径")?,
+        };
+        let xml = self.to_xml_string().ok_or("配置未加载")?;
+        std::fs::write(&path, xml)?;
+        Ok(())
+    }
+
+    /// 新增数据项，parent为空时添加为顶层数据项，position为空时追加到末尾
+    pub fn add_item(
+        &self,
+        parent: Option<&ItemLocator>,
+        position: Option<usize>,
+        element: &XmlElement,
+    ) -> Result<(), Box<dyn Error>> {
+        let item_id = element
+            .get_attribute("id")
+            .cloned()
+            .ok_or("新增数据项缺少id属性")?;
+        self.edit_tree(&item_id, |tree, document| {
+            let mut element = element.clone();
+            let children = match parent {
+                Some(parent) => {
+                    let parent_index = Self::locate(tree, parent)?;
+                    Self::strip_inherited_attributes(
+                        &mut element,
+                        tree.nodes[parent_index].attributes.get("protocol"),
+                        tree.nodes[parent_index].attributes.get("region"),
+                    );
+                    &mut Self::document_node(document, &tree.get_child_positions(parent_index))
+                        .children
+                }
+                None => {
+                    Self::check_top_level_item(tree, &element, None)?;
+                    &mut document.children
+                }
+            };
+            let position = position.unwrap_or(children.len()).min(children.len());
+            children.insert(position, element);
+            Ok(())
+        })
+    }
+
+    /// 修改数据项，用element整体替换定位到的数据项
+    pub fn update_item(
+        &self,
+        target: &ItemLocator,
+        element: &XmlElement,
+    ) -> Result<(), Box<dyn Error>> {
+        let item_id = element
+            .get_attribute("id")
+            .cloned()
+            .unwrap_or_else(|| target.id.clone());
+        self.edit_tree(&item_id, |tree, document| {
+            let index = Self::locate(tree, target)?;
+            let node = &tree.nodes[index];
+            let mut element = element.clone();
+            match node.parent.map(|parent| &tree.nodes[parent]) {
+                Some(parent) if parent.parent.is_some() => Self::strip_inherited_attributes(
+                    &mut element,
+                    parent.attributes.get("protocol"),
+                    parent.attributes.get("region"),
+                ),
+                _ => Self::check_top_level_item(tree, &element, Some(index))?,
+            }
+            // 未记录属性顺序时沿用原节点的顺序
+            if element.attr_order.is_empty() {
+                element.attr_order = node.attr_order.clone();
+            }
+            *Self::document_node(document, &tree.get_child_positions(index)) = element;
+            Ok(())
+        })
+    }
+
+    /// 删除数据项
+    pub fn delete_item(&self, target: &ItemLocator) -> Result<(), Box<dyn Error>> {
+        self.edit_tree("", |tree, document| {
+            let index = Self::locate(tree, target)?;
+            let mut positions = tree.get_child_positions(index);
+            let position = positions.pop().ok_or("不能删除根节点")?;
+            Self::document_node(document, &positions)
+                .children
+                .remove(position);
+            Ok(())
+        })
+    }
+
+    /// 移动数据项到新的父节点下，parent为空时移动到顶层
+    pub fn move_item(
+        &self,
+        target: &ItemLocator,
+        parent: Option<&ItemLocator>,
+        position: Option<usize>,
+    ) -> Result<(), Box<dyn Error>> {
+        self.edit_tree(&target.id, |tree, document| {
+            let index = Self::locate(tree, target)?;
+            let parent_index = match parent {
+                Some(parent) => Self::locate(tree, parent)?,
+                None => tree.root,
+            };
+            if tree.get_node_path(parent_index).contains(&index) {
+                return Err("不能将数据项移动到自身或其子项下".into());
+            }
+
+            // 保留原节点的有效协议和区域，移动后由新的父节点继承时再去除
+            let mut element = tree.node_to_document(index);
+            for key in ["protocol", "region"] {
+                if let Some(value) = tree.nodes[index].attributes.get(key) {
+                    if !element.attributes.contains_key(key) {
+                        element.attributes.insert(key.to_string(), value.clone());
+                        element.attr_order.push(key.to_string());
+                    }
+                }
+            }
+            if parent_index == tree.root {
+                Self::check_top_level_item(tree, &element, Some(index))?;
+            } else {
+                Self::strip_inherited_attributes(
+                    &mut element,
+                    tree.nodes[parent_index].attributes.get("protocol"),
+                    tree.nodes[parent_index].attributes.get("region"),
+                );
+            }
+
+            let mut source = tree.get_child_positions(index);
+            let source_position = source.pop().ok_or("不能移动根节点")?;
+            let mut dest = tree.get_child_positions(parent_index);
+
+            // 先从原位置删除，原节点之后的兄弟节点路径需要前移
+            Self::document_node(document, &source)
+                .children
+                .remove(source_position);
+            let level = source.len();
+            if dest.len() > level && dest[..level] == source[..] && dest[level] > source_position {
+                dest[level] -= 1;
+            }
+
+            // position为移动后在新父节点中的位置
+            let dest_children = &mut Self::document_node(document, &dest).children;
+            let position = position
+                .unwrap_or(dest_children.len())
+                .min(dest_children.len());
+            dest_children.insert(position, element);
+            Ok(())
+        })
+    }
+
+    // 在配置写锁内修改还原的XML元素，校验通过后记入修改层，重建配置树并清空缓存
+    fn edit_tree<F>(&self, item_id: &str, edit: F) -> Result<(), Box<dyn Error>>
+    where
+        F: FnOnce(&XmlTree, &mut XmlElement) -> Result<(), Box<dyn Error>>,
+    {
+        let mut layers = self.layers.write().unwrap();
+        let mut config = self.config.write().unwrap();
+        let tree = config.as_ref().ok_or("配置未加载")?;
+        let before = tree.node_to_document(tree.root);
+        let mut document = before.clone();
+        edit(tree, &mut document)?;
+        let edits = layers.record_edits(&before, &mut document);
+
+        let new_tree = self.build_tree(&document);
+        if !item_id.is_empty() {
+            let errors: Vec<String> = XmlConfigValidator::validate(&new_tree)
+                .into_iter()
+                .filter(|d| {
+                    d.level == DiagnosticLevel::Error && d.item_id.as_deref() == Some(item_id)
+                })
+                .map(|d| d.message)
+                .collect();
+            if !errors.is_empty() {
+                return Err(format!("数据项[{}]校验失败: {}", item_id, errors.join("; ")).into());
+            }
+        }
+
+        layers.edits = edits;
+        let mut cache = self.config_cache.write().unwrap();
+        cache.clear();
+        *config = Some(new_tree);
+        Ok(())
+    }
+
+    fn locate(tree: &XmlTree, locator: &ItemLocator) -> Result<usize, Box<dyn Error>> {
+        tree.find_in_scope(&locator.id, &locator.protocol, &locator.region, locator.dir)
+            .and_then(|node| {
+                tree.find_all_by_id(&locator.id)
+                    .into_iter()
+                    .find(|&index| std::ptr::eq(&tree.nodes[index], node))
+            })
+            .ok_or_else(|| {
+                format!(
+                    "未找到数据项[{}] protocol={} region={}",
+                    locator.id, locator.protocol, locator.region
+                )
+                .into()
+            })
+    }
+
+    fn document_node<'a>(document: &'a mut XmlElement, positions: &[usize]) -> &'a mut XmlElement {
+        positions.iter().fold(document, |element, &position| {
+            &mut element.children[position]
+        })
+    }
+
+    // 顶层数据项必须声明协议和区域，且不能与已有定义的作用域重叠
+    fn check_top_level_item(
+        tree: &XmlTree,
+        element: &XmlElement,
+        exclude: Option<usize>,
+    ) -> Result<(), Box<dyn Error>> {
+        let id = element.get_attribute("id").ok_or("数据项缺少id属性")?;
+        let protocol = element
+            .get_attribute("protocol")
+            .ok_or("顶层数据项缺少protocol属性")?;
+        let region = element
+            .get_attribute("region")
+            .ok_or("顶层数据项缺少region属性")?;
+        let dir = match element.get_attribute("dir") {
+            Some(dir) => Some(
+                dir.parse::<u8>()
+                    .map_err(|_| format!("dir属性[{}]无效", dir))?,
+            ),
+            None => None,
+        };
+        for single_region in region.split(',').map(|r| r.trim()) {
+            if let Some(node) = tree.find_in_scope(id, protocol, single_region, dir) {
+                let index = tree
+                    .find_all_by_id(id)
+                    .into_iter()
+                    .find(|&index| std::ptr::eq(&tree.nodes[index], node));
+                if index != exclude && node.depth == 1 {
+                    return Err(format!("数据项[{}]与第{}行的定义作用域重叠", id, node.line).into());
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // 去除与父节点相同的协议和区域属性，避免写回时每个子项都带上继承属性
+    fn strip_inherited_attributes(
+        element: &mut XmlElement,
+        protocol: Option<&String>,
+        region: Option<&String>,
+    ) {
+        for (key, inherited) in [("protocol", protocol), ("region", region)] {
+            if inherited.is_some() && element.attributes.get(key) == inherited {
+                element.attributes.remove(key);
+                element.attr_order.retain(|k| k != key);
+            }
+        }
+        let protocol = element
+            .attributes
+            .get("protocol")
+            .cloned()
+            .or(protocol.cloned());
+        let region = element
+            .attributes
+            .get("region")
+            .cloned()
+            .or(region.cloned());
+        for child in &mut element.children {
+            Self::strip_inherited_attributes(child, protocol.as_ref(), region.as_ref());
+        }
+    }
+
+    pub fn get_item(
+        &self,
+        item_id: &str,
+        protocol: &str,
+        region: &str,
+        dir: Option<u8>,
+    ) -> Option<XmlElement> {
+        info!(
+            "item_id: {}, protocol: {}, region: {}, dir: {:?}",
+            item_id, protocol, region, dir
+        );
+        let cache_key = Self::generate_cache_key(item_id, protocol, region, dir);
+        // 先持有配置读锁再访问缓存，与replace_tree的加锁顺序一致
+        let config = self.config.read().unwrap();
+        {
+            let mut cache = self.config_cache.write().unwrap();
+            if let Some(cached_result) = cache.get(&cache_key) {
+                info!("get config from cache");
+                return cached_result;
+            }
+        }
+
+        if let Some(tree) = config.as_ref() {
+            if let Some(node) = tree.find_by_id(item_id, protocol, region, dir) {
                 let result = Some(self.node_to_element(tree, node));
                 let mut cache = self.config_cache.write().unwrap();
                 cache.insert(cache_key, result.clone());
@@ -620,10 +1407,16 @@ impl QframeConfig {
             attributes: node.attributes.clone(),
             value: XmlElement::trim_value(node.value.clone()),
             children: Vec::new(),
+            line: node.line,
+            attr_order: node.attr_order.clone(),
+            layer: node.layer.clone(),
         };
 
         for &child_index in &node.children {
             let child_node = &tree.nodes[child_index];
+            if child_node.name == COMMENT_NODE {
+                continue;
+            }
             element
                 .children
                 .push(self.node_to_element(tree, child_node));
@@ -636,6 +1429,15 @@ impl QframeConfig {
         self.config.read().unwrap()
     }
 
+    // 校验当前加载的配置
+    pub fn validate(&self) -> Vec<XmlDiagnostic> {
+        let config = self.config.read().unwrap();
+        config
+            .as_ref()
+            .map(XmlConfigValidator::validate)
+            .unwrap_or_default()
+    }
+
     pub async fn get_all_item(&self) -> Vec<ItemConfigList> {
         let mut result = Vec::new();
         let config_read = self.config.read().unwrap();
@@ -708,207 +1510,495 @@ impl QframeConfig {
     }
 }
 
+// 缓存默认容量
+const DEFAULT_CACHE_CAPACITY: usize = 4096;
+
+// 按最近使用淘汰的查找缓存
 pub struct Cache {
-    results: HashMap<String, Option<XmlElement>>,
+    results: HashMap<String, (Option<XmlElement>, u64)>,
+    order: BTreeMap<u64, String>,
+    tick: u64,
+    capacity: usize,
+    hits: u64,
+    misses: u64,
+    evictions: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub evictions: u64,
+    pub size: usize,
+    pub capacity: usize,
 }
 
 impl Cache {
     pub fn new() -> Self {
+        Self::with_capacity(DEFAULT_CACHE_CAPACITY)
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
         Cache {
             results: HashMap::new(),
+            order: BTreeMap::new(),
+            tick: 0,
+            capacity,
+            hits: 0,
+            misses: 0,
+            evictions: 0,
         }
     }
 
-    pub fn get(&self, key: &str) -> Option<Option<XmlElement>> {
-        self.results.get(key).cloned()
+    pub fn get(&mut self, key: &str) -> Option<Option<XmlElement>> {
+        self.tick += 1;
+        let tick = self.tick;
+        match self.results.get_mut(key) {
+            Some((value, last_used)) => {
+                self.order.remove(last_used);
+                self.order.insert(tick, key.to_string());
+                *last_used = tick;
+                self.hits += 1;
+                Some(value.clone())
+            }
+            None => {
+                self.misses += 1;
+                None
+            }
+        }
     }
 
     pub fn insert(&mut self, key: String, value: Option<XmlElement>) {
-        self.results.insert(key, value);
+        if self.capacity == 0 {
+            return;
+        }
+        self.tick += 1;
+        if let Some((_, last_used)) = self.results.get(&key) {
+            self.order.remove(last_used);
+        }
+        self.order.insert(self.tick, key.clone());
+        self.results.insert(key, (value, self.tick));
+
+        while self.results.len() > self.capacity {
+            let oldest = match self.order.keys().next() {
+                Some(&oldest) => oldest,
+                None => break,
+            };
+            if let Some(key) = self.order.remove(&oldest) {
+                self.results.remove(&key);
+                self.evictions += 1;
+            }
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.results.clear();
+        self.order.clear();
+    }
+
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        while self.results.len() > self.capacity {
+            let oldest = *self.order.keys().next().unwrap();
+            let key = self.order.remove(&oldest).unwrap();
+            self.results.remove(&key);
+            self.evictions += 1;
+        }
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        CacheStats {
+            hits: self.hits,
+            misses: self.misses,
+            evictions: self.evictions,
+            size: self.results.len(),
+            capacity: self.capacity,
+        }
     }
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
-pub struct ItemConfigList {
-    pub item: String,
-    pub name: Option<String>,
-    pub protocol: Option<String>,
-    pub region: Option<String>,
-    pub dir: Option<String>,
+pub const BASE_LAYER: &str = "base";
+/// 通过add_item等接口做的修改所在的配置层，叠加在所有叠加层之上
+pub const EDIT_LAYER: &str = "edit";
+
+/// 配置未用<regions root="...">指定根地区时，未声明上级的地区最终回退到的地区
+pub const DEFAULT_REGION: &str = "南网";
+// 地区继承声明，形如<regions root="南网"><region name="深圳" parent="广东"/></regions>
+pub const REGIONS_NODE: &str = "regions";
+pub const REGION_NODE: &str = "region";
+pub const REGION_ROOT_ATTR: &str = "root";
+
+// 叠加层数据项的操作属性
+const OVERLAY_ACTION: &str = "action";
+
+/// 叠加在基础配置上的配置层
+#[derive(Clone, Debug)]
+struct ConfigOverlay {
+    name: String,
+    root: XmlElement,
 }
 
-#[cfg(feature = "desktop")]
-lazy_static! {
-    pub static ref GLOBAL_CSG13: Result<QframeConfig, Arc<dyn std::error::Error + Send + Sync>> = {
-        let config = QframeConfig::new();
-        let default_path = "./resources/protocolconfig/CSG13.xml".to_string();
-
-        let setpath = load_config_value("protocolsetting", "protocolfile")
-            .and_then(|protocol_config| {
-                protocol_config
-                    .get("nanwang13")
-                    .and_then(|protocol| protocol.get("path"))
-                    .and_then(|path| path.as_str())
-                    .map(String::from)
-            })
-            .unwrap_or(default_path);
+#[derive(Debug, Default)]
+struct ConfigLayers {
+    base: Option<XmlElement>,
+    overlays: Vec<ConfigOverlay>,
+    // 修改后的顶层数据项，带action="remove"属性的表示删除
+    edits: Vec<XmlElement>,
+}
 
-        match config.load(Path::new(&setpath)) {
-            Ok(_) => {
-                info!("CSG13 XML 加载成功");
-                Ok(config)
+impl ConfigLayers {
+    fn check_overlay(name: &str, root: &XmlElement) -> Result<(), Box<dyn Error>> {
+        if name.is_empty() || name == BASE_LAYER || name == EDIT_LAYER {
+            return Err(format!("叠加层名称[{}]无效", name).into());
+        }
+        for item in root.children.iter().filter(|c| {
+            c.name != COMMENT_NODE && c.name != REGIONS_NODE && c.name != AFN_TABLE_NODE
+        }) {
+            if item.get_attribute("id").is_none() {
+                return Err(format!("叠加层[{}]第{}行的数据项缺少id属性", name, item.line).into());
             }
-            Err(e) => {
-                info!("CSG13 XML 加载失败: {}", e);
-                Err(e)
+            match item.get_attribute(OVERLAY_ACTION).map(|a| a.as_str()) {
+                None | Some("override") | Some("remove") => {}
+                Some(action) => {
+                    return Err(format!(
+                        "叠加层[{}]第{}行的操作[{}]无效，可选override/remove",
+                        name, item.line, action
+                    )
+                    .into())
+                }
             }
         }
-    };
-    pub static ref GLOBAL_645: Result<QframeConfig, Arc<dyn std::error::Error + Send + Sync>> = {
-        let config = QframeConfig::new();
-        let default_path = "./resources/protocolconfig/DLT645.xml".to_string();
-
-        let setpath = load_config_value("protocolsetting", "protocolfile")
-            .and_then(|protocol_config| {
-                protocol_config
-                    .get("dlt645")
-                    .and_then(|protocol| protocol.get("path"))
-                    .and_then(|path| path.as_str())
-                    .map(String::from)
-            })
-            .unwrap_or(default_path);
+        Ok(())
+    }
 
-        match config.load(Path::new(&setpath)) {
-            Ok(_) => {
-                info!("645 XML 加载成功");
-                Ok(config)
+    // 叠加层数据项未声明的属性不参与匹配
+    fn is_same_item(overlay_item: &XmlElement, item: &XmlElement) -> bool {
+        ["id", "protocol", "region", "dir"].iter().all(|key| {
+            match (overlay_item.get_attribute(key), item.get_attribute(key)) {
+                (None, _) => true,
+                (Some(a), Some(b)) => a.eq_ignore_ascii_case(b),
+                (Some(_), None) => false,
             }
-            Err(e) => {
-                info!("645 XML 加载失败: {}", e);
-                Err(e)
+        })
+    }
+
+    fn is_item_node(element: &XmlElement) -> bool {
+        element.name != COMMENT_NODE
+            && element.name != REGIONS_NODE
+            && element.name != AFN_TABLE_NODE
+    }
+
+    // 修改层按id、protocol、region、dir完全一致匹配，避免覆盖其他方向的定义
+    fn is_exact_item(a: &XmlElement, b: &XmlElement) -> bool {
+        ["id", "protocol", "region", "dir"].iter().all(|key| {
+            match (a.get_attribute(key), b.get_attribute(key)) {
+                (None, None) => true,
+                (Some(a), Some(b)) => a.eq_ignore_ascii_case(b),
+                _ => false,
             }
-        }
-    };
-    pub static ref GLOBAL_CSG16: Result<QframeConfig, Arc<dyn std::error::Error + Send + Sync>> = {
-        let config = QframeConfig::new();
-        let default_path = "./resources/protocolconfig/CSG16.xml".to_string();
-
-        let setpath = load_config_value("protocolsetting", "protocolfile")
-            .and_then(|protocol_config| {
-                protocol_config
-                    .get("nanwang16")
-                    .and_then(|protocol| protocol.get("path"))
-                    .and_then(|path| path.as_str())
-                    .map(String::from)
-            })
-            .unwrap_or(default_path);
+        })
+    }
 
-        match config.load(Path::new(&setpath)) {
-            Ok(_) => {
-                info!("CSG16 XML 加载成功");
-                Ok(config)
+    // 用item替换匹配到的顶层数据项，未匹配到时追加；remove为true时删除匹配到的数据项
+    fn apply_item(
+        merged: &mut XmlElement,
+        item: XmlElement,
+        remove: bool,
+        is_match: impl Fn(&XmlElement) -> bool,
+    ) {
+        let matched: Vec<usize> = merged
+            .children
+            .iter()
+            .enumerate()
+            .filter(|(_, existing)| existing.name != COMMENT_NODE && is_match(existing))
+            .map(|(index, _)| index)
+            .collect();
+
+        if remove {
+            if matched.is_empty() {
+                info!(
+                    "配置层[{}]要删除的数据项{:?}不存在",
+                    item.layer.as_deref().unwrap_or_default(),
+                    item.attributes
+                );
             }
-            Err(e) => {
-                info!("CSG16 XML 加载失败: {}", e);
-                Err(e)
+            for &index in matched.iter().rev() {
+                merged.children.remove(index);
             }
-        }
-    };
-    pub static ref GLOBAL_Moudle: Result<QframeConfig, Arc<dyn std::error::Error + Send + Sync>> = {
-        let config = QframeConfig::new();
-        let default_path = "./resources/protocolconfig/MOUDLE.xml".to_string();
-
-        let setpath = load_config_value("protocolsetting", "protocolfile")
-            .and_then(|protocol_config| {
-                protocol_config
-                    .get("moudle")
-                    .and_then(|protocol| protocol.get("path"))
-                    .and_then(|path| path.as_str())
-                    .map(String::from)
-            })
-            .unwrap_or(default_path);
-        info!("moudle XML 路径: {}", setpath);
-        match config.load(Path::new(&setpath)) {
-            Ok(_) => {
-                info!("moudle XML 加载成功");
-                Ok(config)
+        } else if let Some((&first, rest)) = matched.split_first() {
+            for &index in rest.iter().rev() {
+                merged.children.remove(index);
             }
-            Err(e) => {
-                info!("moudle XML 加载失败: {}", e);
-                Err(e)
+            merged.children[first] = item;
+        } else {
+            merged.children.push(item);
+        }
+    }
+
+    // 比较修改前后的根节点，返回加上本次修改后的修改层，同一数据项只保留最后一次修改；
+    // 修改过的数据项在after中标记为来自修改层
+    fn record_edits(&self, before: &XmlElement, after: &mut XmlElement) -> Vec<XmlElement> {
+        let mut edits = Vec::new();
+        for item in before.children.iter().filter(|c| Self::is_item_node(c)) {
+            if !after.children.iter().any(|c| Self::is_exact_item(c, item)) {
+                let mut attr_order: Vec<String> = ["id", "protocol", "region", "dir"]
+                    .iter()
+                    .filter(|key| item.attributes.contains_key(**key))
+                    .map(|key| key.to_string())
+                    .collect();
+                let mut attributes: HashMap<String, String> = attr_order
+                    .iter()
+                    .map(|key| (key.clone(), item.attributes[key].clone()))
+                    .collect();
+                attributes.insert(OVERLAY_ACTION.to_string(), "remove".to_string());
+                attr_order.push(OVERLAY_ACTION.to_string());
+                edits.push(XmlElement {
+                    name: item.name.clone(),
+                    attributes,
+                    value: None,
+                    children: Vec::new(),
+                    line: 0,
+                    attr_order,
+                    layer: Some(EDIT_LAYER.to_string()),
+                });
             }
         }
-    };
-    pub static ref GLOBAL_MS: Result<QframeConfig, Arc<dyn std::error::Error + Send + Sync>> = {
-        let config = QframeConfig::new();
-        let default_path = "./resources/protocolconfig/TASK_MS.xml".to_string();
-
-        let setpath = load_config_value("protocolsetting", "protocolfile")
-            .and_then(|protocol_config| {
-                protocol_config
-                    .get("task_ms")
-                    .and_then(|protocol| protocol.get("path"))
-                    .and_then(|path| path.as_str())
-                    .map(String::from)
-            })
-            .unwrap_or(default_path);
-        info!("task_ms XML 路径: {}", setpath);
-        match config.load(Path::new(&setpath)) {
-            Ok(_) => {
-                info!("task_ms XML 加载成功");
-                Ok(config)
+        for item in after.children.iter_mut().filter(|c| Self::is_item_node(c)) {
+            if !before.children.iter().any(|c| c.same_content(item)) {
+                item.layer = Some(EDIT_LAYER.to_string());
+                edits.push(item.clone());
             }
-            Err(e) => {
-                info!("task_ms XML 加载失败: {}", e);
-                Err(e)
+        }
+        let mut recorded = self.edits.clone();
+        for edit in edits {
+            recorded.retain(|existing| !Self::is_exact_item(existing, &edit));
+            recorded.push(edit);
+        }
+        recorded
+    }
+
+    // 按顺序叠加所有配置层，基础配置未加载时返回None
+    fn merge(&self) -> Option<XmlElement> {
+        self.merge_layers(&self.overlays)
+    }
+
+    // 在基础配置上依次叠加给定的叠加层，再应用修改层
+    fn merge_layers(&self, overlays: &[ConfigOverlay]) -> Option<XmlElement> {
+        let mut merged = self.base.clone()?;
+        for item in merged.children.iter_mut() {
+            item.layer = Some(BASE_LAYER.to_string());
+        }
+
+        for overlay in overlays {
+            for overlay_item in overlay.root.children.iter() {
+                if overlay_item.name == COMMENT_NODE {
+                    continue;
+                }
+                // 地区声明和功能码表追加到末尾，覆盖基础配置中的同名声明
+                if overlay_item.name == REGIONS_NODE || overlay_item.name == AFN_TABLE_NODE {
+                    let mut regions = overlay_item.clone();
+                    regions.layer = Some(overlay.name.clone());
+                    merged.children.push(regions);
+                    continue;
+                }
+                // 数据项未声明协议和区域时取叠加层根节点上的属性
+                let mut item = overlay_item.clone();
+                for key in ["protocol", "region"] {
+                    if let Some(value) = overlay.root.get_attribute(key) {
+                        if !item.attributes.contains_key(key) {
+                            item.attributes.insert(key.to_string(), value.clone());
+                            item.attr_order.push(key.to_string());
+                        }
+                    }
+                }
+
+                let action = item.attributes.remove(OVERLAY_ACTION);
+                item.attr_order.retain(|key| key != OVERLAY_ACTION);
+                item.layer = Some(overlay.name.clone());
+                let pattern = item.clone();
+                Self::apply_item(
+                    &mut merged,
+                    item,
+                    action.as_deref() == Some("remove"),
+                    |existing| Self::is_same_item(&pattern, existing),
+                );
             }
         }
-    };
+
+        for edit in &self.edits {
+            let mut item = edit.clone();
+            let action = item.attributes.remove(OVERLAY_ACTION);
+            item.attr_order.retain(|key| key != OVERLAY_ACTION);
+            item.layer = Some(EDIT_LAYER.to_string());
+            Self::apply_item(
+                &mut merged,
+                item,
+                action.as_deref() == Some("remove"),
+                |existing| Self::is_exact_item(edit, existing),
+            );
+        }
+        Some(merged)
+    }
 }
 
-#[cfg(not(feature = "desktop"))]
-lazy_static! {
-    pub static ref GLOBAL_CSG13: Result<QframeConfig, Arc<dyn std::error::Error + Send + Sync>> = {
-        let config = QframeConfig::new();
-        // 这里路径相对于本文件：src-tauri/src/config/xmlconfig.rs -> ../../resources/
-        let xml = include_str!("../../../../public/config/CSG13.xml");
-        match config.load_from_str(xml) {
-            Ok(_) => { info!("CSG13 XML (embedded) 加载成功"); Ok(config) }
-            Err(e) => { info!("CSG13 XML (embedded) 加载失败: {}", e); Err(e) }
-        }
-    };
-    pub static ref GLOBAL_645: Result<QframeConfig, Arc<dyn std::error::Error + Send + Sync>> = {
-        let config = QframeConfig::new();
-        let xml = include_str!("../../../../public/config/DLT645.xml");
-        match config.load_from_str(xml) {
-            Ok(_) => { info!("645 XML (embedded) 加载成功"); Ok(config) }
-            Err(e) => { info!("645 XML (embedded) 加载失败: {}", e); Err(e) }
+/// 配置中的地区及其继承链
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Region {
+    pub name: String,
+    pub parent: Option<String>,
+    pub chain: Vec<String>,
+    // 是否在<regions>中显式声明了上级地区
+    pub declared: bool,
+}
+
+/// 顶层数据项及其来源配置层
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ItemLayer {
+    pub id: String,
+    pub protocol: Option<String>,
+    pub region: Option<String>,
+    pub dir: Option<String>,
+    pub layer: Option<String>,
+}
+
+/// 定位配置中的数据项
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ItemLocator {
+    pub id: String,
+    pub protocol: String,
+    pub region: String,
+    pub dir: Option<u8>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ItemConfigList {
+    pub item: String,
+    pub name: Option<String>,
+    pub protocol: Option<String>,
+    pub region: Option<String>,
+    pub dir: Option<String>,
+}
+
+/// 内置的协议配置，每个解析器实例各持有一份
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BuiltinProtocol {
+    Csg13,
+    Dlt645,
+    Csg16,
+    Moudle,
+    Ms,
+    Dlt64597,
+    Dlt698,
+    Gdw13761,
+    Gdw13762,
+}
+
+impl BuiltinProtocol {
+    pub const ALL: [BuiltinProtocol; 9] = [
+        BuiltinProtocol::Csg13,
+        BuiltinProtocol::Dlt645,
+        BuiltinProtocol::Csg16,
+        BuiltinProtocol::Moudle,
+        BuiltinProtocol::Ms,
+        BuiltinProtocol::Dlt64597,
+        BuiltinProtocol::Dlt698,
+        BuiltinProtocol::Gdw13761,
+        BuiltinProtocol::Gdw13762,
+    ];
+
+    /// 按协议名称匹配内置配置，名称不区分大小写
+    pub fn from_name(protocol: &str) -> Option<Self> {
+        let protocol = protocol.to_uppercase();
+        match protocol.as_str() {
+            p if p.contains("CSG13") => Some(BuiltinProtocol::Csg13),
+            p if p.contains("DLT/645-1997") => Some(BuiltinProtocol::Dlt64597),
+            p if p.contains("DLT/645") => Some(BuiltinProtocol::Dlt645),
+            p if p.contains("DLT/698") => Some(BuiltinProtocol::Dlt698),
+            p if p.contains("1376.1") => Some(BuiltinProtocol::Gdw13761),
+            p if p.contains("1376.2") => Some(BuiltinProtocol::Gdw13762),
+            p if p.contains("CSG16") => Some(BuiltinProtocol::Csg16),
+            p if p.contains("MODULE") || p.contains("MOUDLE") => Some(BuiltinProtocol::Moudle),
+            p if p.contains("MS") => Some(BuiltinProtocol::Ms),
+            _ => None,
         }
-    };
-    pub static ref GLOBAL_CSG16: Result<QframeConfig, Arc<dyn std::error::Error + Send + Sync>> = {
-        let config = QframeConfig::new();
-        let xml = include_str!("../../../../public/config/CSG16.xml");
-        match config.load_from_str(xml) {
-            Ok(_) => { info!("CSG16 XML (embedded) 加载成功"); Ok(config) }
-            Err(e) => { info!("CSG16 XML (embedded) 加载失败: {}", e); Err(e) }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            BuiltinProtocol::Csg13 => "CSG13",
+            BuiltinProtocol::Dlt645 => "DLT/645",
+            BuiltinProtocol::Csg16 => "CSG16",
+            BuiltinProtocol::Moudle => "MODULE",
+            BuiltinProtocol::Ms => "MS",
+            BuiltinProtocol::Dlt64597 => "DLT/645-1997",
+            BuiltinProtocol::Dlt698 => "DLT/698.45",
+            BuiltinProtocol::Gdw13761 => "Q/GDW1376.1",
+            BuiltinProtocol::Gdw13762 => "Q/GDW1376.2",
         }
-    };
-    pub static ref GLOBAL_Moudle: Result<QframeConfig, Arc<dyn std::error::Error + Send + Sync>> = {
-        let config = QframeConfig::new();
-        let xml = include_str!("../../../../public/config/MOUDLE.xml");
-        match config.load_from_str(xml) {
-            Ok(_) => { info!("moudle XML (embedded) 加载成功"); Ok(config) }
-            Err(e) => { info!("moudle XML (embedded) 加载失败: {}", e); Err(e) }
+    }
+
+    /// 编译时内嵌的配置内容
+    pub fn embedded(&self) -> &'static str {
+        match self {
+            BuiltinProtocol::Csg13 => include_str!("../../../../public/config/CSG13.xml"),
+            BuiltinProtocol::Dlt645 => include_str!("../../../../public/config/DLT645.xml"),
+            BuiltinProtocol::Csg16 => include_str!("../../../../public/config/CSG16.xml"),
+            BuiltinProtocol::Moudle => include_str!("../../../../public/config/MOUDLE.xml"),
+            BuiltinProtocol::Ms => include_str!("../../../../public/config/TASK_MS.xml"),
+            BuiltinProtocol::Dlt64597 => include_str!("../../../../public/config/DLT645_97.xml"),
+            BuiltinProtocol::Dlt698 => include_str!("../../../../public/config/DLT698.xml"),
+            BuiltinProtocol::Gdw13761 => include_str!("../../../../public/config/QGDW1376_1.xml"),
+            BuiltinProtocol::Gdw13762 => include_str!("../../../../public/config/QGDW1376_2.xml"),
+        }
+    }
+
+    // 应用配置protocolsetting.protocolfile中的键和desktop下的默认路径
+    fn setting(&self) -> (&'static str, &'static str) {
+        match self {
+            BuiltinProtocol::Csg13 => ("nanwang13", "./resources/protocolconfig/CSG13.xml"),
+            BuiltinProtocol::Dlt645 => ("dlt645", "./resources/protocolconfig/DLT645.xml"),
+            BuiltinProtocol::Csg16 => ("nanwang16", "./resources/protocolconfig/CSG16.xml"),
+            BuiltinProtocol::Moudle => ("moudle", "./resources/protocolconfig/MOUDLE.xml"),
+            BuiltinProtocol::Ms => ("task_ms", "./resources/protocolconfig/TASK_MS.xml"),
+            BuiltinProtocol::Dlt64597 => ("dlt645_97", "./resources/protocolconfig/DLT645_97.xml"),
+            BuiltinProtocol::Dlt698 => ("dlt698", "./resources/protocolconfig/DLT698.xml"),
+            BuiltinProtocol::Gdw13761 => ("gdw1376_1", "./resources/protocolconfig/QGDW1376_1.xml"),
+            BuiltinProtocol::Gdw13762 => ("gdw1376_2", "./resources/protocolconfig/QGDW1376_2.xml"),
         }
-    };
-    pub static ref GLOBAL_MS: Result<QframeConfig, Arc<dyn std::error::Error + Send + Sync>> = {
+    }
+
+    /// 按store中应用配置的选择加载协议配置，未选择时desktop使用默认路径，其他情况使用内嵌内容
+    pub fn load(
+        &self,
+        store: &dyn ConfigStore,
+    ) -> Result<QframeConfig, Arc<dyn std::error::Error + Send + Sync>> {
         let config = QframeConfig::new();
-        let xml = include_str!("../../../../public/config/TASK_MS.xml");
-        match config.load_from_str(xml) {
-            Ok(_) => { info!("task_ms XML (embedded) 加载成功"); Ok(config) }
-            Err(e) => { info!("task_ms XML (embedded) 加载失败: {}", e); Err(e) }
+        let (key, default_path) = self.setting();
+        let result = match select_protocol_file(store, key, default_path) {
+            ProtocolFileSource::Content(xml) => {
+                info!("{} XML 使用应用配置中保存的内容", self.label());
+                config.load_from_str(&xml)
+            }
+            #[cfg(feature = "desktop")]
+            ProtocolFileSource::Path(path) => {
+                info!("{} XML 路径: {}", self.label(), path);
+                config.load(Path::new(&path))
+            }
+            #[cfg(not(feature = "desktop"))]
+            ProtocolFileSource::Path(_) => config.load_from_str(self.embedded()),
+            ProtocolFileSource::Embedded => config.load_from_str(self.embedded()),
+        };
+        match result {
+            Ok(_) => {
+                info!("{} XML 加载成功", self.label());
+                Ok(config)
+            }
+            Err(e) => {
+                info!("{} XML 加载失败: {}", self.label(), e);
+                Err(e)
+            }
         }
-    };
+    }
 }
 
 pub struct ProtocolConfigManager;
@@ -920,84 +2010,7 @@ impl ProtocolConfigManager {
         region: &str,
         dir: Option<u8>,
     ) -> Option<XmlElement> {
-        let find_protocol = protocol.to_uppercase();
-
-        // 如果协议包含逗号，尝试每个协议
-        if find_protocol.contains(',') {
-            for single_protocol in find_protocol.split(',').map(|s| s.trim()) {
-                if let Some(result) = match single_protocol {
-                    p if p.contains("CSG13") => {
-                        GLOBAL_CSG13
-                            .as_ref()
-                            .ok()?
-                            .get_item(data_item_id, protocol, region, dir)
-                    }
-                    p if p.contains("DLT/645") => {
-                        GLOBAL_645
-                            .as_ref()
-                            .ok()?
-                            .get_item(data_item_id, protocol, region, dir)
-                    }
-                    p if p.contains("CSG16") => {
-                        GLOBAL_CSG16
-                            .as_ref()
-                            .ok()?
-                            .get_item(data_item_id, protocol, region, dir)
-                    }
-                    p if p.contains("MOUDLE") => {
-                        GLOBAL_Moudle
-                            .as_ref()
-                            .ok()?
-                            .get_item(data_item_id, protocol, region, dir)
-                    }
-                    p if p.contains("MS") => {
-                        GLOBAL_MS
-                            .as_ref()
-                            .ok()?
-                            .get_item(data_item_id, protocol, region, dir)
-                    }
-                    _ => None,
-                } {
-                    return Some(result);
-                }
-            }
-            None
-        } else {
-            // 单个协议的情况保持不变
-            match find_protocol.as_str() {
-                protocol if protocol.contains("CSG13") => {
-                    GLOBAL_CSG13
-                        .as_ref()
-                        .ok()?
-                        .get_item(data_item_id, protocol, region, dir)
-                }
-                protocol if protocol.contains("DLT/645") => {
-                    GLOBAL_645
-                        .as_ref()
-                        .ok()?
-                        .get_item(data_item_id, protocol, region, dir)
-                }
-                protocol if protocol.contains("CSG16") => {
-                    GLOBAL_CSG16
-                        .as_ref()
-                        .ok()?
-                        .get_item(data_item_id, protocol, region, dir)
-                }
-                protocol if protocol.contains("MOUDLE") => {
-                    GLOBAL_Moudle
-                        .as_ref()
-                        .ok()?
-                        .get_item(data_item_id, protocol, region, dir)
-                }
-                protocol if protocol.contains("MS") => {
-                    GLOBAL_MS
-                        .as_ref()
-                        .ok()?
-                        .get_item(data_item_id, protocol, region, dir)
-                }
-                _ => None,
-            }
-        }
+        Analyzer::global().get_config_xml(data_item_id, protocol, region, dir)
     }
 
     pub fn get_template_element(
@@ -1006,31 +2019,7 @@ impl ProtocolConfigManager {
         region: &str,
         dir: Option<u8>,
     ) -> Option<XmlElement> {
-        let find_protocol = protocol.to_uppercase();
-
-        match find_protocol.as_str() {
-            protocol if protocol.contains("CSG13") => GLOBAL_CSG13
-                .as_ref()
-                .ok()?
-                .get_item(template, protocol, region, dir),
-            protocol if protocol.contains("DLT/645") => GLOBAL_645
-                .as_ref()
-                .ok()?
-                .get_item(template, protocol, region, dir),
-            protocol if protocol.contains("CSG16") => GLOBAL_CSG16
-                .as_ref()
-                .ok()?
-                .get_item(template, protocol, region, dir),
-            protocol if protocol.contains("MOUDLE") => GLOBAL_Moudle
-                .as_ref()
-                .ok()?
-                .get_item(template, protocol, region, dir),
-            protocol if protocol.contains("MS") => GLOBAL_MS
-                .as_ref()
-                .ok()?
-                .get_item(template, protocol, region, dir),
-            _ => None,
-        }
+        Analyzer::global().get_template_element(template, protocol, region, dir)
     }
 
     pub fn update_element(
@@ -1044,7 +2033,7 @@ impl ProtocolConfigManager {
         let region = if let Some(region) = region {
             region
         } else {
-            "南网"
+            DEFAULT_REGION
         };
         let dir = if let Some(dir) = dir {
             Some(dir.parse::<u8>().unwrap())
@@ -1052,10 +2041,25 @@ impl ProtocolConfigManager {
             None
         };
         if let Some(mut current_element) =
-            ProtocolConfigManager::get_config_xml(item, protocol, &region, dir)
+            ProtocolConfigManager::get_config_xml(item, protocol, region, dir)
         {
-            current_element.update_child(element);
-            Ok(())
+            if element.get_attribute("id") == Some(item) {
+                current_element = element.clone();
+            } else if !current_element.update_child(element) {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::NotFound,
+                    "Child element not found",
+                ));
+            }
+            let target = ItemLocator {
+                id: item.clone(),
+                protocol: protocol.to_string(),
+                region: region.to_string(),
+                dir,
+            };
+            Self::get_protocol_config(protocol)
+                .and_then(|config| config.update_item(&target, &current_element))
+                .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e.to_string()))
         } else {
             Err(std::io::Error::new(
                 std::io::ErrorKind::NotFound,
@@ -1064,145 +2068,664 @@ impl ProtocolConfigManager {
         }
     }
 
-    pub fn update_protocol_xmlconfig(protocol: &str, content: &str) -> Result<(), Box<dyn std::error::Error>> {
-        let find_protocol = protocol.to_uppercase();
-        match find_protocol.as_str() {
-            protocol if protocol.contains("CSG13") => {
-                GLOBAL_CSG13
-                    .as_ref()
-                    .map_err(|e| format!("CSG13 global config initialization failed: {}", e))?
-                    .load_from_str(content)
-                    .map_err(|e| Box::new(e) as Box<dyn std::error::Error>)?;
-                Ok(())
-            }
-            protocol if protocol.contains("DLT/645") => {
-                GLOBAL_645
-                    .as_ref()
-                    .map_err(|e| format!("DLT/645 global config initialization failed: {}", e))?
-                    .load_from_str(content)
-                    .map_err(|e| Box::new(e) as Box<dyn std::error::Error>)?;
-                Ok(())
-            }
-            protocol if protocol.contains("CSG16") => {
-                GLOBAL_CSG16
-                    .as_ref()
-                    .map_err(|e| format!("CSG16 global config initialization failed: {}", e))?
-                    .load_from_str(content)
-                    .map_err(|e| Box::new(e) as Box<dyn std::error::Error>)?;
-                Ok(())
-            }
-            protocol if protocol.contains("MODULE") => {
-                GLOBAL_Moudle
-                    .as_ref()
-                    .map_err(|e| format!("MODULE global config initialization failed: {}", e))?
-                    .load_from_str(content)
-                    .map_err(|e| Box::new(e) as Box<dyn std::error::Error>)?;
-                Ok(())
-            }
-            protocol if protocol.contains("MS") => {
-                GLOBAL_MS
-                    .as_ref()
-                    .map_err(|e| format!("MS global config initialization failed: {}", e))?
-                    .load_from_str(content)
-                    .map_err(|e| Box::new(e) as Box<dyn std::error::Error>)?;
-                Ok(())
-            }
-            _ => Err(format!("Unsupported protocol: {}", protocol).into()),
-        }
+    pub fn update_protocol_xmlconfig(
+        protocol: &str,
+        content: &str,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        Self::get_protocol_config(protocol)?
+            .load_from_str(content)
+            .map_err(|e| Box::new(e) as Box<dyn std::error::Error>)?;
+        Ok(())
     }
 
     pub fn reset_protocol_xmlconfig(protocol: &str) -> Result<(), Box<dyn std::error::Error>> {
-        let find_protocol = protocol.to_uppercase();
-        match find_protocol.as_str() {
-            protocol if protocol.contains("CSG13") => {
-                let xml = include_str!("../../../../public/config/CSG13.xml");
-                GLOBAL_CSG13
-                    .as_ref()
-                    .map_err(|e| format!("CSG13 global config initialization failed: {}", e))?
-                    .load_from_str(xml)
-                    .map_err(|e| Box::new(e) as Box<dyn std::error::Error>)?;
-                Ok(())
-            }
-            protocol if protocol.contains("DLT/645") => {
-                let xml = include_str!("../../../../public/config/DLT645.xml");
-                GLOBAL_645
-                    .as_ref()
-                    .map_err(|e| format!("DLT/645 global config initialization failed: {}", e))?
-                    .load_from_str(xml)
-                    .map_err(|e| Box::new(e) as Box<dyn std::error::Error>)?;
-                Ok(())
-            }
-            protocol if protocol.contains("CSG16") => {
-                let xml = include_str!("../../../../public/config/CSG16.xml");
-                GLOBAL_CSG16
-                    .as_ref()
-                    .map_err(|e| format!("CSG16 global config initialization failed: {}", e))?
-                    .load_from_str(xml)
-                    .map_err(|e| Box::new(e) as Box<dyn std::error::Error>)?;
-                Ok(())
-            }
-            protocol if protocol.contains("MODULE") => {
-                let xml = include_str!("../../../../public/config/MOUDLE.xml");
-                GLOBAL_Moudle
-                    .as_ref()
-                    .map_err(|e| format!("MODULE global config initialization failed: {}", e))?
-                    .load_from_str(xml)
-                    .map_err(|e| Box::new(e) as Box<dyn std::error::Error>)?;
-                Ok(())
-            }
-            protocol if protocol.contains("MS") => {
-                let xml = include_str!("../../../../public/config/TASK_MS.xml");
-                GLOBAL_MS
-                    .as_ref()
-                    .map_err(|e| format!("MS global config initialization failed: {}", e))?
-                    .load_from_str(xml)
-                    .map_err(|e| Box::new(e) as Box<dyn std::error::Error>)?;
-                Ok(())
-            }
-            _ => Err(format!("Unsupported protocol: {}", protocol).into()),
+        let builtin = BuiltinProtocol::from_name(protocol)
+            .ok_or_else(|| format!("Unsupported protocol: {}", protocol))?;
+        Self::get_protocol_config(protocol)?
+            .load_from_str(builtin.embedded())
+            .map_err(|e| Box::new(e) as Box<dyn std::error::Error>)?;
+        Ok(())
+    }
+
+    pub async fn get_config_all_itme(
+        protocol: &str,
+    ) -> Result<Vec<ItemConfigList>, Box<dyn std::error::Error>> {
+        let config = Self::get_protocol_config(protocol)?;
+        let items = config.get_all_item().await;
+        Ok(items)
+    }
+
+    // 默认解析器实例中的协议配置
+    fn get_protocol_config(
+        protocol: &str,
+    ) -> Result<Arc<QframeConfig>, Box<dyn std::error::Error>> {
+        Analyzer::global().config(protocol)
+    }
+
+    pub fn get_cache_stats(protocol: &str) -> Result<CacheStats, Box<dyn std::error::Error>> {
+        Ok(Self::get_protocol_config(protocol)?.cache_stats())
+    }
+
+    pub fn add_overlay(
+        protocol: &str,
+        name: &str,
+        content: &str,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        Self::get_protocol_config(protocol)?.add_overlay(name, content)
+    }
+
+    pub fn remove_overlay(protocol: &str, name: &str) -> Result<bool, Box<dyn std::error::Error>> {
+        Ok(Self::get_protocol_config(protocol)?.remove_overlay(name))
+    }
+
+    pub fn discard_edits(protocol: &str) -> Result<bool, Box<dyn std::error::Error>> {
+        Ok(Self::get_protocol_config(protocol)?.discard_edits())
+    }
+
+    pub fn get_overlay_names(protocol: &str) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+        Ok(Self::get_protocol_config(protocol)?.overlay_names())
+    }
+
+    pub fn get_regions(protocol: &str) -> Result<Vec<Region>, Box<dyn std::error::Error>> {
+        Ok(Self::get_protocol_config(protocol)?.regions())
+    }
+
+    /// 内置功能码表加上配置中声明的功能码
+    pub fn get_afn_table(protocol: &str) -> Result<Vec<AfnEntry>, Box<dyn std::error::Error>> {
+        Analyzer::global().get_afn_table(protocol)
+    }
+
+    /// 查找功能码定义，协议配置不可用时只查内置功能码表
+    pub fn find_afn(
+        protocol: &str,
+        code: u8,
+        kind: Option<u8>,
+        item: Option<&str>,
+    ) -> Option<AfnEntry> {
+        Analyzer::global().find_afn(protocol, code, kind, item)
+    }
+
+    pub fn get_item_layers(protocol: &str) -> Result<Vec<ItemLayer>, Box<dyn std::error::Error>> {
+        Ok(Self::get_protocol_config(protocol)?.item_layers())
+    }
+
+    pub fn get_item_layer(
+        item: &str,
+        protocol: &str,
+        region: &str,
+        dir: Option<u8>,
+    ) -> Result<Option<String>, Box<dyn std::error::Error>> {
+        Ok(Self::get_protocol_config(protocol)?.get_item_layer(item, protocol, region, dir))
+    }
+
+    pub fn add_item(
+        protocol: &str,
+        parent: Option<&ItemLocator>,
+        position: Option<usize>,
+        element: &XmlElement,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        Self::get_protocol_config(protocol)?.add_item(parent, position, element)
+    }
+
+    pub fn update_item(
+        protocol: &str,
+        target: &ItemLocator,
+        element: &XmlElement,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        Self::get_protocol_config(protocol)?.update_item(target, element)
+    }
+
+    pub fn delete_item(
+        protocol: &str,
+        target: &ItemLocator,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        Self::get_protocol_config(protocol)?.delete_item(target)
+    }
+
+    pub fn move_item(
+        protocol: &str,
+        target: &ItemLocator,
+        parent: Option<&ItemLocator>,
+        position: Option<usize>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        Self::get_protocol_config(protocol)?.move_item(target, parent, position)
+    }
+
+    pub fn export_xml(protocol: &str) -> Result<String, Box<dyn std::error::Error>> {
+        Self::get_protocol_config(protocol)?
+            .to_xml_string()
+            .ok_or_else(|| format!("{} config not loaded", protocol).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BASE: &str = r#"<config>
+    <dataItem id="E0000001" protocol="csg13" region="南网"><name>基础1</name><length>1</length></dataItem>
+    <dataItem id="E0000002" protocol="csg13" region="南网"><name>基础2</name><length>1</length></dataItem>
+</config>"#;
+    const OVERLAY: &str = r#"<config protocol="csg13" region="南网">
+    <dataItem id="E0000001"><name>叠加1</name><length>1</length></dataItem>
+    <dataItem id="E0000003"><name>叠加3</name><length>1</length></dataItem>
+</config>"#;
+
+    fn name(config: &QframeConfig, id: &str) -> Option<String> {
+        config
+            .get_item(id, "csg13", "南网", None)
+            .and_then(|item| item.get_child_text("name"))
+    }
+
+    fn locator(id: &str) -> ItemLocator {
+        ItemLocator {
+            id: id.to_string(),
+            protocol: "csg13".to_string(),
+            region: "南网".to_string(),
+            dir: None,
         }
     }
 
-    pub async fn get_config_all_itme(protocol: &str) -> Result<Vec<ItemConfigList>, Box<dyn std::error::Error>> {
-        let find_protocol = protocol.to_uppercase();
-            
-        match find_protocol.as_str() {
-            protocol if protocol.contains("CSG13") => {
-                let config = GLOBAL_CSG13
-                    .as_ref()
-                    .map_err(|e| format!("CSG13 global config initialization failed: {}", e))?;
-                let items = config.get_all_item().await;
-                Ok(items)
-            }
-            protocol if protocol.contains("DLT/645") => {
-                let config = GLOBAL_645
-                    .as_ref()
-                    .map_err(|e| format!("DLT/645 global config initialization failed: {}", e))?;
-                let items = config.get_all_item().await;
-                Ok(items)
-            }
-            protocol if protocol.contains("CSG16") => {
-                let config = GLOBAL_CSG16
-                    .as_ref()
-                    .map_err(|e| format!("CSG16 global config initialization failed: {}", e))?;
-                let items = config.get_all_item().await;
-                Ok(items)
-            }
-            protocol if protocol.contains("MODULE") => {
-                let config = GLOBAL_Moudle
-                    .as_ref()
-                    .map_err(|e| format!("MODULE global config initialization failed: {}", e))?;
-                let items = config.get_all_item().await;
-                Ok(items)
-            }
-            protocol if protocol.contains("MS") => {
-                let config = GLOBAL_MS
-                    .as_ref()
-                    .map_err(|e| format!("MS global config initialization failed: {}", e))?;
-                let items = config.get_all_item().await;
-                Ok(items)
-            }
-            _ => Err(format!("Unsupported protocol: {}", protocol).into()),
+    #[test]
+    fn edits_survive_remerge() {
+        let config = QframeConfig::new();
+        config.load_from_str(BASE).unwrap();
+        let item = QframeConfig::parse_xml_str(
+            r#"<dataItem id="E0000001" protocol="csg13" region="南网"><name>修改1</name><length>1</length></dataItem>"#,
+        )
+        .unwrap();
+        config.update_item(&locator("E0000001"), &item).unwrap();
+        config.delete_item(&locator("E0000002")).unwrap();
+
+        // 修改层在所有叠加层之上
+        config.add_overlay("o1", OVERLAY).unwrap();
+        assert_eq!(name(&config, "E0000001").as_deref(), Some("修改1"));
+        assert_eq!(name(&config, "E0000002"), None);
+        assert_eq!(name(&config, "E0000003").as_deref(), Some("叠加3"));
+        assert_eq!(
+            config
+                .get_item_layer("E0000001", "csg13", "南网", None)
+                .as_deref(),
+            Some(EDIT_LAYER)
+        );
+
+        assert!(config.remove_overlay("o1"));
+        config.load_from_str(BASE).unwrap();
+        assert_eq!(name(&config, "E0000001").as_deref(), Some("修改1"));
+        assert_eq!(name(&config, "E0000002"), None);
+
+        assert!(config.discard_edits());
+        assert!(!config.discard_edits());
+        assert_eq!(name(&config, "E0000001").as_deref(), Some("基础1"));
+        assert_eq!(name(&config, "E0000002").as_deref(), Some("基础2"));
+        assert!(config.add_overlay(EDIT_LAYER, OVERLAY).is_err());
+    }
+
+    #[test]
+    fn saved_config_excludes_overlays() {
+        let config = QframeConfig::new();
+        config.load_from_str(BASE).unwrap();
+        config.add_overlay("o1", OVERLAY).unwrap();
+        let item = QframeConfig::parse_xml_str(
+            r#"<dataItem id="E0000002" protocol="csg13" region="南网"><name>修改2</name><length>1</length></dataItem>"#,
+        )
+        .unwrap();
+        config.update_item(&locator("E0000002"), &item).unwrap();
+
+        // 保存的内容只有基础配置和修改层
+        let saved = config.to_xml_string().unwrap();
+        assert!(saved.contains("基础1"));
+        assert!(saved.contains("修改2"));
+        assert!(!saved.contains("叠加"));
+
+        // 重新加载保存的配置后叠加层仍可单独移除
+        let reloaded = QframeConfig::new();
+        reloaded.load_from_str(&saved).unwrap();
+        reloaded.add_overlay("o1", OVERLAY).unwrap();
+        assert_eq!(name(&reloaded, "E0000001").as_deref(), Some("叠加1"));
+        assert!(reloaded.remove_overlay("o1"));
+        assert_eq!(name(&reloaded, "E0000001").as_deref(), Some("基础1"));
+        assert_eq!(name(&reloaded, "E0000002").as_deref(), Some("修改2"));
+        assert_eq!(name(&reloaded, "E0000003"), None);
+    }
+
+    const PRECEDENCE: &str = r#"<config>
+    <regions>
+        <region name="深圳" parent="广东"/>
+    </regions>
+    <dataItem id="E0000010" protocol="csg13"><name>未指定区域</name><length>1</length></dataItem>
+    <dataItem id="E0000010" protocol="csg13" region="南网"><name>南网</name><length>1</length></dataItem>
+    <dataItem id="E0000010" protocol="csg13" region="广东"><name>广东</name><length>1</length></dataItem>
+    <dataItem id="E0000011" protocol="csg13"><name>未指定区域</name><length>1</length></dataItem>
+    <dataItem id="E0000012" protocol="csg13" region="南网" dir="1"><name>南网上行</name><length>1</length></dataItem>
+    <dataItem id="E0000012" protocol="csg13" region="广东"><name>广东</name><length>1</length></dataItem>
+    <dataItem id="E0000013" protocol="csg13" region="南网" dir="1"><name>上行</name><length>1</length></dataItem>
+    <dataItem id="E0000013" protocol="csg13" region="南网"><name>通用</name><length>1</length></dataItem>
+    <dataItem id="E0000014" protocol="csg13" region="南网"><name>靠前</name><length>1</length></dataItem>
+    <dataItem id="E0000014" protocol="csg13" region="南网"><name>靠后</name><length>1</length></dataItem>
+</config>"#;
+
+    fn find(tree: &XmlTree, id: &str, region: &str, dir: Option<u8>) -> Option<String> {
+        let node = tree.find_by_id(id, "csg13", region, dir)?;
+        let index = tree
+            .find_all_by_id(id)
+            .into_iter()
+            .find(|&index| std::ptr::eq(tree.get_node(index).unwrap(), node))?;
+        tree.get_child_text(index, "name")
+    }
+
+    #[test]
+    fn exact_region_beats_fallback() {
+        let config = QframeConfig::new();
+        config.load_from_str(PRECEDENCE).unwrap();
+        let tree = config.get_config();
+        let tree = tree.as_ref().unwrap();
+
+        assert_eq!(
+            find(tree, "E0000010", "广东", None).as_deref(),
+            Some("广东")
+        );
+        // 深圳->广东->南网，最近的上级地区优先
+        assert_eq!(
+            find(tree, "E0000010", "深圳", None).as_deref(),
+            Some("广东")
+        );
+        assert_eq!(
+            find(tree, "E0000010", "云南", None).as_deref(),
+            Some("南网")
+        );
+        assert_eq!(
+            find(tree, "E0000010", "南网", None).as_deref(),
+            Some("南网")
+        );
+    }
+
+    #[test]
+    fn unregioned_is_last_resort() {
+        let config = QframeConfig::new();
+        config.load_from_str(PRECEDENCE).unwrap();
+        let tree = config.get_config();
+        let tree = tree.as_ref().unwrap();
+
+        assert_eq!(
+            find(tree, "E0000011", "广东", None).as_deref(),
+            Some("未指定区域")
+        );
+        assert!(find(tree, "E0000011", "广东", Some(0)).is_some());
+        assert!(find(tree, "E0000011", "云南", None).is_some());
+    }
+
+    #[test]
+    fn region_beats_dir() {
+        let config = QframeConfig::new();
+        config.load_from_str(PRECEDENCE).unwrap();
+        let tree = config.get_config();
+        let tree = tree.as_ref().unwrap();
+
+        // 区域精确匹配的通用定义优先于上级地区指定dir的定义
+        assert_eq!(
+            find(tree, "E0000012", "广东", Some(1)).as_deref(),
+            Some("广东")
+        );
+        assert_eq!(
+            find(tree, "E0000012", "云南", Some(1)).as_deref(),
+            Some("南网上行")
+        );
+        assert_eq!(find(tree, "E0000012", "云南", Some(0)), None);
+    }
+
+    #[test]
+    fn dir_specific_beats_generic() {
+        let config = QframeConfig::new();
+        config.load_from_str(PRECEDENCE).unwrap();
+        let tree = config.get_config();
+        let tree = tree.as_ref().unwrap();
+
+        assert_eq!(
+            find(tree, "E0000013", "南网", Some(1)).as_deref(),
+            Some("上行")
+        );
+        assert_eq!(
+            find(tree, "E0000013", "南网", Some(0)).as_deref(),
+            Some("通用")
+        );
+        // 未指定方向时不区分dir，按文件顺序取靠后的
+        assert_eq!(
+            find(tree, "E0000013", "南网", None).as_deref(),
+            Some("通用")
+        );
+        assert_eq!(
+            find(tree, "E0000014", "南网", None).as_deref(),
+            Some("靠后")
+        );
+    }
+
+    #[test]
+    fn edits_stay_in_exact_scope() {
+        let config = QframeConfig::new();
+        config.load_from_str(PRECEDENCE).unwrap();
+
+        // 上级地区的定义不算重叠，广东下可以新增南网已有的数据项
+        let item = QframeConfig::parse_xml_str(
+            r#"<dataItem id="E0000013" protocol="csg13" region="广东"><name>广东</name><length>1</length></dataItem>"#,
+        )
+        .unwrap();
+        config.add_item(None, None, &item).unwrap();
+        assert_eq!(
+            config
+                .get_item("E0000013", "csg13", "深圳", Some(0))
+                .and_then(|item| item.get_child_text("name"))
+                .as_deref(),
+            Some("广东")
+        );
+
+        // 修改时只定位本区域的定义
+        let mut missing = locator("E0000010");
+        missing.region = "云南".to_string();
+        assert!(config.delete_item(&missing).is_err());
+    }
+
+    fn cached(value: &str) -> Option<XmlElement> {
+        QframeConfig::parse_xml_str(&format!("<{}/>", value)).ok()
+    }
+
+    #[test]
+    fn cache_evicts_least_recently_used() {
+        let mut cache = Cache::with_capacity(2);
+        cache.insert("a".to_string(), cached("a"));
+        cache.insert("b".to_string(), cached("b"));
+        // 访问a后b成为最久未使用的
+        assert!(cache.get("a").is_some());
+        cache.insert("c".to_string(), cached("c"));
+        assert!(cache.get("b").is_none());
+        assert!(cache.get("a").is_some());
+        assert!(cache.get("c").is_some());
+
+        // 重复插入只刷新使用顺序，不计淘汰
+        cache.insert("a".to_string(), cached("a2"));
+        cache.insert("d".to_string(), cached("d"));
+        assert!(cache.get("c").is_none());
+        assert_eq!(cache.get("a").unwrap().unwrap().name, "a2");
+
+        // 缩小容量时从最久未使用的开始淘汰
+        cache.set_capacity(1);
+        assert!(cache.get("d").is_none());
+        assert!(cache.get("a").is_some());
+
+        let stats = cache.stats();
+        assert_eq!(stats.evictions, 3);
+        assert_eq!(stats.size, 1);
+        assert_eq!(stats.capacity, 1);
+    }
+
+    #[test]
+    fn cache_counts_hits_and_misses() {
+        let config = QframeConfig::new();
+        config.load_from_str(BASE).unwrap();
+        assert_eq!(name(&config, "E0000001").as_deref(), Some("基础1"));
+        assert_eq!(name(&config, "E0000001").as_deref(), Some("基础1"));
+        assert_eq!(name(&config, "E0000002").as_deref(), Some("基础2"));
+        // 未找到的数据项不缓存，每次都计为未命中
+        assert_eq!(name(&config, "E0000009"), None);
+        assert_eq!(name(&config, "E0000009"), None);
+
+        let stats = config.cache_stats();
+        assert_eq!(stats.hits, 1);
+        assert_eq!(stats.misses, 4);
+        assert_eq!(stats.size, 2);
+        assert_eq!(stats.evictions, 0);
+
+        // 容量为0时不缓存
+        config.set_cache_capacity(0);
+        assert_eq!(name(&config, "E0000001").as_deref(), Some("基础1"));
+        let stats = config.cache_stats();
+        assert_eq!(stats.size, 0);
+        assert_eq!(stats.evictions, 2);
+    }
+
+    #[test]
+    fn reload_invalidates_cache() {
+        let config = QframeConfig::new();
+        config.load_from_str(BASE).unwrap();
+        assert_eq!(name(&config, "E0000001").as_deref(), Some("基础1"));
+        assert_eq!(config.cache_stats().size, 1);
+
+        config
+            .load_from_str(&BASE.replace("基础1", "重新加载1"))
+            .unwrap();
+        assert_eq!(config.cache_stats().size, 0);
+        assert_eq!(name(&config, "E0000001").as_deref(), Some("重新加载1"));
+
+        let removed = BASE.replace(
+            r#"<dataItem id="E0000001" protocol="csg13" region="南网"><name>基础1</name><length>1</length></dataItem>"#,
+            "",
+        );
+        config.load_from_str(&removed).unwrap();
+        assert_eq!(name(&config, "E0000001"), None);
+    }
+
+    const EDITABLE: &str = r#"<config>
+    <!-- 测试数据项 -->
+    <dataItem region="南网" id="E0000101" protocol="csg13">
+        <name>父项 &amp; 子项</name>
+        <length>2</length>
+        <splitByLength>
+            <name>子项1</name>
+            <length>1</length>
+        </splitByLength>
+        <splitByLength>
+            <name>子项2</name>
+            <length>1</length>
+        </splitByLength>
+    </dataItem>
+    <dataItem id="E0000102" protocol="csg13" region="南网"><name>数据项2</name><length>1</length></dataItem>
+</config>"#;
+
+    #[test]
+    fn serializer_round_trip() {
+        let config = QframeConfig::new();
+        config.load_from_str(EDITABLE).unwrap();
+        let xml = config.to_xml_string().unwrap();
+        // 注释、属性顺序和转义字符原样写回
+        assert!(xml.contains("<!-- 测试数据项 -->"));
+        assert!(xml.contains(r#"<dataItem region="南网" id="E0000101" protocol="csg13">"#));
+        assert!(xml.contains("<name>父项 &amp; 子项</name>"));
+
+        let reloaded = QframeConfig::new();
+        reloaded.load_from_str(&xml).unwrap();
+        assert_eq!(reloaded.to_xml_string().unwrap(), xml);
+        assert_eq!(name(&reloaded, "E0000101").as_deref(), Some("父项 & 子项"));
+    }
+
+    #[test]
+    fn crud_round_trip() {
+        let config = QframeConfig::new();
+        config.load_from_str(EDITABLE).unwrap();
+
+        let added = QframeConfig::parse_xml_str(
+            r#"<dataItem id="E0000103" protocol="csg13" region="南网"><name>新增3</name><length>1</length></dataItem>"#,
+        )
+        .unwrap();
+        config.add_item(None, Some(0), &added).unwrap();
+        let updated = QframeConfig::parse_xml_str(
+            r#"<dataItem id="E0000102" protocol="csg13" region="南网"><name>修改2</name><length>1</length></dataItem>"#,
+        )
+        .unwrap();
+        config.update_item(&locator("E0000102"), &updated).unwrap();
+        // 移动到父项下后继承父项的协议和区域
+        config
+            .move_item(&locator("E0000103"), Some(&locator("E0000101")), None)
+            .unwrap();
+        config.delete_item(&locator("E0000102")).unwrap();
+
+        let xml = config.to_xml_string().unwrap();
+        assert!(xml.contains("<dataItem id=\"E0000103\">"));
+        assert!(!xml.contains("E0000102"));
+
+        let reloaded = QframeConfig::new();
+        reloaded.load_from_str(&xml).unwrap();
+        assert_eq!(name(&reloaded, "E0000103").as_deref(), Some("新增3"));
+        assert_eq!(name(&reloaded, "E0000102"), None);
+        let tree = reloaded.get_config();
+        let tree = tree.as_ref().unwrap();
+        let parent = tree.find_all_by_id("E0000101")[0];
+        let child = tree.find_all_by_id("E0000103")[0];
+        assert!(tree.get_node_path(child).contains(&parent));
+    }
+
+    #[test]
+    fn rejected_edits_leave_config_unchanged() {
+        let config = QframeConfig::new();
+        config.load_from_str(EDITABLE).unwrap();
+        let before = config.to_xml_string().unwrap();
+
+        // 与已有顶层定义作用域重叠
+        let duplicate = QframeConfig::parse_xml_str(
+            r#"<dataItem id="E0000102" protocol="csg13" region="南网"><name>重复</name><length>1</length></dataItem>"#,
+        )
+        .unwrap();
+        assert!(config.add_item(None, None, &duplicate).is_err());
+
+        // 声明长度与子项长度之和不一致，校验失败
+        let invalid = QframeConfig::parse_xml_str(
+            r#"<dataItem id="E0000101" protocol="csg13" region="南网"><name>父项</name><length>3</length><splitByLength><name>子项1</name><length>1</length></splitByLength></dataItem>"#,
+        )
+        .unwrap();
+        assert!(config.update_item(&locator("E0000101"), &invalid).is_err());
+
+        // 不能移动到自身的子项下
+        let target = locator("E0000101");
+        assert!(config.move_item(&target, Some(&target), None).is_err());
+        assert!(config.delete_item(&locator("E0000109")).is_err());
+
+        assert_eq!(config.to_xml_string().unwrap(), before);
+    }
+
+    const REGIONS: &str = r#"<config>
+    <regions>
+        <region name="广东" parent="南网"/>
+        <region name="深圳" parent="广东"/>
+        <region name="客户A" parent="深圳"/>
+        <region name="环1" parent="环2"/>
+        <region name="环2" parent="环1"/>
+    </regions>
+    <dataItem id="E0000201" protocol="csg13" region="南网"><name>南网</name><length>1</length></dataItem>
+    <dataItem id="E0000202" protocol="csg13" region="广东,广西"><name>两广</name><length>1</length></dataItem>
+    <dataItem id="E0000203" protocol="csg13" region="云南"><name>云南</name><length>1</length></dataItem>
+</config>"#;
+
+    fn chain(config: &QframeConfig, region: &str) -> Vec<String> {
+        config.region_chain(region)
+    }
+
+    #[test]
+    fn region_chains_follow_declarations() {
+        let config = QframeConfig::new();
+        // 未加载配置时所有地区直接回退到南网
+        assert_eq!(chain(&config, "深圳"), ["深圳", "南网"]);
+
+        config.load_from_str(REGIONS).unwrap();
+        assert_eq!(chain(&config, "客户A"), ["客户A", "深圳", "广东", "南网"]);
+        assert_eq!(chain(&config, "广西"), ["广西", "南网"]);
+        assert_eq!(chain(&config, "南网"), ["南网"]);
+        // 循环继承在回到链上地区时截断
+        assert_eq!(chain(&config, "环1"), ["环1", "环2"]);
+    }
+
+    #[test]
+    fn root_region_declared_per_config() {
+        let config = QframeConfig::new();
+        config
+            .load_from_str(
+                r#"<config>
+    <regions root="国网"><region name="山东" parent="国网"/></regions>
+    <dataItem id="0CF2" protocol="csg13" region="国网"><name>国网</name><length>1</length></dataItem>
+</config>"#,
+            )
+            .unwrap();
+        // 国网是根地区，不再回退到南网，未声明的地区(包括南网)回退到国网
+        assert_eq!(chain(&config, "国网"), ["国网"]);
+        assert_eq!(chain(&config, "南网"), ["南网", "国网"]);
+        assert_eq!(chain(&config, "山东"), ["山东", "国网"]);
+        assert_eq!(
+            config
+                .get_item("0CF2", "csg13", "南网", None)
+                .and_then(|item| item.get_child_text("name"))
+                .as_deref(),
+            Some("国网")
+        );
+        let regions = config.regions();
+        let root = regions.iter().find(|r| r.name == "国网").unwrap();
+        assert_eq!(root.parent, None);
+    }
+
+    #[test]
+    fn lookups_walk_region_chain() {
+        let config = QframeConfig::new();
+        config.load_from_str(REGIONS).unwrap();
+        let lookup = |id: &str, region: &str| {
+            config
+                .get_item(id, "csg13", region, None)
+                .and_then(|item| item.get_child_text("name"))
+        };
+
+        assert_eq!(lookup("E0000201", "客户A").as_deref(), Some("南网"));
+        // 逗号分隔的多地区定义对每个地区及其下级地区生效
+        assert_eq!(lookup("E0000202", "客户A").as_deref(), Some("两广"));
+        assert_eq!(lookup("E0000202", "广西").as_deref(), Some("两广"));
+        assert_eq!(lookup("E0000202", "南网"), None);
+        // 同级地区之间不继承
+        assert_eq!(lookup("E0000203", "云南").as_deref(), Some("云南"));
+        assert_eq!(lookup("E0000203", "深圳"), None);
+        // 循环继承的地区不会回退到南网
+        assert_eq!(lookup("E0000201", "环1"), None);
+    }
+
+    #[test]
+    fn regions_list_items_and_declarations() {
+        let config = QframeConfig::new();
+        config.load_from_str(REGIONS).unwrap();
+        let regions = config.regions();
+        let names: Vec<&str> = regions.iter().map(|r| r.name.as_str()).collect();
+        for name in [
+            "南网", "广东", "广西", "云南", "深圳", "客户A", "环1", "环2",
+        ] {
+            assert!(names.contains(&name), "缺少地区{}", name);
+        }
+
+        let region = |name: &str| regions.iter().find(|r| r.name == name).unwrap();
+        assert!(region("深圳").declared);
+        assert!(!region("广西").declared);
+        assert_eq!(region("广西").parent.as_deref(), Some("南网"));
+        assert_eq!(region("南网").parent, None);
+        assert_eq!(region("客户A").chain, ["客户A", "深圳", "广东", "南网"]);
+    }
+
+    #[test]
+    fn builtin_dlt645_dictionary() {
+        let config = QframeConfig::new();
+        config
+            .load_from_str(BuiltinProtocol::Dlt645.embedded())
+            .unwrap();
+        let lookup = |id: &str| {
+            config
+                .get_item(id, "DLT/645-2007", "南网", None)
+                .and_then(|item| item.get_child_text("name"))
+        };
+        // 每类数据标识各取一项，数据块中的子项也能直接查找
+        for (id, name) in [
+            ("0000FF00", "(当前)组合有功电能数据块"),
+            ("00000000", "(当前)组合有功总电能"),
+            ("01010000", "(当前)正向有功总最大需量及发生时间"),
+            ("02010100", "A相电压"),
+            ("03300000", "编程总次数"),
+            ("04000101", "日期及星期(其中0代表星期天)"),
+            ("05000001", "(上1次)定时冻结时间"),
+            ("06100101", "负荷记录-A相电压"),
+            ("10000001", "失压总次数"),
+        ] {
+            assert_eq!(lookup(id).as_deref(), Some(name), "{}", id);
         }
+        assert_eq!(lookup("FFFFFFFF"), None);
     }
 }
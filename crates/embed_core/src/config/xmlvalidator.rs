@@ -0,0 +1,731 @@
+use std::collections::HashSet;
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::basefunc::length_expr::LengthExpr;
+use crate::basefunc::switch_case::{SwitchCase, CASE_NODE, DEFAULT_NODE, SWITCH_NODE};
+use crate::config::afntable::{AfnEntry, AfnTable, AFN_NODE, AFN_TABLE_NODE};
+use crate::config::xmlconfig::{QframeConfig, XmlTree, COMMENT_NODE, REGIONS_NODE, REGION_NODE};
+
+// 解析器内置的基础类型
+const SIMPLE_TYPES: [&str; 8] = [
+    "BCD", "BIN", "BIN_FF", "ASCII", "PORT", "IP", "BIN_BE", "NORMAL",
+];
+// 解析器内置的复合类型
+const BUILTIN_TYPES: [&str; 5] = ["PN", "ITEM", "FRAME645", "FRAMECSG13", "IPWITHPORT"];
+// 支持小数位的数值类型
+const DECIMAL_TYPES: [&str; 4] = ["BCD", "BIN", "BIN_FF", "BIN_BE"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DiagnosticLevel {
+    Error,
+    Warning,
+}
+
+/// 配置校验结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct XmlDiagnostic {
+    pub level: DiagnosticLevel,
+    pub item_id: Option<String>,
+    pub line: usize,
+    pub message: String,
+}
+
+pub struct XmlConfigValidator;
+
+impl XmlConfigValidator {
+    /// 校验已加载的配置树
+    pub fn validate(tree: &XmlTree) -> Vec<XmlDiagnostic> {
+        let mut diagnostics = Vec::new();
+        if tree.is_empty() {
+            return diagnostics;
+        }
+        let known_ids: HashSet<String> = tree.ids().cloned().collect();
+
+        let mut stack = vec![tree.root()];
+        while let Some(index) = stack.pop() {
+            Self::validate_node(tree, index, &known_ids, &mut diagnostics);
+            stack.extend(tree.get_children(index).iter().rev());
+        }
+        diagnostics.extend(Self::lint_overlaps(tree));
+        diagnostics.sort_by_key(|d| d.line);
+        diagnostics
+    }
+
+    /// 列出作用域(protocol, region, dir)重叠的顶层重复定义
+    ///
+    /// 数据块内嵌的子项与顶层定义同id属于正常情况，不在检查范围内
+    pub fn lint_overlaps(tree: &XmlTree) -> Vec<XmlDiagnostic> {
+        let mut diagnostics = Vec::new();
+        let mut ids: Vec<&String> = tree.ids().collect();
+        ids.sort();
+
+        for id in ids {
+            let nodes: Vec<usize> = tree
+                .find_all_by_id(id)
+                .into_iter()
+                .filter(|&index| tree.get_node(index).is_some_and(|node| node.depth() == 1))
+                .collect();
+            for (i, &first) in nodes.iter().enumerate() {
+                for &second in &nodes[i + 1..] {
+                    if let Some(scope) = Self::overlap_scope(tree, first, second) {
+                        let first_line = tree.get_node(first).map_or(0, |node| node.line());
+                        Self::push(
+                            &mut diagnostics,
+                            tree,
+                            second,
+                            DiagnosticLevel::Warning,
+                            format!(
+                                "数据项[{}]与第{}行的定义作用域重叠({})",
+                                id, first_line, scope
+                            ),
+                        );
+                    }
+                }
+            }
+        }
+        diagnostics
+    }
+
+    fn split_scope(value: Option<&String>) -> Vec<String> {
+        value
+            .map(|v| {
+                v.split(',')
+                    .map(|s| s.trim().to_uppercase())
+                    .filter(|s| !s.is_empty())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    // 两个节点作用域的交集，不重叠时返回None
+    fn overlap_scope(tree: &XmlTree, first: usize, second: usize) -> Option<String> {
+        let first = tree.get_node(first)?;
+        let second = tree.get_node(second)?;
+
+        let protocols: Vec<String> = Self::split_scope(first.get_attribute("protocol"))
+            .into_iter()
+            .filter(|p| Self::split_scope(second.get_attribute("protocol")).contains(p))
+            .collect();
+        let regions: Vec<String> = Self::split_scope(first.get_attribute("region"))
+            .into_iter()
+            .filter(|r| Self::split_scope(second.get_attribute("region")).contains(r))
+            .collect();
+        if protocols.is_empty() || regions.is_empty() {
+            return None;
+        }
+
+        let dir = match (first.get_attribute("dir"), second.get_attribute("dir")) {
+            (Some(a), Some(b)) if a.trim() != b.trim() => return None,
+            (Some(d), _) | (_, Some(d)) => d.trim().to_string(),
+            (None, None) => "*".to_string(),
+        };
+        Some(format!(
+            "protocol={}, region={}, dir={}",
+            protocols.join(","),
+            regions.join(","),
+            dir
+        ))
+    }
+
+    /// 解析并校验XML字符串
+    pub fn validate_str(xml_str: &str) -> Result<Vec<XmlDiagnostic>, String> {
+        let config = QframeConfig::new();
+        config
+            .load_from_str(xml_str)
+            .map_err(|e| format!("XML解析失败: {}", e))?;
+        let tree = config.get_config();
+        Ok(tree.as_ref().map(Self::validate).unwrap_or_default())
+    }
+
+    fn validate_node(
+        tree: &XmlTree,
+        index: usize,
+        known_ids: &HashSet<String>,
+        diagnostics: &mut Vec<XmlDiagnostic>,
+    ) {
+        let node = tree.get_node(index).unwrap();
+        match node.name() {
+            "dataItem" | "splitByLength" | "template" | "item" => {
+                Self::check_length(tree, index, diagnostics);
+                Self::check_decimal(tree, index, diagnostics);
+                Self::check_lengthrule(tree, index, diagnostics);
+            }
+            "type" => Self::check_type(tree, index, known_ids, diagnostics),
+            "splitbit" => Self::check_splitbit(tree, index, diagnostics),
+            REGION_NODE => Self::check_region(tree, index, diagnostics),
+            SWITCH_NODE => Self::check_switch(tree, index, diagnostics),
+            AFN_NODE => Self::check_afn(tree, index, diagnostics),
+            _ => {}
+        }
+    }
+
+    // switch需要可解析的on表达式，case需要value或raw取值，default最多一个
+    fn check_switch(tree: &XmlTree, index: usize, diagnostics: &mut Vec<XmlDiagnostic>) {
+        let node = tree.get_node(index).unwrap();
+        match node.get_attribute("on") {
+            Some(on) => {
+                if let Err(e) = LengthExpr::parse(on).and_then(|expr| expr.check()) {
+                    Self::push(
+                        diagnostics,
+                        tree,
+                        index,
+                        DiagnosticLevel::Error,
+                        format!("switch的on表达式[{}]无法解析: {}", on, e),
+                    );
+                }
+            }
+            None => Self::push(
+                diagnostics,
+                tree,
+                index,
+                DiagnosticLevel::Error,
+                "switch缺少on属性".to_string(),
+            ),
+        }
+
+        let mut default_count = 0;
+        for &child in tree.get_children(index) {
+            let case = tree.get_node(child).unwrap();
+            match case.name() {
+                CASE_NODE => {
+                    let value = case.get_attribute("value");
+                    if value.is_none() && case.get_attribute("raw").is_none() {
+                        Self::push(
+                            diagnostics,
+                            tree,
+                            child,
+                            DiagnosticLevel::Error,
+                            "case缺少value或raw属性".to_string(),
+                        );
+                    }
+                    if let Some(Err(e)) = value.map(|v| SwitchCase::match_value(v, 0)) {
+                        Self::push(diagnostics, tree, child, DiagnosticLevel::Error, e);
+                    }
+                }
+                DEFAULT_NODE => {
+                    default_count += 1;
+                    if default_count > 1 {
+                        Self::push(
+                            diagnostics,
+                            tree,
+                            child,
+                            DiagnosticLevel::Error,
+                            "switch中存在多个default".to_string(),
+                        );
+                    }
+                }
+                COMMENT_NODE => {}
+                other => Self::push(
+                    diagnostics,
+                    tree,
+                    child,
+                    DiagnosticLevel::Warning,
+                    format!("switch中的<{}>不会被解析，只支持case和default", other),
+                ),
+            }
+        }
+    }
+
+    // 功能码需要可解析的code和name，解析方式需要是解析器支持的
+    fn check_afn(tree: &XmlTree, index: usize, diagnostics: &mut Vec<XmlDiagnostic>) {
+        let node = tree.get_node(index).unwrap();
+        let in_table = node
+            .parent()
+            .and_then(|parent| tree.get_node(parent))
+            .is_some_and(|parent| parent.name() == AFN_TABLE_NODE);
+        if !in_table {
+            return;
+        }
+        match AfnEntry::from_node(node) {
+            Ok(entry) => {
+                if let Some(handler) = entry
+                    .handler
+                    .filter(|handler| !AfnTable::is_known_handler(handler))
+                {
+                    Self::push(
+                        diagnostics,
+                        tree,
+                        index,
+                        DiagnosticLevel::Warning,
+                        format!(
+                            "功能码{:02X}的解析方式[{}]不支持，只显示名称",
+                            entry.code, handler
+                        ),
+                    );
+                }
+            }
+            Err(e) => Self::push(diagnostics, tree, index, DiagnosticLevel::Error, e),
+        }
+    }
+
+    // 地区声明需要name和parent属性，且继承链不能成环
+    fn check_region(tree: &XmlTree, index: usize, diagnostics: &mut Vec<XmlDiagnostic>) {
+        let node = tree.get_node(index).unwrap();
+        let in_regions = node
+            .parent()
+            .and_then(|parent| tree.get_node(parent))
+            .is_some_and(|parent| parent.name() == REGIONS_NODE);
+        if !in_regions {
+            return;
+        }
+        let (name, parent) = match (node.get_attribute("name"), node.get_attribute("parent")) {
+            (Some(name), Some(parent)) => (name.trim(), parent.trim()),
+            _ => {
+                Self::push(
+                    diagnostics,
+                    tree,
+                    index,
+                    DiagnosticLevel::Error,
+                    "地区声明缺少name或parent属性".to_string(),
+                );
+                return;
+            }
+        };
+
+        let mut visited = vec![name];
+        let mut current = parent;
+        loop {
+            if visited.contains(&current) {
+                Self::push(
+                    diagnostics,
+                    tree,
+                    index,
+                    DiagnosticLevel::Error,
+                    format!(
+                        "地区[{}]的继承链存在循环: {}->{}",
+                        name,
+                        visited.join("->"),
+                        current
+                    ),
+                );
+                return;
+            }
+            visited.push(current);
+            match tree.region_parent(current) {
+                Some(next) => current = next,
+                None => return,
+            }
+        }
+    }
+
+    fn push(
+        diagnostics: &mut Vec<XmlDiagnostic>,
+        tree: &XmlTree,
+        index: usize,
+        level: DiagnosticLevel,
+        message: String,
+    ) {
+        diagnostics.push(XmlDiagnostic {
+            level,
+            item_id: Self::owner_id(tree, index),
+            line: tree.get_node(index).map_or(0, |node| node.line()),
+            message,
+        });
+    }
+
+    // 向上查找最近的带id节点，bit的id是位范围而不是数据项标识
+    fn owner_id(tree: &XmlTree, index: usize) -> Option<String> {
+        let mut current = Some(index);
+        while let Some(idx) = current {
+            let node = tree.get_node(idx)?;
+            if let Some(id) = node.id().filter(|_| node.name() != "bit") {
+                return Some(id.clone());
+            }
+            current = node.parent();
+        }
+        None
+    }
+
+    fn item_label(tree: &XmlTree, index: usize) -> String {
+        let node = tree.get_node(index).unwrap();
+        match tree.get_child_text(index, "name") {
+            Some(name) => format!("{}[{}]", node.name(), name),
+            None => node.name().to_string(),
+        }
+    }
+
+    fn numeric_length(tree: &XmlTree, index: usize) -> Option<usize> {
+        tree.get_child_text(index, "length")
+            .and_then(|length| length.parse::<usize>().ok())
+    }
+
+    fn check_length(tree: &XmlTree, index: usize, diagnostics: &mut Vec<XmlDiagnostic>) {
+        let length_text = match tree.get_child_text(index, "length") {
+            Some(length) => length,
+            None => return,
+        };
+        let length = match length_text.parse::<usize>() {
+            Ok(length) => length,
+            Err(_) => {
+                if !length_text.eq_ignore_ascii_case("UNKNOWN") {
+                    Self::push(
+                        diagnostics,
+                        tree,
+                        index,
+                        DiagnosticLevel::Error,
+                        format!(
+                            "{}的长度[{}]不是数字或UNKNOWN",
+                            Self::item_label(tree, index),
+                            length_text
+                        ),
+                    );
+                }
+                return;
+            }
+        };
+
+        let sub_items: Vec<usize> = tree
+            .get_children(index)
+            .iter()
+            .cloned()
+            .filter(|&child| {
+                let name = tree.get_node(child).unwrap().name();
+                name == "splitByLength" || name == "dataItem"
+            })
+            .collect();
+        if sub_items.is_empty() {
+            return;
+        }
+        let mut sum = 0;
+        for &child in &sub_items {
+            match Self::numeric_length(tree, child) {
+                Some(child_length) => sum += child_length,
+                // 子项长度不固定时无法校验
+                None => return,
+            }
+        }
+        if sum != length {
+            Self::push(
+                diagnostics,
+                tree,
+                index,
+                DiagnosticLevel::Error,
+                format!(
+                    "{}声明长度{}与子项长度之和{}不一致",
+                    Self::item_label(tree, index),
+                    length,
+                    sum
+                ),
+            );
+        }
+    }
+
+    fn check_type(
+        tree: &XmlTree,
+        index: usize,
+        known_ids: &HashSet<String>,
+        diagnostics: &mut Vec<XmlDiagnostic>,
+    ) {
+        let value = match tree.get_node(index).and_then(|node| node.value()) {
+            Some(value) => value.trim().to_string(),
+            None => return,
+        };
+        if value.is_empty() {
+            return;
+        }
+        if SIMPLE_TYPES.contains(&value.to_uppercase().as_str())
+            || BUILTIN_TYPES.contains(&value.as_str())
+            || known_ids.contains(&value)
+        {
+            return;
+        }
+        Self::push(
+            diagnostics,
+            tree,
+            index,
+            DiagnosticLevel::Error,
+            format!("类型[{}]既不是基础类型也没有对应的模板", value),
+        );
+    }
+
+    fn check_decimal(tree: &XmlTree, index: usize, diagnostics: &mut Vec<XmlDiagnostic>) {
+        let decimal = match tree.get_child_text(index, "decimal") {
+            Some(decimal) => decimal,
+            None => return,
+        };
+        let decimal = match decimal.parse::<usize>() {
+            Ok(decimal) => decimal,
+            Err(_) => {
+                Self::push(
+                    diagnostics,
+                    tree,
+                    index,
+                    DiagnosticLevel::Error,
+                    format!(
+                        "{}的小数位[{}]不是数字",
+                        Self::item_label(tree, index),
+                        decimal
+                    ),
+                );
+                return;
+            }
+        };
+        // 未配置类型时按BCD解析
+        let data_type = tree
+            .get_child_text(index, "type")
+            .unwrap_or_else(|| "BCD".to_string())
+            .to_uppercase();
+        if !DECIMAL_TYPES.contains(&data_type.as_str()) {
+            Self::push(
+                diagnostics,
+                tree,
+                index,
+                DiagnosticLevel::Warning,
+                format!(
+                    "{}的类型[{}]不支持小数位",
+                    Self::item_label(tree, index),
+                    data_type
+                ),
+            );
+            return;
+        }
+        if data_type == "BCD" {
+            if let Some(length) = Self::numeric_length(tree, index) {
+                if decimal > length * 2 {
+                    Self::push(
+                        diagnostics,
+                        tree,
+                        index,
+                        DiagnosticLevel::Error,
+                        format!(
+                            "{}的小数位{}超过BCD位数{}",
+                            Self::item_label(tree, index),
+                            decimal,
+                            length * 2
+                        ),
+                    );
+                }
+            }
+        }
+    }
+
+    fn check_lengthrule(tree: &XmlTree, index: usize, diagnostics: &mut Vec<XmlDiagnostic>) {
+        let rule = match tree.get_child_text(index, "lengthrule") {
+            Some(rule) => rule,
+            None => return,
+        };
+        let range_pattern = Regex::new(r"^RANGE\(([^)]+)\)$").unwrap();
+        if range_pattern.is_match(&rule) {
+            return;
+        }
+        if let Err(e) = LengthExpr::parse(&rule).and_then(|expr| expr.check()) {
+            Self::push(
+                diagnostics,
+                tree,
+                index,
+                DiagnosticLevel::Error,
+                format!(
+                    "{}的长度规则[{}]无法解析: {}",
+                    Self::item_label(tree, index),
+                    rule,
+                    e
+                ),
+            );
+        }
+    }
+
+    fn check_splitbit(tree: &XmlTree, index: usize, diagnostics: &mut Vec<XmlDiagnostic>) {
+        let byte_length = tree
+            .get_node(index)
+            .and_then(|node| node.parent())
+            .and_then(|parent| Self::numeric_length(tree, parent));
+
+        for &child in tree.get_children(index) {
+            let bit = tree.get_node(child).unwrap();
+            if bit.name() != "bit" {
+                continue;
+            }
+            let bit_id = match bit.get_attribute("id") {
+                Some(id) => id.clone(),
+                None => {
+                    Self::push(
+                        diagnostics,
+                        tree,
+                        child,
+                        DiagnosticLevel::Error,
+                        "bit缺少id属性".to_string(),
+                    );
+                    continue;
+                }
+            };
+            let range: Vec<Option<usize>> = bit_id
+                .split('-')
+                .map(|part| part.trim().parse::<usize>().ok())
+                .collect();
+            let (start, end) = match range.as_slice() {
+                [Some(bit)] => (*bit, *bit),
+                [Some(start), Some(end)] => (*start, *end),
+                _ => {
+                    Self::push(
+                        diagnostics,
+                        tree,
+                        child,
+                        DiagnosticLevel::Error,
+                        format!("bit范围[{}]格式错误", bit_id),
+                    );
+                    continue;
+                }
+            };
+            if start > end {
+                Self::push(
+                    diagnostics,
+                    tree,
+                    child,
+                    DiagnosticLevel::Error,
+                    format!("bit范围[{}]起始位大于结束位", bit_id),
+                );
+            }
+            if let Some(byte_length) = byte_length {
+                if end >= byte_length * 8 {
+                    Self::push(
+                        diagnostics,
+                        tree,
+                        child,
+                        DiagnosticLevel::Error,
+                        format!("bit范围[{}]超出{}字节宽度", bit_id, byte_length),
+                    );
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::xmlconfig::BuiltinProtocol;
+
+    // 只有一条诊断时返回它
+    fn single(xml: &str) -> XmlDiagnostic {
+        let mut diagnostics = XmlConfigValidator::validate_str(xml).unwrap();
+        assert_eq!(diagnostics.len(), 1, "{:?}", diagnostics);
+        diagnostics.remove(0)
+    }
+
+    #[test]
+    fn valid_config() {
+        let xml = r#"<config>
+    <dataItem id="E0000001" protocol="csg13">
+        <name>正常</name>
+        <length>3</length>
+        <splitByLength>
+            <name>A</name>
+            <length>2</length>
+            <type>BCD</type>
+            <decimal>2</decimal>
+        </splitByLength>
+        <splitByLength>
+            <name>B</name>
+            <length>1</length>
+            <splitbit>
+                <bit id="0-7"><name>状态</name></bit>
+            </splitbit>
+        </splitByLength>
+    </dataItem>
+</config>"#;
+        assert!(XmlConfigValidator::validate_str(xml).unwrap().is_empty());
+    }
+
+    #[test]
+    fn length_sum_mismatch() {
+        let xml = r#"<config>
+    <dataItem id="E0000002" protocol="csg13">
+        <name>长度不符</name>
+        <length>4</length>
+        <splitByLength><name>A</name><length>1</length></splitByLength>
+        <splitByLength><name>B</name><length>2</length></splitByLength>
+    </dataItem>
+</config>"#;
+        let diagnostic = single(xml);
+        assert_eq!(diagnostic.level, DiagnosticLevel::Error);
+        assert_eq!(diagnostic.item_id.as_deref(), Some("E0000002"));
+        assert_eq!(diagnostic.line, 2);
+        assert!(diagnostic
+            .message
+            .contains("声明长度4与子项长度之和3不一致"));
+    }
+
+    #[test]
+    fn unknown_type() {
+        let xml = r#"<config>
+    <dataItem id="E0000003" protocol="csg13">
+        <name>类型</name>
+        <length>2</length>
+        <type>NO_SUCH_TEMPLATE</type>
+    </dataItem>
+</config>"#;
+        let diagnostic = single(xml);
+        assert_eq!(diagnostic.item_id.as_deref(), Some("E0000003"));
+        assert_eq!(diagnostic.line, 5);
+        assert!(diagnostic.message.contains("NO_SUCH_TEMPLATE"));
+    }
+
+    #[test]
+    fn splitbit_out_of_range() {
+        let xml = r#"<config>
+    <dataItem id="E0000004" protocol="csg13">
+        <name>状态字</name>
+        <length>1</length>
+        <splitbit>
+            <bit id="0-3"><name>低位</name></bit>
+            <bit id="4-8"><name>越界</name></bit>
+        </splitbit>
+    </dataItem>
+</config>"#;
+        let diagnostic = single(xml);
+        assert_eq!(diagnostic.item_id.as_deref(), Some("E0000004"));
+        assert_eq!(diagnostic.line, 7);
+        assert!(diagnostic.message.contains("bit范围[4-8]超出1字节宽度"));
+    }
+
+    #[test]
+    fn decimal_type_mismatch() {
+        let xml = r#"<config>
+    <dataItem id="E0000005" protocol="csg13">
+        <name>文本</name>
+        <length>4</length>
+        <type>ASCII</type>
+        <decimal>2</decimal>
+    </dataItem>
+</config>"#;
+        let diagnostic = single(xml);
+        assert_eq!(diagnostic.level, DiagnosticLevel::Warning);
+        assert_eq!(diagnostic.item_id.as_deref(), Some("E0000005"));
+        assert_eq!(diagnostic.line, 2);
+        assert!(diagnostic.message.contains("类型[ASCII]不支持小数位"));
+    }
+
+    #[test]
+    fn unparsable_lengthrule() {
+        let xml = r#"<config>
+    <dataItem id="E0000006" protocol="csg13">
+        <name>变长</name>
+        <length>UNKNOWN</length>
+        <lengthrule>7 * (count +</lengthrule>
+    </dataItem>
+</config>"#;
+        let diagnostic = single(xml);
+        assert_eq!(diagnostic.item_id.as_deref(), Some("E0000006"));
+        assert_eq!(diagnostic.line, 2);
+        assert!(diagnostic
+            .message
+            .contains("长度规则[7 * (count +]无法解析"));
+    }
+
+    #[test]
+    fn bundled_configs_validate_clean() {
+        let mut errors = Vec::new();
+        for protocol in BuiltinProtocol::ALL {
+            let diagnostics = XmlConfigValidator::validate_str(protocol.embedded()).unwrap();
+            errors.extend(
+                diagnostics
+                    .into_iter()
+                    .filter(|d| d.level == DiagnosticLevel::Error)
+                    .map(|d| (protocol.label(), d)),
+            );
+        }
+        assert!(errors.is_empty(), "{:#?}", errors);
+    }
+}
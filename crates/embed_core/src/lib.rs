@@ -6,15 +6,31 @@ use futures::future::join_all;
 #[cfg(feature = "wasm")]
 use wasm_bindgen::prelude::*;
 
+pub mod analyzer;
 pub mod basefunc;
 pub mod config;
 pub mod logger;
 // Re-export commonly used types for easier access
+pub use analyzer::Analyzer;
+pub use basefunc::axdr::{AxdrData, AxdrReader};
+pub use basefunc::csg_security::CsgSecurity;
+pub use basefunc::data_completeness::DataCompleteness;
+pub use basefunc::file_transfer::FileTransferReassembler;
+pub use basefunc::frame_13761::Frame13761;
+pub use basefunc::frame_13762::Frame13762;
+pub use basefunc::frame_645::Frame645;
+pub use basefunc::frame_645_97::Frame64597;
+pub use basefunc::frame_698::Frame698;
 pub use basefunc::frame_csg::FrameCsg;
 pub use basefunc::frame_fun::FrameFun;
+pub use basefunc::frame_statistics::FrameStatistics;
 pub use basefunc::protocol::FrameAnalisyic;
+pub use basefunc::sm_crypto::{Sm3, Sm4};
 pub use config::oadmapconfig::TaskOadConfigManager;
-pub use config::xmlconfig::{ItemConfigList, ProtocolConfigManager, QframeConfig, XmlElement};
+pub use config::xmlconfig::{
+    ItemConfigList, ItemLocator, ProtocolConfigManager, QframeConfig, XmlElement,
+};
+pub use config::xmlvalidator::{XmlConfigValidator, XmlDiagnostic};
 
 #[cfg(feature = "wasm")]
 #[wasm_bindgen]
@@ -80,7 +96,17 @@ impl FrameAnalyzer {
     /// Get available protocols
     #[wasm_bindgen]
     pub fn get_available_protocols(&self) -> String {
-        let protocols = vec!["CSG13", "CSG16", "DLT/645-2007", "moudle", "MS"];
+        let protocols = vec![
+            "CSG13",
+            "CSG16",
+            "DLT/645-2007",
+            "DLT/645-1997",
+            "DLT/698.45",
+            "Q/GDW1376.1",
+            "Q/GDW1376.2",
+            "moudle",
+            "MS",
+        ];
         serde_json::to_string(&protocols).unwrap_or_else(|_| "[]".to_string())
     }
 
@@ -96,6 +122,291 @@ impl FrameAnalyzer {
             .map_err(|e| JsValue::from_str(&format!("Failed to reset config: {}", e)))
     }
 
+    /// 校验协议配置XML，返回诊断信息列表
+    #[wasm_bindgen]
+    pub fn validate_protocol_config(&self, content: String) -> Result<String, JsValue> {
+        let diagnostics = XmlConfigValidator::validate_str(&content)
+            .map_err(|e| JsValue::from_str(&format!("Failed to validate config: {}", e)))?;
+        serde_json::to_string(&diagnostics)
+            .map_err(|e| JsValue::from_str(&format!("Serialization error: {}", e)))
+    }
+
+    /// 获取协议配置查找缓存的命中统计
+    #[wasm_bindgen]
+    pub fn get_config_cache_stats(&self, protocol: String) -> Result<String, JsValue> {
+        let stats = ProtocolConfigManager::get_cache_stats(&protocol)
+            .map_err(|e| JsValue::from_str(&format!("Failed to get cache stats: {}", e)))?;
+        serde_json::to_string(&stats)
+            .map_err(|e| JsValue::from_str(&format!("Serialization error: {}", e)))
+    }
+
+    /// 导出协议配置为XML
+    #[wasm_bindgen]
+    pub fn export_protocol_config(&self, protocol: String) -> Result<String, JsValue> {
+        ProtocolConfigManager::export_xml(&protocol)
+            .map_err(|e| JsValue::from_str(&format!("Failed to export config: {}", e)))
+    }
+
+    /// 新增配置数据项，parent_json为空时添加为顶层数据项
+    #[wasm_bindgen]
+    pub fn add_config_item(
+        &self,
+        protocol: String,
+        parent_json: Option<String>,
+        position: Option<usize>,
+        element_json: String,
+    ) -> Result<(), JsValue> {
+        let parent: Option<ItemLocator> = parent_json
+            .map(|json| serde_json::from_str(&json))
+            .transpose()
+            .map_err(|e| JsValue::from_str(&format!("Failed to parse parent JSON: {}", e)))?;
+        let element: XmlElement = serde_json::from_str(&element_json)
+            .map_err(|e| JsValue::from_str(&format!("Failed to parse element JSON: {}", e)))?;
+        ProtocolConfigManager::add_item(&protocol, parent.as_ref(), position, &element)
+            .map_err(|e| JsValue::from_str(&format!("Failed to add config item: {}", e)))
+    }
+
+    /// 修改配置数据项
+    #[wasm_bindgen]
+    pub fn update_config_item(
+        &self,
+        protocol: String,
+        target_json: String,
+        element_json: String,
+    ) -> Result<(), JsValue> {
+        let target: ItemLocator = serde_json::from_str(&target_json)
+            .map_err(|e| JsValue::from_str(&format!("Failed to parse target JSON: {}", e)))?;
+        let element: XmlElement = serde_json::from_str(&element_json)
+            .map_err(|e| JsValue::from_str(&format!("Failed to parse element JSON: {}", e)))?;
+        ProtocolConfigManager::update_item(&protocol, &target, &element)
+            .map_err(|e| JsValue::from_str(&format!("Failed to update config item: {}", e)))
+    }
+
+    /// 删除配置数据项
+    #[wasm_bindgen]
+    pub fn delete_config_item(&self, protocol: String, target_json: String) -> Result<(), JsValue> {
+        let target: ItemLocator = serde_json::from_str(&target_json)
+            .map_err(|e| JsValue::from_str(&format!("Failed to parse target JSON: {}", e)))?;
+        ProtocolConfigManager::delete_item(&protocol, &target)
+            .map_err(|e| JsValue::from_str(&format!("Failed to delete config item: {}", e)))
+    }
+
+    /// 移动配置数据项，parent_json为空时移动到顶层
+    #[wasm_bindgen]
+    pub fn move_config_item(
+        &self,
+        protocol: String,
+        target_json: String,
+        parent_json: Option<String>,
+        position: Option<usize>,
+    ) -> Result<(), JsValue> {
+        let target: ItemLocator = serde_json::from_str(&target_json)
+            .map_err(|e| JsValue::from_str(&format!("Failed to parse target JSON: {}", e)))?;
+        let parent: Option<ItemLocator> = parent_json
+            .map(|json| serde_json::from_str(&json))
+            .transpose()
+            .map_err(|e| JsValue::from_str(&format!("Failed to parse parent JSON: {}", e)))?;
+        ProtocolConfigManager::move_item(&protocol, &target, parent.as_ref(), position)
+            .map_err(|e| JsValue::from_str(&format!("Failed to move config item: {}", e)))
+    }
+
+    /// 在内置协议配置上叠加配置层，同名叠加层会被替换
+    #[wasm_bindgen]
+    pub fn add_config_overlay(
+        &self,
+        protocol: String,
+        name: String,
+        content: String,
+    ) -> Result<(), JsValue> {
+        ProtocolConfigManager::add_overlay(&protocol, &name, &content)
+            .map_err(|e| JsValue::from_str(&format!("Failed to add config overlay: {}", e)))
+    }
+
+    /// 移除配置叠加层
+    #[wasm_bindgen]
+    pub fn remove_config_overlay(&self, protocol: String, name: String) -> Result<bool, JsValue> {
+        ProtocolConfigManager::remove_overlay(&protocol, &name)
+            .map_err(|e| JsValue::from_str(&format!("Failed to remove config overlay: {}", e)))
+    }
+
+    /// 丢弃通过增删改移接口对协议配置做的修改
+    #[wasm_bindgen]
+    pub fn discard_config_edits(&self, protocol: String) -> Result<bool, JsValue> {
+        ProtocolConfigManager::discard_edits(&protocol)
+            .map_err(|e| JsValue::from_str(&format!("Failed to discard config edits: {}", e)))
+    }
+
+    /// 按叠加顺序获取配置叠加层名称
+    #[wasm_bindgen]
+    pub fn get_config_overlays(&self, protocol: String) -> Result<String, JsValue> {
+        let names = ProtocolConfigManager::get_overlay_names(&protocol)
+            .map_err(|e| JsValue::from_str(&format!("Failed to get config overlays: {}", e)))?;
+        serde_json::to_string(&names)
+            .map_err(|e| JsValue::from_str(&format!("Serialization error: {}", e)))
+    }
+
+    /// 获取协议配置中的地区及其继承链
+    #[wasm_bindgen]
+    pub fn get_config_regions(&self, protocol: String) -> Result<String, JsValue> {
+        let regions = ProtocolConfigManager::get_regions(&protocol)
+            .map_err(|e| JsValue::from_str(&format!("Failed to get regions: {}", e)))?;
+        serde_json::to_string(&regions)
+            .map_err(|e| JsValue::from_str(&format!("Serialization error: {}", e)))
+    }
+
+    /// 获取合并后各顶层数据项的来源配置层
+    #[wasm_bindgen]
+    pub fn get_config_item_layers(&self, protocol: String) -> Result<String, JsValue> {
+        let layers = ProtocolConfigManager::get_item_layers(&protocol)
+            .map_err(|e| JsValue::from_str(&format!("Failed to get item layers: {}", e)))?;
+        serde_json::to_string(&layers)
+            .map_err(|e| JsValue::from_str(&format!("Serialization error: {}", e)))
+    }
+
+    /// 获取协议的功能码表(内置及配置中声明的)
+    #[wasm_bindgen]
+    pub fn get_afn_table(&self, protocol: String) -> Result<String, JsValue> {
+        let entries = ProtocolConfigManager::get_afn_table(&protocol)
+            .map_err(|e| JsValue::from_str(&format!("Failed to get afn table: {}", e)))?;
+        serde_json::to_string(&entries)
+            .map_err(|e| JsValue::from_str(&format!("Serialization error: {}", e)))
+    }
+
+    /// 组645-1997报文，di为4位十六进制数据标识(如"9010")，data为加33H前的数据内容
+    #[wasm_bindgen]
+    pub fn build_dlt645_97_frame(
+        &self,
+        address: &str,
+        control: u8,
+        di: &str,
+        data: &str,
+    ) -> Result<Vec<u8>, JsValue> {
+        api::build_dlt645_97_frame(address, control, di, data)
+            .map_err(|e| JsValue::from_str(&format!("Failed to build frame: {}", e)))
+    }
+
+    /// 组698.45报文，address为服务器地址(高字节在前)，apdu为十六进制字符串
+    #[wasm_bindgen]
+    pub fn build_dlt698_frame(
+        &self,
+        control: u8,
+        address_type: u8,
+        address: &str,
+        client_address: u8,
+        apdu: &str,
+    ) -> Result<Vec<u8>, JsValue> {
+        api::build_dlt698_frame(control, address_type, address, client_address, apdu)
+            .map_err(|e| JsValue::from_str(&format!("Failed to build frame: {}", e)))
+    }
+
+    /// 组1376.2报文，info为12位十六进制信息域R，addresses为逗号分隔的源地址、中继地址和目的地址
+    #[wasm_bindgen]
+    pub fn build_gdw13762_frame(
+        &self,
+        control: u8,
+        info: &str,
+        addresses: &str,
+        afn: u8,
+        fn_value: u16,
+        data: &str,
+    ) -> Result<Vec<u8>, JsValue> {
+        let addresses: Vec<&str> = addresses
+            .split(',')
+            .map(|a| a.trim())
+            .filter(|a| !a.is_empty())
+            .collect();
+        api::build_gdw13762_frame(control, info, &addresses, afn, fn_value, data)
+            .map_err(|e| JsValue::from_str(&format!("Failed to build frame: {}", e)))
+    }
+
+    /// 组1376.2监控从节点(13H-F1)报文，content为十六进制的电表报文
+    #[wasm_bindgen]
+    pub fn build_gdw13762_monitor_frame(
+        &self,
+        comm_mode: u8,
+        seq: u8,
+        source: &str,
+        target: &str,
+        protocol_type: u8,
+        content: &str,
+    ) -> Result<Vec<u8>, JsValue> {
+        api::build_gdw13762_monitor_frame(comm_mode, seq, source, target, protocol_type, content)
+            .map_err(|e| JsValue::from_str(&format!("Failed to build frame: {}", e)))
+    }
+
+    /// PPP FCS16查表计算，fcs为初值，结果未取反
+    #[wasm_bindgen]
+    pub fn ppp_fcs16(&self, fcs: u16, data: &[u8]) -> u16 {
+        api::ppp_fcs16(fcs, data)
+    }
+
+    /// HDLC帧的HCS/FCS值(初值FFFF，结果取反)
+    #[wasm_bindgen]
+    pub fn calculate_fcs16(&self, data: &[u8]) -> u16 {
+        FrameFun::calculate_fcs16(data)
+    }
+
+    /// 在数据末尾追加FCS16(低字节在前)
+    #[wasm_bindgen]
+    pub fn append_fcs16(&self, data: &[u8]) -> Vec<u8> {
+        api::append_fcs16(data)
+    }
+
+    /// 设置终端密钥(只保存在内存中)，usage为mac或data，terminal为*时作为默认密钥，key为空时删除
+    #[wasm_bindgen]
+    pub fn set_csg_key(&self, terminal: &str, usage: &str, key: &str) -> Result<(), JsValue> {
+        api::set_csg_key(terminal, usage, key)
+            .map_err(|e| JsValue::from_str(&format!("Failed to set key: {}", e)))
+    }
+
+    /// 在不带PW的南网报文中插入按终端密钥计算的PW
+    #[wasm_bindgen]
+    pub fn append_csg_pw(&self, frame: &[u8]) -> Result<Vec<u8>, JsValue> {
+        api::append_csg_pw(frame)
+            .map_err(|e| JsValue::from_str(&format!("Failed to append PW: {}", e)))
+    }
+
+    /// 用终端数据密钥解密数据单元
+    #[wasm_bindgen]
+    pub fn decrypt_csg_data(&self, terminal: &str, data: &[u8]) -> Result<Vec<u8>, JsValue> {
+        api::decrypt_csg_data(terminal, data)
+            .map_err(|e| JsValue::from_str(&format!("Failed to decrypt: {}", e)))
+    }
+
+    /// 用终端数据密钥加密数据单元
+    #[wasm_bindgen]
+    pub fn encrypt_csg_data(&self, terminal: &str, data: &[u8]) -> Result<Vec<u8>, JsValue> {
+        api::encrypt_csg_data(terminal, data)
+            .map_err(|e| JsValue::from_str(&format!("Failed to encrypt: {}", e)))
+    }
+
+    /// SM3杂凑值
+    #[wasm_bindgen]
+    pub fn sm3_hash(&self, data: &[u8]) -> Vec<u8> {
+        api::sm3_hash(data)
+    }
+
+    /// 读取应用配置(浏览器中保存在localStorage)，key为空时返回整个section
+    #[wasm_bindgen]
+    pub fn get_app_config_value(&self, section: String, key: String) -> Result<String, JsValue> {
+        let value = config::appconfig::get_config_value(&section, &key)
+            .map_err(|e| JsValue::from_str(&format!("Failed to get app config: {}", e)))?;
+        serde_json::to_string(&value)
+            .map_err(|e| JsValue::from_str(&format!("Serialization error: {}", e)))
+    }
+
+    /// 写入应用配置，value为JSON文本
+    #[wasm_bindgen]
+    pub fn set_app_config_value(
+        &self,
+        section: String,
+        key: String,
+        value: String,
+    ) -> Result<(), JsValue> {
+        config::appconfig::set_config_value(&section, &key, &value)
+            .map_err(|e| JsValue::from_str(&format!("Failed to set app config: {}", e)))
+    }
+
     #[wasm_bindgen]
     pub fn init_oad_map(&self, main_yaml: &str, sub_yaml_map_json: &str) -> Result<(), JsValue> {
         // Parse JSON string to HashMap
@@ -188,6 +499,7 @@ impl FrameAnalyzer {
 
         // 解析数据
         let item_data = FrameAnalisyic::prase_data(
+            &Analyzer::global(),
             &mut data_item_elem,
             &protocol,
             &region,
@@ -262,7 +574,12 @@ impl FrameAnalyzer {
                 let result = Self::try_convert_da_to_point(&cleaned_input)?;
                 Ok(result)
             }
-            _ => Err("Invalid convert type. Expected: point_to_da or da_to_point".to_string()),
+            "fn_to_dt" => Self::try_convert_fn_to_dt(&cleaned_input),
+            "dt_to_fn" => Self::try_convert_dt_to_fn(&cleaned_input),
+            _ => Err(
+                "Invalid convert type. Expected: point_to_da, da_to_point, fn_to_dt or dt_to_fn"
+                    .to_string(),
+            ),
         }
     }
 
@@ -329,6 +646,48 @@ impl FrameAnalyzer {
         Ok(result)
     }
 
+    // 1376.1信息类Fn转DT，输入形如"1,2,25"，同组的Fn合并为一个DT
+    fn try_convert_fn_to_dt(input: &str) -> Result<String, String> {
+        let mut fns = Vec::new();
+        for value in input.split(',').map(|v| v.trim()).filter(|v| !v.is_empty()) {
+            let fn_value = value
+                .trim_start_matches(['F', 'f'])
+                .parse::<u16>()
+                .ok()
+                .filter(|f| (1..=248).contains(f))
+                .ok_or_else(|| format!("无效的Fn: {}", value))?;
+            fns.push(fn_value);
+        }
+        if fns.is_empty() {
+            return Err("请输入有效的Fn".to_string());
+        }
+        fns.sort();
+        fns.dedup();
+        Ok(FrameFun::to_dt(&fns)
+            .iter()
+            .map(|&(dt1, dt2)| format!("{:02X}{:02X}", dt1, dt2))
+            .collect::<Vec<String>>()
+            .join(","))
+    }
+
+    // 1376.1信息类DT转Fn，输入为逗号分隔的4位十六进制DT
+    fn try_convert_dt_to_fn(input: &str) -> Result<String, String> {
+        let mut all_results = Vec::new();
+        for dt_value in input.split(',').map(|v| v.trim()).filter(|v| !v.is_empty()) {
+            let dt_cleaned = dt_value.trim_start_matches("0x").replace([' ', '\n'], "");
+            if dt_cleaned.len() != 4 || !dt_cleaned.chars().all(|c| c.is_ascii_hexdigit()) {
+                return Err(format!("无效的16进制DT值: {}", dt_cleaned));
+            }
+            let dt = FrameFun::get_frame_list_from_str(&dt_cleaned);
+            let (_, fns) = FrameFun::calculate_fn(&dt);
+            all_results.extend(fns.iter().map(|f| f.to_string()));
+        }
+        if all_results.is_empty() {
+            return Err("转换结果为空".to_string());
+        }
+        Ok(all_results.join(","))
+    }
+
     fn try_convert_da_to_point(input: &str) -> Result<String, String> {
         // 处理逗号分隔的多个DA值
         let da_values: Vec<&str> = input.split(',').collect();
@@ -386,9 +745,71 @@ impl FrameAnalyzer {
         Ok(all_results.join(","))
     }
 
+    /// 数据完整率统计，samples_json为DataSample数组，时间格式为%Y-%m-%d %H:%M:%S；
+    /// series_json为应采的[{"point": 测量点, "item": 数据项}]数组，为空时取samples中出现的组合
+    #[wasm_bindgen]
+    pub fn analyze_data_completeness(
+        &self,
+        density: u8,
+        start: String,
+        end: String,
+        samples_json: String,
+        series_json: String,
+    ) -> Result<String, JsValue> {
+        let result =
+            api::analyze_data_completeness(density, &start, &end, &samples_json, &series_json)
+                .map_err(|e| {
+                    JsValue::from_str(&format!("Failed to analyze completeness: {}", e))
+                })?;
+        serde_json::to_string(&result)
+            .map_err(|e| JsValue::from_str(&format!("Serialization error: {}", e)))
+    }
+
+    /// 报文统计，frames_json为[{"frame": "68...16", "timestamp": 毫秒}]数组
+    #[wasm_bindgen]
+    pub fn analyze_frame_statistics(
+        &self,
+        frames_json: String,
+        region: &str,
+        top_n: usize,
+    ) -> Result<String, JsValue> {
+        let summary = api::analyze_frame_statistics(&frames_json, region, top_n)
+            .map_err(|e| JsValue::from_str(&format!("Failed to analyze statistics: {}", e)))?;
+        serde_json::to_string(&summary)
+            .map_err(|e| JsValue::from_str(&format!("Serialization error: {}", e)))
+    }
+
+    /// 重组南网文件传输报文，frames_json格式同analyze_frame_statistics
+    #[wasm_bindgen]
+    pub fn reassemble_file_transfer(&self, frames_json: String) -> Result<String, JsValue> {
+        let report = api::reassemble_file_transfer(&frames_json)
+            .map_err(|e| JsValue::from_str(&format!("Failed to reassemble: {}", e)))?;
+        serde_json::to_string(&report)
+            .map_err(|e| JsValue::from_str(&format!("Serialization error: {}", e)))
+    }
+
+    /// 导出第index次文件传输重组后的文件内容，文件未收全或总校验不符时返回错误
+    #[wasm_bindgen]
+    pub fn export_file_transfer(
+        &self,
+        frames_json: String,
+        index: usize,
+    ) -> Result<Vec<u8>, JsValue> {
+        api::export_file_transfer(&frames_json, index)
+            .map_err(|e| JsValue::from_str(&format!("Failed to export file: {}", e)))
+    }
+
     #[wasm_bindgen]
     pub async fn get_all_config_item_lists(&self) -> String {
-        let protocols = vec!["CSG13", "CSG16", "DLT/645-2007"];
+        let protocols = vec![
+            "CSG13",
+            "CSG16",
+            "DLT/645-2007",
+            "DLT/645-1997",
+            "DLT/698.45",
+            "Q/GDW1376.1",
+            "Q/GDW1376.2",
+        ];
         
         // 创建异步任务
         let futures: Vec<_> = protocols
@@ -450,4 +871,339 @@ pub mod api {
     pub fn bytes_to_hex(data: &[u8]) -> String {
         FrameFun::get_data_str_with_space(data)
     }
+
+    /// Get lookup cache hit/miss counters for a protocol config
+    pub fn get_config_cache_stats(
+        protocol: &str,
+    ) -> Result<config::xmlconfig::CacheStats, Box<dyn std::error::Error>> {
+        ProtocolConfigManager::get_cache_stats(protocol)
+    }
+
+    /// Export a protocol config back to XML
+    pub fn export_protocol_config(protocol: &str) -> Result<String, Box<dyn std::error::Error>> {
+        ProtocolConfigManager::export_xml(protocol)
+    }
+
+    /// Stack an overlay XML on top of the built-in protocol config
+    pub fn add_config_overlay(
+        protocol: &str,
+        name: &str,
+        content: &str,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        ProtocolConfigManager::add_overlay(protocol, name, content)
+    }
+
+    /// Remove an overlay, returns false if no overlay has this name
+    pub fn remove_config_overlay(
+        protocol: &str,
+        name: &str,
+    ) -> Result<bool, Box<dyn std::error::Error>> {
+        ProtocolConfigManager::remove_overlay(protocol, name)
+    }
+
+    /// Drop the item edits made through `add_item`/`update_item`/`delete_item`/
+    /// `move_item`; returns false if there were none. Edits otherwise survive
+    /// overlay changes and base config reloads
+    pub fn discard_config_edits(protocol: &str) -> Result<bool, Box<dyn std::error::Error>> {
+        ProtocolConfigManager::discard_edits(protocol)
+    }
+
+    /// List the regions in a protocol config with their fallback chains
+    pub fn get_config_regions(
+        protocol: &str,
+    ) -> Result<Vec<config::xmlconfig::Region>, Box<dyn std::error::Error>> {
+        ProtocolConfigManager::get_regions(protocol)
+    }
+
+    /// List the layer each effective top-level item came from
+    pub fn get_config_item_layers(
+        protocol: &str,
+    ) -> Result<Vec<config::xmlconfig::ItemLayer>, Box<dyn std::error::Error>> {
+        ProtocolConfigManager::get_item_layers(protocol)
+    }
+
+    /// List the built-in AFN/control-code table plus entries declared in config
+    pub fn get_afn_table(
+        protocol: &str,
+    ) -> Result<Vec<config::afntable::AfnEntry>, Box<dyn std::error::Error>> {
+        ProtocolConfigManager::get_afn_table(protocol)
+    }
+
+    /// Build a DL/T645-1997 frame; `di` is a 4-digit hex data identifier (empty for
+    /// commands without one) and `data` the hex payload before the 33H offset
+    pub fn build_dlt645_97_frame(
+        address: &str,
+        control: u8,
+        di: &str,
+        data: &str,
+    ) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+        let mut content = Vec::new();
+        if !di.is_empty() {
+            let di = u16::from_str_radix(di, 16)
+                .ok()
+                .filter(|_| di.len() == 4)
+                .ok_or_else(|| format!("Invalid data identifier: {}", di))?;
+            content.extend_from_slice(&di.to_le_bytes());
+        }
+        let data = data.replace(' ', "");
+        if !data.len().is_multiple_of(2) || !data.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(format!("Invalid hex data: {}", data).into());
+        }
+        content.extend(FrameFun::get_hex_frame(&data).unwrap_or_default());
+        Ok(Frame645::build_frame(address, control, &content)?)
+    }
+
+    /// Build a DL/T698.45 frame with HCS and FCS; `address` is the server address
+    /// (most significant byte first) and `apdu` the hex APDU
+    pub fn build_dlt698_frame(
+        control: u8,
+        address_type: u8,
+        address: &str,
+        client_address: u8,
+        apdu: &str,
+    ) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+        let apdu = apdu.replace(' ', "");
+        if !apdu.len().is_multiple_of(2) || !apdu.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(format!("Invalid hex APDU: {}", apdu).into());
+        }
+        let apdu = FrameFun::get_hex_frame(&apdu).unwrap_or_default();
+        Ok(Frame698::build_frame(
+            control,
+            address_type,
+            address,
+            client_address,
+            &apdu,
+        )?)
+    }
+
+    /// Build a Q/GDW1376.2 frame; `info` is the 12-digit hex info field R and
+    /// `addresses` the source, relay and destination addresses (empty for none)
+    pub fn build_gdw13762_frame(
+        control: u8,
+        info: &str,
+        addresses: &[&str],
+        afn: u8,
+        fn_value: u16,
+        data: &str,
+    ) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+        let info = info.replace(' ', "");
+        let info: [u8; 6] = FrameFun::get_hex_frame(&info)
+            .filter(|_| info.chars().all(|c| c.is_ascii_hexdigit()))
+            .and_then(|bytes| bytes.try_into().ok())
+            .ok_or_else(|| format!("Invalid info field: {}", info))?;
+        let data = data.replace(' ', "");
+        if !data.len().is_multiple_of(2) || !data.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(format!("Invalid hex data: {}", data).into());
+        }
+        let data = FrameFun::get_hex_frame(&data).unwrap_or_default();
+        Ok(Frame13762::build_frame(
+            control, info, addresses, afn, fn_value, &data,
+        )?)
+    }
+
+    /// Build a Q/GDW1376.2 "monitor slave node" (AFN 13H F1) frame carrying the
+    /// hex meter frame `content`
+    pub fn build_gdw13762_monitor_frame(
+        comm_mode: u8,
+        seq: u8,
+        source: &str,
+        target: &str,
+        protocol_type: u8,
+        content: &str,
+    ) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+        let content = content.replace(' ', "");
+        if !content.len().is_multiple_of(2) || !content.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(format!("Invalid hex content: {}", content).into());
+        }
+        let content = FrameFun::get_hex_frame(&content).unwrap_or_default();
+        Ok(Frame13762::build_monitor_frame(
+            comm_mode,
+            seq,
+            source,
+            target,
+            protocol_type,
+            &content,
+        )?)
+    }
+
+    /// PPP FCS16 table lookup starting from `fcs`, without the final complement
+    pub fn ppp_fcs16(fcs: u16, data: &[u8]) -> u16 {
+        FrameFun::ppp_fcs16(fcs, data)
+    }
+
+    /// Append the HDLC FCS16 of `data` (low byte first)
+    pub fn append_fcs16(data: &[u8]) -> Vec<u8> {
+        let mut frame = data.to_vec();
+        FrameFun::append_fcs16(&mut frame, 0);
+        frame
+    }
+
+    /// Set a CSG terminal key in the default analyzer's key store; `usage` is "mac" or
+    /// "data", terminal "*" sets the fallback key and an empty `key` removes it
+    pub fn set_csg_key(
+        terminal: &str,
+        usage: &str,
+        key: &str,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let store = Analyzer::global().key_store();
+        Ok(config::keystore::set_key_hex(
+            store.as_ref(),
+            terminal,
+            usage,
+            key,
+        )?)
+    }
+
+    /// Insert the PW computed with the terminal's MAC key into a CSG frame without PW
+    pub fn append_csg_pw(frame: &[u8]) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+        Ok(CsgSecurity::append_pw(&Analyzer::global(), frame)?)
+    }
+
+    /// Decrypt a data unit with the terminal's data key
+    pub fn decrypt_csg_data(
+        terminal: &str,
+        data: &[u8],
+    ) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+        Ok(CsgSecurity::decrypt_data(
+            &Analyzer::global(),
+            terminal,
+            data,
+        )?)
+    }
+
+    /// Encrypt a data unit with the terminal's data key
+    pub fn encrypt_csg_data(
+        terminal: &str,
+        data: &[u8],
+    ) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+        Ok(CsgSecurity::encrypt_data(
+            &Analyzer::global(),
+            terminal,
+            data,
+        )?)
+    }
+
+    /// SM3 digest of `data`
+    pub fn sm3_hash(data: &[u8]) -> Vec<u8> {
+        Sm3::hash(data).to_vec()
+    }
+
+    /// Add a data item under `parent`, or as a top-level item when `parent` is None
+    pub fn add_config_item(
+        protocol: &str,
+        parent: Option<&ItemLocator>,
+        position: Option<usize>,
+        element: &XmlElement,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        ProtocolConfigManager::add_item(protocol, parent, position, element)
+    }
+
+    /// Replace a data item
+    pub fn update_config_item(
+        protocol: &str,
+        target: &ItemLocator,
+        element: &XmlElement,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        ProtocolConfigManager::update_item(protocol, target, element)
+    }
+
+    /// Delete a data item
+    pub fn delete_config_item(
+        protocol: &str,
+        target: &ItemLocator,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        ProtocolConfigManager::delete_item(protocol, target)
+    }
+
+    /// Move a data item under `parent`, or to the top level when `parent` is None
+    pub fn move_config_item(
+        protocol: &str,
+        target: &ItemLocator,
+        parent: Option<&ItemLocator>,
+        position: Option<usize>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        ProtocolConfigManager::move_item(protocol, target, parent, position)
+    }
+
+    /// Build a data completeness report from collected samples
+    ///
+    /// `series_json` lists the expected `{"point", "item"}` series; when it is
+    /// empty, the series present in the samples are used.
+    pub fn analyze_data_completeness(
+        density: u8,
+        start: &str,
+        end: &str,
+        samples_json: &str,
+        series_json: &str,
+    ) -> Result<basefunc::data_completeness::CompletenessReport, Box<dyn std::error::Error>> {
+        let start = chrono::NaiveDateTime::parse_from_str(start, "%Y-%m-%d %H:%M:%S")?;
+        let end = chrono::NaiveDateTime::parse_from_str(end, "%Y-%m-%d %H:%M:%S")?;
+        let samples: Vec<basefunc::data_completeness::DataSample> =
+            serde_json::from_str(samples_json)?;
+        let series: Vec<basefunc::data_completeness::ExpectedSeries> =
+            if series_json.trim().is_empty() {
+                Vec::new()
+            } else {
+                serde_json::from_str(series_json)?
+            };
+        let report = DataCompleteness::analyze(density, start, end, &samples, &series)?;
+        Ok(report)
+    }
+
+    /// Build traffic statistics from a list of hex frames with optional timestamps
+    pub fn analyze_frame_statistics(
+        frames_json: &str,
+        region: &str,
+        top_n: usize,
+    ) -> Result<basefunc::frame_statistics::StatisticsSummary, Box<dyn std::error::Error>> {
+        let frames: Vec<Value> = serde_json::from_str(frames_json)?;
+        let mut statistics = FrameStatistics::new();
+        for item in frames {
+            let frame_str = item
+                .get("frame")
+                .and_then(|v| v.as_str())
+                .ok_or("Missing frame field")?;
+            let frame =
+                FrameFun::get_hex_frame(&frame_str.replace(' ', "")).ok_or("Invalid hex string")?;
+            let timestamp = item.get("timestamp").and_then(|v| v.as_i64());
+            statistics.add_frame(&frame, region, timestamp);
+        }
+        Ok(statistics.summary(top_n))
+    }
+
+    /// Reassemble CSG file transfers (AFN 0FH) from a list of hex frames with
+    /// optional timestamps, in the same format as `analyze_frame_statistics`
+    pub fn reassemble_file_transfer(
+        frames_json: &str,
+    ) -> Result<basefunc::file_transfer::FileTransferReport, Box<dyn std::error::Error>> {
+        Ok(file_transfer_reassembler(frames_json)?.report())
+    }
+
+    /// Reconstructed content of the `index`-th transfer; fails with the missing
+    /// segment numbers when the transfer is incomplete or was cancelled, and
+    /// when the reassembled data does not match the declared checksum
+    pub fn export_file_transfer(
+        frames_json: &str,
+        index: usize,
+    ) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+        Ok(file_transfer_reassembler(frames_json)?.image(index)?)
+    }
+
+    fn file_transfer_reassembler(
+        frames_json: &str,
+    ) -> Result<FileTransferReassembler, Box<dyn std::error::Error>> {
+        let frames: Vec<Value> = serde_json::from_str(frames_json)?;
+        let mut reassembler = FileTransferReassembler::new();
+        for item in frames {
+            let frame_str = item
+                .get("frame")
+                .and_then(|v| v.as_str())
+                .ok_or("Missing frame field")?;
+            let frame =
+                FrameFun::get_hex_frame(&frame_str.replace(' ', "")).ok_or("Invalid hex string")?;
+            let timestamp = item.get("timestamp").and_then(|v| v.as_i64());
+            reassembler.add_frame(&frame, timestamp);
+        }
+        Ok(reassembler)
+    }
 }
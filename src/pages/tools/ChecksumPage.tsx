@@ -2,9 +2,10 @@ import { useState } from 'react'
 import { Link } from 'react-router-dom'
 import { toast } from 'react-hot-toast'
 import { ArrowLeft, Calculator, Copy, CheckCircle, AlertCircle, Info, Edit, Target } from 'lucide-react'
-import { pppFcs16 } from '../../utils/pppFcs'
+import { useWasm } from '../../contexts/WasmContext'
 
 export default function ChecksumPage() {
+  const { analyzer } = useWasm()
   const [input, setInput] = useState('')
   const [checksumType, setChecksumType] = useState<'sum' | 'xor' | 'crc16' | 'crc32'>('sum')
   const [crcVariant, setCrcVariant] = useState<string>('modbus') // CRC变体
@@ -129,8 +130,8 @@ export default function ChecksumPage() {
 
     // 使用专门的PPP FCS算法
     if (crcVariant === 'ppp') {
-      const fcs = pppFcs16(0xFFFF, bytes)
-      return fcs & 0xFFFF
+      if (!analyzer) throw new Error('WASM analyzer not initialized')
+      return analyzer.ppp_fcs16(0xFFFF, new Uint8Array(bytes))
     }
 
     // 对于反向多项式的算法（如MODBUS），使用查表法
@@ -1,148 +1,177 @@
-import { useState, useEffect } from 'react'
+import { useState } from 'react'
+import type { ChangeEvent } from 'react'
 import { toast } from 'react-hot-toast'
+import { useWasm } from '../../contexts/WasmContext'
 
-interface AnalysisRecord {
-  id: string
-  timestamp: number
-  protocol: string
-  region: string
-  frameLength: number
-  success: boolean
-  errorMessage?: string
+interface LatencyBucket {
+  label: string
+  count: number
 }
 
-interface StatisticsData {
-  totalFrames: number
-  successRate: number
-  protocolDistribution: Record<string, number>
-  regionDistribution: Record<string, number>
-  errorTypes: Record<string, number>
-  avgFrameLength: number
-  recentActivity: AnalysisRecord[]
+interface LatencySummary {
+  count: number
+  min: number | null
+  max: number | null
+  avg: number | null
+  buckets: LatencyBucket[]
 }
 
-export default function StatisticsPage() {
-  const [statistics, setStatistics] = useState<StatisticsData>({
-    totalFrames: 0,
-    successRate: 0,
-    protocolDistribution: {},
-    regionDistribution: {},
-    errorTypes: {},
-    avgFrameLength: 0,
-    recentActivity: []
-  })
-  const [timeRange, setTimeRange] = useState<'1h' | '24h' | '7d' | '30d'>('24h')
-  const [loading, setLoading] = useState(false)
+interface TalkerCount {
+  address: string
+  count: number
+}
+
+// 与embed_core中StatisticsSummary的序列化结果一致
+interface StatisticsSummary {
+  total_frames: number
+  parse_errors: number
+  parse_error_rate: number
+  checksum_errors: number
+  checksum_error_rate: number
+  avg_frame_length: number
+  protocol_distribution: Record<string, number>
+  afn_distribution: Record<string, number>
+  di_distribution: Record<string, number>
+  address_distribution: Record<string, number>
+  direction_distribution: Record<string, number>
+  latency: LatencySummary
+  top_talkers: TalkerCount[]
+}
+
+interface FrameInput {
+  frame: string
+  timestamp?: number
+}
+
+const TOP_N = 10
+
+// 每行一帧报文，行首可带时间：毫秒时间戳、秒时间戳或"YYYY-MM-DD HH:MM:SS.mmm"
+const parseFrameLines = (text: string): FrameInput[] => {
+  const frames: FrameInput[] = []
+  for (const rawLine of text.split(/\r?\n/)) {
+    const line = rawLine.trim()
+    if (!line) continue
 
-  // 模拟数据生成
-  const generateMockData = (): StatisticsData => {
-    const protocols = ['CSG13', 'CSG16', 'DLT/645-2007', 'Modbus', 'MS']
-    const regions = ['南网', '国网', '蒙西', '其他']
-    const errors = ['校验错误', '格式错误', '长度错误', '协议不支持', '数据异常']
-    
-    const totalFrames = Math.floor(Math.random() * 1000) + 100
-    const successCount = Math.floor(totalFrames * (0.7 + Math.random() * 0.25))
-    
-    const protocolDistribution: Record<string, number> = {}
-    const regionDistribution: Record<string, number> = {}
-    const errorTypes: Record<string, number> = {}
-    
-    // 生成协议分布
-    protocols.forEach(protocol => {
-      protocolDistribution[protocol] = Math.floor(Math.random() * totalFrames * 0.3)
-    })
-    
-    // 生成区域分布
-    regions.forEach(region => {
-      regionDistribution[region] = Math.floor(Math.random() * totalFrames * 0.4)
-    })
-    
-    // 生成错误类型分布
-    errors.forEach(error => {
-      errorTypes[error] = Math.floor(Math.random() * (totalFrames - successCount) * 0.3)
-    })
-    
-    // 生成最近活动记录
-    const recentActivity: AnalysisRecord[] = []
-    for (let i = 0; i < 10; i++) {
-      recentActivity.push({
-        id: `record_${i}`,
-        timestamp: Date.now() - Math.random() * 24 * 60 * 60 * 1000,
-        protocol: protocols[Math.floor(Math.random() * protocols.length)],
-        region: regions[Math.floor(Math.random() * regions.length)],
-        frameLength: Math.floor(Math.random() * 200) + 20,
-        success: Math.random() > 0.3,
-        errorMessage: Math.random() > 0.7 ? errors[Math.floor(Math.random() * errors.length)] : undefined
-      })
+    const dateMatch = line.match(/^(\d{4}-\d{2}-\d{2}[ T]\d{2}:\d{2}:\d{2}(?:\.\d{1,3})?)[\s,]+(.+)$/)
+    if (dateMatch) {
+      const time = new Date(dateMatch[1].replace(' ', 'T')).getTime()
+      frames.push({ frame: dateMatch[2], timestamp: isNaN(time) ? undefined : time })
+      continue
     }
-    
-    return {
-      totalFrames,
-      successRate: (successCount / totalFrames) * 100,
-      protocolDistribution,
-      regionDistribution,
-      errorTypes,
-      avgFrameLength: Math.floor(Math.random() * 100) + 50,
-      recentActivity: recentActivity.sort((a, b) => b.timestamp - a.timestamp)
+
+    const epochMatch = line.match(/^(\d{10}|\d{13})[\s,]+(.+)$/)
+    if (epochMatch) {
+      const value = Number(epochMatch[1])
+      frames.push({ frame: epochMatch[2], timestamp: epochMatch[1].length === 10 ? value * 1000 : value })
+      continue
     }
+
+    frames.push({ frame: line })
   }
+  return frames
+}
+
+export default function StatisticsPage() {
+  const { analyzer } = useWasm()
+  const [input, setInput] = useState('')
+  const [region, setRegion] = useState('南网')
+  const [statistics, setStatistics] = useState<StatisticsSummary | null>(null)
+  const [loading, setLoading] = useState(false)
 
-  const loadStatistics = async () => {
+  const getRegions = () => ["南网", "云南", "广东", "深圳", "广西", "贵州", "海南"]
+
+  const loadStatistics = () => {
+    if (!analyzer) {
+      toast.error('WASM模块未初始化')
+      return
+    }
+    const frames = parseFrameLines(input)
+    if (frames.length === 0) {
+      toast.error('请输入报文，每行一帧')
+      return
+    }
     setLoading(true)
     try {
-      // 模拟 API 调用
-      await new Promise(resolve => setTimeout(resolve, 1000))
-      const data = generateMockData()
-      setStatistics(data)
-      toast.success('统计数据加载完成')
+      const result = analyzer.analyze_frame_statistics(JSON.stringify(frames), region, TOP_N)
+      setStatistics(JSON.parse(result))
+      toast.success('统计完成')
     } catch (error) {
-      toast.error('加载统计数据失败')
+      toast.error(`统计失败: ${error instanceof Error ? error.message : String(error)}`)
     } finally {
       setLoading(false)
     }
   }
 
-  useEffect(() => {
-    loadStatistics()
-  }, [timeRange])
+  const importFile = (event: ChangeEvent<HTMLInputElement>) => {
+    const file = event.target.files?.[0]
+    if (!file) return
+    const reader = new FileReader()
+    reader.onload = () => setInput(String(reader.result ?? ''))
+    reader.onerror = () => toast.error('读取文件失败')
+    reader.readAsText(file)
+    event.target.value = ''
+  }
 
   const exportData = () => {
+    if (!statistics) return
     const dataStr = JSON.stringify(statistics, null, 2)
     const dataBlob = new Blob([dataStr], { type: 'application/json' })
     const url = URL.createObjectURL(dataBlob)
     const link = document.createElement('a')
     link.href = url
-    link.download = `analysis_statistics_${new Date().toISOString().split('T')[0]}.json`
+    link.download = `frame_statistics_${new Date().toISOString().split('T')[0]}.json`
     link.click()
     URL.revokeObjectURL(url)
     toast.success('统计数据已导出')
   }
 
   const clearData = () => {
-    if (confirm('确定要清空所有统计数据吗？此操作不可恢复。')) {
-      setStatistics({
-        totalFrames: 0,
-        successRate: 0,
-        protocolDistribution: {},
-        regionDistribution: {},
-        errorTypes: {},
-        avgFrameLength: 0,
-        recentActivity: []
-      })
-      toast.success('统计数据已清空')
-    }
+    setInput('')
+    setStatistics(null)
   }
 
-  const formatTime = (timestamp: number) => {
-    return new Date(timestamp).toLocaleString('zh-CN')
-  }
+  const formatRate = (rate: number) => `${rate.toFixed(1)}%`
+
+  const formatLatency = (value: number | null) => (value === null ? '-' : `${Math.round(value)} ms`)
 
-  const getTopItems = (distribution: Record<string, number>, limit = 5) => {
+  const getTopItems = (distribution: Record<string, number>, limit = TOP_N) => {
     return Object.entries(distribution)
       .sort(([, a], [, b]) => b - a)
       .slice(0, limit)
   }
 
+  const renderDistribution = (title: string, distribution: Record<string, number>, color: string) => {
+    const total = Object.values(distribution).reduce((a, b) => a + b, 0)
+    const items = getTopItems(distribution)
+    return (
+      <div className="card bg-base-100 shadow-lg border border-base-300">
+        <div className="card-body">
+          <h2 className="card-title">{title}</h2>
+          {items.length === 0 ? (
+            <p className="text-sm text-base-content/60">无数据</p>
+          ) : (
+            <div className="space-y-3">
+              {items.map(([name, count]) => (
+                <div key={name} className="flex items-center justify-between gap-4">
+                  <span className="font-medium font-mono truncate">{name}</span>
+                  <div className="flex items-center gap-2">
+                    <div className="w-32 bg-base-300 rounded-full h-2">
+                      <div
+                        className={`${color} h-2 rounded-full transition-all duration-300`}
+                        style={{ width: `${total ? (count / total) * 100 : 0}%` }}
+                      />
+                    </div>
+                    <span className="text-sm font-mono w-12 text-right">{count}</span>
+                  </div>
+                </div>
+              ))}
+            </div>
+          )}
+        </div>
+      </div>
+    )
+  }
+
   return (
     <div className="h-full flex flex-col">
       {/* Header */}
@@ -151,27 +180,25 @@ export default function StatisticsPage() {
           <div>
             <h1 className="text-2xl font-bold">数据统计</h1>
             <p className="text-sm text-base-content/70 mt-1">
-              解析结果统计分析
+              按协议、AFN、数据标识、终端地址和方向统计报文，给出错误率和响应时延
             </p>
           </div>
           <div className="flex items-center gap-3">
-            <div className="badge badge-warning">开发中</div>
-            <select 
+            <select
               className="select select-bordered select-sm"
-              value={timeRange}
-              onChange={(e) => setTimeRange(e.target.value as any)}
+              value={region}
+              onChange={(e) => setRegion(e.target.value)}
             >
-              <option value="1h">最近 1 小时</option>
-              <option value="24h">最近 24 小时</option>
-              <option value="7d">最近 7 天</option>
-              <option value="30d">最近 30 天</option>
+              {getRegions().map(r => (
+                <option key={r} value={r}>{r}</option>
+              ))}
             </select>
-            <button 
-              className="btn btn-outline btn-sm"
+            <button
+              className="btn btn-primary btn-sm"
               onClick={loadStatistics}
-              disabled={loading}
+              disabled={loading || !analyzer}
             >
-              {loading ? '加载中...' : '刷新'}
+              {loading ? '统计中...' : '开始统计'}
             </button>
           </div>
         </div>
@@ -180,172 +207,135 @@ export default function StatisticsPage() {
       {/* Content */}
       <div className="flex-1 p-6 overflow-auto">
         <div className="max-w-6xl mx-auto space-y-6">
-          {/* Overview Stats */}
-          <div className="stats stats-horizontal shadow w-full">
-            <div className="stat">
-              <div className="stat-figure text-primary">
-                <svg className="w-8 h-8" fill="none" stroke="currentColor" viewBox="0 0 24 24">
-                  <path strokeLinecap="round" strokeLinejoin="round" strokeWidth={2} d="M9 19v-6a2 2 0 00-2-2H5a2 2 0 00-2 2v6a2 2 0 002 2h2a2 2 0 002-2zm0 0V9a2 2 0 012-2h2a2 2 0 012 2v10m-6 0a2 2 0 002 2h2a2 2 0 002-2m0 0V5a2 2 0 012-2h2a2 2 0 012 2v14a2 2 0 01-2 2h-2a2 2 0 01-2-2z" />
-                </svg>
+          {/* Input */}
+          <div className="card bg-base-100 shadow-lg border border-base-300">
+            <div className="card-body">
+              <div className="flex items-center justify-between">
+                <h2 className="card-title">报文</h2>
+                <label className="btn btn-outline btn-sm">
+                  导入文件
+                  <input type="file" accept=".txt,.log" className="hidden" onChange={importFile} />
+                </label>
               </div>
-              <div className="stat-title">总解析次数</div>
-              <div className="stat-value text-primary">{statistics.totalFrames}</div>
-              <div className="stat-desc">累计处理报文数量</div>
+              <textarea
+                className="textarea textarea-bordered font-mono text-sm h-40"
+                placeholder={'每行一帧报文，行首可带时间用于计算响应时延，例如：\n2024-01-01 12:00:00.123 68 ...\n1704081600123 68 ...'}
+                value={input}
+                onChange={(e) => setInput(e.target.value)}
+              />
             </div>
+          </div>
 
-            <div className="stat">
-              <div className="stat-figure text-success">
-                <svg className="w-8 h-8" fill="none" stroke="currentColor" viewBox="0 0 24 24">
-                  <path strokeLinecap="round" strokeLinejoin="round" strokeWidth={2} d="M9 12l2 2 4-4m6 2a9 9 0 11-18 0 9 9 0 0118 0z" />
-                </svg>
-              </div>
-              <div className="stat-title">成功率</div>
-              <div className="stat-value text-success">{statistics.successRate.toFixed(1)}%</div>
-              <div className="stat-desc">解析成功的比例</div>
-            </div>
+          {statistics && (
+            <>
+              {/* Overview Stats */}
+              <div className="stats stats-horizontal shadow w-full">
+                <div className="stat">
+                  <div className="stat-title">报文总数</div>
+                  <div className="stat-value text-primary">{statistics.total_frames}</div>
+                  <div className="stat-desc">平均帧长 {statistics.avg_frame_length.toFixed(1)} 字节</div>
+                </div>
 
-            <div className="stat">
-              <div className="stat-figure text-info">
-                <svg className="w-8 h-8" fill="none" stroke="currentColor" viewBox="0 0 24 24">
-                  <path strokeLinecap="round" strokeLinejoin="round" strokeWidth={2} d="M13 10V3L4 14h7v7l9-11h-7z" />
-                </svg>
-              </div>
-              <div className="stat-title">平均帧长</div>
-              <div className="stat-value text-info">{statistics.avgFrameLength}</div>
-              <div className="stat-desc">字节</div>
-            </div>
-          </div>
+                <div className="stat">
+                  <div className="stat-title">解析失败率</div>
+                  <div className="stat-value text-error">{formatRate(statistics.parse_error_rate)}</div>
+                  <div className="stat-desc">{statistics.parse_errors} 帧无法识别或解析</div>
+                </div>
 
-          <div className="grid grid-cols-1 lg:grid-cols-2 gap-6">
-            {/* Protocol Distribution */}
-            <div className="card bg-base-100 shadow-lg border border-base-300">
-              <div className="card-body">
-                <h2 className="card-title">协议分布</h2>
-                <div className="space-y-3">
-                  {getTopItems(statistics.protocolDistribution).map(([protocol, count]) => (
-                    <div key={protocol} className="flex items-center justify-between">
-                      <span className="font-medium">{protocol}</span>
-                      <div className="flex items-center gap-2">
-                        <div className="w-32 bg-base-300 rounded-full h-2">
-                          <div 
-                            className="bg-primary h-2 rounded-full transition-all duration-300"
-                            style={{ width: `${(count / statistics.totalFrames) * 100}%` }}
-                          />
-                        </div>
-                        <span className="text-sm font-mono w-12 text-right">{count}</span>
-                      </div>
-                    </div>
-                  ))}
+                <div className="stat">
+                  <div className="stat-title">校验失败率</div>
+                  <div className="stat-value text-warning">{formatRate(statistics.checksum_error_rate)}</div>
+                  <div className="stat-desc">{statistics.checksum_errors} 帧校验错误</div>
+                </div>
+
+                <div className="stat">
+                  <div className="stat-title">平均响应时延</div>
+                  <div className="stat-value text-info">{formatLatency(statistics.latency.avg)}</div>
+                  <div className="stat-desc">
+                    {statistics.latency.count} 对请求/应答，
+                    {formatLatency(statistics.latency.min)} ~ {formatLatency(statistics.latency.max)}
+                  </div>
                 </div>
               </div>
-            </div>
 
-            {/* Region Distribution */}
-            <div className="card bg-base-100 shadow-lg border border-base-300">
-              <div className="card-body">
-                <h2 className="card-title">区域分布</h2>
-                <div className="space-y-3">
-                  {getTopItems(statistics.regionDistribution).map(([region, count]) => (
-                    <div key={region} className="flex items-center justify-between">
-                      <span className="font-medium">{region}</span>
-                      <div className="flex items-center gap-2">
-                        <div className="w-32 bg-base-300 rounded-full h-2">
-                          <div 
-                            className="bg-secondary h-2 rounded-full transition-all duration-300"
-                            style={{ width: `${(count / statistics.totalFrames) * 100}%` }}
-                          />
-                        </div>
-                        <span className="text-sm font-mono w-12 text-right">{count}</span>
+              <div className="grid grid-cols-1 lg:grid-cols-2 gap-6">
+                {renderDistribution('协议分布', statistics.protocol_distribution, 'bg-primary')}
+                {renderDistribution('方向分布', statistics.direction_distribution, 'bg-secondary')}
+                {renderDistribution('AFN分布', statistics.afn_distribution, 'bg-accent')}
+                {renderDistribution('数据标识分布', statistics.di_distribution, 'bg-info')}
+              </div>
+
+              <div className="grid grid-cols-1 lg:grid-cols-2 gap-6">
+                {/* Latency */}
+                <div className="card bg-base-100 shadow-lg border border-base-300">
+                  <div className="card-body">
+                    <h2 className="card-title">响应时延分布</h2>
+                    {statistics.latency.count === 0 ? (
+                      <p className="text-sm text-base-content/60">没有可配对的请求和应答，报文需带时间</p>
+                    ) : (
+                      <div className="overflow-x-auto">
+                        <table className="table table-zebra">
+                          <thead>
+                            <tr>
+                              <th>时延</th>
+                              <th className="text-right">次数</th>
+                            </tr>
+                          </thead>
+                          <tbody>
+                            {statistics.latency.buckets.map((bucket) => (
+                              <tr key={bucket.label}>
+                                <td>{bucket.label}</td>
+                                <td className="font-mono text-right">{bucket.count}</td>
+                              </tr>
+                            ))}
+                          </tbody>
+                        </table>
                       </div>
-                    </div>
-                  ))}
+                    )}
+                  </div>
                 </div>
-              </div>
-            </div>
-          </div>
 
-          {/* Error Analysis */}
-          <div className="card bg-base-100 shadow-lg border border-base-300">
-            <div className="card-body">
-              <h2 className="card-title">错误分析</h2>
-              <div className="grid grid-cols-1 md:grid-cols-2 lg:grid-cols-3 gap-4">
-                {getTopItems(statistics.errorTypes).map(([error, count]) => (
-                  <div key={error} className="stat bg-error/10 rounded-lg">
-                    <div className="stat-title text-error">{error}</div>
-                    <div className="stat-value text-error text-lg">{count}</div>
-                    <div className="stat-desc">
-                      占错误总数 {((count / Object.values(statistics.errorTypes).reduce((a, b) => a + b, 0)) * 100).toFixed(1)}%
+                {/* Top Talkers */}
+                <div className="card bg-base-100 shadow-lg border border-base-300">
+                  <div className="card-body">
+                    <h2 className="card-title">报文最多的终端</h2>
+                    <div className="overflow-x-auto">
+                      <table className="table table-zebra">
+                        <thead>
+                          <tr>
+                            <th>终端地址</th>
+                            <th className="text-right">报文数</th>
+                          </tr>
+                        </thead>
+                        <tbody>
+                          {statistics.top_talkers.map((talker) => (
+                            <tr key={talker.address}>
+                              <td className="font-mono">{talker.address}</td>
+                              <td className="font-mono text-right">{talker.count}</td>
+                            </tr>
+                          ))}
+                        </tbody>
+                      </table>
                     </div>
                   </div>
-                ))}
-              </div>
-            </div>
-          </div>
-
-          {/* Recent Activity */}
-          <div className="card bg-base-100 shadow-lg border border-base-300">
-            <div className="card-body">
-              <h2 className="card-title">最近活动</h2>
-              <div className="overflow-x-auto">
-                <table className="table table-zebra">
-                  <thead>
-                    <tr>
-                      <th>时间</th>
-                      <th>协议</th>
-                      <th>区域</th>
-                      <th>帧长</th>
-                      <th>状态</th>
-                      <th>错误信息</th>
-                    </tr>
-                  </thead>
-                  <tbody>
-                    {statistics.recentActivity.map((record) => (
-                      <tr key={record.id}>
-                        <td className="text-sm font-mono">
-                          {formatTime(record.timestamp)}
-                        </td>
-                        <td>
-                          <span className="badge badge-outline">{record.protocol}</span>
-                        </td>
-                        <td>{record.region}</td>
-                        <td className="font-mono">{record.frameLength} 字节</td>
-                        <td>
-                          {record.success ? (
-                            <span className="badge badge-success">成功</span>
-                          ) : (
-                            <span className="badge badge-error">失败</span>
-                          )}
-                        </td>
-                        <td className="text-sm text-error">
-                          {record.errorMessage || '-'}
-                        </td>
-                      </tr>
-                    ))}
-                  </tbody>
-                </table>
+                </div>
               </div>
-            </div>
-          </div>
+            </>
+          )}
 
           {/* Actions */}
           <div className="card bg-base-100 shadow-lg border border-base-300">
             <div className="card-body">
               <h2 className="card-title">数据管理</h2>
               <div className="flex gap-4">
-                <button 
+                <button
                   className="btn btn-primary"
                   onClick={exportData}
+                  disabled={!statistics}
                 >
                   导出数据
                 </button>
-                <button 
-                  className="btn btn-outline"
-                  onClick={loadStatistics}
-                  disabled={loading}
-                >
-                  刷新统计
-                </button>
-                <button 
+                <button
                   className="btn btn-error btn-outline"
                   onClick={clearData}
                 >
@@ -358,4 +348,4 @@ export default function StatisticsPage() {
       </div>
     </div>
   )
-}
\ No newline at end of file
+}